@@ -0,0 +1,63 @@
+use std::{error::Error, fmt::Display};
+use crate::{cryptography::elliptic_curve::error::PointError, network::Network, serialization::{base58::error::Base58Error, bech32::error::Bech32Error, sec::serialize::SerializeSECError}};
+
+#[derive(Debug)]
+pub enum AddressError {
+    Base58(Base58Error),
+    Bech32(Bech32Error),
+    InvalidLength(usize),
+    UnknownVersion(u8),
+    UnknownHrp(String),
+    InvalidWitnessVersion(u8),
+    InvalidWitnessProgramLength(usize),
+    InvalidChecksumVariant(u8),
+    InvalidPublicKey(SerializeSECError),
+    InvalidTaprootKey(PointError),
+    WrongNetwork(Network, Network),
+    NoAddressForm,
+}
+
+impl Error for AddressError { }
+
+impl Display for AddressError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AddressError::Base58(e) => write!(f, "AddressError::Base58({})", e),
+            AddressError::Bech32(e) => write!(f, "AddressError::Bech32({})", e),
+            AddressError::InvalidLength(len) => write!(f, "AddressError::InvalidLength({} bytes, expected 21)", len),
+            AddressError::UnknownVersion(version) => write!(f, "AddressError::UnknownVersion({:#04x})", version),
+            AddressError::UnknownHrp(hrp) => write!(f, "AddressError::UnknownHrp({})", hrp),
+            AddressError::InvalidWitnessVersion(version) => write!(f, "AddressError::InvalidWitnessVersion({})", version),
+            AddressError::InvalidWitnessProgramLength(len) => write!(f, "AddressError::InvalidWitnessProgramLength({})", len),
+            AddressError::InvalidChecksumVariant(version) => write!(f, "AddressError::InvalidChecksumVariant(wrong checksum for witness version {})", version),
+            AddressError::InvalidPublicKey(e) => write!(f, "AddressError::InvalidPublicKey({})", e),
+            AddressError::InvalidTaprootKey(e) => write!(f, "AddressError::InvalidTaprootKey({})", e),
+            AddressError::WrongNetwork(actual, expected) => write!(f, "AddressError::WrongNetwork({} address, expected {})", actual, expected),
+            AddressError::NoAddressForm => write!(f, "AddressError::NoAddressForm(script has no address)"),
+        }
+    }
+}
+
+impl From<Base58Error> for AddressError {
+    fn from(value: Base58Error) -> Self {
+        AddressError::Base58(value)
+    }
+}
+
+impl From<Bech32Error> for AddressError {
+    fn from(value: Bech32Error) -> Self {
+        AddressError::Bech32(value)
+    }
+}
+
+impl From<SerializeSECError> for AddressError {
+    fn from(value: SerializeSECError) -> Self {
+        AddressError::InvalidPublicKey(value)
+    }
+}
+
+impl From<PointError> for AddressError {
+    fn from(value: PointError) -> Self {
+        AddressError::InvalidTaprootKey(value)
+    }
+}
@@ -0,0 +1,31 @@
+use crate::{cryptography::hash::digest::{PubkeyHash, ScriptHash}, network::Network, serialization::base58};
+use super::{error::AddressError, Address, Payload};
+
+pub(super) fn encode(version: u8, hash: &[u8]) -> String {
+    let mut bytes = Vec::with_capacity(21);
+
+    bytes.push(version);
+    bytes.extend_from_slice(hash);
+
+    base58::encode_check(&bytes)
+}
+
+pub(super) fn decode(s: &str) -> Result<Address, AddressError> {
+    let bytes = base58::decode_check(s)?;
+
+    if bytes.len() != 21 {
+        return Err(AddressError::InvalidLength(bytes.len()));
+    }
+
+    let (version, hash) = (bytes[0], &bytes[1..]);
+
+    if let Some(network) = Network::from_p2pkh_prefix(version) {
+        return Ok(Address::new(Payload::PubkeyHash(PubkeyHash::from_slice(hash).unwrap()), network));
+    }
+
+    if let Some(network) = Network::from_p2sh_prefix(version) {
+        return Ok(Address::new(Payload::ScriptHash(ScriptHash::from_slice(hash).unwrap()), network));
+    }
+
+    Err(AddressError::UnknownVersion(version))
+}
@@ -0,0 +1,413 @@
+use std::{fmt::Display, str::FromStr};
+use crate::{cryptography::{elliptic_curve::bitcoin_point::BitcoinPoint, hash::{digest::{PubkeyHash, ScriptHash}, sha256}, taproot::{tweak_public_key, x_only}}, network::Network, script::{standard::StandardScript, Script}, serialization::sec::serialize::{SerializeSEC, SerializeSECError}};
+use self::{error::AddressError, segwit::WitnessProgram};
+
+pub mod error;
+pub mod legacy;
+pub mod segwit;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AddressType {
+    P2PKH,
+    P2SH,
+    P2WPKH,
+    P2WSH,
+    P2TR,
+    WitnessUnknown,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Payload {
+    PubkeyHash(PubkeyHash),
+    ScriptHash(ScriptHash),
+    WitnessProgram(WitnessProgram),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Address {
+    payload: Payload,
+    network: Network,
+}
+
+impl Address {
+    pub fn new(payload: Payload, network: Network) -> Self {
+        Self {
+            payload,
+            network,
+        }
+    }
+
+    /// Parses `s`, rejecting it unless it is encoded for `network`.
+    pub fn parse(s: &str, network: Network) -> Result<Self, AddressError> {
+        let address = Address::from_str(s)?;
+
+        if !address.is_valid_for_network(network) {
+            return Err(AddressError::WrongNetwork(address.network, network));
+        }
+
+        Ok(Self::new(address.payload, network))
+    }
+
+    pub fn p2pkh(point: &BitcoinPoint, compressed: bool, network: Network) -> Result<Self, AddressError> {
+        let sec = point.serialize_sec(compressed)?;
+
+        Ok(Self::new(Payload::PubkeyHash(PubkeyHash::hash(&sec)), network))
+    }
+
+    pub fn p2sh(redeem_script: &[u8], network: Network) -> Self {
+        Self::new(Payload::ScriptHash(ScriptHash::hash(redeem_script)), network)
+    }
+
+    /// Segwit v0 only commits to compressed public keys.
+    pub fn p2wpkh(point: &BitcoinPoint, network: Network) -> Result<Self, AddressError> {
+        let sec = point.serialize_sec(true)?;
+        let program = WitnessProgram::new(0, PubkeyHash::hash(&sec).as_ref().to_vec())?;
+
+        Ok(Self::new(Payload::WitnessProgram(program), network))
+    }
+
+    pub fn p2wsh(witness_script: &[u8], network: Network) -> Self {
+        let program = WitnessProgram::new(0, sha256(witness_script).to_vec()).unwrap();
+
+        Self::new(Payload::WitnessProgram(program), network)
+    }
+
+    /// Taproot output for `internal_key`, tweaked with the script tree's
+    ///  `merkle_root` if it has one.
+    pub fn p2tr(internal_key: &BitcoinPoint, merkle_root: Option<&[u8; 32]>, network: Network) -> Result<Self, AddressError> {
+        let internal_key = x_only(internal_key).ok_or(AddressError::InvalidPublicKey(SerializeSECError))?;
+        let (output_key, _) = tweak_public_key(&internal_key, merkle_root)?;
+
+        // The tweaked key is never the identity, so it always has an x.
+        Ok(Self::p2tr_tweaked(&x_only(&output_key).unwrap(), network))
+    }
+
+    /// Taproot output for an already tweaked output key.
+    pub fn p2tr_tweaked(output_key: &[u8; 32], network: Network) -> Self {
+        let program = WitnessProgram::new(1, output_key.to_vec()).unwrap();
+
+        Self::new(Payload::WitnessProgram(program), network)
+    }
+
+    /// The address paying to `script_pubkey`. P2PK, bare multisig and
+    ///  OP_RETURN outputs have no address form.
+    pub fn from_script(script_pubkey: &Script, network: Network) -> Result<Self, AddressError> {
+        let payload = match script_pubkey.classify() {
+            StandardScript::P2PKH(hash) => Payload::PubkeyHash(hash),
+            StandardScript::P2SH(hash) => Payload::ScriptHash(hash),
+            StandardScript::P2WPKH(hash) => Payload::WitnessProgram(WitnessProgram::new(0, hash.as_ref().to_vec())?),
+            StandardScript::P2WSH(hash) => Payload::WitnessProgram(WitnessProgram::new(0, hash.to_vec())?),
+            StandardScript::P2TR(output_key) => Payload::WitnessProgram(WitnessProgram::new(1, output_key.to_vec())?),
+            StandardScript::WitnessUnknown { version, program } => Payload::WitnessProgram(WitnessProgram::new(version, program)?),
+            _ => return Err(AddressError::NoAddressForm),
+        };
+
+        Ok(Self::new(payload, network))
+    }
+
+    /// The output script paying to this address.
+    pub fn script_pubkey(&self) -> Script {
+        match &self.payload {
+            Payload::PubkeyHash(hash) => Script::p2pkh(hash),
+            Payload::ScriptHash(hash) => Script::p2sh(hash),
+            Payload::WitnessProgram(wp) => Script::witness_program(wp.version(), wp.program()),
+        }
+    }
+
+    pub fn address_type(&self) -> AddressType {
+        match &self.payload {
+            Payload::PubkeyHash(_) => AddressType::P2PKH,
+            Payload::ScriptHash(_) => AddressType::P2SH,
+            Payload::WitnessProgram(wp) => match (wp.version(), wp.program().len()) {
+                (0, 20) => AddressType::P2WPKH,
+                (0, 32) => AddressType::P2WSH,
+                (1, 32) => AddressType::P2TR,
+                _ => AddressType::WitnessUnknown,
+            },
+        }
+    }
+
+    pub fn payload(&self) -> &Payload {
+        &self.payload
+    }
+
+    /// The network the address was created for. Addresses decoded with
+    ///  `from_str` get the first network sharing their prefix, see `parse`.
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    /// Whether the address encodes identically on `network`, e.g. a testnet3
+    ///  P2PKH address is also a valid regtest address but a bech32 one is not.
+    pub fn is_valid_for_network(&self, network: Network) -> bool {
+        let ours = self.network.params();
+        let theirs = network.params();
+
+        match self.payload {
+            Payload::PubkeyHash(_) => ours.p2pkh_prefix == theirs.p2pkh_prefix,
+            Payload::ScriptHash(_) => ours.p2sh_prefix == theirs.p2sh_prefix,
+            Payload::WitnessProgram(_) => ours.bech32_hrp == theirs.bech32_hrp,
+        }
+    }
+}
+
+impl Display for Address {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let params = self.network.params();
+
+        let encoded = match &self.payload {
+            Payload::PubkeyHash(hash) => legacy::encode(params.p2pkh_prefix, hash.as_ref()),
+            Payload::ScriptHash(hash) => legacy::encode(params.p2sh_prefix, hash.as_ref()),
+            Payload::WitnessProgram(wp) => segwit::encode(params.bech32_hrp, wp).map_err(|_| std::fmt::Error)?,
+        };
+
+        write!(f, "{}", encoded)
+    }
+}
+
+impl FromStr for Address {
+    type Err = AddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if segwit::is_segwit(s) {
+            segwit::decode(s)
+        } else {
+            legacy::decode(s)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+    use num_bigint::BigUint;
+    use crate::{cryptography::{elliptic_curve::bitcoin_point::BitcoinPoint, hash::digest::ScriptHash}, network::Network, script::Script, serialization::{base58, sec::deserialize::DeserializeSEC}};
+    use super::{error::AddressError, segwit::WitnessProgram, Address, AddressType, Payload};
+
+    #[test]
+    pub fn test_p2pkh() {
+        let point = (BitcoinPoint::g() * &BigUint::from(5002u32)).unwrap();
+        assert_eq!(Address::p2pkh(&point, false, Network::Testnet3).unwrap().to_string(), "mmTPbXQFxboEtNRkwfh6K51jvdtHLxGeMA");
+
+        let point = (BitcoinPoint::g() * &BigUint::from(2020u32).pow(5)).unwrap();
+        assert_eq!(Address::p2pkh(&point, true, Network::Testnet3).unwrap().to_string(), "mopVkxp8UhXqRYbCYJsbeE1h1fiF64jcoH");
+
+        let point = (BitcoinPoint::g() * &BigUint::from(0x12345deadbeefu64)).unwrap();
+        assert_eq!(Address::p2pkh(&point, true, Network::Mainnet).unwrap().to_string(), "1F1Pn2y6pDb68E5nYJJeba4TLg2U7B6KF1");
+    }
+
+    #[test]
+    pub fn test_p2pkh_identity() {
+        assert!(Address::p2pkh(&BitcoinPoint::identity(), true, Network::Mainnet).is_err());
+    }
+
+    #[test]
+    pub fn test_p2sh() {
+        // 2-of-2 multisig redeem script from "Programming Bitcoin" chapter 8.
+        let redeem_script = hex::decode("5221022626e955ea6ea6d98850c994f9107b036b1334f18ca8830bfff1295d21cfdb702103b287eaf122eea69030a0e9feed096bed8045c8b98bec453e1ffac7fbdbd4bb7152ae").unwrap();
+
+        assert_eq!(Address::p2sh(&redeem_script, Network::Mainnet).to_string(), "3CLoMMyuoDQTPRD3XYZtCvgvkadrAdvdXh");
+        assert_eq!(Address::p2sh(&redeem_script, Network::Testnet3).to_string(), "2N3u1R6uwQfuobCqbCgBkpsgBxvr1tZpe7B");
+    }
+
+    #[test]
+    pub fn test_parse() {
+        let address = Address::from_str("1F1Pn2y6pDb68E5nYJJeba4TLg2U7B6KF1").unwrap();
+        assert_eq!(address.address_type(), AddressType::P2PKH);
+        assert_eq!(address.network(), Network::Mainnet);
+
+        let address = Address::from_str("2N3u1R6uwQfuobCqbCgBkpsgBxvr1tZpe7B").unwrap();
+        assert_eq!(address.address_type(), AddressType::P2SH);
+        assert_eq!(address.network(), Network::Testnet3);
+        assert_eq!(
+            address.payload(),
+            &Payload::ScriptHash(ScriptHash::from_str("74d691da1574e6b3c192ecfb52cc8984ee7b6c56").unwrap())
+        );
+    }
+
+    #[test]
+    pub fn test_parse_round_trip() {
+        for s in ["mmTPbXQFxboEtNRkwfh6K51jvdtHLxGeMA", "3CLoMMyuoDQTPRD3XYZtCvgvkadrAdvdXh"] {
+            assert_eq!(Address::from_str(s).unwrap().to_string(), s);
+        }
+    }
+
+    #[test]
+    pub fn test_parse_invalid() {
+        assert!(matches!(Address::from_str("1F1Pn2y6pDb68E5nYJJeba4TLg2U7B6KF2"), Err(AddressError::Base58(_))));
+
+        let mut bytes = vec![0x30];
+        bytes.extend_from_slice(&[0x11; 20]);
+        assert!(matches!(Address::from_str(&base58::encode_check(&bytes)), Err(AddressError::UnknownVersion(0x30))));
+
+        let bytes = vec![0x00; 20];
+        assert!(matches!(Address::from_str(&base58::encode_check(&bytes)), Err(AddressError::InvalidLength(20))));
+    }
+
+    // BIP350 valid addresses with their witness version and program.
+    const SEGWIT_VALID: [(&str, u8, &str); 8] = [
+        ("BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4", 0, "751e76e8199196d454941c45d1b3a323f1433bd6"),
+        ("tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7", 0, "1863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262"),
+        ("bc1pw508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7kt5nd6y", 1, "751e76e8199196d454941c45d1b3a323f1433bd6751e76e8199196d454941c45d1b3a323f1433bd6"),
+        ("BC1SW50QGDZ25J", 16, "751e"),
+        ("bc1zw508d6qejxtdg4y5r3zarvaryvaxxpcs", 2, "751e76e8199196d454941c45d1b3a323"),
+        ("tb1qqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvsesrxh6hy", 0, "000000c4a5cad46221b2a187905e5266362b99d5e91c6ce24d165dab93e86433"),
+        ("tb1pqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvsesf3hn0c", 1, "000000c4a5cad46221b2a187905e5266362b99d5e91c6ce24d165dab93e86433"),
+        ("bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0", 1, "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"),
+    ];
+
+    #[test]
+    pub fn test_segwit_valid() {
+        for (s, version, program) in SEGWIT_VALID {
+            let address = Address::from_str(s).unwrap();
+            let expected = WitnessProgram::new(version, hex::decode(program).unwrap()).unwrap();
+
+            assert_eq!(address.payload(), &Payload::WitnessProgram(expected));
+            assert_eq!(address.to_string(), s.to_lowercase());
+        }
+    }
+
+    #[test]
+    pub fn test_script_pubkey() {
+        for (s, version, program) in SEGWIT_VALID {
+            let address = Address::from_str(s).unwrap();
+            let script_pubkey = address.script_pubkey();
+
+            assert_eq!(script_pubkey.witness_version_and_program(), Some((version, &hex::decode(program).unwrap()[..])));
+            assert_eq!(Address::from_script(&script_pubkey, address.network()).unwrap(), address);
+        }
+
+        for (s, script_pubkey) in [
+            ("1F1Pn2y6pDb68E5nYJJeba4TLg2U7B6KF1", "76a91499a4c61750789253f69fd750ac0d02126337330588ac"),
+            ("3CLoMMyuoDQTPRD3XYZtCvgvkadrAdvdXh", "a91474d691da1574e6b3c192ecfb52cc8984ee7b6c5687"),
+        ] {
+            let address = Address::from_str(s).unwrap();
+
+            assert_eq!(hex::encode(address.script_pubkey().as_bytes()), script_pubkey);
+            assert_eq!(Address::from_script(&address.script_pubkey(), Network::Mainnet).unwrap().to_string(), s);
+        }
+    }
+
+    #[test]
+    pub fn test_from_script_without_address() {
+        let public_key = hex::decode("022626e955ea6ea6d98850c994f9107b036b1334f18ca8830bfff1295d21cfdb70").unwrap();
+
+        for script_pubkey in [Script::p2pk(&public_key), Script::p2ms(1, &[&public_key]).unwrap(), Script::null_data(b"data"), Script::from_bytes(vec![0x51])] {
+            assert!(matches!(Address::from_script(&script_pubkey, Network::Mainnet), Err(AddressError::NoAddressForm)));
+        }
+    }
+
+    #[test]
+    pub fn test_segwit_invalid() {
+        let cases = [
+            "tc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq5zuyut",
+            "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqh2y7hd",
+            "tb1z0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqglt7rf",
+            "BC1S0XLXVLHEMJA6C4DQV22UAPCTQUPFHLXM9H8Z3K2E72Q4K9HCZ7VQ54WELL",
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kemeawh",
+            "tb1q0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq24jc47",
+            "bc1p38j9r5y49hruaue7wxjce0updqjuyyx0kh56v8s25huc6995vvpql3jow4",
+            "BC130XLXVLHEMJA6C4DQV22UAPCTQUPFHLXM9H8Z3K2E72Q4K9HCZ7VQ7ZWS8R",
+            "bc1pw5dgrnzv",
+            "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7v8n0nx0muaewav253zgeav",
+            "BC1QR508D6QEJXTDG4Y5R3ZARVARYV98GJ9P",
+            "tb1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq47Zagq",
+            "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7v07qwwzcrf",
+            "tb1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vpggkg4j",
+            "bc1gmk9yu",
+            // BIP173 invalid addresses.
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t5",
+            "BC13W508D6QEJXTDG4Y5R3ZARVARY0C5XW7KN40WF2",
+            "bc1rw5uspcuh",
+            "bc10w508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7kw5rljs90",
+            "tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sL5k7",
+            "bc1zw508d6qejxtdg4y5r3zarvaryvqyzf3du",
+            "tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3pjxtptv",
+        ];
+
+        for s in cases {
+            assert!(Address::from_str(s).is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    pub fn test_segwit_errors() {
+        assert!(matches!(
+            Address::from_str("bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqh2y7hd"),
+            Err(AddressError::InvalidChecksumVariant(1))
+        ));
+        assert!(matches!(
+            Address::from_str("tb1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq47Zagq"),
+            Err(AddressError::Bech32(_))
+        ));
+        assert!(matches!(
+            Address::from_str("BC1QR508D6QEJXTDG4Y5R3ZARVARYV98GJ9P"),
+            Err(AddressError::InvalidWitnessProgramLength(16))
+        ));
+        assert!(matches!(
+            Address::from_str("tc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq5zuyut"),
+            Err(AddressError::UnknownHrp(hrp)) if hrp == "tc"
+        ));
+    }
+
+    #[test]
+    pub fn test_p2wpkh() {
+        let address = Address::p2wpkh(&BitcoinPoint::g(), Network::Mainnet).unwrap();
+
+        assert_eq!(address.address_type(), AddressType::P2WPKH);
+        assert_eq!(address.to_string(), "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4");
+    }
+
+    #[test]
+    pub fn test_p2wsh() {
+        // BIP173: the witness script is the compressed generator followed by OP_CHECKSIG.
+        let witness_script = hex::decode("210279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798ac").unwrap();
+        let address = Address::p2wsh(&witness_script, Network::Testnet3);
+
+        assert_eq!(address.address_type(), AddressType::P2WSH);
+        assert_eq!(address.to_string(), "tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7");
+    }
+
+    #[test]
+    pub fn test_p2tr() {
+        // BIP341 `scriptPubKey` test vector 0.
+        let internal = BitcoinPoint::deserialize_sec(&hex::decode("02d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d").unwrap()[..]).unwrap();
+        let address = Address::p2tr(&internal, None, Network::Mainnet).unwrap();
+
+        assert_eq!(address.address_type(), AddressType::P2TR);
+        assert_eq!(address.to_string(), "bc1p2wsldez5mud2yam29q22wgfh9439spgduvct83k3pm50fcxa5dps59h4z5");
+    }
+
+    #[test]
+    pub fn test_network_prefixes() {
+        let point = BitcoinPoint::g();
+
+        let regtest = Address::p2wpkh(&point, Network::Regtest).unwrap();
+        assert_eq!(regtest.to_string(), "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080");
+        assert_eq!(Address::from_str(&regtest.to_string()).unwrap(), regtest);
+
+        let signet = Address::p2wpkh(&point, Network::Signet).unwrap();
+        assert!(signet.to_string().starts_with("tb1"));
+
+        let mainnet = Address::p2pkh(&point, true, Network::Mainnet).unwrap();
+        assert!(mainnet.to_string().starts_with('1'));
+    }
+
+    #[test]
+    pub fn test_parse_for_network() {
+        // Regtest shares testnet's Base58 prefixes but not its bech32 HRP.
+        let address = Address::parse("mopVkxp8UhXqRYbCYJsbeE1h1fiF64jcoH", Network::Regtest).unwrap();
+        assert_eq!(address.network(), Network::Regtest);
+        assert_eq!(address.to_string(), "mopVkxp8UhXqRYbCYJsbeE1h1fiF64jcoH");
+
+        assert!(matches!(
+            Address::parse("tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7", Network::Regtest),
+            Err(AddressError::WrongNetwork(Network::Testnet3, Network::Regtest))
+        ));
+        assert!(matches!(
+            Address::parse("1F1Pn2y6pDb68E5nYJJeba4TLg2U7B6KF1", Network::Regtest),
+            Err(AddressError::WrongNetwork(Network::Mainnet, Network::Regtest))
+        ));
+        assert!(Address::parse("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4", Network::Mainnet).is_ok());
+    }
+}
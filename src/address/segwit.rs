@@ -0,0 +1,82 @@
+use crate::{network::Network, serialization::bech32::{self, Variant}};
+use super::{error::AddressError, Address, Payload};
+
+/// A witness version and program, validated against BIP141's length rules.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WitnessProgram {
+    version: u8,
+    program: Vec<u8>,
+}
+
+impl WitnessProgram {
+    pub fn new(version: u8, program: Vec<u8>) -> Result<Self, AddressError> {
+        if version > 16 {
+            return Err(AddressError::InvalidWitnessVersion(version));
+        }
+
+        if !(2..=40).contains(&program.len()) {
+            return Err(AddressError::InvalidWitnessProgramLength(program.len()));
+        }
+
+        if version == 0 && program.len() != 20 && program.len() != 32 {
+            return Err(AddressError::InvalidWitnessProgramLength(program.len()));
+        }
+
+        Ok(Self {
+            version,
+            program,
+        })
+    }
+
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    pub fn program(&self) -> &[u8] {
+        &self.program
+    }
+
+    /// BIP350: version 0 keeps bech32, everything after it uses bech32m.
+    pub fn variant(&self) -> Variant {
+        match self.version {
+            0 => Variant::Bech32,
+            _ => Variant::Bech32m,
+        }
+    }
+}
+
+pub(super) fn encode(hrp: &str, witness_program: &WitnessProgram) -> Result<String, AddressError> {
+    let mut data = vec![witness_program.version];
+    data.extend(bech32::convert_bits(&witness_program.program, 8, 5, true)?);
+
+    Ok(bech32::encode(hrp, &data, witness_program.variant())?)
+}
+
+pub(super) fn decode(s: &str) -> Result<Address, AddressError> {
+    let (hrp, data, variant) = bech32::decode(s)?;
+
+    let network = Network::from_bech32_hrp(&hrp).ok_or(AddressError::UnknownHrp(hrp))?;
+
+    let (version, program) = data.split_first().ok_or(AddressError::InvalidWitnessProgramLength(0))?;
+
+    let program = bech32::convert_bits(program, 5, 8, false)?;
+    let witness_program = WitnessProgram::new(*version, program)?;
+
+    if witness_program.variant() != variant {
+        return Err(AddressError::InvalidChecksumVariant(*version));
+    }
+
+    Ok(Address::new(Payload::WitnessProgram(witness_program), network))
+}
+
+/// Whether `s` should be decoded as bech32 rather than Base58Check: it
+///  has a known HRP, or is valid bech32 whatever its HRP, which `decode`
+///  then rejects as unknown.
+pub(super) fn is_segwit(s: &str) -> bool {
+    let lower = s.to_lowercase();
+
+    match lower.rfind('1') {
+        Some(separator) => Network::from_bech32_hrp(&lower[..separator]).is_some() || bech32::decode(s).is_ok(),
+        None => false,
+    }
+}
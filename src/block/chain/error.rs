@@ -0,0 +1,53 @@
+use std::{error::Error, fmt::Display};
+use crate::{block::error::BlockError, cryptography::hash::digest::BlockHash, serialization::consensus::deserialize::DeserializeConsensusError};
+
+#[derive(Debug)]
+pub enum ChainError {
+    UnknownParent(BlockHash),
+    BadDifficultyBits(u32, u32),
+    TimeTooOld(u32),
+    TimeWarp(u32),
+    CheckpointMismatch(u32),
+    ForkBeforeCheckpoint(u32),
+    WrongNetwork([u8; 4]),
+    Block(BlockError),
+    Deserialize(DeserializeConsensusError),
+    Io(std::io::Error),
+}
+
+impl Error for ChainError { }
+
+impl Display for ChainError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChainError::UnknownParent(hash) => write!(f, "ChainError::UnknownParent({})", hash),
+            ChainError::BadDifficultyBits(expected, bits) => write!(f, "ChainError::BadDifficultyBits(expected {:#010x}, got {:#010x})", expected, bits),
+            ChainError::TimeTooOld(median) => write!(f, "ChainError::TimeTooOld(not after median time past {})", median),
+            ChainError::TimeWarp(time) => write!(f, "ChainError::TimeWarp(period starts more than ten minutes before {})", time),
+            ChainError::CheckpointMismatch(height) => write!(f, "ChainError::CheckpointMismatch(chain doesn't go through the checkpoint at {})", height),
+            ChainError::ForkBeforeCheckpoint(height) => write!(f, "ChainError::ForkBeforeCheckpoint(fork below the checkpoint at {})", height),
+            ChainError::WrongNetwork(magic) => write!(f, "ChainError::WrongNetwork(magic {})", hex::encode(magic)),
+            ChainError::Block(e) => write!(f, "ChainError::Block({})", e),
+            ChainError::Deserialize(e) => write!(f, "ChainError::Deserialize({})", e),
+            ChainError::Io(e) => write!(f, "ChainError::Io({})", e),
+        }
+    }
+}
+
+impl From<BlockError> for ChainError {
+    fn from(value: BlockError) -> Self {
+        ChainError::Block(value)
+    }
+}
+
+impl From<DeserializeConsensusError> for ChainError {
+    fn from(value: DeserializeConsensusError) -> Self {
+        ChainError::Deserialize(value)
+    }
+}
+
+impl From<std::io::Error> for ChainError {
+    fn from(value: std::io::Error) -> Self {
+        ChainError::Io(value)
+    }
+}
@@ -0,0 +1,583 @@
+use std::{collections::{BTreeMap, HashMap}, fs, path::Path};
+use num_bigint::BigUint;
+use crate::{cryptography::hash::digest::BlockHash, network::{params::ConsensusParams, Network}, serialization::consensus::{deserialize::DeserializeConsensus, serialize::SerializeConsensus}};
+use self::error::ChainError;
+use super::{header::BlockHeader, pow::next_work_required};
+
+pub mod error;
+
+/// Blocks whose timestamps the median time past is taken over.
+const MEDIAN_TIME_SPAN: u32 = 11;
+
+/// BIP94: how far the first block of a retarget period may go back in time
+///  from the last block of the previous one.
+const MAX_TIMEWARP: i64 = 600;
+
+/// A header along with where it sits in the tree of known headers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainEntry {
+    pub hash: BlockHash,
+    pub header: BlockHeader,
+    pub height: u32,
+    /// Total work of the chain up to and including this header.
+    pub chainwork: BigUint,
+}
+
+/// What accepting a header did to the active chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChainUpdate {
+    /// The header was already known.
+    Duplicate,
+    /// Stored on a side chain without more work than the tip.
+    SideChain,
+    /// The header is the new tip, on top of the old one.
+    Extended,
+    /// A side chain got more work and became the active chain. The headers
+    ///  that left it are tip first, those that joined it in height order.
+    Reorganized {
+        disconnected: Vec<BlockHash>,
+        connected: Vec<BlockHash>,
+    },
+}
+
+/// A header-only view of a network, as an SPV client keeps: every valid
+///  header seen, with the most-work chain through them active. Headers are
+///  checked for proof of work, the difficulty the chain requires of them,
+///  their timestamps and the checkpoints given, but not their blocks.
+pub struct HeaderChain {
+    network: Network,
+    params: ConsensusParams,
+    entries: HashMap<BlockHash, ChainEntry>,
+    /// Hashes of the active chain, by height.
+    active: Vec<BlockHash>,
+    checkpoints: BTreeMap<u32, BlockHash>,
+}
+
+impl HeaderChain {
+    /// A chain holding just `network`'s genesis header.
+    pub fn new(network: Network) -> Self {
+        let params = network.params();
+        let genesis = BlockHeader::deserialize_consensus(&hex::decode(params.genesis_header).unwrap()).unwrap();
+        let hash = genesis.block_hash();
+
+        let entry = ChainEntry {
+            hash,
+            header: genesis,
+            height: 0,
+            chainwork: genesis.work(),
+        };
+
+        Self {
+            network,
+            params: params.consensus.clone(),
+            entries: HashMap::from([(hash, entry)]),
+            active: vec![hash],
+            checkpoints: BTreeMap::new(),
+        }
+    }
+
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    pub fn tip(&self) -> &ChainEntry {
+        &self.entries[self.active.last().unwrap()]
+    }
+
+    pub fn height(&self) -> u32 {
+        self.tip().height
+    }
+
+    /// Any known header, active or not.
+    pub fn get(&self, hash: &BlockHash) -> Option<&ChainEntry> {
+        self.entries.get(hash)
+    }
+
+    /// The active chain's header at `height`.
+    pub fn get_at(&self, height: u32) -> Option<&ChainEntry> {
+        self.active.get(height as usize).map(|hash| &self.entries[hash])
+    }
+
+    /// Whether `hash` is in the active chain, which is what makes a proof
+    ///  of inclusion in its block worth anything.
+    pub fn is_active(&self, hash: &BlockHash) -> bool {
+        self.entries.get(hash).is_some_and(|entry| self.active.get(entry.height as usize) == Some(hash))
+    }
+
+    /// Headers of any chain not going through `hash` at `height` are
+    ///  rejected from then on. A checkpoint the active chain already
+    ///  contradicts isn't added.
+    pub fn add_checkpoint(&mut self, height: u32, hash: BlockHash) -> Result<(), ChainError> {
+        if self.active.get(height as usize).is_some_and(|active| *active != hash) {
+            return Err(ChainError::CheckpointMismatch(height));
+        }
+
+        self.checkpoints.insert(height, hash);
+
+        Ok(())
+    }
+
+    /// Core's `AcceptBlockHeader` and the reorganization that may follow.
+    ///  The header's parent has to be known already.
+    pub fn accept_header(&mut self, header: BlockHeader) -> Result<ChainUpdate, ChainError> {
+        let hash = header.block_hash();
+
+        if self.entries.contains_key(&hash) {
+            return Ok(ChainUpdate::Duplicate);
+        }
+
+        let parent = self.entries.get(&header.prev_blockhash).ok_or(ChainError::UnknownParent(header.prev_blockhash))?;
+        let height = parent.height + 1;
+
+        self.check_checkpoints(parent, &hash, height)?;
+
+        header.validate_pow(&self.params)?;
+
+        let expected = next_work_required(&self.params, &parent.header, parent.height, header.time, |height| self.ancestor(parent, height).header);
+
+        if header.bits != expected {
+            return Err(ChainError::BadDifficultyBits(expected, header.bits));
+        }
+
+        let median = self.median_time_past(parent);
+
+        if header.time <= median {
+            return Err(ChainError::TimeTooOld(median));
+        }
+
+        if self.params.enforce_bip94 && height.is_multiple_of(self.params.difficulty_adjustment_interval()) && (header.time as i64) < parent.header.time as i64 - MAX_TIMEWARP {
+            return Err(ChainError::TimeWarp(parent.header.time));
+        }
+
+        let entry = ChainEntry {
+            hash,
+            header,
+            height,
+            chainwork: &parent.chainwork + header.work(),
+        };
+
+        let more_work = entry.chainwork > self.tip().chainwork;
+        self.entries.insert(hash, entry);
+
+        if !more_work {
+            return Ok(ChainUpdate::SideChain);
+        }
+
+        Ok(self.activate(hash))
+    }
+
+    /// Core's `GetLocator`: hashes of the active chain for a `getheaders`
+    ///  request, the ten from the tip and then exponentially fewer back to
+    ///  genesis, so a peer finds where its chain forks off ours.
+    pub fn locator(&self) -> Vec<BlockHash> {
+        let mut locator = Vec::new();
+        let mut height = self.height();
+        let mut step = 1;
+
+        loop {
+            locator.push(self.active[height as usize]);
+
+            if height == 0 {
+                break;
+            }
+
+            height = height.saturating_sub(step);
+
+            if locator.len() > 10 {
+                step *= 2;
+            }
+        }
+
+        locator
+    }
+
+    /// The highest header of `locator` in the active chain, where a reply
+    ///  to a peer's `getheaders` starts. Genesis when there is none.
+    pub fn find_fork(&self, locator: &[BlockHash]) -> &ChainEntry {
+        locator
+            .iter()
+            .find(|hash| self.is_active(hash))
+            .map_or_else(|| self.get_at(0).unwrap(), |hash| &self.entries[hash])
+    }
+
+    /// Writes every known header but genesis to `path`, after the network's
+    ///  magic. The active chain goes first, so that loading makes it active
+    ///  again even when a side chain has as much work. The file is replaced
+    ///  in one rename, so a crash leaves either the old or the new one.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ChainError> {
+        let mut side_chains: Vec<&ChainEntry> = self.entries.values().filter(|entry| !self.is_active(&entry.hash)).collect();
+        side_chains.sort_by_key(|entry| entry.height);
+
+        let headers: Vec<BlockHeader> = self.active[1..]
+            .iter()
+            .map(|hash| &self.entries[hash])
+            .chain(side_chains)
+            .map(|entry| entry.header)
+            .collect();
+
+        let mut bytes = self.network.params().magic.to_vec();
+        headers.serialize_consensus_to(&mut bytes);
+
+        let path = path.as_ref();
+        let temporary = path.with_extension("tmp");
+
+        fs::write(&temporary, bytes)?;
+        fs::rename(temporary, path)?;
+
+        Ok(())
+    }
+
+    /// Reads back a chain [`save`](Self::save) wrote, validating every header
+    ///  again. Checkpoints aren't saved, so are added afterwards.
+    pub fn load(network: Network, path: impl AsRef<Path>) -> Result<Self, ChainError> {
+        let bytes = fs::read(path)?;
+
+        let magic: [u8; 4] = bytes.get(..4).and_then(|magic| magic.try_into().ok()).ok_or(std::io::Error::from(std::io::ErrorKind::UnexpectedEof))?;
+
+        if magic != network.params().magic {
+            return Err(ChainError::WrongNetwork(magic));
+        }
+
+        let mut chain = Self::new(network);
+
+        for header in Vec::<BlockHeader>::deserialize_consensus(&bytes[4..])? {
+            chain.accept_header(header)?;
+        }
+
+        Ok(chain)
+    }
+
+    /// The entry at `height` on the chain ending at `entry`, walking back
+    ///  only as far as where it joins the active chain.
+    fn ancestor<'a>(&'a self, mut entry: &'a ChainEntry, height: u32) -> &'a ChainEntry {
+        while entry.height > height && self.active.get(entry.height as usize) != Some(&entry.hash) {
+            entry = &self.entries[&entry.header.prev_blockhash];
+        }
+
+        if entry.height > height {
+            return &self.entries[&self.active[height as usize]];
+        }
+
+        entry
+    }
+
+    /// Core's `GetMedianTimePast`, over `entry` and the ten headers before it.
+    fn median_time_past<'a>(&'a self, mut entry: &'a ChainEntry) -> u32 {
+        let mut times = vec![entry.header.time];
+
+        while entry.height > 0 && times.len() < MEDIAN_TIME_SPAN as usize {
+            entry = &self.entries[&entry.header.prev_blockhash];
+            times.push(entry.header.time);
+        }
+
+        times.sort_unstable();
+
+        times[times.len() / 2]
+    }
+
+    /// A header is checked against the highest checkpoint at or below its
+    ///  height. Below the highest checkpoint the active chain has passed,
+    ///  nothing new can be accepted at all: the header would start a fork
+    ///  that can't go through it.
+    fn check_checkpoints(&self, parent: &ChainEntry, hash: &BlockHash, height: u32) -> Result<(), ChainError> {
+        if let Some((&passed, _)) = self.checkpoints.range(..=self.height()).next_back() {
+            if height < passed {
+                return Err(ChainError::ForkBeforeCheckpoint(passed));
+            }
+        }
+
+        if let Some((&checkpoint_height, checkpoint)) = self.checkpoints.range(..=height).next_back() {
+            let ancestor = match checkpoint_height == height {
+                true => hash,
+                false => &self.ancestor(parent, checkpoint_height).hash,
+            };
+
+            if ancestor != checkpoint {
+                return Err(ChainError::CheckpointMismatch(checkpoint_height));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Makes the chain ending at `hash` the active one.
+    fn activate(&mut self, hash: BlockHash) -> ChainUpdate {
+        let mut connected = Vec::new();
+        let mut entry = &self.entries[&hash];
+
+        while self.active.get(entry.height as usize) != Some(&entry.hash) {
+            connected.push(entry.hash);
+            entry = &self.entries[&entry.header.prev_blockhash];
+        }
+
+        let mut disconnected = self.active.split_off(entry.height as usize + 1);
+        disconnected.reverse();
+        connected.reverse();
+
+        self.active.extend(&connected);
+
+        match disconnected.is_empty() {
+            true => ChainUpdate::Extended,
+            false => ChainUpdate::Reorganized { disconnected, connected },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{block::{error::BlockError, header::BlockHeader}, cryptography::hash::digest::{BlockHash, TxMerkleNode}, network::Network};
+    use super::{error::ChainError, ChainUpdate, HeaderChain};
+
+    /// A regtest header on top of `prev`, ten minutes later. `tag` tells
+    ///  apart headers of different forks.
+    fn mine(prev: &BlockHeader, tag: u8) -> BlockHeader {
+        let mut header = BlockHeader {
+            version: 4,
+            prev_blockhash: prev.block_hash(),
+            merkle_root: TxMerkleNode::from_byte_array([tag; 32]),
+            time: prev.time + 600,
+            bits: 0x207fffff,
+            nonce: 0,
+        };
+
+        while header.validate_pow(&Network::Regtest.params().consensus).is_err() {
+            header.nonce += 1;
+        }
+
+        header
+    }
+
+    /// `count` headers on top of `prev`.
+    fn mine_many(prev: &BlockHeader, count: usize, tag: u8) -> Vec<BlockHeader> {
+        let mut headers: Vec<BlockHeader> = Vec::new();
+
+        for _ in 0..count {
+            headers.push(mine(headers.last().unwrap_or(prev), tag));
+        }
+
+        headers
+    }
+
+    fn hashes(headers: &[BlockHeader]) -> Vec<BlockHash> {
+        headers.iter().map(|header| header.block_hash()).collect()
+    }
+
+    #[test]
+    pub fn test_extend_and_reorganize() {
+        let mut chain = HeaderChain::new(Network::Regtest);
+        let genesis = chain.tip().header;
+
+        assert_eq!(chain.height(), 0);
+        assert_eq!(chain.tip().hash, Network::Regtest.genesis_block_hash());
+
+        let main = mine_many(&genesis, 5, 1);
+
+        for header in &main {
+            assert_eq!(chain.accept_header(*header).unwrap(), ChainUpdate::Extended);
+        }
+
+        assert_eq!(chain.accept_header(main[2]).unwrap(), ChainUpdate::Duplicate);
+        assert_eq!(chain.height(), 5);
+        assert_eq!(chain.tip().chainwork, genesis.work() * 6u32);
+
+        // A fork from height 2 only takes over once it has more work.
+        let fork = mine_many(&main[1], 4, 2);
+
+        for header in &fork[..3] {
+            assert_eq!(chain.accept_header(*header).unwrap(), ChainUpdate::SideChain);
+        }
+
+        assert_eq!(chain.tip().hash, main[4].block_hash());
+        assert!(!chain.is_active(&fork[0].block_hash()));
+        assert_eq!(chain.get(&fork[2].block_hash()).unwrap().height, 5);
+
+        assert_eq!(chain.accept_header(fork[3]).unwrap(), ChainUpdate::Reorganized {
+            disconnected: hashes(&[main[4], main[3], main[2]]),
+            connected: hashes(&fork),
+        });
+
+        assert_eq!(chain.height(), 6);
+        assert_eq!(chain.get_at(2).unwrap().hash, main[1].block_hash());
+        assert_eq!(chain.get_at(3).unwrap().hash, fork[0].block_hash());
+        assert!(chain.is_active(&main[1].block_hash()));
+        assert!(!chain.is_active(&main[2].block_hash()));
+
+        // The old chain comes back when it overtakes again.
+        let more = mine_many(&main[4], 2, 1);
+        assert_eq!(chain.accept_header(more[0]).unwrap(), ChainUpdate::SideChain);
+        assert_eq!(chain.accept_header(more[1]).unwrap(), ChainUpdate::Reorganized {
+            disconnected: hashes(&[fork[3], fork[2], fork[1], fork[0]]),
+            connected: hashes(&[main[2], main[3], main[4], more[0], more[1]]),
+        });
+    }
+
+    #[test]
+    pub fn test_invalid_headers() {
+        let mut chain = HeaderChain::new(Network::Regtest);
+        let genesis = chain.tip().header;
+        let headers = mine_many(&genesis, 11, 1);
+
+        for header in &headers {
+            chain.accept_header(*header).unwrap();
+        }
+
+        let tip = &headers[10];
+
+        let orphan = mine(&mine(tip, 2), 2);
+        assert!(matches!(chain.accept_header(orphan), Err(ChainError::UnknownParent(hash)) if hash == orphan.prev_blockhash));
+
+        let mut unmined = mine(tip, 2);
+        unmined.nonce += 1;
+        while unmined.validate_pow(&Network::Regtest.params().consensus).is_ok() {
+            unmined.nonce += 1;
+        }
+        assert!(matches!(chain.accept_header(unmined), Err(ChainError::Block(BlockError::HighHash(_)))));
+
+        // A harder target than required is still wrong.
+        let mut harder = mine(tip, 2);
+        harder.bits = 0x207ffffe;
+        while harder.validate_pow(&Network::Regtest.params().consensus).is_err() {
+            harder.nonce += 1;
+        }
+        assert!(matches!(chain.accept_header(harder), Err(ChainError::BadDifficultyBits(0x207fffff, 0x207ffffe))));
+
+        // The median of the last eleven timestamps is that of headers[5].
+        let mut early = mine(tip, 2);
+        early.time = headers[5].time;
+        while early.validate_pow(&Network::Regtest.params().consensus).is_err() {
+            early.nonce += 1;
+        }
+        assert!(matches!(chain.accept_header(early), Err(ChainError::TimeTooOld(time)) if time == headers[5].time));
+
+        early.time += 1;
+        while early.validate_pow(&Network::Regtest.params().consensus).is_err() {
+            early.nonce += 1;
+        }
+        assert_eq!(chain.accept_header(early).unwrap(), ChainUpdate::Extended);
+    }
+
+    #[test]
+    pub fn test_timewarp() {
+        let mut chain = HeaderChain::new(Network::Regtest);
+        let genesis = chain.tip().header;
+
+        // BIP94 with retarget periods of four blocks.
+        chain.params.enforce_bip94 = true;
+        chain.params.pow_target_timespan = 4 * chain.params.pow_target_spacing;
+
+        let headers = mine_many(&genesis, 7, 1);
+
+        for header in &headers {
+            chain.accept_header(*header).unwrap();
+        }
+
+        // Height 8 starts a period.
+        let warp = |offset: u32| {
+            let mut header = mine(&headers[6], 2);
+            header.time = headers[6].time - offset;
+            while header.validate_pow(&Network::Regtest.params().consensus).is_err() {
+                header.nonce += 1;
+            }
+            header
+        };
+
+        assert!(matches!(chain.accept_header(warp(601)), Err(ChainError::TimeWarp(time)) if time == headers[6].time));
+        assert_eq!(chain.accept_header(warp(600)).unwrap(), ChainUpdate::Extended);
+    }
+
+    #[test]
+    pub fn test_checkpoints() {
+        let mut chain = HeaderChain::new(Network::Regtest);
+        let genesis = chain.tip().header;
+        let main = mine_many(&genesis, 6, 1);
+        let fork = mine_many(&main[1], 4, 2);
+
+        chain.add_checkpoint(4, main[3].block_hash()).unwrap();
+
+        // Below the checkpoint a fork is fine until the chain passes it.
+        for header in &main[..3] {
+            chain.accept_header(*header).unwrap();
+        }
+
+        chain.accept_header(fork[0]).unwrap();
+        assert!(matches!(chain.accept_header(fork[1]), Err(ChainError::CheckpointMismatch(4))));
+
+        for header in &main[3..] {
+            chain.accept_header(*header).unwrap();
+        }
+
+        assert!(matches!(chain.accept_header(mine(&main[0], 3)), Err(ChainError::ForkBeforeCheckpoint(4))));
+        assert!(matches!(chain.accept_header(mine(&main[3], 3)), Ok(ChainUpdate::SideChain)));
+
+        // The active chain has to agree with a new checkpoint.
+        assert!(matches!(chain.add_checkpoint(5, fork[3].block_hash()), Err(ChainError::CheckpointMismatch(5))));
+        assert!(chain.add_checkpoint(8, BlockHash::default()).is_ok());
+    }
+
+    #[test]
+    pub fn test_locator() {
+        let mut chain = HeaderChain::new(Network::Regtest);
+        let genesis = chain.tip().header;
+        let headers = mine_many(&genesis, 30, 1);
+
+        assert_eq!(chain.locator(), vec![genesis.block_hash()]);
+
+        for header in &headers {
+            chain.accept_header(*header).unwrap();
+        }
+
+        // Ten steps of one, then doubling, down to genesis.
+        let heights = [30, 29, 28, 27, 26, 25, 24, 23, 22, 21, 20, 19, 17, 13, 5, 0];
+        let expected: Vec<BlockHash> = heights.iter().map(|height| chain.get_at(*height).unwrap().hash).collect();
+        assert_eq!(chain.locator(), expected);
+
+        // A peer on a fork from height 12 is sent headers from the highest
+        //  height its locator shares with us.
+        let mut peer = HeaderChain::new(Network::Regtest);
+        for header in headers[..12].iter().chain(&mine_many(&headers[11], 20, 2)) {
+            peer.accept_header(*header).unwrap();
+        }
+
+        assert_eq!(chain.find_fork(&peer.locator()).height, 7);
+        assert_eq!(chain.find_fork(&[BlockHash::default()]).height, 0);
+    }
+
+    #[test]
+    pub fn test_persistence() {
+        let mut chain = HeaderChain::new(Network::Regtest);
+        let genesis = chain.tip().header;
+        let main = mine_many(&genesis, 8, 1);
+
+        for header in main.iter().chain(&mine_many(&main[3], 2, 2)) {
+            chain.accept_header(*header).unwrap();
+        }
+
+        let path = std::env::temp_dir().join(format!("headers-{}.dat", std::process::id()));
+        chain.save(&path).unwrap();
+
+        let loaded = HeaderChain::load(Network::Regtest, &path).unwrap();
+        assert_eq!(loaded.tip(), chain.tip());
+        assert_eq!(loaded.entries, chain.entries);
+        assert_eq!(loaded.active, chain.active);
+
+        // Forks as long as the active chain, whichever way the hashes sort,
+        //  stay inactive.
+        for tag in 3..8 {
+            for header in mine_many(&main[tag as usize - 3], 10 - tag as usize, tag) {
+                assert_eq!(chain.accept_header(header).unwrap(), ChainUpdate::SideChain);
+            }
+        }
+
+        chain.save(&path).unwrap();
+
+        let loaded = HeaderChain::load(Network::Regtest, &path).unwrap();
+        assert_eq!(loaded.tip(), chain.tip());
+        assert_eq!(loaded.entries, chain.entries);
+        assert_eq!(loaded.active, chain.active);
+
+        assert!(matches!(HeaderChain::load(Network::Testnet4, &path), Err(ChainError::WrongNetwork([0xfa, 0xbf, 0xb5, 0xda]))));
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(HeaderChain::load(Network::Regtest, &path), Err(ChainError::Io(_))));
+    }
+}
@@ -0,0 +1,47 @@
+use std::{error::Error, fmt::Display};
+use crate::cryptography::hash::digest::BlockHash;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum BlockError {
+    InvalidTarget(u32),
+    HighHash(BlockHash),
+    NoTransactions,
+    NoCoinbase,
+    MultipleCoinbases(usize),
+    MerkleRootMismatch,
+    DuplicateTransactions,
+    ExcessiveWeight(usize),
+    ExcessiveSigops(usize),
+    BadCoinbaseHeight(u32),
+    InvalidWitnessNonce,
+    WitnessCommitmentMismatch,
+    UnexpectedWitness(usize),
+    TooManyTransactions(u32),
+    MalformedPartialMerkleTree,
+    MatchesLengthMismatch(usize, usize),
+}
+
+impl Error for BlockError { }
+
+impl Display for BlockError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BlockError::InvalidTarget(bits) => write!(f, "BlockError::InvalidTarget({:#010x} is negative, zero or above the network's limit)", bits),
+            BlockError::HighHash(hash) => write!(f, "BlockError::HighHash({} is above its target)", hash),
+            BlockError::NoTransactions => write!(f, "BlockError::NoTransactions"),
+            BlockError::NoCoinbase => write!(f, "BlockError::NoCoinbase(first transaction is not a coinbase)"),
+            BlockError::MultipleCoinbases(index) => write!(f, "BlockError::MultipleCoinbases(transaction {} is a coinbase too)", index),
+            BlockError::MerkleRootMismatch => write!(f, "BlockError::MerkleRootMismatch"),
+            BlockError::DuplicateTransactions => write!(f, "BlockError::DuplicateTransactions(merkle tree repeats transactions, see CVE-2012-2459)"),
+            BlockError::ExcessiveWeight(weight) => write!(f, "BlockError::ExcessiveWeight({})", weight),
+            BlockError::ExcessiveSigops(cost) => write!(f, "BlockError::ExcessiveSigops({})", cost),
+            BlockError::BadCoinbaseHeight(height) => write!(f, "BlockError::BadCoinbaseHeight(coinbase doesn't start with height {})", height),
+            BlockError::InvalidWitnessNonce => write!(f, "BlockError::InvalidWitnessNonce(coinbase witness is not a single 32 byte item)"),
+            BlockError::WitnessCommitmentMismatch => write!(f, "BlockError::WitnessCommitmentMismatch"),
+            BlockError::UnexpectedWitness(index) => write!(f, "BlockError::UnexpectedWitness(transaction {} has a witness the block doesn't commit to)", index),
+            BlockError::TooManyTransactions(count) => write!(f, "BlockError::TooManyTransactions({} can't fit in a block)", count),
+            BlockError::MalformedPartialMerkleTree => write!(f, "BlockError::MalformedPartialMerkleTree"),
+            BlockError::MatchesLengthMismatch(matches, txids) => write!(f, "BlockError::MatchesLengthMismatch({} matches for {} transactions)", matches, txids),
+        }
+    }
+}
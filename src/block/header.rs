@@ -0,0 +1,127 @@
+use num_bigint::BigUint;
+use crate::{cryptography::hash::digest::{BlockHash, TxMerkleNode}, network::params::ConsensusParams, serialization::consensus::serialize::SerializeConsensus};
+use super::{error::BlockError, pow::{difficulty, target_from_bits, work_from_bits}};
+
+pub const HEADER_SIZE: usize = 80;
+
+/// The 80 bytes a block's proof of work commits to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlockHeader {
+    pub version: i32,
+    pub prev_blockhash: BlockHash,
+    pub merkle_root: TxMerkleNode,
+    pub time: u32,
+    /// The target in compact form, see [`target_from_bits`].
+    pub bits: u32,
+    pub nonce: u32,
+}
+
+impl BlockHeader {
+    pub fn block_hash(&self) -> BlockHash {
+        BlockHash::hash(&self.serialize_consensus())
+    }
+
+    /// `None` when `bits` is negative or overflows.
+    pub fn target(&self) -> Option<BigUint> {
+        target_from_bits(self.bits)
+    }
+
+    /// This header's contribution to its chain's total work.
+    pub fn work(&self) -> BigUint {
+        work_from_bits(self.bits)
+    }
+
+    pub fn difficulty(&self) -> f64 {
+        difficulty(self.bits)
+    }
+
+    /// Core's `CheckProofOfWork`: the target has to be positive and no
+    ///  easier than the network allows, and the hash, read as a little
+    ///  endian number, no greater than it. Whether `bits` is the value the
+    ///  chain requires at this height is a separate, contextual check.
+    pub fn validate_pow(&self, params: &ConsensusParams) -> Result<(), BlockError> {
+        let target = self
+            .target()
+            .filter(|target| target.bits() > 0 && *target <= params.pow_limit())
+            .ok_or(BlockError::InvalidTarget(self.bits))?;
+
+        let hash = self.block_hash();
+
+        if BigUint::from_bytes_le(hash.as_bytes()) > target {
+            return Err(BlockError::HighHash(hash));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigUint;
+    use crate::{block::error::BlockError, network::Network, serialization::consensus::{deserialize::DeserializeConsensus, serialize::SerializeConsensus}};
+    use super::{BlockHeader, HEADER_SIZE};
+
+    // An early mainnet block, 00000000b0c5a240b2a61d2e75692224efd4cbecdf6eaf4cc2cf477ca7c270e7.
+    const EARLY_BLOCK: &str = "010000004ddccd549d28f385ab457e98d1b11ce80bfea2c5ab93015ade4973e400000000bf4473e53794beae34e64fccc471dace6ae544180816f89591894e0f417a914cd74d6e49ffff001d323b3a7b";
+    // A mainnet block from 2021, with version bits set.
+    const RECENT_BLOCK: &str = "04e0ff3f5f3b2119529dc4013ff0f427a457bed706e7b5b8dec30900000000000000000022906797f7ab258c0f896c35b2d6725c0554f579af99754b241aec8c76727d4091a45561ebd00e17e3d6da41";
+
+    #[test]
+    pub fn test_genesis_headers() {
+        for network in Network::ALL {
+            let params = network.params();
+            let bytes = hex::decode(params.genesis_header).unwrap();
+            let header = BlockHeader::deserialize_consensus(&bytes).unwrap();
+
+            assert_eq!(header.serialize_consensus(), bytes);
+            assert_eq!(header.block_hash(), network.genesis_block_hash());
+            assert_eq!(header.bits, params.consensus.pow_limit_bits);
+            assert!(header.validate_pow(&params.consensus).is_ok());
+        }
+    }
+
+    #[test]
+    pub fn test_mainnet_headers() {
+        let params = &Network::Mainnet.params().consensus;
+
+        let bytes = hex::decode(EARLY_BLOCK).unwrap();
+        let header = BlockHeader::deserialize_consensus(&bytes).unwrap();
+
+        assert_eq!(bytes.len(), HEADER_SIZE);
+        assert_eq!(header.version, 1);
+        assert_eq!(header.prev_blockhash.to_string(), "00000000e47349de5a0193abc5a2fe0be81cb1d1987e45ab85f3289d54cddc4d");
+        assert_eq!(header.merkle_root.to_string(), "4c917a410f4e899195f816081844e56aceda71c4cc4fe634aebe9437e57344bf");
+        assert_eq!(header.time, 1231965655);
+        assert_eq!(header.bits, 0x1d00ffff);
+        assert_eq!(header.nonce, 2067413810);
+        assert_eq!(header.block_hash().to_string(), "00000000b0c5a240b2a61d2e75692224efd4cbecdf6eaf4cc2cf477ca7c270e7");
+        assert_eq!(header.work(), BigUint::from(0x100010001u64));
+        assert_eq!(header.difficulty(), 1.0);
+        assert!(header.validate_pow(params).is_ok());
+
+        let bytes = hex::decode(RECENT_BLOCK).unwrap();
+        let header = BlockHeader::deserialize_consensus(&bytes).unwrap();
+
+        assert_eq!(header.serialize_consensus(), bytes);
+        assert_eq!(header.version, 0x3fffe004);
+        assert_eq!(header.bits, 0x170ed0eb);
+        assert_eq!(header.block_hash().to_string(), "000000000000000000000c835b2adcaedc20fdf6ee440009c249452c726dafae");
+        assert_eq!(header.difficulty().round(), 18997641161759.0);
+        assert!(header.validate_pow(params).is_ok());
+
+        // Any change to the header spoils the proof of work.
+        let mut tampered = header;
+        tampered.nonce += 1;
+        assert_eq!(tampered.validate_pow(params), Err(BlockError::HighHash(tampered.block_hash())));
+
+        // Targets have to be positive and within the network's limit.
+        for bits in [0x1d010000, 0x1e00ffff, 0x00000000, 0x04923456] {
+            let mut header = header;
+            header.bits = bits;
+
+            assert_eq!(header.validate_pow(params), Err(BlockError::InvalidTarget(bits)));
+        }
+
+        assert!(BlockHeader::deserialize_consensus(&bytes[..79]).is_err());
+    }
+}
@@ -0,0 +1,120 @@
+use crate::cryptography::hash::hash256;
+
+/// Hash of two sibling nodes, in internal byte order.
+pub fn merkle_parent(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut data = [0u8; 64];
+    data[..32].copy_from_slice(left);
+    data[32..].copy_from_slice(right);
+
+    hash256(&data)
+}
+
+/// Core's `ComputeMerkleRoot`: hashes are paired up level by level, a
+///  level with an odd count pairing its last hash with itself. Also tells
+///  whether any level had two identical hashes side by side, which is how
+///  CVE-2012-2459 mutates a block without changing its root: repeating the
+///  transactions at the end of an odd level. An empty list has a zero root.
+pub fn merkle_root(mut hashes: Vec<[u8; 32]>) -> ([u8; 32], bool) {
+    if hashes.is_empty() {
+        return ([0u8; 32], false);
+    }
+
+    let mut mutated = false;
+
+    while hashes.len() > 1 {
+        mutated |= hashes.chunks_exact(2).any(|pair| pair[0] == pair[1]);
+
+        if !hashes.len().is_multiple_of(2) {
+            hashes.push(*hashes.last().unwrap());
+        }
+
+        hashes = hashes.chunks_exact(2).map(|pair| merkle_parent(&pair[0], &pair[1])).collect();
+    }
+
+    (hashes[0], mutated)
+}
+
+/// The siblings on the path from leaf `index` to the root, bottom up: an
+///  inclusion proof for that leaf, as Electrum servers hand out.
+pub fn merkle_branch(mut hashes: Vec<[u8; 32]>, mut index: usize) -> Vec<[u8; 32]> {
+    let mut branch = Vec::new();
+
+    while hashes.len() > 1 {
+        if !hashes.len().is_multiple_of(2) {
+            hashes.push(*hashes.last().unwrap());
+        }
+
+        branch.push(hashes[index ^ 1]);
+        hashes = hashes.chunks_exact(2).map(|pair| merkle_parent(&pair[0], &pair[1])).collect();
+        index >>= 1;
+    }
+
+    branch
+}
+
+/// Core's `ComputeMerkleRootFromBranch`: the root `leaf` hashes up to along
+///  `branch` from position `index`. A proof checks out when this is the
+///  root in the block's header.
+pub fn merkle_root_from_branch(leaf: &[u8; 32], branch: &[[u8; 32]], mut index: usize) -> [u8; 32] {
+    let mut hash = *leaf;
+
+    for sibling in branch {
+        hash = match index & 1 {
+            0 => merkle_parent(&hash, sibling),
+            _ => merkle_parent(sibling, &hash),
+        };
+
+        index >>= 1;
+    }
+
+    hash
+}
+
+#[cfg(test)]
+mod test {
+    use super::{merkle_branch, merkle_parent, merkle_root, merkle_root_from_branch};
+
+    #[test]
+    pub fn test_merkle_root() {
+        let leaves: Vec<[u8; 32]> = (1..=5).map(|i| [i; 32]).collect();
+
+        assert_eq!(merkle_root(vec![]), ([0u8; 32], false));
+        assert_eq!(merkle_root(leaves[..1].to_vec()), (leaves[0], false));
+
+        let ab = merkle_parent(&leaves[0], &leaves[1]);
+        let cc = merkle_parent(&leaves[2], &leaves[2]);
+        let abcc = merkle_parent(&ab, &cc);
+        assert_eq!(merkle_root(leaves[..3].to_vec()), (abcc, false));
+
+        // Repeating the odd one out gives the same root, but is caught.
+        let repeated = vec![leaves[0], leaves[1], leaves[2], leaves[2]];
+        assert_eq!(merkle_root(repeated), (abcc, true));
+
+        // So is a repeated pair, which first shows higher up the tree.
+        let leaves: Vec<[u8; 32]> = (1..=6).map(|i| [i; 32]).collect();
+        let (root, mutated) = merkle_root(leaves.clone());
+        assert!(!mutated);
+        assert_eq!(merkle_root([&leaves[..], &leaves[4..]].concat()), (root, true));
+    }
+
+    #[test]
+    pub fn test_merkle_branch() {
+        for count in 1..=9 {
+            let leaves: Vec<[u8; 32]> = (0..count).map(|i| [i; 32]).collect();
+            let (root, _) = merkle_root(leaves.clone());
+
+            for (index, leaf) in leaves.iter().enumerate() {
+                let branch = merkle_branch(leaves.clone(), index);
+
+                assert_eq!(branch.len(), (count as usize).next_power_of_two().trailing_zeros() as usize);
+                assert_eq!(merkle_root_from_branch(leaf, &branch, index), root);
+
+                // The branch proves this position only, unless the leaf is
+                //  the odd one out and so its own sibling.
+                if index ^ 1 < count as usize {
+                    assert_ne!(merkle_root_from_branch(leaf, &branch, index ^ 1), root);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,341 @@
+use crate::{cryptography::hash::digest::{TxMerkleNode, Txid}, transaction::WITNESS_SCALE_FACTOR};
+use super::{error::BlockError, header::BlockHeader, merkle::merkle_parent, Block, MAX_BLOCK_WEIGHT};
+
+/// Weight of the smallest transaction there can be, which bounds how many
+///  a block holds.
+const MIN_TRANSACTION_WEIGHT: usize = WITNESS_SCALE_FACTOR * 60;
+
+/// BIP37's `merkleblock`, also what Core's `gettxoutproof` returns: a
+///  header and the part of its merkle tree proving which transactions
+///  matched.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleBlock {
+    pub header: BlockHeader,
+    pub txn: PartialMerkleTree,
+}
+
+impl MerkleBlock {
+    /// Proof for the transactions of `block` for which `matches` is true.
+    ///  A block without transactions has no merkle tree to prove anything
+    ///  with.
+    pub fn from_block(block: &Block, matches: impl Fn(&Txid) -> bool) -> Result<Self, BlockError> {
+        let txids: Vec<Txid> = block.transactions.iter().map(|tx| tx.txid()).collect();
+        let flags: Vec<bool> = txids.iter().map(matches).collect();
+
+        Ok(Self {
+            header: block.header,
+            txn: PartialMerkleTree::from_txids(&txids, &flags)?,
+        })
+    }
+
+    /// What an SPV client checks, like Core's `verifytxoutproof`: the tree
+    ///  has to be well formed and hash up to the header's merkle root. Gives
+    ///  the position and txid of every matched transaction. Whether the
+    ///  header is in the best chain is for the caller to check.
+    pub fn verify(&self) -> Result<Vec<(u32, Txid)>, BlockError> {
+        let (root, matches) = self.txn.extract_matches()?;
+
+        if root != self.header.merkle_root {
+            return Err(BlockError::MerkleRootMismatch);
+        }
+
+        Ok(matches)
+    }
+}
+
+/// Core's `CPartialMerkleTree`. Walking the tree depth first, each node
+///  gets a bit saying whether any matched transaction is below it. The
+///  hashes of nodes without one, and of matched leaves, are all that is
+///  needed to rebuild the root.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PartialMerkleTree {
+    pub transaction_count: u32,
+    pub hashes: Vec<TxMerkleNode>,
+    /// Serialized as bytes, so padded with zeros to a multiple of eight.
+    pub bits: Vec<bool>,
+}
+
+impl PartialMerkleTree {
+    /// `matches` says for each of `txids` whether to prove it's included.
+    pub fn from_txids(txids: &[Txid], matches: &[bool]) -> Result<Self, BlockError> {
+        if txids.is_empty() {
+            return Err(BlockError::NoTransactions);
+        }
+
+        if txids.len() != matches.len() {
+            return Err(BlockError::MatchesLengthMismatch(matches.len(), txids.len()));
+        }
+
+        let mut tree = Self {
+            transaction_count: txids.len() as u32,
+            hashes: Vec::new(),
+            bits: Vec::new(),
+        };
+
+        tree.build(tree.height(), 0, txids, matches);
+        tree.bits.resize(tree.bits.len().div_ceil(8) * 8, false);
+
+        Ok(tree)
+    }
+
+    /// The merkle root and the matched transactions with their positions,
+    ///  following Core's `ExtractMatches`. The tree is rejected when it
+    ///  leaves any bit or hash unused, and when two siblings are equal,
+    ///  which only a CVE-2012-2459 mutation makes them.
+    pub fn extract_matches(&self) -> Result<(TxMerkleNode, Vec<(u32, Txid)>), BlockError> {
+        if self.transaction_count == 0 {
+            return Err(BlockError::NoTransactions);
+        }
+
+        if self.transaction_count as usize > MAX_BLOCK_WEIGHT / MIN_TRANSACTION_WEIGHT {
+            return Err(BlockError::TooManyTransactions(self.transaction_count));
+        }
+
+        if self.hashes.len() > self.transaction_count as usize || self.bits.len() < self.hashes.len() {
+            return Err(BlockError::MalformedPartialMerkleTree);
+        }
+
+        let mut bits_used = 0;
+        let mut hashes_used = 0;
+        let mut matches = Vec::new();
+
+        let root = self.traverse(self.height(), 0, &mut bits_used, &mut hashes_used, &mut matches)?;
+
+        if bits_used.div_ceil(8) != self.bits.len().div_ceil(8) || hashes_used != self.hashes.len() {
+            return Err(BlockError::MalformedPartialMerkleTree);
+        }
+
+        Ok((TxMerkleNode::from_byte_array(root), matches))
+    }
+
+    /// Nodes at `height` above the leaves.
+    fn width(&self, height: u32) -> u32 {
+        ((self.transaction_count as u64 + (1 << height) - 1) >> height) as u32
+    }
+
+    fn height(&self) -> u32 {
+        let mut height = 0;
+
+        while self.width(height) > 1 {
+            height += 1;
+        }
+
+        height
+    }
+
+    fn hash(&self, height: u32, position: u32, txids: &[Txid]) -> [u8; 32] {
+        if height == 0 {
+            return txids[position as usize].to_byte_array();
+        }
+
+        let left = self.hash(height - 1, position * 2, txids);
+
+        // A node without a right child is paired with itself.
+        let right = match position * 2 + 1 < self.width(height - 1) {
+            true => self.hash(height - 1, position * 2 + 1, txids),
+            false => left,
+        };
+
+        merkle_parent(&left, &right)
+    }
+
+    fn build(&mut self, height: u32, position: u32, txids: &[Txid], matches: &[bool]) {
+        let start = (position as usize) << height;
+        let end = (((position + 1) as usize) << height).min(txids.len());
+        let parent_of_match = matches[start..end].iter().any(|matched| *matched);
+
+        self.bits.push(parent_of_match);
+
+        if height == 0 || !parent_of_match {
+            let hash = self.hash(height, position, txids);
+            self.hashes.push(TxMerkleNode::from_byte_array(hash));
+            return;
+        }
+
+        self.build(height - 1, position * 2, txids, matches);
+
+        if position * 2 + 1 < self.width(height - 1) {
+            self.build(height - 1, position * 2 + 1, txids, matches);
+        }
+    }
+
+    fn traverse(&self, height: u32, position: u32, bits_used: &mut usize, hashes_used: &mut usize, matches: &mut Vec<(u32, Txid)>) -> Result<[u8; 32], BlockError> {
+        let parent_of_match = *self.bits.get(*bits_used).ok_or(BlockError::MalformedPartialMerkleTree)?;
+        *bits_used += 1;
+
+        if height == 0 || !parent_of_match {
+            let hash = self.hashes.get(*hashes_used).ok_or(BlockError::MalformedPartialMerkleTree)?.to_byte_array();
+            *hashes_used += 1;
+
+            if height == 0 && parent_of_match {
+                matches.push((position, Txid::from_byte_array(hash)));
+            }
+
+            return Ok(hash);
+        }
+
+        let left = self.traverse(height - 1, position * 2, bits_used, hashes_used, matches)?;
+
+        let right = match position * 2 + 1 < self.width(height - 1) {
+            true => self.traverse(height - 1, position * 2 + 1, bits_used, hashes_used, matches)?,
+            false => left,
+        };
+
+        // An explicit right child equal to the left can only come from
+        //  repeated transactions.
+        if position * 2 + 1 < self.width(height - 1) && right == left {
+            return Err(BlockError::DuplicateTransactions);
+        }
+
+        Ok(merkle_parent(&left, &right))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use crate::{block::{error::BlockError, merkle::merkle_root, Block}, cryptography::hash::digest::{TxMerkleNode, Txid}, serialization::consensus::{deserialize::DeserializeConsensus, serialize::SerializeConsensus}};
+    use super::{MerkleBlock, PartialMerkleTree};
+
+    const BLOCKS: &str = include_str!("../../test_data/blocks.txt");
+
+    /// Block 0000000000013b8a…, nine transactions.
+    fn block() -> Block {
+        let line = BLOCKS.lines().find(|line| line.contains("0000000000013b8ab2cd513b0261a14096412195a72a0c4827d229dcc7e0f7af")).unwrap();
+
+        Block::deserialize_consensus(&hex::decode(line.split(' ').nth(3).unwrap()).unwrap()).unwrap()
+    }
+
+    fn txid(s: &str) -> Txid {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    pub fn test_gettxoutproof() {
+        // Bitcoin Core's `gettxoutproof` for one transaction of block 13b8a.
+        let proof = hex::decode("0100000090f0a9f110702f808219ebea1173056042a714bad51b916cb6800000000000005275289558f51c9966699404ae2294730c3c9f9bda53523ce50e9b95e558da2fdb261b4d4c86041b1ab1bf930900000005fac7708a6e81b2a986dea60db2663840ed141130848162eb1bd1dee54f309a1b2ee1e12587e497ada70d9bd10d31e83f0a924825b96cb8d04e8936d793fb60db7ad8b910d0c7ba2369bc7f18bb53d80e1869ba2c32274996cebe1ae264bc0e2289189ff0316cdc10511da71da757e553cada9f3b5b1434f3923673adb57d83caac392c38af156d6fc30b55fad4112df2b95531e68114e9ad10011e72f7b7cfdb025700").unwrap();
+        let merkle_block = MerkleBlock::deserialize_consensus(&proof).unwrap();
+
+        assert_eq!(merkle_block.serialize_consensus(), proof);
+        assert_eq!(merkle_block.header, block().header);
+        assert_eq!(merkle_block.txn.transaction_count, 9);
+        assert_eq!(merkle_block.verify().unwrap(), vec![(3, txid("220ebc64e21abece964927322cba69180ed853bb187fbc6923bac7d010b9d87a"))]);
+
+        // Building it from the block gives the same bytes.
+        let rebuilt = MerkleBlock::from_block(&block(), |txid| txid.to_string().starts_with("220ebc64")).unwrap();
+        assert_eq!(rebuilt, merkle_block);
+
+        // And a two transaction block's, proving the second.
+        let proof = hex::decode("01000000ba8b9cda965dd8e536670f9ddec10e53aab14b20bacad27b9137190000000000190760b278fe7b8565fda3b968b918d5fd997f993b23674c0af3b6fde300b38f33a5914ce6ed5b1b01e32f570200000002252bf9d75c4f481ebb6278d708257d1f12beb6dd30301d26c623f789b2ba6fc0e2d32adb5f8ca820731dff234a84e78ec30bce4ec69dbd562d0b2b8266bf4e5a0105").unwrap();
+        let merkle_block = MerkleBlock::deserialize_consensus(&proof).unwrap();
+
+        assert_eq!(merkle_block.header.block_hash().to_string(), "000000000043a8c0fd1d6f726790caa2a406010d19efd2780db27bdbbd93baf6");
+        assert_eq!(merkle_block.verify().unwrap(), vec![(1, txid("5a4ebf66822b0b2d56bd9dc64ece0bc38ee7844a23ff1d7320a88c5fdb2ad3e2"))]);
+
+        // A proof for some other header fails.
+        let mut other = merkle_block.clone();
+        other.header.merkle_root = TxMerkleNode::default();
+        assert_eq!(other.verify(), Err(BlockError::MerkleRootMismatch));
+    }
+
+    #[test]
+    pub fn test_from_block() {
+        let block = block();
+        let wanted = [txid("74d681e0e03bafa802c8aa084379aa98d9fcd632ddc2ed9782b586ec87451f20"), txid("f9fc751cb7dc372406a9f8d738d5e6f8f63bab71986a39cf36ee70ee17036d07")];
+
+        let merkle_block = MerkleBlock::from_block(&block, |txid| wanted.contains(txid)).unwrap();
+        let bytes = merkle_block.serialize_consensus();
+
+        assert_eq!(MerkleBlock::deserialize_consensus(&bytes).unwrap(), merkle_block);
+        assert_eq!(merkle_block.verify().unwrap(), vec![(1, wanted[1]), (8, wanted[0])]);
+
+        // Nothing matched leaves only the root.
+        let merkle_block = MerkleBlock::from_block(&block, |_| false).unwrap();
+        assert_eq!(merkle_block.txn.hashes, vec![block.header.merkle_root]);
+        assert_eq!(merkle_block.verify().unwrap(), vec![]);
+
+        let mut empty = block.clone();
+        empty.transactions.clear();
+        assert_eq!(MerkleBlock::from_block(&empty, |_| true), Err(BlockError::NoTransactions));
+    }
+
+    #[test]
+    pub fn test_partial_merkle_trees() {
+        // Bitcoin Core's pmt_tests: every size of tree, matching every
+        //  transaction with a few probabilities.
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for count in [1, 4, 7, 17, 56, 100, 127, 256, 312, 513, 1000, 4095] {
+            let txids: Vec<Txid> = (0..count as u32).map(|i| Txid::hash(&i.to_le_bytes())).collect();
+            let (root, _) = merkle_root(txids.iter().map(|txid| txid.to_byte_array()).collect());
+
+            for attempt in 1..15 {
+                let matches: Vec<bool> = (0..count).map(|_| rng.gen_ratio(1, 1 << (attempt / 2))).collect();
+                let expected: Vec<(u32, Txid)> = (0..count).filter(|i| matches[*i]).map(|i| (i as u32, txids[i])).collect();
+
+                let tree = PartialMerkleTree::from_txids(&txids, &matches).unwrap();
+                let bytes = tree.serialize_consensus();
+                let tree = PartialMerkleTree::deserialize_consensus(&bytes).unwrap();
+
+                assert_eq!(tree.extract_matches().unwrap(), (TxMerkleNode::from_byte_array(root), expected.clone()));
+
+                // Damaging any hash changes the root.
+                for _ in 0..4 {
+                    let mut damaged = tree.clone();
+                    let index = rng.gen_range(0..damaged.hashes.len());
+                    let mut bytes = damaged.hashes[index].to_byte_array();
+                    bytes[rng.gen_range(0..32)] ^= 1 << rng.gen_range(0..8);
+                    damaged.hashes[index] = TxMerkleNode::from_byte_array(bytes);
+
+                    assert_ne!(damaged.extract_matches().map(|(root, _)| root), Ok(TxMerkleNode::from_byte_array(root)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_malleability() {
+        // Repeating the last two transactions keeps the root, so Core's
+        //  pmt_malleability test: proving them has to fail.
+        let txids: Vec<Txid> = [1u8, 2, 3, 4, 5, 6, 7, 8, 9, 10, 9, 10].iter().map(|i| Txid::from_byte_array([*i; 32])).collect();
+        let matches = [false, false, false, false, false, false, false, false, false, true, true, false];
+
+        let tree = PartialMerkleTree::from_txids(&txids, &matches).unwrap();
+        assert_eq!(tree.extract_matches(), Err(BlockError::DuplicateTransactions));
+    }
+
+    #[test]
+    pub fn test_malformed_trees() {
+        let txids: Vec<Txid> = (0..5u8).map(|i| Txid::from_byte_array([i; 32])).collect();
+        let tree = PartialMerkleTree::from_txids(&txids, &[false, true, false, false, true]).unwrap();
+        assert!(tree.extract_matches().is_ok());
+
+        assert_eq!(PartialMerkleTree::from_txids(&[], &[]), Err(BlockError::NoTransactions));
+        assert_eq!(PartialMerkleTree::from_txids(&txids, &[true]), Err(BlockError::MatchesLengthMismatch(1, 5)));
+
+        let mut empty = tree.clone();
+        empty.transaction_count = 0;
+        assert_eq!(empty.extract_matches(), Err(BlockError::NoTransactions));
+
+        let mut huge = tree.clone();
+        huge.transaction_count = 16_667;
+        assert_eq!(huge.extract_matches(), Err(BlockError::TooManyTransactions(16_667)));
+
+        // Running out of hashes or bits, or leaving some unused.
+        let mut short = tree.clone();
+        short.hashes.pop();
+        assert_eq!(short.extract_matches(), Err(BlockError::MalformedPartialMerkleTree));
+
+        let mut long = tree.clone();
+        long.hashes.push(TxMerkleNode::default());
+        assert_eq!(long.extract_matches(), Err(BlockError::MalformedPartialMerkleTree));
+
+        let mut truncated = tree.clone();
+        truncated.bits.truncate(3);
+        assert_eq!(truncated.extract_matches(), Err(BlockError::MalformedPartialMerkleTree));
+
+        let mut padded = tree.clone();
+        padded.bits.resize(24, false);
+        assert_eq!(padded.extract_matches(), Err(BlockError::MalformedPartialMerkleTree));
+    }
+}
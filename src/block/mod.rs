@@ -0,0 +1,359 @@
+use crate::{cryptography::hash::{digest::{BlockHash, TxMerkleNode}, hash256}, network::params::ConsensusParams, script::{interpreter::flags::VerifyFlags, Script}, serialization::consensus::{compact_size::compact_size_len, serialize::SerializeConsensus}, transaction::{sigops::{legacy_sigop_count, sigop_cost}, tx_out::TxOut, Transaction, WITNESS_SCALE_FACTOR}};
+use self::{error::BlockError, header::{BlockHeader, HEADER_SIZE}, merkle::merkle_root};
+
+pub mod chain;
+pub mod error;
+pub mod header;
+pub mod merkle;
+pub mod merkle_block;
+pub mod pow;
+
+/// BIP141's limit on block weight, which also bounds legacy size to 1MB.
+pub const MAX_BLOCK_WEIGHT: usize = 4_000_000;
+pub const MAX_BLOCK_SIGOPS_COST: usize = 80_000;
+
+/// BIP141: OP_RETURN, a 36 byte push and this tag start the coinbase output
+///  committing to the block's witnesses.
+const WITNESS_COMMITMENT_HEADER: [u8; 6] = [0x6a, 0x24, 0xaa, 0x21, 0xa9, 0xed];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Block {
+    pub header: BlockHeader,
+    pub transactions: Vec<Transaction>,
+}
+
+impl Block {
+    pub fn new(header: BlockHeader, transactions: Vec<Transaction>) -> Self {
+        Self {
+            header,
+            transactions,
+        }
+    }
+
+    pub fn block_hash(&self) -> BlockHash {
+        self.header.block_hash()
+    }
+
+    /// The first transaction, if it is a coinbase as it should be.
+    pub fn coinbase(&self) -> Option<&Transaction> {
+        self.transactions.first().filter(|tx| tx.is_coinbase())
+    }
+
+    /// The merkle root of the txids, and whether the tree was mutated by
+    ///  repeating transactions, see [`merkle_root`].
+    pub fn compute_merkle_root(&self) -> (TxMerkleNode, bool) {
+        let (root, mutated) = merkle_root(self.transactions.iter().map(|tx| tx.txid().to_byte_array()).collect());
+
+        (TxMerkleNode::from_byte_array(root), mutated)
+    }
+
+    /// BIP141: the merkle root of the wtxids, with the coinbase's taken as
+    ///  zero since it can't commit to itself.
+    pub fn compute_witness_root(&self) -> [u8; 32] {
+        let wtxids = self
+            .transactions
+            .iter()
+            .enumerate()
+            .map(|(i, tx)| if i == 0 { [0u8; 32] } else { tx.wtxid().to_byte_array() })
+            .collect();
+
+        merkle_root(wtxids).0
+    }
+
+    /// The witness commitment in the coinbase, from its last output that
+    ///  has one.
+    pub fn witness_commitment(&self) -> Option<[u8; 32]> {
+        self.coinbase()?
+            .outputs
+            .iter()
+            .rev()
+            .find(|output| output.script_pubkey.len() >= 38 && output.script_pubkey.starts_with(&WITNESS_COMMITMENT_HEADER))
+            .map(|output| output.script_pubkey[6..38].try_into().unwrap())
+    }
+
+    /// Size in bytes without witness data.
+    pub fn base_size(&self) -> usize {
+        HEADER_SIZE + compact_size_len(self.transactions.len() as u64) + self.transactions.iter().map(|tx| tx.base_size()).sum::<usize>()
+    }
+
+    pub fn total_size(&self) -> usize {
+        self.serialize_consensus().len()
+    }
+
+    pub fn weight(&self) -> usize {
+        self.base_size() * (WITNESS_SCALE_FACTOR - 1) + self.total_size()
+    }
+
+    /// The sigop cost of every transaction, see [`sigop_cost`].
+    ///  `prevouts[i]` are the outputs spent by transaction `i`, and may be
+    ///  empty for the coinbase.
+    pub fn sigop_cost(&self, prevouts: &[Vec<TxOut>], flags: VerifyFlags) -> usize {
+        self.transactions.iter().zip(prevouts).map(|(tx, prevouts)| sigop_cost(tx, prevouts, flags)).sum()
+    }
+
+    /// The limit on [`sigop_cost`](Self::sigop_cost), which unlike the
+    ///  legacy count [`check`](Self::check) makes needs the spent outputs.
+    pub fn check_sigop_cost(&self, prevouts: &[Vec<TxOut>], flags: VerifyFlags) -> Result<(), BlockError> {
+        let cost = self.sigop_cost(prevouts, flags);
+
+        if cost > MAX_BLOCK_SIGOPS_COST {
+            return Err(BlockError::ExcessiveSigops(cost));
+        }
+
+        Ok(())
+    }
+
+    /// Core's `CheckBlock`, the checks that need nothing but the block: its
+    ///  proof of work, its merkle root, its size without witnesses, a
+    ///  single coinbase in front and the legacy sigop count.
+    pub fn check(&self, params: &ConsensusParams) -> Result<(), BlockError> {
+        self.header.validate_pow(params)?;
+
+        if self.transactions.is_empty() {
+            return Err(BlockError::NoTransactions);
+        }
+
+        let (root, mutated) = self.compute_merkle_root();
+
+        if root != self.header.merkle_root {
+            return Err(BlockError::MerkleRootMismatch);
+        }
+
+        if mutated {
+            return Err(BlockError::DuplicateTransactions);
+        }
+
+        let base_weight = self.base_size() * WITNESS_SCALE_FACTOR;
+
+        if base_weight > MAX_BLOCK_WEIGHT {
+            return Err(BlockError::ExcessiveWeight(base_weight));
+        }
+
+        if self.coinbase().is_none() {
+            return Err(BlockError::NoCoinbase);
+        }
+
+        if let Some(index) = self.transactions.iter().skip(1).position(|tx| tx.is_coinbase()) {
+            return Err(BlockError::MultipleCoinbases(index + 1));
+        }
+
+        let legacy_cost = self.transactions.iter().map(legacy_sigop_count).sum::<usize>() * WITNESS_SCALE_FACTOR;
+
+        if legacy_cost > MAX_BLOCK_SIGOPS_COST {
+            return Err(BlockError::ExcessiveSigops(legacy_cost));
+        }
+
+        Ok(())
+    }
+
+    /// The parts of Core's `ContextualCheckBlock` that only need the
+    ///  block's height: BIP34's height in the coinbase, the witness
+    ///  commitment once segwit is active, and the weight limit.
+    pub fn check_contextual(&self, height: u32, params: &ConsensusParams) -> Result<(), BlockError> {
+        let coinbase = self.coinbase().ok_or(BlockError::NoCoinbase)?;
+
+        if height >= params.bip34_height {
+            let mut expected = Script::new();
+            expected.push_int(height as i64);
+
+            if !coinbase.inputs[0].script_sig.starts_with(expected.as_bytes()) {
+                return Err(BlockError::BadCoinbaseHeight(height));
+            }
+        }
+
+        let mut has_commitment = false;
+
+        if height >= params.segwit_height {
+            if let Some(commitment) = self.witness_commitment() {
+                let nonce = match coinbase.inputs[0].witness.items() {
+                    [nonce] if nonce.len() == 32 => nonce,
+                    _ => return Err(BlockError::InvalidWitnessNonce),
+                };
+
+                if compute_witness_commitment(&self.compute_witness_root(), nonce) != commitment {
+                    return Err(BlockError::WitnessCommitmentMismatch);
+                }
+
+                has_commitment = true;
+            }
+        }
+
+        // Witnesses the header doesn't commit to could be swapped freely.
+        if !has_commitment {
+            if let Some(index) = self.transactions.iter().position(|tx| tx.has_witness()) {
+                return Err(BlockError::UnexpectedWitness(index));
+            }
+        }
+
+        let weight = self.weight();
+
+        if weight > MAX_BLOCK_WEIGHT {
+            return Err(BlockError::ExcessiveWeight(weight));
+        }
+
+        Ok(())
+    }
+}
+
+/// BIP141: the commitment to `witness_root` the coinbase carries, mixed
+///  with the nonce in its witness that future soft forks may give meaning.
+pub fn compute_witness_commitment(witness_root: &[u8; 32], nonce: &[u8]) -> [u8; 32] {
+    hash256(&[witness_root, nonce].concat())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{network::Network, script::interpreter::flags::VerifyFlags, serialization::consensus::{deserialize::DeserializeConsensus, serialize::SerializeConsensus}, transaction::{out_point::OutPoint, tx_in::TxIn}};
+    use super::{compute_witness_commitment, error::BlockError, Block, MAX_BLOCK_WEIGHT};
+
+    const BLOCKS: &str = include_str!("../../test_data/blocks.txt");
+
+    /// The block with `hash` from the fixtures, with its network and height.
+    fn fixture(hash: &str) -> (Block, Network, Option<u32>) {
+        let line = BLOCKS.lines().find(|line| line.contains(hash)).unwrap();
+        let fields: Vec<&str> = line.split(' ').collect();
+
+        let bytes = hex::decode(fields[3]).unwrap();
+        let block = Block::deserialize_consensus(&bytes).unwrap();
+
+        assert_eq!(block.serialize_consensus(), bytes);
+        assert_eq!(block.block_hash().to_string(), fields[2]);
+
+        (block, fields[0].parse().unwrap(), fields[1].parse().ok())
+    }
+
+    #[test]
+    pub fn test_fixtures() {
+        for line in BLOCKS.lines().filter(|line| !line.starts_with('#')) {
+            let (block, network, height) = fixture(line.split(' ').nth(2).unwrap());
+            let params = &network.params().consensus;
+
+            assert!(block.check(params).is_ok());
+
+            if let Some(height) = height {
+                assert!(block.check_contextual(height, params).is_ok());
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_legacy_block() {
+        let (block, _, _) = fixture("00000000b0c5a240b2a61d2e75692224efd4cbecdf6eaf4cc2cf477ca7c270e7");
+
+        assert_eq!(block.transactions.len(), 2);
+        assert_eq!(block.compute_merkle_root(), (block.header.merkle_root, false));
+        assert_eq!(block.total_size(), block.base_size());
+        assert_eq!(block.weight(), block.total_size() * 4);
+        assert_eq!(block.witness_commitment(), None);
+
+        // Both transactions pay to P2PK, one sigop each.
+        assert_eq!(block.sigop_cost(&[vec![], vec![]], VerifyFlags::NONE), 8);
+    }
+
+    #[test]
+    pub fn test_segwit_block() {
+        let (block, network, _) = fixture("000000000000045e0b1660b6445b5e5c5ab63c9a4f956be7e1e69be04fa4497b");
+        let params = &network.params().consensus;
+
+        assert_eq!(block.transactions.len(), 15);
+        assert_eq!(block.base_size(), 4283);
+        assert_eq!(block.weight(), 17168);
+
+        let nonce = &block.transactions[0].inputs[0].witness.items()[0];
+        let commitment = compute_witness_commitment(&block.compute_witness_root(), nonce);
+        assert_eq!(block.witness_commitment(), Some(commitment));
+
+        // Before segwit the same block's witnesses are uncommitted.
+        let mut pre_segwit = Network::Testnet3.params().consensus.clone();
+        pre_segwit.segwit_height = 1_000_000;
+        assert_eq!(block.check_contextual(924634, &pre_segwit), Err(BlockError::UnexpectedWitness(0)));
+
+        // Changing a witness breaks the commitment but not the merkle root.
+        let mut tampered = block.clone();
+        tampered.transactions[1].inputs[0].witness.push(vec![0x01]);
+        assert!(tampered.check(params).is_ok());
+        assert_eq!(tampered.check_contextual(924634, params), Err(BlockError::WitnessCommitmentMismatch));
+
+        let mut tampered = block.clone();
+        tampered.transactions[0].inputs[0].witness.push(vec![0x01]);
+        assert_eq!(tampered.check_contextual(924634, params), Err(BlockError::InvalidWitnessNonce));
+
+        // Without the commitment no transaction may have a witness.
+        let mut tampered = block.clone();
+        let outputs = &mut tampered.transactions[0].outputs;
+        outputs.retain(|output| output.script_pubkey[0] != 0x6a);
+        assert_eq!(tampered.check_contextual(924634, params), Err(BlockError::UnexpectedWitness(0)));
+    }
+
+    #[test]
+    pub fn test_duplicate_transactions() {
+        // CVE-2012-2459: with an odd transaction count, repeating the last
+        //  one keeps the merkle root valid.
+        let (mut block, network, _) = fixture("000000000000045e0b1660b6445b5e5c5ab63c9a4f956be7e1e69be04fa4497b");
+        let params = &network.params().consensus;
+
+        let last = block.transactions.last().unwrap().clone();
+        block.transactions.push(last);
+
+        assert_eq!(block.compute_merkle_root().0, block.header.merkle_root);
+        assert_eq!(block.check(params), Err(BlockError::DuplicateTransactions));
+    }
+
+    #[test]
+    pub fn test_check_failures() {
+        let (block, network, height) = fixture("00000000009e2958c15ff9290d571bf9459e93b19765c6801ddeccadbb160a1e");
+        let params = &network.params().consensus;
+        let height = height.unwrap();
+
+        assert_eq!(block.check_contextual(height + 1, params), Err(BlockError::BadCoinbaseHeight(height + 1)));
+        // BIP34 wasn't active yet.
+        assert!(block.check_contextual(height + 1, &Network::Mainnet.params().consensus).is_ok());
+
+        let mut tampered = block.clone();
+        tampered.transactions[0].outputs[0].value += 1;
+        assert_eq!(tampered.check(params), Err(BlockError::MerkleRootMismatch));
+
+        let mut tampered = block.clone();
+        tampered.header.nonce += 1;
+        assert!(matches!(tampered.check(params), Err(BlockError::HighHash(_))));
+
+        // The remaining checks come after the proof of work and merkle root,
+        //  so use regtest's easy target and fix the root up.
+        let regtest = &Network::Regtest.params().consensus;
+        let mine = |mut block: Block| {
+            block.header.bits = regtest.pow_limit_bits;
+            block.header.merkle_root = block.compute_merkle_root().0;
+
+            while block.header.validate_pow(regtest).is_err() {
+                block.header.nonce += 1;
+            }
+
+            block
+        };
+
+        assert!(mine(block.clone()).check(regtest).is_ok());
+        assert_eq!(mine(Block::new(block.header, vec![])).check(regtest), Err(BlockError::NoTransactions));
+
+        let mut tampered = block.clone();
+        tampered.transactions[0].inputs[0].previous_output = OutPoint::new(Default::default(), 0);
+        assert_eq!(mine(tampered).check(regtest), Err(BlockError::NoCoinbase));
+
+        let mut tampered = block.clone();
+        tampered.transactions.push(block.transactions[0].clone());
+        tampered.transactions[1].lock_time = 1;
+        assert_eq!(mine(tampered).check(regtest), Err(BlockError::MultipleCoinbases(1)));
+
+        // Legacy sigops weigh four each, so 20,001 OP_CHECKSIGs are too many.
+        let mut tampered = block.clone();
+        tampered.transactions[0].outputs[0].script_pubkey = vec![0xac; 20_001];
+        assert!(matches!(mine(tampered).check(regtest), Err(BlockError::ExcessiveSigops(_))));
+
+        // Pushes weigh without counting as sigops.
+        let mut tampered = block.clone();
+        let mut input = TxIn::new(OutPoint::new(Default::default(), 0));
+        input.script_sig = vec![0x00; MAX_BLOCK_WEIGHT / 4];
+        tampered.transactions.push(block.transactions[0].clone());
+        tampered.transactions[1].inputs[0] = input;
+        assert!(matches!(mine(tampered).check(regtest), Err(BlockError::ExcessiveWeight(_))));
+    }
+}
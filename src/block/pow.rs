@@ -0,0 +1,302 @@
+use num_bigint::BigUint;
+use crate::network::params::ConsensusParams;
+use super::header::BlockHeader;
+
+/// Expands compact `bits` into the target a block hash must not exceed,
+///  following Core's `arith_uint256::SetCompact`. The top byte is a base 256
+///  exponent and the rest a signed mantissa; negative and overflowing
+///  encodings give `None`.
+pub fn target_from_bits(bits: u32) -> Option<BigUint> {
+    let size = bits >> 24;
+    let mut mantissa = bits & 0x007f_ffff;
+
+    if size <= 3 {
+        mantissa >>= 8 * (3 - size);
+    }
+
+    let negative = mantissa != 0 && bits & 0x0080_0000 != 0;
+    let overflow = mantissa != 0 && (size > 34 || (mantissa > 0xff && size > 33) || (mantissa > 0xffff && size > 32));
+
+    if negative || overflow {
+        return None;
+    }
+
+    if size <= 3 {
+        Some(BigUint::from(mantissa))
+    } else {
+        Some(BigUint::from(mantissa) << (8 * (size - 3)))
+    }
+}
+
+/// Core's `arith_uint256::GetCompact`, which drops all but the three most
+///  significant bytes of `target`.
+pub fn bits_from_target(target: &BigUint) -> u32 {
+    let mut size = target.bits().div_ceil(8) as u32;
+
+    let shifted = if size <= 3 {
+        target << (8 * (3 - size))
+    } else {
+        target >> (8 * (size - 3))
+    };
+
+    let mut mantissa = shifted.iter_u32_digits().next().unwrap_or(0);
+
+    // The mantissa's top bit is its sign, so a target using it takes
+    //  another byte of exponent instead.
+    if mantissa & 0x0080_0000 != 0 {
+        mantissa >>= 8;
+        size += 1;
+    }
+
+    size << 24 | mantissa
+}
+
+/// Expected number of hashes to find a block at `bits`, 2^256 / (target + 1),
+///  which is what chainwork sums. Invalid or zero targets are worth nothing.
+pub fn work_from_bits(bits: u32) -> BigUint {
+    match target_from_bits(bits) {
+        Some(target) if target.bits() > 0 => (BigUint::from(1u8) << 256) / (target + 1u8),
+        _ => BigUint::from(0u32),
+    }
+}
+
+/// How many times harder than the mainnet minimum `bits` is, as reported
+///  by Core's `getdifficulty`.
+pub fn difficulty(bits: u32) -> f64 {
+    let mut shift = bits >> 24;
+    let mut difficulty = 0xffff as f64 / (bits & 0x00ff_ffff) as f64;
+
+    while shift < 29 {
+        difficulty *= 256.0;
+        shift += 1;
+    }
+
+    while shift > 29 {
+        difficulty /= 256.0;
+        shift -= 1;
+    }
+
+    difficulty
+}
+
+/// Core's `GetNextWorkRequired`: the `bits` a header with timestamp `time`
+///  must have to follow `last`, the header at `last_height`. `ancestor` has
+///  to return the header at any earlier height of the same chain, back to
+///  the start of `last`'s retarget period.
+pub fn next_work_required(params: &ConsensusParams, last: &BlockHeader, last_height: u32, time: u32, ancestor: impl Fn(u32) -> BlockHeader) -> u32 {
+    let interval = params.difficulty_adjustment_interval();
+
+    if !(last_height + 1).is_multiple_of(interval) {
+        if !params.pow_allow_min_difficulty_blocks {
+            return last.bits;
+        }
+
+        // Testnet allows a minimum difficulty block once nothing has been
+        //  found for twice the target spacing.
+        if time as i64 > last.time as i64 + 2 * params.pow_target_spacing as i64 {
+            return params.pow_limit_bits;
+        }
+
+        // Otherwise the difficulty is that of the last block mined without
+        //  the exception.
+        let mut header = *last;
+        let mut height = last_height;
+
+        while height > 0 && !height.is_multiple_of(interval) && header.bits == params.pow_limit_bits {
+            height -= 1;
+            header = ancestor(height);
+        }
+
+        return header.bits;
+    }
+
+    let first = ancestor(last_height + 1 - interval);
+
+    calculate_next_work_required(params, last, &first)
+}
+
+/// Core's `CalculateNextWorkRequired`: the `bits` of the block after `last`,
+///  which ends the retarget period `first` started. The target scales with
+///  how long the period took, by at most a factor of four either way.
+pub fn calculate_next_work_required(params: &ConsensusParams, last: &BlockHeader, first: &BlockHeader) -> u32 {
+    if params.pow_no_retargeting {
+        return last.bits;
+    }
+
+    let timespan = params.pow_target_timespan as i64;
+    let actual_timespan = (last.time as i64 - first.time as i64).clamp(timespan / 4, timespan * 4);
+
+    // BIP94 scales the period's own difficulty, so a minimum difficulty
+    //  block at its end can't drag the next period down with it.
+    let bits = if params.enforce_bip94 { first.bits } else { last.bits };
+
+    let target = target_from_bits(bits).unwrap_or_default() * actual_timespan as u64 / timespan as u64;
+
+    bits_from_target(&target.min(params.pow_limit()))
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigUint;
+    use crate::{block::header::BlockHeader, cryptography::hash::digest::{BlockHash, TxMerkleNode}, network::Network};
+    use super::{bits_from_target, calculate_next_work_required, difficulty, next_work_required, target_from_bits, work_from_bits};
+
+    fn header(time: u32, bits: u32) -> BlockHeader {
+        BlockHeader {
+            version: 1,
+            prev_blockhash: BlockHash::default(),
+            merkle_root: TxMerkleNode::default(),
+            time,
+            bits,
+            nonce: 0,
+        }
+    }
+
+    #[test]
+    pub fn test_compact_target() {
+        // Bitcoin Core's arith_uint256 SetCompact/GetCompact cases.
+        let cases = [
+            (0x00000000, 0u64, 0x00000000),
+            (0x00123456, 0, 0x00000000),
+            (0x01003456, 0, 0x00000000),
+            (0x02000056, 0, 0x00000000),
+            (0x03000000, 0, 0x00000000),
+            (0x04000000, 0, 0x00000000),
+            (0x00923456, 0, 0x00000000),
+            (0x01803456, 0, 0x00000000),
+            (0x02800056, 0, 0x00000000),
+            (0x03800000, 0, 0x00000000),
+            (0x04800000, 0, 0x00000000),
+            (0x01123456, 0x12, 0x01120000),
+            (0x02123456, 0x1234, 0x02123400),
+            (0x03123456, 0x123456, 0x03123456),
+            (0x04123456, 0x12345600, 0x04123456),
+            (0x05009234, 0x92340000, 0x05009234),
+        ];
+
+        for (bits, target, compact) in cases {
+            let expanded = target_from_bits(bits).unwrap();
+
+            assert_eq!(expanded, BigUint::from(target));
+            assert_eq!(bits_from_target(&expanded), compact);
+        }
+
+        let big = target_from_bits(0x20123456).unwrap();
+        assert_eq!(big, BigUint::from(0x123456u32) << 232);
+        assert_eq!(bits_from_target(&big), 0x20123456);
+
+        // A mantissa with its top bit set moves up a byte.
+        assert_eq!(bits_from_target(&BigUint::from(0x80u32)), 0x02008000);
+
+        // Negative and overflowing.
+        assert_eq!(target_from_bits(0x01fedcba), None);
+        assert_eq!(target_from_bits(0x04923456), None);
+        assert_eq!(target_from_bits(0xff123456), None);
+    }
+
+    #[test]
+    pub fn test_work_and_difficulty() {
+        assert_eq!(work_from_bits(0x1d00ffff), BigUint::from(0x100010001u64));
+        assert_eq!(work_from_bits(0x170ed0eb), BigUint::from(0x11474cee790d6d2482aau128));
+        assert_eq!(work_from_bits(0x00000000), BigUint::from(0u32));
+        assert_eq!(work_from_bits(0x04923456), BigUint::from(0u32));
+
+        assert_eq!(difficulty(0x1d00ffff), 1.0);
+        assert_eq!(difficulty(0x1c00ffff), 256.0);
+        assert_eq!(difficulty(0x1b00ffff), 65536.0);
+
+        // Bitcoin Core's blockchain_tests, which compare to six decimals.
+        let cases = [
+            (0x1f111111, 0.000001),
+            (0x1ef88f6f, 0.000016),
+            (0x1df88f6f, 0.004023),
+            (0x1cf88f6f, 1.029916),
+            (0x12345678, 5913134931067755359633408.0),
+        ];
+
+        for (bits, expected) in cases {
+            assert!((difficulty(bits) - expected).abs() <= 0.000001 * expected.max(1.0), "{:#x}", bits);
+        }
+    }
+
+    #[test]
+    pub fn test_mainnet_retargets() {
+        let params = &Network::Mainnet.params().consensus;
+
+        // Bitcoin Core's pow_tests: the times of the first and last blocks of
+        //  real mainnet periods, and the bits the next period got.
+        let cases = [
+            // Block 32255, an ordinary adjustment.
+            (1261130161, 1262152739, 0x1d00ffff, 0x1d00d86a),
+            // Block 2015, too slow but already at the limit.
+            (1231006505, 1233061996, 0x1d00ffff, 0x1d00ffff),
+            // Block 68543, clamped to a quarter of the timespan.
+            (1279008237, 1279297671, 0x1c05a3f4, 0x1c0168fd),
+            // Block 46367, clamped to four times the timespan.
+            (1263163443, 1269211443, 0x1c387f6f, 0x1d00e1fd),
+        ];
+
+        for (first_time, last_time, bits, expected) in cases {
+            let first = header(first_time, bits);
+            let last = header(last_time, bits);
+
+            assert_eq!(calculate_next_work_required(params, &last, &first), expected);
+        }
+
+        // Between retargets the bits carry over, whatever the timestamps.
+        let last = header(1262152739, 0x1d00d86a);
+        assert_eq!(next_work_required(params, &last, 32256, last.time + 7200, |_| unreachable!()), 0x1d00d86a);
+
+        let first = header(1261130161, 0x1d00ffff);
+        let last = header(1262152739, 0x1d00ffff);
+        assert_eq!(next_work_required(params, &last, 32255, last.time + 600, |height| {
+            assert_eq!(height, 30240);
+            first
+        }), 0x1d00d86a);
+    }
+
+    #[test]
+    pub fn test_min_difficulty_blocks() {
+        let params = &Network::Testnet3.params().consensus;
+        let limit = params.pow_limit_bits;
+
+        // Heights 4032 to 4035 mined normally, then minimum difficulty up to 4040.
+        let chain: Vec<BlockHeader> = (0..9)
+            .map(|i| header(1_300_000_000 + 600 * i, if i < 4 { 0x1c0ffff0 } else { limit }))
+            .collect();
+        let ancestor = |height: u32| chain[(height - 4032) as usize];
+        let last = chain[8];
+
+        // Twenty minutes without a block allow one at minimum difficulty.
+        assert_eq!(next_work_required(params, &last, 4040, last.time + 1201, ancestor), limit);
+
+        // Before that the bits are those of the last normally mined block.
+        assert_eq!(next_work_required(params, &last, 4040, last.time + 1200, ancestor), 0x1c0ffff0);
+
+        // The walk back stops at the start of the period.
+        let chain: Vec<BlockHeader> = (0..9).map(|i| header(1_300_000_000 + 600 * i, limit)).collect();
+        let ancestor = |height: u32| chain[(height - 4032) as usize];
+        assert_eq!(next_work_required(params, &chain[8], 4040, chain[8].time, ancestor), limit);
+
+        // Mainnet has no such exception.
+        let mainnet = &Network::Mainnet.params().consensus;
+        assert_eq!(next_work_required(mainnet, &chain[0], 4032, chain[0].time + 7200, |_| unreachable!()), limit);
+    }
+
+    #[test]
+    pub fn test_retarget_variants() {
+        let first = header(1_300_000_000, 0x1c0ffff0);
+        let last = header(1_300_000_000 + 600_000, 0x1d00ffff);
+
+        // Regtest never retargets.
+        let regtest = &Network::Regtest.params().consensus;
+        assert_eq!(calculate_next_work_required(regtest, &last, &first), 0x1d00ffff);
+
+        // Testnet4 scales the first block's bits, not a minimum difficulty last block's.
+        let testnet4 = &Network::Testnet4.params().consensus;
+        assert_eq!(calculate_next_work_required(testnet4, &last, &first), 0x1c07efb7);
+
+        let testnet3 = &Network::Testnet3.params().consensus;
+        assert_eq!(calculate_next_work_required(testnet3, &last, &first), 0x1c7efb70);
+    }
+}
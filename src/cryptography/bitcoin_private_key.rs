@@ -1,17 +1,101 @@
 use num_bigint::BigUint;
-use super::{elliptic_curve::{bitcoin_point::BitcoinPoint, error::PointError}, private_key::PrivateKey, signature::Signature, BITCOIN_SECP256K1_CONFIG};
+use crate::serialization::sec::serialize::{SerializeSEC, SerializeSECError};
+use super::{elliptic_curve::{bitcoin_point::BitcoinPoint, error::PointError, point::Point}, hash::hash256_z, private_key::PrivateKey, schnorr::{self, error::SchnorrError, SchnorrSignature}, signature::Signature, BITCOIN_SECP256K1_CONFIG};
 
-pub struct BitcoinPrivateKey(PrivateKey);
+pub struct BitcoinPrivateKey {
+    key: PrivateKey,
+    compressed: bool,
+}
 
 impl BitcoinPrivateKey {
+    /// Creates a key whose public key is serialized compressed, the default
+    ///  for every modern wallet.
     pub fn new(secret: impl Into<BigUint>) -> Result<Self, PointError>  {
+        Self::new_with_compression(secret, true)
+    }
+
+    pub fn new_with_compression(secret: impl Into<BigUint>, compressed: bool) -> Result<Self, PointError>  {
         let n = BigUint::from_slice(&BITCOIN_SECP256K1_CONFIG.n);
         let g = BitcoinPoint::g();
         
-        Ok(Self(PrivateKey::new(secret, g, n)?))
+        Ok(Self {
+            key: PrivateKey::new(secret, g, n)?,
+            compressed,
+        })
+    }
+
+    pub fn secret(&self) -> &BigUint {
+        self.key.secret()
+    }
+
+    pub fn compressed(&self) -> bool {
+        self.compressed
+    }
+
+    pub fn point(&self) -> &Point {
+        self.key.point()
+    }
+
+    pub fn public_key(&self) -> BitcoinPoint {
+        BitcoinPoint::from_point(self.key.point().clone())
+    }
+
+    /// SEC encoding of the public key, honouring the key's compression flag.
+    pub fn serialize_public_key(&self) -> Result<Vec<u8>, SerializeSECError> {
+        self.public_key().serialize_sec(self.compressed)
     }
 
     pub fn sign<'a>(&self, z: impl Into<&'a BigUint>) -> Result<Signature,  PointError> {
-        self.0.sign(z)
+        self.key.sign(z)
+    }
+
+    /// Signs the double SHA-256 of `message`, for callers that have the
+    ///  preimage rather than a pre-computed `z`.
+    pub fn sign_message_hash(&self, message: &[u8]) -> Result<Signature, PointError> {
+        let z = hash256_z(message);
+
+        self.sign(&z)
+    }
+
+    /// BIP340 signature over `message`, which for taproot spends is the
+    ///  32-byte signature hash.
+    pub fn sign_schnorr(&self, message: &[u8], aux_rand: &[u8; 32]) -> Result<SchnorrSignature, SchnorrError> {
+        schnorr::sign(self.secret(), message, aux_rand)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigUint;
+    use crate::cryptography::hash::hash256_z;
+    use super::BitcoinPrivateKey;
+
+    #[test]
+    pub fn test_sign_message_hash() {
+        let private_key = BitcoinPrivateKey::new(BigUint::from(12345u32)).unwrap();
+        let message = b"Programming Bitcoin!";
+
+        let signature = private_key.sign_message_hash(message).unwrap();
+        let z = hash256_z(message);
+
+        assert!(private_key.point().verify_signature(&z, signature.clone()).unwrap());
+
+        let other = hash256_z(b"Programming Bitcoin?");
+        assert!(!private_key.point().verify_signature(&other, signature).unwrap());
+    }
+
+    #[test]
+    pub fn test_serialize_public_key() {
+        let compressed = BitcoinPrivateKey::new(5001u32).unwrap();
+        let uncompressed = BitcoinPrivateKey::new_with_compression(5000u32, false).unwrap();
+
+        assert_eq!(
+            hex::encode(compressed.serialize_public_key().unwrap()),
+            "0357a4f368868a8a6d572991e484e664810ff14c05c0fa023275251151fe0e53d1"
+        );
+        assert_eq!(
+            hex::encode(uncompressed.serialize_public_key().unwrap()),
+            "04ffe558e388852f0120e46af2d1b370f85854a8eb0841811ece0e3e03d282d57c315dc72890a4f10a1481c031b03b351b0dc79901ca18a00cf009dbdb157a1d10"
+        );
     }
-}
\ No newline at end of file
+}
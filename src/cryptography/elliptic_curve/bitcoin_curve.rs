@@ -19,8 +19,14 @@ impl BitcoinCurve {
     }
 }
 
-impl Into<Curve> for BitcoinCurve {
-    fn into(self) -> Curve {
-        self.0
+impl Default for BitcoinCurve {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<BitcoinCurve> for Curve {
+    fn from(value: BitcoinCurve) -> Self {
+        value.0
     }
 }
\ No newline at end of file
@@ -3,6 +3,7 @@ use num_bigint::BigUint;
 use crate::cryptography::{field_element::{bitcoin_field_element::BitcoinFieldElement, FieldElement}, BITCOIN_SECP256K1_CONFIG};
 use super::{bitcoin_curve::BitcoinCurve, error::PointError, point::Point};
 
+#[derive(Clone, Debug)]
 pub struct BitcoinPoint(Point);
 
 impl BitcoinPoint {
@@ -13,12 +14,17 @@ impl BitcoinPoint {
         Self(point)
     }
 
+    /// Wraps a point already known to be on the Bitcoin curve.
+    pub(crate) fn from_point(point: Point) -> Self {
+        Self(point)
+    }
+
     pub fn g() -> Self {
         let gx = BigUint::from_slice(&BITCOIN_SECP256K1_CONFIG.gx);
         let gy = BigUint::from_slice(&BITCOIN_SECP256K1_CONFIG.gy);
 
-        let x = BitcoinFieldElement::new(gx).unwrap().into();
-        let y = BitcoinFieldElement::new(gy).unwrap().into();
+        let x = BitcoinFieldElement::new(gx).unwrap();
+        let y = BitcoinFieldElement::new(gy).unwrap();
 
         Self::new(x, y)
     }
@@ -44,9 +50,9 @@ impl BitcoinPoint {
     }
 }
 
-impl Into<Point> for BitcoinPoint {
-    fn into(self) -> Point {
-        self.0
+impl From<BitcoinPoint> for Point {
+    fn from(value: BitcoinPoint) -> Self {
+        value.0
     }
 }
 
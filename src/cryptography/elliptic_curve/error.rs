@@ -3,11 +3,14 @@ use crate::cryptography::field_element::{error::FieldError, FieldElement};
 
 use super::Curve;
 
+/// Curves are boxed to keep this error, and every error wrapping it,
+///  small enough to return by value.
 #[derive(Debug)]
 pub enum PointError {
-    NotOnCurve(FieldElement, FieldElement, Curve),
-    MismatchCurves(Curve, Curve),
+    NotOnCurve(Box<(FieldElement, FieldElement, Curve)>),
+    MismatchCurves(Box<(Curve, Curve)>),
     FieldError(FieldError),
+    InvalidXOnlyKey,
 }
 
 impl Error for PointError { }
@@ -15,9 +18,10 @@ impl Error for PointError { }
 impl Display for PointError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            PointError::NotOnCurve(x, y, c) => write!(f, "PointError::NotOnCurve(({}, {}) not on curve {})", x, y, c),
-            PointError::MismatchCurves(l, r) => write!(f, "PointError::MismatchCurve({} != {})", l, r),
+            PointError::NotOnCurve(point) => write!(f, "PointError::NotOnCurve(({}, {}) not on curve {})", point.0, point.1, point.2),
+            PointError::MismatchCurves(curves) => write!(f, "PointError::MismatchCurve({} != {})", curves.0, curves.1),
             PointError::FieldError(e) => write!(f, "PointError::FieldError({})", e),
+            PointError::InvalidXOnlyKey => write!(f, "PointError::InvalidXOnlyKey"),
         }
     }
 }
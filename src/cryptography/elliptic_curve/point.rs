@@ -21,7 +21,7 @@ impl Point {
             let rhs = &curve.b + &(&xp.pow(3u32) + &(&curve.a * xp)?)?;
 
             if lhs != rhs? {
-                return Err(PointError::NotOnCurve(xp.clone(), yp.clone(), curve.clone()));
+                return Err(PointError::NotOnCurve(Box::new((xp.clone(), yp.clone(), curve.clone()))));
             }
         }
 
@@ -79,11 +79,20 @@ impl Add for &Point {
 
     fn add(self, rhs: Self) -> Self::Output {
         if self.curve != rhs.curve {
-            return Err(PointError::MismatchCurves(self.curve.clone(), rhs.curve.clone()));
+            return Err(PointError::MismatchCurves(Box::new((self.curve.clone(), rhs.curve.clone()))));
         }
 
-        if self.is_identity() || rhs.is_identity() {
-            return Ok(Point::identity(self.curve.clone()))
+        if self.is_identity() {
+            return Ok(rhs.clone())
+        }
+
+        if rhs.is_identity() {
+            return Ok(self.clone())
+        }
+
+        // Vertical line through `P` and `-P`, the sum is the point at infinity.
+        if self.x == rhs.x && self.y != rhs.y {
+            return Ok(Point::infinity(self.curve.clone()))
         }
 
         if self == rhs {
@@ -143,7 +152,7 @@ impl<'a, T: Into<&'a BigUint>> Mul<T> for &Point {
 
             lhs = (&lhs + &lhs)?;
 
-            rhs = rhs >> 1;
+            rhs >>= 1;
         }
 
         Ok(result)
@@ -315,7 +324,26 @@ mod test {
         let p = Point::new(ax, ay, curve).unwrap();
         let identity = Point::identity(Curve::new(a, b));
 
-        assert!((&p + &identity).unwrap().is_identity());
-        assert!((&identity + &p).unwrap().is_identity());
+        assert_eq!((&p + &identity).unwrap(), p);
+        assert_eq!((&identity + &p).unwrap(), p);
+    }
+
+    #[test]
+    pub fn test_add_inverse() {
+        let prime: u32 = 13;
+
+        let ax = FieldElement::new(3u32, prime).unwrap();
+        let ay = FieldElement::new(6u32, prime).unwrap();
+        let bx = FieldElement::new(3u32, prime).unwrap();
+        let by = FieldElement::new(7u32, prime).unwrap();
+
+        let a = FieldElement::new(2u32, prime).unwrap();
+        let b = FieldElement::new(3u32, prime).unwrap();
+        let curve = Curve::new(a, b);
+
+        let p1 = Point::new(ax, ay, curve.clone()).unwrap();
+        let p2 = Point::new(bx, by, curve).unwrap();
+
+        assert!((&p1 + &p2).unwrap().is_identity());
     }
 }
\ No newline at end of file
@@ -30,9 +30,9 @@ impl BitcoinFieldElement {
     }
 }
 
-impl Into<FieldElement> for BitcoinFieldElement {
-    fn into(self) -> FieldElement {
-        self.0
+impl From<BitcoinFieldElement> for FieldElement {
+    fn from(value: BitcoinFieldElement) -> Self {
+        value.0
     }
 }
 
@@ -16,7 +16,7 @@ impl FieldElement {
         let number = number.into();
         let prime = prime.into();
 
-        if &number >= &prime {
+        if number >= prime {
             return Err(FieldError::InvalidNumber(number, prime.clone()))
         }
 
@@ -58,7 +58,7 @@ impl Add for &FieldElement {
     type Output = Result<FieldElement, FieldError>;
 
     fn add(self, rhs: Self) -> Self::Output {
-        if &self.prime != &rhs.prime {
+        if self.prime != rhs.prime {
             return Err(FieldError::MismatchPrimes(self.prime.clone(), rhs.prime.clone()));
         }
 
@@ -75,7 +75,7 @@ impl Sub for &FieldElement {
     type Output = Result<FieldElement, FieldError>;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        if &self.prime != &rhs.prime {
+        if self.prime != rhs.prime {
             return Err(FieldError::MismatchPrimes(self.prime.clone(), rhs.prime.clone()));
         }
 
@@ -115,7 +115,7 @@ impl Div for &FieldElement {
     type Output = Result<FieldElement, FieldError>;
 
     fn div(self, rhs: &FieldElement) -> Self::Output {
-        if &self.prime != &rhs.prime {
+        if self.prime != rhs.prime {
             return Err(FieldError::MismatchPrimes(self.prime.clone(), rhs.prime.clone()));
         }
 
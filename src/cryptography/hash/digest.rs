@@ -0,0 +1,139 @@
+use std::{fmt::Display, str::FromStr};
+use super::{error::HashError, hash160, hash256, tagged_hash};
+
+/// Declares a fixed-size digest newtype. Digests flagged `reversed` are
+///  displayed and parsed in the byte order Bitcoin Core uses for txids
+///  and block hashes, which is the reverse of the internal order.
+macro_rules! hash_newtype {
+    ($name:ident, $len:expr, $hash:ident, reversed = $reversed:expr) => {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+        pub struct $name([u8; $len]);
+
+        impl $name {
+            pub const LEN: usize = $len;
+
+            pub fn from_byte_array(bytes: [u8; $len]) -> Self {
+                Self(bytes)
+            }
+
+            pub fn from_slice(bytes: &[u8]) -> Result<Self, HashError> {
+                let bytes: [u8; $len] = bytes
+                    .try_into()
+                    .map_err(|_| HashError::InvalidLength($len, bytes.len()))?;
+
+                Ok(Self(bytes))
+            }
+
+            /// Hashes `data` with the algorithm this digest type is defined by.
+            pub fn hash(data: &[u8]) -> Self {
+                Self($hash(data))
+            }
+
+            pub fn as_bytes(&self) -> &[u8; $len] {
+                &self.0
+            }
+
+            pub fn to_byte_array(self) -> [u8; $len] {
+                self.0
+            }
+        }
+
+        impl From<[u8; $len]> for $name {
+            fn from(value: [u8; $len]) -> Self {
+                Self(value)
+            }
+        }
+
+        impl AsRef<[u8]> for $name {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let mut bytes = self.0;
+
+                if $reversed {
+                    bytes.reverse();
+                }
+
+                write!(f, "{}", hex::encode(bytes))
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = HashError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let mut bytes = hex::decode(s)?;
+
+                if $reversed {
+                    bytes.reverse();
+                }
+
+                Self::from_slice(&bytes)
+            }
+        }
+    };
+}
+
+hash_newtype!(Txid, 32, hash256, reversed = true);
+hash_newtype!(Wtxid, 32, hash256, reversed = true);
+hash_newtype!(BlockHash, 32, hash256, reversed = true);
+hash_newtype!(TxMerkleNode, 32, hash256, reversed = true);
+hash_newtype!(Sighash, 32, hash256, reversed = true);
+
+fn tap_sighash(data: &[u8]) -> [u8; 32] {
+    tagged_hash("TapSighash", data)
+}
+
+// BIP341 digests are plain SHA-256 output and are shown as such.
+hash_newtype!(TapSighash, 32, tap_sighash, reversed = false);
+
+// HASH160 digests are embedded in scripts and addresses as-is, so unlike
+//  Core's `uint160::GetHex` they are shown in internal order to match the
+//  `OP_HASH160 <hash>` notation everyone copies around.
+hash_newtype!(PubkeyHash, 20, hash160, reversed = false);
+hash_newtype!(ScriptHash, 20, hash160, reversed = false);
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+    use super::{BlockHash, PubkeyHash, Txid};
+
+    #[test]
+    pub fn test_txid_display_reversed() {
+        // Coinbase of block 170, the first bitcoin transaction.
+        let display = "b1fea52486ce0c62bb442b530a3f0132b826c74e473d1f2c220bfa78111c5082";
+        let txid = Txid::from_str(display).unwrap();
+
+        assert_eq!(txid.as_bytes()[0], 0x82);
+        assert_eq!(txid.to_string(), display);
+    }
+
+    #[test]
+    pub fn test_block_hash() {
+        let header = hex::decode("0100000000000000000000000000000000000000000000000000000000000000000000003ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa4b1e5e4a29ab5f49ffff001d1dac2b7c").unwrap();
+
+        assert_eq!(
+            BlockHash::hash(&header).to_string(),
+            "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f"
+        );
+    }
+
+    #[test]
+    pub fn test_pubkey_hash_display() {
+        let g = hex::decode("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798").unwrap();
+        let hash = PubkeyHash::hash(&g);
+
+        assert_eq!(hash.to_string(), "751e76e8199196d454941c45d1b3a323f1433bd6");
+        assert_eq!(PubkeyHash::from_str("751e76e8199196d454941c45d1b3a323f1433bd6").unwrap(), hash);
+    }
+
+    #[test]
+    pub fn test_invalid_length() {
+        assert!(Txid::from_str("00").is_err());
+        assert!(PubkeyHash::from_slice(&[0u8; 21]).is_err());
+    }
+}
@@ -0,0 +1,24 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HashError {
+    InvalidHex,
+    InvalidLength(usize, usize),
+}
+
+impl Error for HashError { }
+
+impl Display for HashError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HashError::InvalidHex => write!(f, "HashError::InvalidHex"),
+            HashError::InvalidLength(expected, actual) => write!(f, "HashError::InvalidLength(expected {} bytes, got {})", expected, actual),
+        }
+    }
+}
+
+impl From<hex::FromHexError> for HashError {
+    fn from(_: hex::FromHexError) -> Self {
+        HashError::InvalidHex
+    }
+}
@@ -0,0 +1,127 @@
+use hmac::{Hmac, Mac};
+use num_bigint::BigUint;
+use ripemd::Ripemd160;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
+
+pub mod error;
+pub mod digest;
+
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+/// Double SHA-256, used for txids, block hashes and checksums.
+pub fn hash256(data: &[u8]) -> [u8; 32] {
+    sha256(&sha256(data))
+}
+
+/// Only reachable through OP_SHA1.
+pub fn sha1(data: &[u8]) -> [u8; 20] {
+    Sha1::digest(data).into()
+}
+
+pub fn ripemd160(data: &[u8]) -> [u8; 20] {
+    Ripemd160::digest(data).into()
+}
+
+/// RIPEMD-160 of SHA-256, used for public key and script hashes.
+pub fn hash160(data: &[u8]) -> [u8; 20] {
+    ripemd160(&sha256(data))
+}
+
+pub fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    // HMAC accepts keys of any length, so this cannot fail.
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).unwrap();
+    mac.update(data);
+
+    mac.finalize().into_bytes().into()
+}
+
+pub fn hmac_sha512(key: &[u8], data: &[u8]) -> [u8; 64] {
+    let mut mac = Hmac::<Sha512>::new_from_slice(key).unwrap();
+    mac.update(data);
+
+    mac.finalize().into_bytes().into()
+}
+
+/// BIP340 tagged hash, `SHA256(SHA256(tag) || SHA256(tag) || data)`.
+pub fn tagged_hash(tag: &str, data: &[u8]) -> [u8; 32] {
+    let tag_hash = sha256(tag.as_bytes());
+
+    let mut hasher = Sha256::new();
+    hasher.update(tag_hash);
+    hasher.update(tag_hash);
+    hasher.update(data);
+
+    hasher.finalize().into()
+}
+
+/// Interprets a message as the `z` value expected by `sign` and `verify_signature`.
+pub fn hash256_z(message: &[u8]) -> BigUint {
+    BigUint::from_bytes_be(&hash256(message))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{hash160, hash256, hmac_sha256, hmac_sha512, ripemd160, sha1, sha256, tagged_hash};
+
+    #[test]
+    pub fn test_sha256() {
+        assert_eq!(
+            hex::encode(sha256(b"abc")),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    pub fn test_hash256() {
+        assert_eq!(
+            hex::encode(hash256(b"hello")),
+            "9595c9df90075148eb06860365df33584b75bff782a510c6cd4883a419833d50"
+        );
+    }
+
+    #[test]
+    pub fn test_sha1() {
+        assert_eq!(hex::encode(sha1(b"abc")), "a9993e364706816aba3e25717850c26c9cd0d89d");
+    }
+
+    #[test]
+    pub fn test_ripemd160() {
+        assert_eq!(hex::encode(ripemd160(b"")), "9c1185a5c5e9fc54612808977ee8f548b2258d31");
+        assert_eq!(hex::encode(ripemd160(b"abc")), "8eb208f7e05d987a9b044a8e98c6b087f15a0bfc");
+    }
+
+    #[test]
+    pub fn test_hash160() {
+        // Compressed SEC encoding of the generator point.
+        let g = hex::decode("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798").unwrap();
+
+        assert_eq!(hex::encode(hash160(&g)), "751e76e8199196d454941c45d1b3a323f1433bd6");
+    }
+
+    #[test]
+    pub fn test_hmac() {
+        // RFC 4231, test case 2.
+        let key = b"Jefe";
+        let data = b"what do ya want for nothing?";
+
+        assert_eq!(
+            hex::encode(hmac_sha256(key, data)),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert_eq!(
+            hex::encode(hmac_sha512(key, data)),
+            "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea2505549758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737"
+        );
+    }
+
+    #[test]
+    pub fn test_tagged_hash() {
+        assert_eq!(
+            hex::encode(tagged_hash("TapLeaf", b"data")),
+            "b173657cd0dd0c23aa902ed33d34bb8e6561edae5a1f403ae657346349adb535"
+        );
+    }
+}
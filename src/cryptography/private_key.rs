@@ -22,6 +22,14 @@ impl PrivateKey {
         })
     }
 
+    pub fn secret(&self) -> &BigUint {
+        &self.secret
+    }
+
+    pub fn point(&self) -> &Point {
+        &self.point
+    }
+
     pub fn sign<'a>(&self, z: impl Into<&'a BigUint>) -> Result<Signature, PointError> {
         let z = z.into();
         let two = BigUint::from_slice(&[0x00000002]);
@@ -32,7 +40,7 @@ impl PrivateKey {
 
         let r = (g * &k)?;
         let r = r.x().as_ref().unwrap().number();
-        let k_inv = k.modpow(&(n - &two), &n);
+        let k_inv = k.modpow(&(n - &two), n);
 
         let mut s = (z + r * &self.secret) * k_inv % n;
 
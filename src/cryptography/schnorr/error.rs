@@ -0,0 +1,29 @@
+use std::{error::Error, fmt::Display};
+use crate::cryptography::elliptic_curve::error::PointError;
+
+#[derive(Debug)]
+pub enum SchnorrError {
+    InvalidSecretKey,
+    InvalidNonce,
+    InvalidSignature,
+    Point(PointError),
+}
+
+impl Error for SchnorrError { }
+
+impl Display for SchnorrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchnorrError::InvalidSecretKey => write!(f, "SchnorrError::InvalidSecretKey(0 < secret < n)"),
+            SchnorrError::InvalidNonce => write!(f, "SchnorrError::InvalidNonce"),
+            SchnorrError::InvalidSignature => write!(f, "SchnorrError::InvalidSignature(produced signature failed to verify)"),
+            SchnorrError::Point(e) => write!(f, "SchnorrError::Point({})", e),
+        }
+    }
+}
+
+impl From<PointError> for SchnorrError {
+    fn from(value: PointError) -> Self {
+        SchnorrError::Point(value)
+    }
+}
@@ -0,0 +1,156 @@
+use num_bigint::BigUint;
+use crate::serialization::sec::bitcoin_point::to_32_bytes;
+use self::error::SchnorrError;
+use super::{elliptic_curve::bitcoin_point::BitcoinPoint, hash::tagged_hash, taproot::{has_even_y, lift_x, x_only}, BITCOIN_SECP256K1_CONFIG};
+
+pub mod error;
+
+/// BIP340 signature: the x coordinate of the nonce point `R` followed by `s`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SchnorrSignature([u8; 64]);
+
+impl SchnorrSignature {
+    pub fn from_byte_array(bytes: [u8; 64]) -> Self {
+        Self(bytes)
+    }
+
+    pub fn from_slice(bytes: &[u8]) -> Option<Self> {
+        Some(Self(bytes.try_into().ok()?))
+    }
+
+    pub fn to_byte_array(self) -> [u8; 64] {
+        self.0
+    }
+
+    pub fn r(&self) -> &[u8] {
+        &self.0[..32]
+    }
+
+    pub fn s(&self) -> &[u8] {
+        &self.0[32..]
+    }
+}
+
+/// Signs `message` following BIP340's default signing algorithm. `aux_rand`
+///  is mixed into the nonce; all zeros is still safe, just deterministic.
+pub fn sign(secret: &BigUint, message: &[u8], aux_rand: &[u8; 32]) -> Result<SchnorrSignature, SchnorrError> {
+    let n = BigUint::from_slice(&BITCOIN_SECP256K1_CONFIG.n);
+
+    if *secret == BigUint::from(0u32) || *secret >= n {
+        return Err(SchnorrError::InvalidSecretKey);
+    }
+
+    let p = (BitcoinPoint::g() * secret)?;
+    let p_bytes = x_only(&p).ok_or(SchnorrError::InvalidSecretKey)?;
+
+    let d = if has_even_y(&p) { secret.clone() } else { &n - secret };
+
+    let mut t = to_32_bytes(&d);
+
+    for (byte, mask) in t.iter_mut().zip(tagged_hash("BIP0340/aux", aux_rand)) {
+        *byte ^= mask;
+    }
+
+    let rand = tagged_hash("BIP0340/nonce", &[&t[..], &p_bytes, message].concat());
+    let k = BigUint::from_bytes_be(&rand) % &n;
+
+    if k == BigUint::from(0u32) {
+        return Err(SchnorrError::InvalidNonce);
+    }
+
+    let r = (BitcoinPoint::g() * &k)?;
+    let r_bytes = x_only(&r).ok_or(SchnorrError::InvalidNonce)?;
+
+    let k = if has_even_y(&r) { k } else { &n - k };
+    let e = challenge(&r_bytes, &p_bytes, message);
+
+    let s = (k + e * d) % &n;
+
+    let mut signature = [0u8; 64];
+    signature[..32].copy_from_slice(&r_bytes);
+    signature[32..].copy_from_slice(&to_32_bytes(&s));
+
+    let signature = SchnorrSignature(signature);
+
+    // BIP340 recommends this to catch faults before a bad signature leaks the key.
+    if !verify(&p_bytes, message, &signature) {
+        return Err(SchnorrError::InvalidSignature);
+    }
+
+    Ok(signature)
+}
+
+/// BIP340 verification against the x-only `public_key`.
+pub fn verify(public_key: &[u8; 32], message: &[u8], signature: &SchnorrSignature) -> bool {
+    let p = BigUint::from_slice(&BITCOIN_SECP256K1_CONFIG.p);
+    let n = BigUint::from_slice(&BITCOIN_SECP256K1_CONFIG.n);
+
+    let point = match lift_x(public_key) {
+        Some(point) => point,
+        None => return false,
+    };
+
+    let r = BigUint::from_bytes_be(signature.r());
+    let s = BigUint::from_bytes_be(signature.s());
+
+    if r >= p || s >= n {
+        return false;
+    }
+
+    let e = challenge(signature.r(), public_key, message);
+
+    // R = sG - eP, computed as sG + (n - e)P.
+    let r_point = match (BitcoinPoint::g() * &s, point * &(&n - e)) {
+        (Ok(sg), Ok(ep)) => &sg + &ep,
+        _ => return false,
+    };
+
+    match r_point {
+        Ok(r_point) if !r_point.is_identity() && has_even_y(&r_point) => x_only(&r_point).is_some_and(|x| x[..] == *signature.r()),
+        _ => false,
+    }
+}
+
+fn challenge(r: &[u8], public_key: &[u8], message: &[u8]) -> BigUint {
+    let n = BigUint::from_slice(&BITCOIN_SECP256K1_CONFIG.n);
+    let e = tagged_hash("BIP0340/challenge", &[r, public_key, message].concat());
+
+    BigUint::from_bytes_be(&e) % n
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigUint;
+    use crate::cryptography::{elliptic_curve::bitcoin_point::BitcoinPoint, taproot::x_only};
+    use super::{sign, verify, SchnorrSignature};
+
+    const BIP340_VECTORS: &str = include_str!("../../../test_data/bip340_test_vectors.csv");
+
+    #[test]
+    pub fn test_bip340_vectors() {
+        // index, secret key, public key, aux_rand, message, signature, verification result, comment
+        for line in BIP340_VECTORS.lines().skip(1) {
+            let fields: Vec<&str> = line.split(',').collect();
+
+            let public_key: [u8; 32] = hex::decode(fields[2]).unwrap().try_into().unwrap();
+            let message = hex::decode(fields[4]).unwrap();
+            let signature = SchnorrSignature::from_slice(&hex::decode(fields[5]).unwrap()).unwrap();
+            let valid = fields[6] == "TRUE";
+
+            if !fields[1].is_empty() {
+                let secret = BigUint::from_bytes_be(&hex::decode(fields[1]).unwrap());
+                let aux_rand: [u8; 32] = hex::decode(fields[3]).unwrap().try_into().unwrap();
+
+                assert_eq!(x_only(&(BitcoinPoint::g() * &secret).unwrap()).unwrap(), public_key, "vector {}", fields[0]);
+                assert_eq!(sign(&secret, &message, &aux_rand).unwrap(), signature, "vector {}", fields[0]);
+            }
+
+            assert_eq!(verify(&public_key, &message, &signature), valid, "vector {}", fields[0]);
+        }
+    }
+
+    #[test]
+    pub fn test_sign_invalid_secret() {
+        assert!(sign(&BigUint::from(0u32), &[0u8; 32], &[0u8; 32]).is_err());
+    }
+}
@@ -0,0 +1,280 @@
+use num_bigint::BigUint;
+use crate::serialization::{consensus::compact_size::write_compact_size, sec::{deserialize::DeserializeSEC, serialize::SerializeSEC}};
+use super::{elliptic_curve::{bitcoin_point::BitcoinPoint, error::PointError}, hash::tagged_hash, BITCOIN_SECP256K1_CONFIG};
+
+/// The 32-byte x coordinate BIP340 uses in place of a full public key.
+pub fn x_only(point: &BitcoinPoint) -> Option<[u8; 32]> {
+    let sec = point.serialize_sec(true).ok()?;
+
+    sec[1..].try_into().ok()
+}
+
+pub fn has_even_y(point: &BitcoinPoint) -> bool {
+    matches!(point.serialize_sec(true).map(|sec| sec[0]), Ok(0x02))
+}
+
+/// The point with x coordinate `x` and an even y, if one exists.
+pub fn lift_x(x: &[u8; 32]) -> Option<BitcoinPoint> {
+    let mut sec = [0u8; 33];
+    sec[0] = 0x02;
+    sec[1..].copy_from_slice(x);
+
+    BitcoinPoint::deserialize_sec(&sec[..]).ok()
+}
+
+/// `hash_TapTweak(P || merkle_root)`, or just `P` for key-path-only outputs.
+pub fn tap_tweak_hash(internal_key: &[u8; 32], merkle_root: Option<&[u8; 32]>) -> [u8; 32] {
+    let mut data = internal_key.to_vec();
+
+    if let Some(root) = merkle_root {
+        data.extend_from_slice(root);
+    }
+
+    tagged_hash("TapTweak", &data)
+}
+
+/// Leaf version of BIP342 tapscript.
+pub const TAPSCRIPT_LEAF_VERSION: u8 = 0xc0;
+/// The bits of a control block's first byte holding the leaf version, the
+///  remaining one is the parity of the output key.
+pub const TAPROOT_LEAF_MASK: u8 = 0xfe;
+pub const TAPROOT_CONTROL_BASE_SIZE: usize = 33;
+pub const TAPROOT_CONTROL_NODE_SIZE: usize = 32;
+pub const TAPROOT_CONTROL_MAX_NODE_COUNT: usize = 128;
+pub const TAPROOT_CONTROL_MAX_SIZE: usize = TAPROOT_CONTROL_BASE_SIZE + TAPROOT_CONTROL_NODE_SIZE * TAPROOT_CONTROL_MAX_NODE_COUNT;
+
+/// `hash_TapLeaf(leaf_version || compact_size(script) || script)`.
+pub fn tap_leaf_hash(leaf_version: u8, script: &[u8]) -> [u8; 32] {
+    let mut data = vec![leaf_version];
+    write_compact_size(&mut data, script.len() as u64);
+    data.extend_from_slice(script);
+
+    tagged_hash("TapLeaf", &data)
+}
+
+/// `hash_TapBranch` of two nodes, which are sorted first so that a proof
+///  does not need to say which side each sibling is on.
+pub fn tap_branch_hash(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+
+    tagged_hash("TapBranch", &[&left[..], &right[..]].concat())
+}
+
+/// The last witness item of a script-path spend: the leaf version, the
+///  parity of the output key, the internal key and the merkle path from the
+///  executed leaf up to the root. Control blocks order as their
+///  serializations do.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ControlBlock {
+    pub leaf_version: u8,
+    /// Whether the output key has an odd y coordinate.
+    pub output_key_parity: bool,
+    pub internal_key: [u8; 32],
+    pub merkle_branch: Vec<[u8; 32]>,
+}
+
+impl ControlBlock {
+    /// `None` unless `bytes` is 33 bytes plus up to 128 path nodes of 32.
+    ///  The internal key is not checked to be on the curve here.
+    pub fn from_slice(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < TAPROOT_CONTROL_BASE_SIZE || bytes.len() > TAPROOT_CONTROL_MAX_SIZE || !(bytes.len() - TAPROOT_CONTROL_BASE_SIZE).is_multiple_of(TAPROOT_CONTROL_NODE_SIZE) {
+            return None;
+        }
+
+        Some(Self {
+            leaf_version: bytes[0] & TAPROOT_LEAF_MASK,
+            output_key_parity: bytes[0] & 1 == 1,
+            internal_key: bytes[1..TAPROOT_CONTROL_BASE_SIZE].try_into().unwrap(),
+            merkle_branch: bytes[TAPROOT_CONTROL_BASE_SIZE..].chunks_exact(TAPROOT_CONTROL_NODE_SIZE).map(|node| node.try_into().unwrap()).collect(),
+        })
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = vec![self.leaf_version | self.output_key_parity as u8];
+        bytes.extend_from_slice(&self.internal_key);
+
+        for node in &self.merkle_branch {
+            bytes.extend_from_slice(node);
+        }
+
+        bytes
+    }
+
+    /// The root of the script tree, hashing up from `leaf_hash`.
+    pub fn merkle_root(&self, leaf_hash: &[u8; 32]) -> [u8; 32] {
+        self.merkle_branch.iter().fold(*leaf_hash, |node, sibling| tap_branch_hash(&node, sibling))
+    }
+
+    /// Whether tweaking the internal key with the tree containing the leaf
+    ///  `leaf_hash` gives `output_key`, with the parity this block claims.
+    pub fn verify_commitment(&self, output_key: &[u8; 32], leaf_hash: &[u8; 32]) -> bool {
+        let merkle_root = self.merkle_root(leaf_hash);
+
+        match tweak_public_key(&self.internal_key, Some(&merkle_root)) {
+            Ok((q, odd)) => x_only(&q).as_ref() == Some(output_key) && odd == self.output_key_parity,
+            Err(_) => false,
+        }
+    }
+}
+
+/// BIP341 output key `Q = lift_x(P) + tG`, returned with the parity of its y
+///  coordinate, which script-path control blocks need.
+pub fn tweak_public_key(internal_key: &[u8; 32], merkle_root: Option<&[u8; 32]>) -> Result<(BitcoinPoint, bool), PointError> {
+    let p = lift_x(internal_key).ok_or(PointError::InvalidXOnlyKey)?;
+
+    let t = BigUint::from_bytes_be(&tap_tweak_hash(internal_key, merkle_root));
+
+    if t >= BigUint::from_slice(&BITCOIN_SECP256K1_CONFIG.n) {
+        return Err(PointError::InvalidXOnlyKey);
+    }
+
+    let q = (&p + &(BitcoinPoint::g() * &t)?)?;
+
+    if q.is_identity() {
+        return Err(PointError::InvalidXOnlyKey);
+    }
+
+    let odd = !has_even_y(&q);
+
+    Ok((q, odd))
+}
+
+/// Secret key for the output key `tweak_public_key` derives from the public
+///  key of `secret`, negating `secret` first if that key has an odd y.
+pub fn tweak_private_key(secret: &BigUint, merkle_root: Option<&[u8; 32]>) -> Result<BigUint, PointError> {
+    let n = BigUint::from_slice(&BITCOIN_SECP256K1_CONFIG.n);
+
+    let p = (BitcoinPoint::g() * secret)?;
+    let internal_key = x_only(&p).ok_or(PointError::InvalidXOnlyKey)?;
+
+    let d = if has_even_y(&p) { secret.clone() } else { &n - secret };
+    let t = BigUint::from_bytes_be(&tap_tweak_hash(&internal_key, merkle_root));
+
+    if t >= n {
+        return Err(PointError::InvalidXOnlyKey);
+    }
+
+    let tweaked = (d + t) % &n;
+
+    if tweaked == BigUint::from(0u32) {
+        return Err(PointError::InvalidXOnlyKey);
+    }
+
+    Ok(tweaked)
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigUint;
+    use crate::cryptography::elliptic_curve::bitcoin_point::BitcoinPoint;
+    use serde_json::Value;
+    use super::{tap_leaf_hash, tap_tweak_hash, tweak_private_key, tweak_public_key, x_only, ControlBlock, TAPSCRIPT_LEAF_VERSION};
+
+    const BIP341_VECTORS: &str = include_str!("../../test_data/bip341_wallet_vectors.json");
+
+    fn bytes32(h: &str) -> [u8; 32] {
+        hex::decode(h).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    pub fn test_tweak_public_key() {
+        // BIP341 `scriptPubKey` test vectors 0 and 1.
+        let internal = bytes32("d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d");
+
+        assert_eq!(hex::encode(tap_tweak_hash(&internal, None)), "b86e7be8f39bab32a6f2c0443abbc210f0edac0e2c53d501b36b64437d9c6c70");
+
+        let (q, _) = tweak_public_key(&internal, None).unwrap();
+        assert_eq!(hex::encode(x_only(&q).unwrap()), "53a1f6e454df1aa2776a2814a721372d6258050de330b3c6d10ee8f4e0dda343");
+
+        let internal = bytes32("187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27");
+        let merkle_root = bytes32("5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21");
+
+        let (q, odd) = tweak_public_key(&internal, Some(&merkle_root)).unwrap();
+        assert_eq!(hex::encode(x_only(&q).unwrap()), "147c9c57132f6e7ecddba9800bb0c4449251c92a1e60371ee77557b6620f3ea3");
+        // Control block `c1...`: leaf version 0xc0 with the parity bit set.
+        assert!(odd);
+    }
+
+    #[test]
+    pub fn test_tweak_invalid_key() {
+        // x = 5 is not on the curve.
+        let mut x = [0u8; 32];
+        x[31] = 5;
+
+        assert!(tweak_public_key(&x, None).is_err());
+    }
+
+    #[test]
+    pub fn test_tweak_private_key() {
+        // BIP341 `keyPathSpending` input 1.
+        let secret = BigUint::from_bytes_be(&hex::decode("1e4da49f6aaf4e5cd175fe08a32bb5cb4863d963921255f33d3bc31e1343907f").unwrap());
+        let merkle_root = bytes32("5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21");
+
+        let tweaked = tweak_private_key(&secret, Some(&merkle_root)).unwrap();
+        assert_eq!(hex::encode(tweaked.to_bytes_be()), "ea260c3b10e60f6de018455cd0278f2f5b7e454be1999572789e6a9565d26080");
+
+        let internal = x_only(&(BitcoinPoint::g() * &secret).unwrap()).unwrap();
+        let (q, _) = tweak_public_key(&internal, Some(&merkle_root)).unwrap();
+
+        assert_eq!(x_only(&(BitcoinPoint::g() * &tweaked).unwrap()), x_only(&q));
+    }
+
+    #[test]
+    pub fn test_tap_leaf_hash() {
+        // BIP341 `scriptPubKey` test vector 1, a single-leaf tree.
+        let script = hex::decode("20d85a959b0290bf19bb89ed43c916be835475d013da4b362117393e25a48229b8ac").unwrap();
+
+        assert_eq!(hex::encode(tap_leaf_hash(TAPSCRIPT_LEAF_VERSION, &script)), "5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21");
+    }
+
+    /// Flattens a BIP341 `scriptTree` into its leaves, ordered by id.
+    fn collect_leaves(tree: &Value, leaves: &mut Vec<(u64, u8, Vec<u8>)>) {
+        match tree {
+            Value::Array(branches) => branches.iter().for_each(|branch| collect_leaves(branch, leaves)),
+            Value::Object(leaf) => leaves.push((leaf["id"].as_u64().unwrap(), leaf["leafVersion"].as_u64().unwrap() as u8, hex::decode(leaf["script"].as_str().unwrap()).unwrap())),
+            _ => { },
+        }
+    }
+
+    #[test]
+    pub fn test_control_blocks() {
+        let vectors: Value = serde_json::from_str(BIP341_VECTORS).unwrap();
+
+        for vector in vectors["scriptPubKey"].as_array().unwrap() {
+            let mut leaves = Vec::new();
+            collect_leaves(&vector["given"]["scriptTree"], &mut leaves);
+            leaves.sort_by_key(|(id, _, _)| *id);
+
+            let output_key = bytes32(vector["intermediary"]["tweakedPubkey"].as_str().unwrap());
+            let control_blocks = vector["expected"]["scriptPathControlBlocks"].as_array().map(Vec::as_slice).unwrap_or_default();
+
+            assert_eq!(control_blocks.len(), leaves.len());
+
+            for ((_, leaf_version, script), expected) in leaves.iter().zip(control_blocks) {
+                let bytes = hex::decode(expected.as_str().unwrap()).unwrap();
+                let control_block = ControlBlock::from_slice(&bytes).unwrap();
+                let leaf_hash = tap_leaf_hash(*leaf_version, script);
+
+                assert_eq!(control_block.leaf_version, *leaf_version);
+                assert_eq!(control_block.serialize(), bytes);
+                assert_eq!(hex::encode(control_block.merkle_root(&leaf_hash)), vector["intermediary"]["merkleRoot"].as_str().unwrap());
+                assert!(control_block.verify_commitment(&output_key, &leaf_hash));
+
+                // Flipping the parity or committing to another leaf fails.
+                let mut flipped = control_block.clone();
+                flipped.output_key_parity = !flipped.output_key_parity;
+
+                assert!(!flipped.verify_commitment(&output_key, &leaf_hash));
+                assert!(!control_block.verify_commitment(&output_key, &tap_leaf_hash(*leaf_version, &[0x51])));
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_control_block_size() {
+        assert!(ControlBlock::from_slice(&[0xc0; 32]).is_none());
+        assert!(ControlBlock::from_slice(&[0xc0; 34]).is_none());
+        assert!(ControlBlock::from_slice(&[0xc0; 33 + 32 * 128]).is_some());
+        assert!(ControlBlock::from_slice(&[0xc0; 33 + 32 * 129]).is_none());
+    }
+}
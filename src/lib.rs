@@ -1,2 +1,8 @@
-mod cryptography;
-mod serialization;
\ No newline at end of file
+
+pub mod address;
+pub mod block;
+pub mod cryptography;
+pub mod network;
+pub mod script;
+pub mod serialization;
+pub mod transaction;
\ No newline at end of file
@@ -1,5 +1,120 @@
-use rust_bitcoin::*;
+use std::{env, io::{self, BufRead, Write}, process::ExitCode};
+use rust_bitcoin::script::{interpreter::{checker::NoSignatureChecker, debugger::{Debugger, Step}, execution::{eval_script, Execution}, flags::VerifyFlags, SigVersion}, Script};
 
-fn main() {
-    println!("Hello, world!");
+const USAGE: &str = "usage: rust_bitcoin [--flags=P2SH,...] '<script asm>' [stack items asm]...
+
+Steps through the script like btcdeb, starting from the stack the items
+push. Signature checks always fail as there is no transaction.
+
+commands:
+  step, s, <enter>   execute the next opcode
+  run, r             execute the rest of the script
+  rewind, b          go back one opcode
+  quit, q            exit";
+
+fn main() -> ExitCode {
+    let mut flags = VerifyFlags::STANDARD;
+    let mut args = Vec::new();
+
+    for arg in env::args().skip(1) {
+        match arg.strip_prefix("--flags=") {
+            Some(names) => match VerifyFlags::from_names(names) {
+                Some(names) => flags = names,
+                None => return fail(&format!("unknown flags {}", names)),
+            },
+            None => args.push(arg),
+        }
+    }
+
+    let Some((script, items)) = args.split_first() else {
+        return fail(USAGE);
+    };
+
+    // btcdeb's brackets are optional.
+    let script = match Script::from_asm(script.trim_start_matches('[').trim_end_matches(']')) {
+        Ok(script) => script.into_bytes(),
+        Err(e) => return fail(&e.to_string()),
+    };
+
+    let items = match Script::from_asm(&items.join(" ")) {
+        Ok(items) if items.is_push_only() => items,
+        Ok(_) => return fail("stack items must be pushes"),
+        Err(e) => return fail(&e.to_string()),
+    };
+
+    // Pushes can still be too large, or too many.
+    let stack = match eval_script(vec![], items.as_bytes(), VerifyFlags::NONE, &NoSignatureChecker, SigVersion::Base) {
+        Ok(stack) => stack,
+        Err(e) => return fail(&e.to_string()),
+    };
+
+    let new_debugger = || Execution::new(&script, stack.clone(), flags, SigVersion::Base, &NoSignatureChecker).map(Debugger::new);
+
+    let mut debugger = match new_debugger() {
+        Ok(debugger) => debugger,
+        Err(e) => return fail(&e.to_string()),
+    };
+
+    println!("{}", debugger);
+
+    let mut history: Vec<Step> = Vec::new();
+    let mut lines = io::stdin().lock().lines();
+
+    loop {
+        print!("> ");
+        io::stdout().flush().unwrap();
+
+        let Some(Ok(line)) = lines.next() else {
+            return ExitCode::SUCCESS;
+        };
+
+        match line.trim() {
+            "" | "s" | "step" => {
+                step(&mut debugger, &mut history);
+            },
+            "r" | "run" => while step(&mut debugger, &mut history) { },
+            "b" | "rewind" => {
+                // Executions can't be undone, so replay all but the last step.
+                history.pop();
+
+                debugger = new_debugger().unwrap();
+                debugger.by_ref().take(history.len()).for_each(drop);
+
+                match history.last() {
+                    Some(step) => println!("{}", step),
+                    None => println!("{}", debugger),
+                }
+            },
+            "q" | "quit" => return ExitCode::SUCCESS,
+            command => println!("unknown command {}\n\n{}", command, USAGE),
+        }
+    }
+}
+
+/// Executes and shows the next opcode, returning whether there are more.
+fn step(debugger: &mut Debugger, history: &mut Vec<Step>) -> bool {
+    match debugger.next() {
+        Some(Ok(step)) => {
+            println!("{}", step);
+            history.push(step);
+
+            true
+        },
+        Some(Err(e)) => {
+            println!("error: {}", e);
+
+            false
+        },
+        None => {
+            println!("at end of script");
+
+            false
+        },
+    }
+}
+
+fn fail(message: &str) -> ExitCode {
+    eprintln!("{}", message);
+
+    ExitCode::FAILURE
 }
@@ -0,0 +1,16 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NetworkError {
+    UnknownNetwork(String),
+}
+
+impl Error for NetworkError { }
+
+impl Display for NetworkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NetworkError::UnknownNetwork(name) => write!(f, "NetworkError::UnknownNetwork({})", name),
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use std::{fmt::Display, str::FromStr};
+use crate::cryptography::hash::digest::BlockHash;
+use self::{error::NetworkError, params::{NetworkParams, MAINNET_PARAMS, REGTEST_PARAMS, SIGNET_PARAMS, TESTNET3_PARAMS, TESTNET4_PARAMS}};
+
+pub mod error;
+pub mod params;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Network {
+    Mainnet,
+    Testnet3,
+    Testnet4,
+    Signet,
+    Regtest,
+}
+
+impl Network {
+    pub const ALL: [Network; 5] = [Network::Mainnet, Network::Testnet3, Network::Testnet4, Network::Signet, Network::Regtest];
+
+    pub fn params(&self) -> &'static NetworkParams {
+        match self {
+            Network::Mainnet => &MAINNET_PARAMS,
+            Network::Testnet3 => &TESTNET3_PARAMS,
+            Network::Testnet4 => &TESTNET4_PARAMS,
+            Network::Signet => &SIGNET_PARAMS,
+            Network::Regtest => &REGTEST_PARAMS,
+        }
+    }
+
+    /// Serialized genesis block: the 80 byte header, a transaction count of
+    ///  one and the coinbase.
+    pub fn genesis_block(&self) -> Vec<u8> {
+        let params = self.params();
+
+        let mut block = hex::decode(params.genesis_header).unwrap();
+        block.push(0x01);
+        block.extend(hex::decode(params.genesis_coinbase).unwrap());
+
+        block
+    }
+
+    pub fn genesis_block_hash(&self) -> BlockHash {
+        BlockHash::hash(&hex::decode(self.params().genesis_header).unwrap())
+    }
+
+    /// Several networks share Base58 and bech32 prefixes, so decoding can
+    ///  only tell which family an encoding belongs to. These return the
+    ///  first network in `ALL` using the prefix; callers that know their
+    ///  network should check with `NetworkParams` instead.
+    pub fn from_p2pkh_prefix(prefix: u8) -> Option<Network> {
+        Self::ALL.into_iter().find(|n| n.params().p2pkh_prefix == prefix)
+    }
+
+    pub fn from_p2sh_prefix(prefix: u8) -> Option<Network> {
+        Self::ALL.into_iter().find(|n| n.params().p2sh_prefix == prefix)
+    }
+
+    pub fn from_bech32_hrp(hrp: &str) -> Option<Network> {
+        Self::ALL.into_iter().find(|n| n.params().bech32_hrp == hrp)
+    }
+
+    pub fn from_wif_prefix(prefix: u8) -> Option<Network> {
+        Self::ALL.into_iter().find(|n| n.params().wif_prefix == prefix)
+    }
+
+    pub fn from_magic(magic: [u8; 4]) -> Option<Network> {
+        Self::ALL.into_iter().find(|n| n.params().magic == magic)
+    }
+}
+
+impl Display for Network {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.params().name)
+    }
+}
+
+impl FromStr for Network {
+    type Err = NetworkError;
+
+    /// Accepts the names used here as well as Bitcoin Core's `-chain` values.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mainnet" | "main" | "bitcoin" => Ok(Network::Mainnet),
+            "testnet3" | "testnet" | "test" => Ok(Network::Testnet3),
+            "testnet4" => Ok(Network::Testnet4),
+            "signet" => Ok(Network::Signet),
+            "regtest" => Ok(Network::Regtest),
+            _ => Err(NetworkError::UnknownNetwork(s.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+    use crate::cryptography::hash::hash256;
+    use super::Network;
+
+    #[test]
+    pub fn test_genesis_block_hash() {
+        let cases = [
+            (Network::Mainnet, "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f"),
+            (Network::Testnet3, "000000000933ea01ad0ee984209779baaec3ced90fa3f408719526f8d77f4943"),
+            (Network::Testnet4, "00000000da84f2bafbbc53dee25a72ae507ff4914b867c565be350b0da8bf043"),
+            (Network::Signet, "00000008819873e925422c1ff0f99f7cc9bbb232af63a077a480a3633bee1ef6"),
+            (Network::Regtest, "0f9188f13cb7b2c71f2a335e3a4fc328bf5beb436012afca590b1a11466e2206"),
+        ];
+
+        for (network, expected) in cases {
+            assert_eq!(network.genesis_block_hash().to_string(), expected);
+        }
+    }
+
+    #[test]
+    pub fn test_genesis_merkle_root() {
+        // With a single transaction the merkle root is the coinbase txid.
+        for network in Network::ALL {
+            let block = network.genesis_block();
+
+            assert_eq!(&block[36..68], &hash256(&block[81..]));
+        }
+    }
+
+    #[test]
+    pub fn test_prefix_lookup() {
+        assert_eq!(Network::from_p2pkh_prefix(0x00), Some(Network::Mainnet));
+        assert_eq!(Network::from_p2sh_prefix(0xc4), Some(Network::Testnet3));
+        assert_eq!(Network::from_bech32_hrp("bcrt"), Some(Network::Regtest));
+        assert_eq!(Network::from_magic([0x1c, 0x16, 0x3f, 0x28]), Some(Network::Testnet4));
+        assert_eq!(Network::from_wif_prefix(0x81), None);
+    }
+
+    #[test]
+    pub fn test_from_str() {
+        for network in Network::ALL {
+            assert_eq!(Network::from_str(&network.to_string()).unwrap(), network);
+        }
+
+        assert_eq!(Network::from_str("main").unwrap(), Network::Mainnet);
+        assert!(Network::from_str("litecoin").is_err());
+    }
+
+    #[test]
+    pub fn test_difficulty_adjustment_interval() {
+        assert_eq!(Network::Mainnet.params().consensus.difficulty_adjustment_interval(), 2016);
+    }
+}
@@ -0,0 +1,204 @@
+use num_bigint::BigUint;
+use crate::block::pow::target_from_bits;
+
+/// Everything that differs between chains, from encoding prefixes to
+///  consensus rules.
+pub struct NetworkParams {
+    pub name: &'static str,
+    pub p2pkh_prefix: u8,
+    pub p2sh_prefix: u8,
+    pub bech32_hrp: &'static str,
+    pub wif_prefix: u8,
+    pub xpub_version: [u8; 4],
+    pub xprv_version: [u8; 4],
+    pub magic: [u8; 4],
+    pub default_port: u16,
+    pub genesis_header: &'static str,
+    pub genesis_coinbase: &'static str,
+    pub consensus: ConsensusParams,
+}
+
+#[derive(Clone)]
+pub struct ConsensusParams {
+    /// Easiest allowed target, in compact `bits` form.
+    pub pow_limit_bits: u32,
+    pub pow_target_timespan: u32,
+    pub pow_target_spacing: u32,
+    /// Testnet's rule allowing a minimum difficulty block after 20 minutes.
+    pub pow_allow_min_difficulty_blocks: bool,
+    pub pow_no_retargeting: bool,
+    /// Testnet4's fix for the timewarp and block storm attacks.
+    pub enforce_bip94: bool,
+    pub subsidy_halving_interval: u32,
+    pub bip34_height: u32,
+    pub bip65_height: u32,
+    pub bip66_height: u32,
+    pub csv_height: u32,
+    pub segwit_height: u32,
+}
+
+impl ConsensusParams {
+    /// Blocks between difficulty adjustments, 2016 on every network.
+    pub fn difficulty_adjustment_interval(&self) -> u32 {
+        self.pow_target_timespan / self.pow_target_spacing
+    }
+
+    /// `pow_limit_bits` expanded.
+    pub fn pow_limit(&self) -> BigUint {
+        target_from_bits(self.pow_limit_bits).unwrap()
+    }
+}
+
+const TWO_WEEKS: u32 = 14 * 24 * 60 * 60;
+const TEN_MINUTES: u32 = 10 * 60;
+
+const MAINNET_XPUB: [u8; 4] = [0x04, 0x88, 0xb2, 0x1e];
+const MAINNET_XPRV: [u8; 4] = [0x04, 0x88, 0xad, 0xe4];
+const TESTNET_XPUB: [u8; 4] = [0x04, 0x35, 0x87, 0xcf];
+const TESTNET_XPRV: [u8; 4] = [0x04, 0x35, 0x83, 0x94];
+
+// "The Times 03/Jan/2009 Chancellor on brink of second bailout for banks",
+//  shared by every network except testnet4.
+const GENESIS_COINBASE: &str = "01000000010000000000000000000000000000000000000000000000000000000000000000ffffffff4d04ffff001d0104455468652054696d65732030332f4a616e2f32303039204368616e63656c6c6f72206f6e206272696e6b206f66207365636f6e64206261696c6f757420666f722062616e6b73ffffffff0100f2052a01000000434104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac00000000";
+const TESTNET4_GENESIS_COINBASE: &str = "01000000010000000000000000000000000000000000000000000000000000000000000000ffffffff5504ffff001d01044c4c30332f4d61792f323032342030303030303030303030303030303030303030303165626435386332343439373062336161396437383362623030313031316662653865613865393865303065ffffffff0100f2052a010000002321000000000000000000000000000000000000000000000000000000000000000000ac00000000";
+
+pub const MAINNET_PARAMS: NetworkParams = NetworkParams {
+    name: "mainnet",
+    p2pkh_prefix: 0x00,
+    p2sh_prefix: 0x05,
+    bech32_hrp: "bc",
+    wif_prefix: 0x80,
+    xpub_version: MAINNET_XPUB,
+    xprv_version: MAINNET_XPRV,
+    magic: [0xf9, 0xbe, 0xb4, 0xd9],
+    default_port: 8333,
+    genesis_header: "0100000000000000000000000000000000000000000000000000000000000000000000003ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa4b1e5e4a29ab5f49ffff001d1dac2b7c",
+    genesis_coinbase: GENESIS_COINBASE,
+    consensus: ConsensusParams {
+        pow_limit_bits: 0x1d00ffff,
+        pow_target_timespan: TWO_WEEKS,
+        pow_target_spacing: TEN_MINUTES,
+        pow_allow_min_difficulty_blocks: false,
+        pow_no_retargeting: false,
+        enforce_bip94: false,
+        subsidy_halving_interval: 210_000,
+        bip34_height: 227_931,
+        bip65_height: 388_381,
+        bip66_height: 363_725,
+        csv_height: 419_328,
+        segwit_height: 481_824,
+    },
+};
+
+pub const TESTNET3_PARAMS: NetworkParams = NetworkParams {
+    name: "testnet3",
+    p2pkh_prefix: 0x6f,
+    p2sh_prefix: 0xc4,
+    bech32_hrp: "tb",
+    wif_prefix: 0xef,
+    xpub_version: TESTNET_XPUB,
+    xprv_version: TESTNET_XPRV,
+    magic: [0x0b, 0x11, 0x09, 0x07],
+    default_port: 18333,
+    genesis_header: "0100000000000000000000000000000000000000000000000000000000000000000000003ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa4b1e5e4adae5494dffff001d1aa4ae18",
+    genesis_coinbase: GENESIS_COINBASE,
+    consensus: ConsensusParams {
+        pow_limit_bits: 0x1d00ffff,
+        pow_target_timespan: TWO_WEEKS,
+        pow_target_spacing: TEN_MINUTES,
+        pow_allow_min_difficulty_blocks: true,
+        pow_no_retargeting: false,
+        enforce_bip94: false,
+        subsidy_halving_interval: 210_000,
+        bip34_height: 21_111,
+        bip65_height: 581_885,
+        bip66_height: 330_776,
+        csv_height: 770_112,
+        segwit_height: 834_624,
+    },
+};
+
+pub const TESTNET4_PARAMS: NetworkParams = NetworkParams {
+    name: "testnet4",
+    p2pkh_prefix: 0x6f,
+    p2sh_prefix: 0xc4,
+    bech32_hrp: "tb",
+    wif_prefix: 0xef,
+    xpub_version: TESTNET_XPUB,
+    xprv_version: TESTNET_XPRV,
+    magic: [0x1c, 0x16, 0x3f, 0x28],
+    default_port: 48333,
+    genesis_header: "0100000000000000000000000000000000000000000000000000000000000000000000004e7b2b9128fe0291db0693af2ae418b767e657cd407e80cb1434221eaea7a07a046f3566ffff001dbb0c7817",
+    genesis_coinbase: TESTNET4_GENESIS_COINBASE,
+    consensus: ConsensusParams {
+        pow_limit_bits: 0x1d00ffff,
+        pow_target_timespan: TWO_WEEKS,
+        pow_target_spacing: TEN_MINUTES,
+        pow_allow_min_difficulty_blocks: true,
+        pow_no_retargeting: false,
+        enforce_bip94: true,
+        subsidy_halving_interval: 210_000,
+        bip34_height: 1,
+        bip65_height: 1,
+        bip66_height: 1,
+        csv_height: 1,
+        segwit_height: 1,
+    },
+};
+
+pub const SIGNET_PARAMS: NetworkParams = NetworkParams {
+    name: "signet",
+    p2pkh_prefix: 0x6f,
+    p2sh_prefix: 0xc4,
+    bech32_hrp: "tb",
+    wif_prefix: 0xef,
+    xpub_version: TESTNET_XPUB,
+    xprv_version: TESTNET_XPRV,
+    // Magic of the default signet, custom signets derive theirs from the challenge.
+    magic: [0x0a, 0x03, 0xcf, 0x40],
+    default_port: 38333,
+    genesis_header: "0100000000000000000000000000000000000000000000000000000000000000000000003ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa4b1e5e4a008f4d5fae77031e8ad22203",
+    genesis_coinbase: GENESIS_COINBASE,
+    consensus: ConsensusParams {
+        pow_limit_bits: 0x1e0377ae,
+        pow_target_timespan: TWO_WEEKS,
+        pow_target_spacing: TEN_MINUTES,
+        pow_allow_min_difficulty_blocks: false,
+        pow_no_retargeting: false,
+        enforce_bip94: false,
+        subsidy_halving_interval: 210_000,
+        bip34_height: 1,
+        bip65_height: 1,
+        bip66_height: 1,
+        csv_height: 1,
+        segwit_height: 1,
+    },
+};
+
+pub const REGTEST_PARAMS: NetworkParams = NetworkParams {
+    name: "regtest",
+    p2pkh_prefix: 0x6f,
+    p2sh_prefix: 0xc4,
+    bech32_hrp: "bcrt",
+    wif_prefix: 0xef,
+    xpub_version: TESTNET_XPUB,
+    xprv_version: TESTNET_XPRV,
+    magic: [0xfa, 0xbf, 0xb5, 0xda],
+    default_port: 18444,
+    genesis_header: "0100000000000000000000000000000000000000000000000000000000000000000000003ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa4b1e5e4adae5494dffff7f2002000000",
+    genesis_coinbase: GENESIS_COINBASE,
+    consensus: ConsensusParams {
+        pow_limit_bits: 0x207fffff,
+        pow_target_timespan: TWO_WEEKS,
+        pow_target_spacing: TEN_MINUTES,
+        pow_allow_min_difficulty_blocks: true,
+        pow_no_retargeting: true,
+        enforce_bip94: false,
+        subsidy_halving_interval: 150,
+        bip34_height: 1,
+        bip65_height: 1,
+        bip66_height: 1,
+        csv_height: 1,
+        segwit_height: 0,
+    },
+};
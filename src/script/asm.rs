@@ -0,0 +1,62 @@
+use super::{error::ScriptError, instruction::{Instruction, Instructions}, num::{decode_num, MAX_NUM_SIZE}, opcode::Opcode, Script};
+
+/// Formats `script` like Bitcoin Core's `ScriptToAsmStr`, which is what
+///  `decodescript` shows as `asm`: pushes of up to four bytes as decimal
+///  script numbers, longer ones in hex, and `[error]` for a malformed tail.
+pub(super) fn format(script: &[u8], f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    for (index, instruction) in Instructions::new(script).enumerate() {
+        if index > 0 {
+            write!(f, " ")?;
+        }
+
+        match instruction {
+            Ok(Instruction::Push(_, data)) if data.len() <= MAX_NUM_SIZE => write!(f, "{}", decode_num(data, MAX_NUM_SIZE, false).unwrap())?,
+            Ok(Instruction::Push(_, data)) => write!(f, "{}", hex::encode(data))?,
+            Ok(Instruction::Op(Opcode::OP_1NEGATE)) => write!(f, "-1")?,
+            Ok(Instruction::Op(opcode)) => match opcode.small_int() {
+                Some(n) => write!(f, "{}", n)?,
+                None => write!(f, "{}", opcode.name())?,
+            },
+            Err(_) => write!(f, "[error]")?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses the output of `format`, accepting opcode names with or without
+///  their `OP_` prefix. Numbers are pushed minimally, so non-minimal pushes
+///  do not survive a round trip. Ten-digit tokens are ambiguous between a
+///  number and five bytes of hex; they are read as a number if they fit in
+///  four bytes, as `format` would have printed such a push.
+pub(super) fn parse(s: &str) -> Result<Script, ScriptError> {
+    let mut script = Script::new();
+
+    for token in s.split_whitespace() {
+        if let Some(value) = parse_number(token) {
+            script.push_int(value);
+        } else if let Some(opcode) = Opcode::from_name(token) {
+            script.push_opcode(opcode);
+        } else if token.len() > 2 * MAX_NUM_SIZE && token.len() % 2 == 0 {
+            let data = hex::decode(token).map_err(|_| ScriptError::InvalidAsmToken(token.to_string()))?;
+
+            script.push_slice(&data);
+        } else {
+            return Err(ScriptError::InvalidAsmToken(token.to_string()));
+        }
+    }
+
+    Ok(script)
+}
+
+fn parse_number(token: &str) -> Option<i64> {
+    let digits = token.strip_prefix('-').unwrap_or(token);
+
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) || (digits.len() > 1 && digits.starts_with('0')) {
+        return None;
+    }
+
+    let value: i64 = token.parse().ok()?;
+
+    (value.unsigned_abs() <= i32::MAX as u64).then_some(value)
+}
@@ -0,0 +1,24 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptError {
+    UnexpectedEnd(usize),
+    NumberOverflow(usize),
+    NonMinimalNumber,
+    InvalidAsmToken(String),
+    InvalidMultisig(usize, usize),
+}
+
+impl Error for ScriptError { }
+
+impl Display for ScriptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScriptError::UnexpectedEnd(position) => write!(f, "ScriptError::UnexpectedEnd(push at {} runs past the end of the script)", position),
+            ScriptError::NumberOverflow(len) => write!(f, "ScriptError::NumberOverflow({} bytes)", len),
+            ScriptError::NonMinimalNumber => write!(f, "ScriptError::NonMinimalNumber"),
+            ScriptError::InvalidAsmToken(token) => write!(f, "ScriptError::InvalidAsmToken({})", token),
+            ScriptError::InvalidMultisig(required, keys) => write!(f, "ScriptError::InvalidMultisig({} of {} keys)", required, keys),
+        }
+    }
+}
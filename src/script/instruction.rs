@@ -0,0 +1,190 @@
+use super::{error::ScriptError, opcode::Opcode};
+
+/// One parsed opcode. Pushes carry the data they push along with the
+///  opcode, since how the data was encoded matters for minimality rules.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Instruction<'a> {
+    Push(Opcode, &'a [u8]),
+    Op(Opcode),
+}
+
+impl<'a> Instruction<'a> {
+    pub fn opcode(&self) -> Opcode {
+        match self {
+            Instruction::Push(opcode, _) | Instruction::Op(opcode) => *opcode,
+        }
+    }
+
+    pub fn push_data(&self) -> Option<&'a [u8]> {
+        match self {
+            Instruction::Push(_, data) => Some(data),
+            Instruction::Op(_) => None,
+        }
+    }
+
+    /// Whether a push uses the shortest way of pushing its data, as Core's
+    ///  MINIMALDATA requires: OP_0, OP_1NEGATE or OP_1 through OP_16 where
+    ///  they apply, then a direct push, then the smallest OP_PUSHDATA.
+    pub fn is_minimal_push(&self) -> bool {
+        let (opcode, data) = match self {
+            Instruction::Push(opcode, data) => (*opcode, *data),
+            Instruction::Op(_) => return true,
+        };
+
+        match data {
+            [] => opcode == Opcode::OP_0,
+            [byte @ 1..=16] => opcode == Opcode::from_small_int(*byte).unwrap(),
+            [0x81] => opcode == Opcode::OP_1NEGATE,
+            _ if data.len() <= 0x4b => opcode.to_u8() as usize == data.len(),
+            _ if data.len() <= 0xff => opcode == Opcode::OP_PUSHDATA1,
+            _ if data.len() <= 0xffff => opcode == Opcode::OP_PUSHDATA2,
+            _ => true,
+        }
+    }
+}
+
+/// Iterates over the instructions of a script. A push running past the end
+///  of the script yields one error and ends the iteration, leaving the
+///  malformed tail in `remaining` for callers that, like Core, keep it.
+#[derive(Clone, Debug)]
+pub struct Instructions<'a> {
+    script: &'a [u8],
+    position: usize,
+    finished: bool,
+}
+
+impl<'a> Instructions<'a> {
+    pub fn new(script: &'a [u8]) -> Self {
+        Self {
+            script,
+            position: 0,
+            finished: false,
+        }
+    }
+
+    /// Offset of the next instruction, or of the malformed push once
+    ///  iteration has failed.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.script[self.position..]
+    }
+
+    fn read_push(&self, opcode: Opcode) -> Option<(&'a [u8], usize)> {
+        let start = self.position + 1;
+
+        let (len, start) = match opcode {
+            Opcode::OP_PUSHDATA1 => (*self.script.get(start)? as usize, start + 1),
+            Opcode::OP_PUSHDATA2 => (u16::from_le_bytes(self.script.get(start..start + 2)?.try_into().unwrap()) as usize, start + 2),
+            Opcode::OP_PUSHDATA4 => (u32::from_le_bytes(self.script.get(start..start + 4)?.try_into().unwrap()) as usize, start + 4),
+            _ => (opcode.to_u8() as usize, start),
+        };
+
+        let end = start.checked_add(len)?;
+
+        Some((self.script.get(start..end)?, end))
+    }
+}
+
+impl<'a> Iterator for Instructions<'a> {
+    type Item = Result<Instruction<'a>, ScriptError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let Some(byte) = self.script.get(self.position) else {
+            self.finished = true;
+            return None;
+        };
+
+        let opcode = Opcode::from_u8(*byte);
+
+        if !opcode.is_push() {
+            self.position += 1;
+            return Some(Ok(Instruction::Op(opcode)));
+        }
+
+        match self.read_push(opcode) {
+            Some((data, next)) => {
+                self.position = next;
+                Some(Ok(Instruction::Push(opcode, data)))
+            },
+            None => {
+                self.finished = true;
+                Some(Err(ScriptError::UnexpectedEnd(self.position)))
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::script::{error::ScriptError, opcode::Opcode};
+    use super::{Instruction, Instructions};
+
+    #[test]
+    pub fn test_instructions() {
+        let script = hex::decode("76a91489abcdefabbaabbaabbaabbaabbaabbaabbaabba88ac").unwrap();
+        let instructions: Vec<_> = Instructions::new(&script).collect::<Result<_, _>>().unwrap();
+
+        assert_eq!(instructions, [
+            Instruction::Op(Opcode::OP_DUP),
+            Instruction::Op(Opcode::OP_HASH160),
+            Instruction::Push(Opcode::from_u8(0x14), &script[3..23]),
+            Instruction::Op(Opcode::OP_EQUALVERIFY),
+            Instruction::Op(Opcode::OP_CHECKSIG),
+        ]);
+
+        let script = hex::decode("004c02abcd4d0100ff4e01000000ee").unwrap();
+        let data: Vec<_> = Instructions::new(&script).map(|instruction| instruction.unwrap().push_data().unwrap()).collect();
+
+        assert_eq!(data, [&[][..], &[0xab, 0xcd], &[0xff], &[0xee]]);
+    }
+
+    #[test]
+    pub fn test_malformed_tail() {
+        for (script, position) in [("51024c", 1), ("ac4c", 1), ("4d01", 0), ("754e0500000001", 1), ("4effffffff00", 0)] {
+            let script = hex::decode(script).unwrap();
+            let mut instructions = Instructions::new(&script);
+
+            while let Some(Ok(_)) = instructions.next() { }
+
+            assert_eq!(instructions.position(), position);
+            assert_eq!(instructions.remaining(), &script[position..]);
+            assert!(instructions.next().is_none());
+
+            let error = Instructions::new(&script).find_map(Result::err);
+            assert_eq!(error, Some(ScriptError::UnexpectedEnd(position)));
+        }
+    }
+
+    #[test]
+    pub fn test_minimal_push() {
+        let minimal = ["00", "4f", "51", "60", "0100", "0111", "0180", "4b"];
+        let non_minimal = ["0101", "0110", "0181", "4c0111", "4c00", "4d0100ff", "4e01000000ff"];
+
+        for script in minimal.iter().chain(non_minimal.iter()) {
+            let mut bytes = hex::decode(script).unwrap();
+
+            if bytes == [0x4b] {
+                bytes.extend_from_slice(&[0x22; 0x4b]);
+            }
+
+            let instruction = Instructions::new(&bytes).next().unwrap().unwrap();
+
+            assert_eq!(instruction.is_minimal_push(), minimal.contains(script), "{}", script);
+        }
+
+        let mut pushdata1 = vec![0x4c, 0x4c];
+        pushdata1.extend_from_slice(&[0x22; 0x4c]);
+        assert!(Instructions::new(&pushdata1).next().unwrap().unwrap().is_minimal_push());
+
+        let mut pushdata2 = vec![0x4d, 0xff, 0x00];
+        pushdata2.extend_from_slice(&[0x22; 0xff]);
+        assert!(!Instructions::new(&pushdata2).next().unwrap().unwrap().is_minimal_push());
+    }
+}
@@ -0,0 +1,206 @@
+use num_bigint::BigUint;
+use crate::{cryptography::{elliptic_curve::{bitcoin_point::BitcoinPoint, point::Point}, schnorr::{self, SchnorrSignature}, signature::Signature, BITCOIN_SECP256K1_CONFIG}, serialization::{der::deserialize::DeserializeDER, sec::deserialize::DeserializeSEC}, transaction::{sighash::{error::SighashError, legacy::legacy_sighash, taproot::{Prevouts, ScriptPath}, SighashCache, SighashType}, tx_in::{SEQUENCE_FINAL, SEQUENCE_LOCKTIME_DISABLE_FLAG, SEQUENCE_LOCKTIME_MASK, SEQUENCE_LOCKTIME_TYPE_FLAG}, tx_out::TxOut, Transaction, LOCKTIME_THRESHOLD}};
+use super::{error::InterpreterError, SigVersion};
+
+/// What the interpreter needs from the transaction being validated. Every
+///  check fails by default, which is how scripts are evaluated outside of
+///  a transaction.
+pub trait SignatureChecker {
+    /// `signature` still carries its sighash byte and `script_code` is the
+    ///  executed script from the last OP_CODESEPARATOR on.
+    fn check_ecdsa_signature(&self, _signature: &[u8], _public_key: &[u8], _script_code: &[u8], _sig_version: SigVersion) -> bool {
+        false
+    }
+
+    /// `signature` is 64 bytes, or 65 with its sighash byte. `script_path`
+    ///  is `None` for key-path spends. Unlike ECDSA a failure is an error,
+    ///  as only empty signatures may fail.
+    fn check_schnorr_signature(&self, _signature: &[u8], _public_key: &[u8; 32], _annex: Option<&[u8]>, _script_path: Option<&ScriptPath>) -> Result<(), InterpreterError> {
+        Err(InterpreterError::SchnorrSig)
+    }
+
+    fn check_lock_time(&self, _lock_time: i64) -> bool {
+        false
+    }
+
+    fn check_sequence(&self, _sequence: i64) -> bool {
+        false
+    }
+}
+
+/// Checker for scripts evaluated without a transaction.
+pub struct NoSignatureChecker;
+
+impl SignatureChecker for NoSignatureChecker { }
+
+/// Checks signatures against input `input_index` of a transaction, which
+///  spends an output worth `value`.
+pub struct TransactionSignatureChecker<'a> {
+    cache: SighashCache<'a>,
+    input_index: usize,
+    value: u64,
+    prevouts: Option<&'a [TxOut]>,
+}
+
+impl<'a> TransactionSignatureChecker<'a> {
+    /// Checker for legacy and segwit v0 inputs. Taproot signatures commit to
+    ///  every spent output and always fail without them.
+    pub fn new(tx: &'a Transaction, input_index: usize, value: u64) -> Self {
+        Self {
+            cache: SighashCache::new(tx),
+            input_index,
+            value,
+            prevouts: None,
+        }
+    }
+
+    /// Checker for any input, given the outputs spent by every input of
+    ///  `tx` in order.
+    pub fn with_prevouts(tx: &'a Transaction, input_index: usize, prevouts: &'a [TxOut]) -> Self {
+        Self {
+            cache: SighashCache::new(tx),
+            input_index,
+            value: prevouts.get(input_index).map_or(0, |prevout| prevout.value),
+            prevouts: Some(prevouts),
+        }
+    }
+
+    fn tx(&self) -> &Transaction {
+        self.cache.transaction()
+    }
+}
+
+impl SignatureChecker for TransactionSignatureChecker<'_> {
+    fn check_ecdsa_signature(&self, signature: &[u8], public_key: &[u8], script_code: &[u8], sig_version: SigVersion) -> bool {
+        let Some(public_key) = parse_public_key(public_key) else {
+            return false;
+        };
+
+        let Some((sighash_type, der)) = signature.split_last() else {
+            return false;
+        };
+
+        let Ok(signature) = Signature::deserialize_der_lax(der) else {
+            return false;
+        };
+
+        let sighash_type = SighashType::from_u32(*sighash_type as u32);
+
+        let sighash = match sig_version {
+            SigVersion::Base => legacy_sighash(self.tx(), self.input_index, script_code, sighash_type),
+            SigVersion::WitnessV0 => self.cache.segwit_v0_sighash(self.input_index, script_code, self.value, sighash_type),
+            SigVersion::Taproot | SigVersion::Tapscript => return false,
+        };
+
+        match sighash {
+            Ok(sighash) => verify_ecdsa(&public_key, &sighash.to_z(), signature),
+            Err(_) => false,
+        }
+    }
+
+    fn check_schnorr_signature(&self, signature: &[u8], public_key: &[u8; 32], annex: Option<&[u8]>, script_path: Option<&ScriptPath>) -> Result<(), InterpreterError> {
+        let (signature, sighash_type) = match signature {
+            [signature @ .., sighash_type] if signature.len() == 64 => {
+                // DEFAULT is only ever implied by leaving the byte off.
+                if *sighash_type == SighashType::DEFAULT.to_u32() as u8 {
+                    return Err(InterpreterError::SchnorrSigHashtype);
+                }
+
+                (signature, SighashType::from_u32(*sighash_type as u32))
+            },
+            signature if signature.len() == 64 => (signature, SighashType::DEFAULT),
+            _ => return Err(InterpreterError::SchnorrSigSize),
+        };
+
+        let prevouts = self.prevouts.ok_or(InterpreterError::SchnorrSig)?;
+
+        let sighash = self.cache.taproot_sighash(self.input_index, &Prevouts::All(prevouts), annex, script_path, sighash_type).map_err(|e| match e {
+            SighashError::InvalidSighashType(_) | SighashError::SingleWithoutOutput(..) => InterpreterError::SchnorrSigHashtype,
+            _ => InterpreterError::SchnorrSig,
+        })?;
+
+        let signature = SchnorrSignature::from_slice(signature).ok_or(InterpreterError::SchnorrSigSize)?;
+
+        match schnorr::verify(public_key, sighash.as_bytes(), &signature) {
+            true => Ok(()),
+            false => Err(InterpreterError::SchnorrSig),
+        }
+    }
+
+    fn check_lock_time(&self, lock_time: i64) -> bool {
+        let tx_lock_time = self.tx().lock_time as i64;
+        let threshold = LOCKTIME_THRESHOLD as i64;
+
+        // Heights can only be compared with heights and times with times.
+        if (tx_lock_time < threshold) != (lock_time < threshold) {
+            return false;
+        }
+
+        if lock_time > tx_lock_time {
+            return false;
+        }
+
+        // A final input makes the transaction final whatever its locktime,
+        //  which would bypass the check.
+        self.tx().inputs.get(self.input_index).is_some_and(|input| input.sequence != SEQUENCE_FINAL)
+    }
+
+    fn check_sequence(&self, sequence: i64) -> bool {
+        let Some(tx_sequence) = self.tx().inputs.get(self.input_index).map(|input| input.sequence) else {
+            return false;
+        };
+
+        // BIP68 only applies from version 2 on.
+        if (self.tx().version as u32) < 2 {
+            return false;
+        }
+
+        if tx_sequence & SEQUENCE_LOCKTIME_DISABLE_FLAG != 0 {
+            return false;
+        }
+
+        let mask = (SEQUENCE_LOCKTIME_TYPE_FLAG | SEQUENCE_LOCKTIME_MASK) as i64;
+        let tx_sequence = tx_sequence as i64 & mask;
+        let sequence = sequence & mask;
+        let type_flag = SEQUENCE_LOCKTIME_TYPE_FLAG as i64;
+
+        if (tx_sequence < type_flag) != (sequence < type_flag) {
+            return false;
+        }
+
+        sequence <= tx_sequence
+    }
+}
+
+/// Parses a public key the way consensus does, which unlike SEC accepts
+///  the hybrid encodings 0x06 and 0x07 that carry both coordinates along
+///  with the parity of y.
+pub(crate) fn parse_public_key(bytes: &[u8]) -> Option<BitcoinPoint> {
+    match bytes {
+        [0x06 | 0x07, rest @ ..] if rest.len() == 64 => {
+            if rest[63] & 1 != bytes[0] & 1 {
+                return None;
+            }
+
+            let uncompressed = [&[0x04], rest].concat();
+
+            BitcoinPoint::deserialize_sec(&uncompressed[..]).ok()
+        },
+        _ => BitcoinPoint::deserialize_sec(bytes).ok(),
+    }
+}
+
+/// ECDSA verification of `signature` over `z`. High S values verify too,
+///  rejecting them is left to LOW_S.
+pub(crate) fn verify_ecdsa(public_key: &BitcoinPoint, z: &BigUint, signature: Signature) -> bool {
+    let n = BigUint::from_slice(&BITCOIN_SECP256K1_CONFIG.n);
+    let zero = BigUint::from(0u32);
+
+    if *signature.r() == zero || *signature.s() == zero || *signature.r() >= n || *signature.s() >= n {
+        return false;
+    }
+
+    let point: Point = public_key.clone().into();
+
+    point.verify_signature(z, signature).unwrap_or(false)
+}
@@ -0,0 +1,236 @@
+use std::fmt::Display;
+use crate::script::{instruction::Instructions, Script};
+use super::{error::InterpreterError, execution::Execution};
+
+/// Width of each column in the table views.
+const COLUMN_WIDTH: usize = 40;
+
+/// One executed opcode and the state of the execution right after it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    /// Offset of the opcode in the script.
+    pub position: usize,
+    /// The opcode along with any data it pushes.
+    pub instruction: Script,
+    /// False for opcodes skipped in an unexecuted IF branch.
+    pub executed: bool,
+    pub stack: Vec<Vec<u8>>,
+    pub alt_stack: Vec<Vec<u8>>,
+    pub conditions: Vec<bool>,
+    pub remaining: Script,
+}
+
+/// Runs an `Execution` one opcode at a time, yielding a `Step` for each.
+///  A failing opcode yields its error and ends the iteration, as does a
+///  script ending inside an IF.
+pub struct Debugger<'a> {
+    execution: Execution<'a>,
+    finished: bool,
+}
+
+impl<'a> Debugger<'a> {
+    pub fn new(execution: Execution<'a>) -> Self {
+        Self {
+            execution,
+            finished: false,
+        }
+    }
+
+    pub fn execution(&self) -> &Execution<'a> {
+        &self.execution
+    }
+}
+
+impl Iterator for Debugger<'_> {
+    type Item = Result<Step, InterpreterError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let position = self.execution.position();
+        let remaining = self.execution.remaining();
+        let executed = self.execution.conditions().iter().all(|condition| *condition);
+
+        match self.execution.step() {
+            Ok(Some(_)) => Some(Ok(Step {
+                position,
+                instruction: Script::from(&remaining[..self.execution.position() - position]),
+                executed,
+                stack: self.execution.stack().to_vec(),
+                alt_stack: self.execution.alt_stack().to_vec(),
+                conditions: self.execution.conditions().to_vec(),
+                remaining: Script::from(self.execution.remaining()),
+            })),
+            Ok(None) => {
+                self.finished = true;
+
+                (!self.execution.conditions().is_empty()).then_some(Err(InterpreterError::UnbalancedConditional))
+            },
+            Err(e) => {
+                self.finished = true;
+
+                Some(Err(e))
+            },
+        }
+    }
+}
+
+/// The state before the next opcode, laid out like `Step`.
+impl Display for Debugger<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let execution = &self.execution;
+
+        write_state(f, execution.remaining(), execution.stack(), execution.alt_stack(), execution.conditions())
+    }
+}
+
+/// The opcode, then btcdeb's view of the remaining script beside the stack
+///  with its top first.
+impl Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:04} {}", self.position, self.instruction)?;
+
+        if !self.executed {
+            write!(f, " (not executed)")?;
+        }
+
+        writeln!(f)?;
+
+        write_state(f, self.remaining.as_bytes(), &self.stack, &self.alt_stack, &self.conditions)
+    }
+}
+
+fn write_state(f: &mut std::fmt::Formatter<'_>, remaining: &[u8], stack: &[Vec<u8>], alt_stack: &[Vec<u8>], conditions: &[bool]) -> std::fmt::Result {
+    let script = split_instructions(remaining);
+    let stack: Vec<String> = stack.iter().rev().map(|item| format_item(item)).collect();
+
+    writeln!(f, "{:<width$} | {:>width$}", "script", "stack", width = COLUMN_WIDTH)?;
+    writeln!(f, "{}-+-{}", "-".repeat(COLUMN_WIDTH), "-".repeat(COLUMN_WIDTH))?;
+
+    for row in 0..script.len().max(stack.len()) {
+        let left = script.get(row).map_or("", String::as_str);
+        let right = stack.get(row).map_or("", String::as_str);
+
+        writeln!(f, "{:<width$} | {:>width$}", truncate(left), truncate(right), width = COLUMN_WIDTH)?;
+    }
+
+    if !alt_stack.is_empty() {
+        let items: Vec<String> = alt_stack.iter().rev().map(|item| format_item(item)).collect();
+
+        writeln!(f, "altstack: {}", items.join(" "))?;
+    }
+
+    if !conditions.is_empty() {
+        let conditions: Vec<&str> = conditions.iter().map(|condition| if *condition { "true" } else { "false" }).collect();
+
+        writeln!(f, "conditions: {}", conditions.join(" "))?;
+    }
+
+    Ok(())
+}
+
+/// Each instruction's ASM on its own. A malformed tail shows as one
+///  `[error]`.
+fn split_instructions(script: &[u8]) -> Vec<String> {
+    let mut instructions = Instructions::new(script);
+    let mut split = Vec::new();
+    let mut start = 0;
+
+    while let Some(instruction) = instructions.next() {
+        match instruction {
+            Ok(_) => split.push(Script::from(&script[start..instructions.position()]).to_string()),
+            Err(_) => split.push("[error]".to_string()),
+        }
+
+        start = instructions.position();
+    }
+
+    split
+}
+
+fn format_item(item: &[u8]) -> String {
+    match item.is_empty() {
+        true => "<empty>".to_string(),
+        false => hex::encode(item),
+    }
+}
+
+fn truncate(s: &str) -> String {
+    match s.len() > COLUMN_WIDTH {
+        true => format!("{}...", &s[..COLUMN_WIDTH - 3]),
+        false => s.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::script::{interpreter::{checker::NoSignatureChecker, error::InterpreterError, execution::Execution, flags::VerifyFlags, SigVersion}, Script};
+    use super::{Debugger, Step};
+
+    fn steps(asm: &str) -> Vec<Result<Step, InterpreterError>> {
+        let script = Script::from_asm(asm).unwrap().into_bytes();
+        let execution = Execution::new(&script, vec![], VerifyFlags::STANDARD, SigVersion::Base, &NoSignatureChecker).unwrap();
+
+        Debugger::new(execution).collect()
+    }
+
+    #[test]
+    pub fn test_steps() {
+        let steps: Vec<_> = steps("2 3 OP_ADD 5 OP_EQUAL").into_iter().collect::<Result<_, _>>().unwrap();
+
+        let instructions: Vec<_> = steps.iter().map(|step| step.instruction.to_string()).collect();
+        assert_eq!(instructions, ["2", "3", "OP_ADD", "5", "OP_EQUAL"]);
+
+        assert_eq!(steps[1].stack, [vec![2], vec![3]]);
+        assert_eq!(steps[2].stack, [vec![5]]);
+        assert_eq!(steps[2].position, 2);
+        assert_eq!(steps[2].remaining.to_string(), "5 OP_EQUAL");
+        assert_eq!(steps[4].stack, [vec![1]]);
+        assert!(steps[4].remaining.is_empty());
+    }
+
+    #[test]
+    pub fn test_branches() {
+        let steps: Vec<_> = steps("1 OP_TOALTSTACK 0 OP_IF 2 OP_ELSE 3 OP_ENDIF").into_iter().collect::<Result<_, _>>().unwrap();
+
+        assert_eq!(steps[1].alt_stack, [vec![1]]);
+        assert_eq!(steps[3].conditions, [false]);
+        assert!(!steps[4].executed);
+        assert_eq!(steps[5].conditions, [true]);
+        assert!(steps[6].executed);
+        assert_eq!(steps[6].stack, [vec![3]]);
+        assert!(steps[7].conditions.is_empty());
+    }
+
+    #[test]
+    pub fn test_errors_end_iteration() {
+        let failing = steps("1 OP_VERIFY OP_VERIFY 2");
+
+        assert_eq!(failing.len(), 3);
+        assert_eq!(failing[2], Err(InterpreterError::InvalidStackOperation));
+
+        assert_eq!(steps("1 OP_IF").last(), Some(&Err(InterpreterError::UnbalancedConditional)));
+    }
+
+    #[test]
+    pub fn test_view() {
+        let script = Script::from_asm("0 OP_TOALTSTACK 7 OP_DUP OP_IF OP_DROP OP_ENDIF").unwrap().into_bytes();
+        let execution = Execution::new(&script, vec![], VerifyFlags::STANDARD, SigVersion::Base, &NoSignatureChecker).unwrap();
+        let mut debugger = Debugger::new(execution);
+
+        assert_eq!(debugger.to_string().lines().nth(2).unwrap(), format!("{:<40} | {:>40}", "0", ""));
+
+        let step = debugger.nth(4).unwrap().unwrap();
+        let view = step.to_string();
+        let lines: Vec<_> = view.lines().collect();
+
+        assert_eq!(lines[0], "#0004 OP_IF");
+        assert_eq!(lines[1], format!("{:<40} | {:>40}", "script", "stack"));
+        assert_eq!(lines[3], format!("{:<40} | {:>40}", "OP_DROP", "07"));
+        assert_eq!(lines[4], format!("{:<40} | {:>40}", "OP_ENDIF", ""));
+        assert_eq!(lines[5], "altstack: <empty>");
+        assert_eq!(lines[6], "conditions: true");
+    }
+}
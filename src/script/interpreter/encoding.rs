@@ -0,0 +1,192 @@
+use num_bigint::BigUint;
+use crate::{cryptography::{signature::Signature, BITCOIN_SECP256K1_CONFIG}, script::{instruction::Instructions, Script}, serialization::der::deserialize::DeserializeDER, transaction::sighash::SighashType};
+use super::{error::InterpreterError, flags::VerifyFlags, SigVersion};
+
+/// The signature checks DERSIG, LOW_S and STRICTENC add. Empty signatures
+///  always pass, they are the standard way to make a check fail on purpose.
+pub(crate) fn check_signature_encoding(signature: &[u8], flags: VerifyFlags) -> Result<(), InterpreterError> {
+    if signature.is_empty() {
+        return Ok(());
+    }
+
+    let der_flags = VerifyFlags::DERSIG | VerifyFlags::LOW_S | VerifyFlags::STRICTENC;
+
+    if der_flags.to_u32() & flags.to_u32() != 0 && !is_valid_signature_encoding(signature) {
+        return Err(InterpreterError::SigDer);
+    }
+
+    if flags.contains(VerifyFlags::LOW_S) && !is_low_s(&signature[..signature.len() - 1]) {
+        return Err(InterpreterError::SigHighS);
+    }
+
+    if flags.contains(VerifyFlags::STRICTENC) && !is_defined_hashtype(signature) {
+        return Err(InterpreterError::SigHashtype);
+    }
+
+    Ok(())
+}
+
+pub(crate) fn check_public_key_encoding(public_key: &[u8], flags: VerifyFlags, sig_version: SigVersion) -> Result<(), InterpreterError> {
+    if flags.contains(VerifyFlags::STRICTENC) && !is_compressed_or_uncompressed(public_key) {
+        return Err(InterpreterError::PubkeyType);
+    }
+
+    if flags.contains(VerifyFlags::WITNESS_PUBKEYTYPE) && sig_version == SigVersion::WitnessV0 && !is_compressed(public_key) {
+        return Err(InterpreterError::WitnessPubkeyType);
+    }
+
+    Ok(())
+}
+
+/// BIP66's check of a DER signature followed by its sighash byte.
+pub(crate) fn is_valid_signature_encoding(signature: &[u8]) -> bool {
+    // 0x30 [total-length] 0x02 [R-length] [R] 0x02 [S-length] [S] [sighash]
+    let len = signature.len();
+
+    if !(9..=73).contains(&len) || signature[0] != 0x30 || signature[1] as usize != len - 3 {
+        return false;
+    }
+
+    let r_len = signature[3] as usize;
+
+    if 5 + r_len >= len {
+        return false;
+    }
+
+    let s_len = signature[5 + r_len] as usize;
+
+    if r_len + s_len + 7 != len {
+        return false;
+    }
+
+    let r = &signature[4..4 + r_len];
+    let s = &signature[6 + r_len..6 + r_len + s_len];
+
+    signature[2] == 0x02 && is_valid_integer(r) && signature[4 + r_len] == 0x02 && is_valid_integer(s)
+}
+
+/// Non-empty, positive and without padding beyond what keeps it positive.
+fn is_valid_integer(bytes: &[u8]) -> bool {
+    match bytes {
+        [] => false,
+        [first, ..] if first & 0x80 != 0 => false,
+        [0x00, second, ..] => second & 0x80 != 0,
+        _ => true,
+    }
+}
+
+/// Whether S is at most half the group order, which makes the signature
+///  the only one of the pair (r, s), (r, n - s) that is standard.
+fn is_low_s(der: &[u8]) -> bool {
+    let Ok(signature) = Signature::deserialize_der_lax(der) else {
+        return false;
+    };
+
+    let half_n = BigUint::from_slice(&BITCOIN_SECP256K1_CONFIG.n) >> 1;
+
+    *signature.s() <= half_n
+}
+
+fn is_defined_hashtype(signature: &[u8]) -> bool {
+    let Some(sighash_type) = signature.last() else {
+        return false;
+    };
+
+    let base_type = *sighash_type as u32 & !SighashType::ANYONECANPAY;
+
+    (SighashType::ALL.to_u32()..=SighashType::SINGLE.to_u32()).contains(&base_type)
+}
+
+fn is_compressed_or_uncompressed(public_key: &[u8]) -> bool {
+    match public_key.first() {
+        Some(0x04) => public_key.len() == 65,
+        Some(0x02 | 0x03) => public_key.len() == 33,
+        _ => false,
+    }
+}
+
+fn is_compressed(public_key: &[u8]) -> bool {
+    matches!(public_key.first(), Some(0x02 | 0x03)) && public_key.len() == 33
+}
+
+/// Core's `FindAndDelete`: removes every push of `signature` that starts on
+///  an opcode boundary of `script_code`, as legacy signatures cannot sign
+///  themselves. Returns how many were removed.
+pub(crate) fn find_and_delete(script_code: &mut Vec<u8>, signature: &[u8]) -> usize {
+    let mut needle = Script::new();
+    needle.push_slice(signature);
+    let needle = needle.as_bytes();
+
+    let mut result = Vec::with_capacity(script_code.len());
+    let mut found = 0;
+    let mut position = 0;
+    let mut copied_to = 0;
+
+    loop {
+        result.extend_from_slice(&script_code[copied_to..position]);
+
+        while script_code[position..].starts_with(needle) {
+            position += needle.len();
+            found += 1;
+        }
+
+        copied_to = position;
+
+        let mut instructions = Instructions::new(&script_code[position..]);
+
+        match instructions.next() {
+            Some(Ok(_)) => position += instructions.position(),
+            Some(Err(_)) | None => break,
+        }
+    }
+
+    if found > 0 {
+        result.extend_from_slice(&script_code[copied_to..]);
+        *script_code = result;
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod test {
+    use super::{find_and_delete, is_low_s, is_valid_signature_encoding};
+
+    #[test]
+    pub fn test_find_and_delete() {
+        // (script code, signature, result, removed)
+        let cases = [
+            ("0302ff03", "02ff03", "", 1),
+            ("0302ff030302ff03", "02ff03", "", 2),
+            ("510302ff03ac0302ff03", "02ff03", "51ac", 2),
+            // Only pushes starting on an opcode boundary are removed.
+            ("050302ff0300", "02ff03", "050302ff0300", 0),
+            ("0302ff03", "ff", "0302ff03", 0),
+            // The push must be encoded exactly as the signature would be.
+            ("4c0302ff03", "02ff03", "4c0302ff03", 0),
+            // A malformed tail is kept as is.
+            ("0302ff034c", "02ff03", "4c", 1),
+        ];
+
+        for (script_code, signature, expected, removed) in cases {
+            let mut script_code = hex::decode(script_code).unwrap();
+
+            assert_eq!(find_and_delete(&mut script_code, &hex::decode(signature).unwrap()), removed);
+            assert_eq!(hex::encode(script_code), expected);
+        }
+    }
+
+    #[test]
+    pub fn test_signature_encoding() {
+        assert!(is_valid_signature_encoding(&hex::decode("300602010102010101").unwrap()));
+        // Wrong total length, padded R, negative S and a missing sighash byte.
+        assert!(!is_valid_signature_encoding(&hex::decode("300702010102010101").unwrap()));
+        assert!(!is_valid_signature_encoding(&hex::decode("30070202000102010101").unwrap()));
+        assert!(!is_valid_signature_encoding(&hex::decode("300602010102018101").unwrap()));
+        assert!(!is_valid_signature_encoding(&hex::decode("3006020101020101").unwrap()));
+
+        // S of exactly half the group order, and one more.
+        assert!(is_low_s(&hex::decode("302502010102207fffffffffffffffffffffffffffffff5d576e7357a4501ddfe92f46681b20a0").unwrap()));
+        assert!(!is_low_s(&hex::decode("302502010102207fffffffffffffffffffffffffffffff5d576e7357a4501ddfe92f46681b20a1").unwrap()));
+    }
+}
@@ -0,0 +1,158 @@
+use std::{error::Error, fmt::Display};
+use crate::{script::error::ScriptError, transaction::sighash::error::SighashError};
+
+/// Why a script failed, mirroring Bitcoin Core's `ScriptError_t`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InterpreterError {
+    EvalFalse,
+    OpReturn,
+    ScriptSize,
+    PushSize,
+    OpCount,
+    StackSize,
+    SigCount,
+    PubkeyCount,
+    Verify,
+    EqualVerify,
+    CheckMultisigVerify,
+    CheckSigVerify,
+    NumEqualVerify,
+    BadOpcode,
+    DisabledOpcode,
+    InvalidStackOperation,
+    InvalidAltstackOperation,
+    UnbalancedConditional,
+    NegativeLocktime,
+    UnsatisfiedLocktime,
+    SigHashtype,
+    SigDer,
+    MinimalData,
+    SigPushOnly,
+    SigHighS,
+    SigNullDummy,
+    PubkeyType,
+    CleanStack,
+    MinimalIf,
+    NullFail,
+    DiscourageUpgradableNops,
+    DiscourageUpgradableWitnessProgram,
+    WitnessProgramWrongLength,
+    WitnessProgramWitnessEmpty,
+    WitnessProgramMismatch,
+    WitnessMalleated,
+    WitnessMalleatedP2sh,
+    WitnessUnexpected,
+    WitnessPubkeyType,
+    OpCodeSeparator,
+    SigFindAndDelete,
+    SchnorrSigSize,
+    SchnorrSigHashtype,
+    SchnorrSig,
+    TaprootWrongControlSize,
+    TapscriptValidationWeight,
+    TapscriptCheckMultisig,
+    TapscriptMinimalIf,
+    TapscriptEmptyPubkey,
+    DiscourageUpgradableTaprootVersion,
+    DiscourageOpSuccess,
+    DiscourageUpgradablePubkeyType,
+    /// A script number that is too long or, under MINIMALDATA, not
+    ///  minimally encoded.
+    InvalidNumber(ScriptError),
+    /// CLEANSTACK without P2SH and WITNESS, or WITNESS without P2SH,
+    ///  which Core refuses with an assertion.
+    InvalidFlags,
+    /// An input index or prevouts that don't match the transaction.
+    Sighash(SighashError),
+}
+
+impl InterpreterError {
+    /// Core's name for the error, as used in `script_tests.json`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            InterpreterError::EvalFalse => "EVAL_FALSE",
+            InterpreterError::OpReturn => "OP_RETURN",
+            InterpreterError::ScriptSize => "SCRIPT_SIZE",
+            InterpreterError::PushSize => "PUSH_SIZE",
+            InterpreterError::OpCount => "OP_COUNT",
+            InterpreterError::StackSize => "STACK_SIZE",
+            InterpreterError::SigCount => "SIG_COUNT",
+            InterpreterError::PubkeyCount => "PUBKEY_COUNT",
+            InterpreterError::Verify => "VERIFY",
+            InterpreterError::EqualVerify => "EQUALVERIFY",
+            InterpreterError::CheckMultisigVerify => "CHECKMULTISIGVERIFY",
+            InterpreterError::CheckSigVerify => "CHECKSIGVERIFY",
+            InterpreterError::NumEqualVerify => "NUMEQUALVERIFY",
+            InterpreterError::BadOpcode => "BAD_OPCODE",
+            InterpreterError::DisabledOpcode => "DISABLED_OPCODE",
+            InterpreterError::InvalidStackOperation => "INVALID_STACK_OPERATION",
+            InterpreterError::InvalidAltstackOperation => "INVALID_ALTSTACK_OPERATION",
+            InterpreterError::UnbalancedConditional => "UNBALANCED_CONDITIONAL",
+            InterpreterError::NegativeLocktime => "NEGATIVE_LOCKTIME",
+            InterpreterError::UnsatisfiedLocktime => "UNSATISFIED_LOCKTIME",
+            InterpreterError::SigHashtype => "SIG_HASHTYPE",
+            InterpreterError::SigDer => "SIG_DER",
+            InterpreterError::MinimalData => "MINIMALDATA",
+            InterpreterError::SigPushOnly => "SIG_PUSHONLY",
+            InterpreterError::SigHighS => "SIG_HIGH_S",
+            InterpreterError::SigNullDummy => "SIG_NULLDUMMY",
+            InterpreterError::PubkeyType => "PUBKEYTYPE",
+            InterpreterError::CleanStack => "CLEANSTACK",
+            InterpreterError::MinimalIf => "MINIMALIF",
+            InterpreterError::NullFail => "NULLFAIL",
+            InterpreterError::DiscourageUpgradableNops => "DISCOURAGE_UPGRADABLE_NOPS",
+            InterpreterError::DiscourageUpgradableWitnessProgram => "DISCOURAGE_UPGRADABLE_WITNESS_PROGRAM",
+            InterpreterError::WitnessProgramWrongLength => "WITNESS_PROGRAM_WRONG_LENGTH",
+            InterpreterError::WitnessProgramWitnessEmpty => "WITNESS_PROGRAM_WITNESS_EMPTY",
+            InterpreterError::WitnessProgramMismatch => "WITNESS_PROGRAM_MISMATCH",
+            InterpreterError::WitnessMalleated => "WITNESS_MALLEATED",
+            InterpreterError::WitnessMalleatedP2sh => "WITNESS_MALLEATED_P2SH",
+            InterpreterError::WitnessUnexpected => "WITNESS_UNEXPECTED",
+            InterpreterError::WitnessPubkeyType => "WITNESS_PUBKEYTYPE",
+            InterpreterError::OpCodeSeparator => "OP_CODESEPARATOR",
+            InterpreterError::SigFindAndDelete => "SIG_FINDANDDELETE",
+            InterpreterError::SchnorrSigSize => "SCHNORR_SIG_SIZE",
+            InterpreterError::SchnorrSigHashtype => "SCHNORR_SIG_HASHTYPE",
+            InterpreterError::SchnorrSig => "SCHNORR_SIG",
+            InterpreterError::TaprootWrongControlSize => "TAPROOT_WRONG_CONTROL_SIZE",
+            InterpreterError::TapscriptValidationWeight => "TAPSCRIPT_VALIDATION_WEIGHT",
+            InterpreterError::TapscriptCheckMultisig => "TAPSCRIPT_CHECKMULTISIG",
+            InterpreterError::TapscriptMinimalIf => "TAPSCRIPT_MINIMALIF",
+            InterpreterError::TapscriptEmptyPubkey => "TAPSCRIPT_EMPTY_PUBKEY",
+            InterpreterError::DiscourageUpgradableTaprootVersion => "DISCOURAGE_UPGRADABLE_TAPROOT_VERSION",
+            InterpreterError::DiscourageOpSuccess => "DISCOURAGE_OP_SUCCESS",
+            InterpreterError::DiscourageUpgradablePubkeyType => "DISCOURAGE_UPGRADABLE_PUBKEYTYPE",
+            InterpreterError::InvalidNumber(_) => "UNKNOWN_ERROR",
+            InterpreterError::InvalidFlags => "UNKNOWN_ERROR",
+            InterpreterError::Sighash(_) => "UNKNOWN_ERROR",
+        }
+    }
+}
+
+impl Error for InterpreterError { }
+
+impl Display for InterpreterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InterpreterError::InvalidNumber(e) => write!(f, "InterpreterError::InvalidNumber({})", e),
+            InterpreterError::InvalidFlags => write!(f, "InterpreterError::InvalidFlags"),
+            InterpreterError::Sighash(e) => write!(f, "InterpreterError::Sighash({})", e),
+            _ => write!(f, "InterpreterError::{:?}({})", self, self.code()),
+        }
+    }
+}
+
+impl From<ScriptError> for InterpreterError {
+    fn from(value: ScriptError) -> Self {
+        match value {
+            ScriptError::UnexpectedEnd(_) => InterpreterError::BadOpcode,
+            _ => InterpreterError::InvalidNumber(value),
+        }
+    }
+}
+
+impl From<SighashError> for InterpreterError {
+    fn from(value: SighashError) -> Self {
+        InterpreterError::Sighash(value)
+    }
+}
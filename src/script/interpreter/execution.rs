@@ -0,0 +1,749 @@
+use crate::{cryptography::hash::{hash160, hash256, ripemd160, sha1, sha256}, transaction::sighash::taproot::ScriptPath, script::{instruction::{Instruction, Instructions}, num::{cast_to_bool, decode_num, encode_num, MAX_NUM_SIZE}, opcode::Opcode, MAX_SCRIPT_ELEMENT_SIZE, MAX_SCRIPT_SIZE}, transaction::tx_in::SEQUENCE_LOCKTIME_DISABLE_FLAG};
+use super::{checker::SignatureChecker, encoding::{check_public_key_encoding, check_signature_encoding, find_and_delete}, error::InterpreterError, flags::VerifyFlags, SigVersion, TapscriptData};
+
+pub const MAX_OPS_PER_SCRIPT: usize = 201;
+pub const MAX_PUBKEYS_PER_MULTISIG: i64 = 20;
+/// Limit on the combined size of the main and alt stacks.
+pub const MAX_STACK_SIZE: usize = 1000;
+
+/// BIP342: each executed signature check uses up this much of the budget
+///  the witness size buys.
+pub const VALIDATION_WEIGHT_PER_SIGOP_PASSED: i64 = 50;
+
+/// Locktime operands get one more byte than other numbers, so that
+///  timestamps past 2038 fit.
+const LOCKTIME_NUM_SIZE: usize = 5;
+
+/// The state of one script being evaluated, advanced an opcode at a time
+///  with `step`. `eval_script` runs one to completion.
+pub struct Execution<'a> {
+    script: &'a [u8],
+    instructions: Instructions<'a>,
+    flags: VerifyFlags,
+    sig_version: SigVersion,
+    checker: &'a dyn SignatureChecker,
+    stack: Vec<Vec<u8>>,
+    alt_stack: Vec<Vec<u8>>,
+    conditions: Vec<bool>,
+    op_count: usize,
+    /// Offset of the script code signatures commit to, just past the last
+    ///  executed OP_CODESEPARATOR.
+    code_separator: usize,
+    /// Index of the next opcode, counting pushes.
+    opcode_position: u32,
+    /// Opcode index of the last executed OP_CODESEPARATOR, which tapscript
+    ///  signatures commit to instead of the script code.
+    code_separator_position: u32,
+    tapscript: TapscriptData,
+}
+
+impl<'a> Execution<'a> {
+    /// Tapscripts should use `new_tapscript`, as signature checks need the
+    ///  leaf they are in.
+    pub fn new(script: &'a [u8], stack: Vec<Vec<u8>>, flags: VerifyFlags, sig_version: SigVersion, checker: &'a dyn SignatureChecker) -> Result<Self, InterpreterError> {
+        // Tapscripts are only limited by the block size.
+        if matches!(sig_version, SigVersion::Base | SigVersion::WitnessV0) && script.len() > MAX_SCRIPT_SIZE {
+            return Err(InterpreterError::ScriptSize);
+        }
+
+        Ok(Self {
+            script,
+            instructions: Instructions::new(script),
+            flags,
+            sig_version,
+            checker,
+            stack,
+            alt_stack: Vec::new(),
+            conditions: Vec::new(),
+            op_count: 0,
+            code_separator: 0,
+            opcode_position: 0,
+            code_separator_position: u32::MAX,
+            tapscript: TapscriptData::default(),
+        })
+    }
+
+    pub fn new_tapscript(script: &'a [u8], stack: Vec<Vec<u8>>, flags: VerifyFlags, checker: &'a dyn SignatureChecker, tapscript: TapscriptData) -> Self {
+        Self {
+            tapscript,
+            ..Self::new(script, stack, flags, SigVersion::Tapscript, checker).unwrap()
+        }
+    }
+
+    pub fn stack(&self) -> &[Vec<u8>] {
+        &self.stack
+    }
+
+    pub fn alt_stack(&self) -> &[Vec<u8>] {
+        &self.alt_stack
+    }
+
+    /// Whether each enclosing IF branch is being executed, outermost first.
+    pub fn conditions(&self) -> &[bool] {
+        &self.conditions
+    }
+
+    /// Offset in the script of the next opcode.
+    pub fn position(&self) -> usize {
+        self.instructions.position()
+    }
+
+    /// The opcodes still to run, from `position` on.
+    pub fn remaining(&self) -> &'a [u8] {
+        self.instructions.remaining()
+    }
+
+    /// The signature budget left, for tapscripts.
+    pub fn validation_weight_left(&self) -> i64 {
+        self.tapscript.validation_weight_left
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position() >= self.script.len()
+    }
+
+    fn is_executing(&self) -> bool {
+        self.conditions.iter().all(|condition| *condition)
+    }
+
+    /// Executes the next opcode, returning it, or `None` once the script
+    ///  has run out. Opcodes in unexecuted branches are returned too.
+    pub fn step(&mut self) -> Result<Option<Instruction<'a>>, InterpreterError> {
+        let executing = self.is_executing();
+
+        let instruction = match self.instructions.next() {
+            Some(instruction) => instruction?,
+            None => return Ok(None),
+        };
+
+        let opcode = instruction.opcode();
+
+        if instruction.push_data().is_some_and(|data| data.len() > MAX_SCRIPT_ELEMENT_SIZE) {
+            return Err(InterpreterError::PushSize);
+        }
+
+        // Pushes, including OP_RESERVED, do not count towards the limit,
+        //  which tapscript drops in favor of the validation weight.
+        if opcode > Opcode::OP_16 && matches!(self.sig_version, SigVersion::Base | SigVersion::WitnessV0) {
+            self.op_count += 1;
+
+            if self.op_count > MAX_OPS_PER_SCRIPT {
+                return Err(InterpreterError::OpCount);
+            }
+        }
+
+        if opcode.is_disabled() {
+            return Err(InterpreterError::DisabledOpcode);
+        }
+
+        if opcode == Opcode::OP_CODESEPARATOR && self.sig_version == SigVersion::Base && self.flags.contains(VerifyFlags::CONST_SCRIPTCODE) {
+            return Err(InterpreterError::OpCodeSeparator);
+        }
+
+        match instruction {
+            Instruction::Push(_, data) if executing => {
+                if self.flags.contains(VerifyFlags::MINIMALDATA) && !instruction.is_minimal_push() {
+                    return Err(InterpreterError::MinimalData);
+                }
+
+                self.stack.push(data.to_vec());
+            },
+            Instruction::Op(opcode) if executing || (Opcode::OP_IF..=Opcode::OP_ENDIF).contains(&opcode) => self.execute(opcode, executing)?,
+            _ => { },
+        }
+
+        if self.stack.len() + self.alt_stack.len() > MAX_STACK_SIZE {
+            return Err(InterpreterError::StackSize);
+        }
+
+        self.opcode_position += 1;
+
+        Ok(Some(instruction))
+    }
+
+    /// Runs the remaining opcodes and returns the final stack.
+    pub fn run(mut self) -> Result<Vec<Vec<u8>>, InterpreterError> {
+        while self.step()?.is_some() { }
+
+        if !self.conditions.is_empty() {
+            return Err(InterpreterError::UnbalancedConditional);
+        }
+
+        Ok(self.stack)
+    }
+
+    fn require(&self, items: usize) -> Result<(), InterpreterError> {
+        match self.stack.len() < items {
+            true => Err(InterpreterError::InvalidStackOperation),
+            false => Ok(()),
+        }
+    }
+
+    /// The item `depth` places from the top, the top being 1.
+    fn top(&self, depth: usize) -> &Vec<u8> {
+        &self.stack[self.stack.len() - depth]
+    }
+
+    fn pop(&mut self) -> Vec<u8> {
+        self.stack.pop().unwrap()
+    }
+
+    fn num(&self, depth: usize, max_size: usize) -> Result<i64, InterpreterError> {
+        Ok(decode_num(self.top(depth), max_size, self.flags.contains(VerifyFlags::MINIMALDATA))?)
+    }
+
+    fn push_bool(&mut self, value: bool) {
+        self.stack.push(if value { vec![0x01] } else { vec![] });
+    }
+
+    fn execute(&mut self, opcode: Opcode, executing: bool) -> Result<(), InterpreterError> {
+        if opcode == Opcode::OP_1NEGATE {
+            self.stack.push(encode_num(-1));
+            return Ok(());
+        }
+
+        if let Some(n) = opcode.small_int() {
+            self.stack.push(encode_num(n as i64));
+            return Ok(());
+        }
+
+        match opcode {
+            Opcode::OP_NOP => { },
+
+            Opcode::OP_CHECKLOCKTIMEVERIFY => {
+                if !self.flags.contains(VerifyFlags::CHECKLOCKTIMEVERIFY) {
+                    return self.upgradable_nop();
+                }
+
+                self.require(1)?;
+                let lock_time = self.num(1, LOCKTIME_NUM_SIZE)?;
+
+                if lock_time < 0 {
+                    return Err(InterpreterError::NegativeLocktime);
+                }
+
+                if !self.checker.check_lock_time(lock_time) {
+                    return Err(InterpreterError::UnsatisfiedLocktime);
+                }
+            },
+
+            Opcode::OP_CHECKSEQUENCEVERIFY => {
+                if !self.flags.contains(VerifyFlags::CHECKSEQUENCEVERIFY) {
+                    return self.upgradable_nop();
+                }
+
+                self.require(1)?;
+                let sequence = self.num(1, LOCKTIME_NUM_SIZE)?;
+
+                if sequence < 0 {
+                    return Err(InterpreterError::NegativeLocktime);
+                }
+
+                // Left as a NOP for future soft forks.
+                if sequence & SEQUENCE_LOCKTIME_DISABLE_FLAG as i64 != 0 {
+                    return Ok(());
+                }
+
+                if !self.checker.check_sequence(sequence) {
+                    return Err(InterpreterError::UnsatisfiedLocktime);
+                }
+            },
+
+            Opcode::OP_NOP1 | Opcode::OP_NOP4 | Opcode::OP_NOP5 | Opcode::OP_NOP6 | Opcode::OP_NOP7 | Opcode::OP_NOP8 | Opcode::OP_NOP9 | Opcode::OP_NOP10 => {
+                self.upgradable_nop()?;
+            },
+
+            Opcode::OP_IF | Opcode::OP_NOTIF => {
+                let mut value = false;
+
+                if executing {
+                    if self.stack.is_empty() {
+                        return Err(InterpreterError::UnbalancedConditional);
+                    }
+
+                    let top = self.top(1);
+                    let minimal = top.is_empty() || top == &[0x01];
+
+                    // Policy for segwit v0, consensus for tapscript.
+                    if self.sig_version == SigVersion::Tapscript && !minimal {
+                        return Err(InterpreterError::TapscriptMinimalIf);
+                    }
+
+                    if self.sig_version == SigVersion::WitnessV0 && self.flags.contains(VerifyFlags::MINIMALIF) && !minimal {
+                        return Err(InterpreterError::MinimalIf);
+                    }
+
+                    value = cast_to_bool(top) != (opcode == Opcode::OP_NOTIF);
+                    self.pop();
+                }
+
+                self.conditions.push(value);
+            },
+
+            Opcode::OP_ELSE => {
+                let condition = self.conditions.last_mut().ok_or(InterpreterError::UnbalancedConditional)?;
+                *condition = !*condition;
+            },
+
+            Opcode::OP_ENDIF => {
+                self.conditions.pop().ok_or(InterpreterError::UnbalancedConditional)?;
+            },
+
+            Opcode::OP_VERIFY => {
+                self.require(1)?;
+
+                if !cast_to_bool(self.top(1)) {
+                    return Err(InterpreterError::Verify);
+                }
+
+                self.pop();
+            },
+
+            Opcode::OP_RETURN => return Err(InterpreterError::OpReturn),
+
+            Opcode::OP_TOALTSTACK => {
+                self.require(1)?;
+                let item = self.pop();
+                self.alt_stack.push(item);
+            },
+
+            Opcode::OP_FROMALTSTACK => {
+                let item = self.alt_stack.pop().ok_or(InterpreterError::InvalidAltstackOperation)?;
+                self.stack.push(item);
+            },
+
+            Opcode::OP_2DROP => {
+                self.require(2)?;
+                self.pop();
+                self.pop();
+            },
+
+            Opcode::OP_2DUP => {
+                self.require(2)?;
+                let items = self.stack[self.stack.len() - 2..].to_vec();
+                self.stack.extend(items);
+            },
+
+            Opcode::OP_3DUP => {
+                self.require(3)?;
+                let items = self.stack[self.stack.len() - 3..].to_vec();
+                self.stack.extend(items);
+            },
+
+            Opcode::OP_2OVER => {
+                self.require(4)?;
+                let len = self.stack.len();
+                let items = self.stack[len - 4..len - 2].to_vec();
+                self.stack.extend(items);
+            },
+
+            Opcode::OP_2ROT => {
+                self.require(6)?;
+                let len = self.stack.len();
+                let items: Vec<_> = self.stack.drain(len - 6..len - 4).collect();
+                self.stack.extend(items);
+            },
+
+            Opcode::OP_2SWAP => {
+                self.require(4)?;
+                let len = self.stack.len();
+                self.stack.swap(len - 4, len - 2);
+                self.stack.swap(len - 3, len - 1);
+            },
+
+            Opcode::OP_IFDUP => {
+                self.require(1)?;
+
+                if cast_to_bool(self.top(1)) {
+                    self.stack.push(self.top(1).clone());
+                }
+            },
+
+            Opcode::OP_DEPTH => self.stack.push(encode_num(self.stack.len() as i64)),
+
+            Opcode::OP_DROP => {
+                self.require(1)?;
+                self.pop();
+            },
+
+            Opcode::OP_DUP => {
+                self.require(1)?;
+                self.stack.push(self.top(1).clone());
+            },
+
+            Opcode::OP_NIP => {
+                self.require(2)?;
+                let len = self.stack.len();
+                self.stack.remove(len - 2);
+            },
+
+            Opcode::OP_OVER => {
+                self.require(2)?;
+                self.stack.push(self.top(2).clone());
+            },
+
+            Opcode::OP_PICK | Opcode::OP_ROLL => {
+                self.require(2)?;
+                let n = self.num(1, MAX_NUM_SIZE)?;
+                self.pop();
+
+                if n < 0 || n >= self.stack.len() as i64 {
+                    return Err(InterpreterError::InvalidStackOperation);
+                }
+
+                let index = self.stack.len() - 1 - n as usize;
+
+                let item = match opcode {
+                    Opcode::OP_ROLL => self.stack.remove(index),
+                    _ => self.stack[index].clone(),
+                };
+
+                self.stack.push(item);
+            },
+
+            Opcode::OP_ROT => {
+                self.require(3)?;
+                let len = self.stack.len();
+                let item = self.stack.remove(len - 3);
+                self.stack.push(item);
+            },
+
+            Opcode::OP_SWAP => {
+                self.require(2)?;
+                let len = self.stack.len();
+                self.stack.swap(len - 2, len - 1);
+            },
+
+            Opcode::OP_TUCK => {
+                self.require(2)?;
+                let len = self.stack.len();
+                let item = self.top(1).clone();
+                self.stack.insert(len - 2, item);
+            },
+
+            Opcode::OP_SIZE => {
+                self.require(1)?;
+                self.stack.push(encode_num(self.top(1).len() as i64));
+            },
+
+            Opcode::OP_EQUAL | Opcode::OP_EQUALVERIFY => {
+                self.require(2)?;
+                let equal = self.pop() == self.pop();
+                self.push_bool(equal);
+
+                if opcode == Opcode::OP_EQUALVERIFY {
+                    if !equal {
+                        return Err(InterpreterError::EqualVerify);
+                    }
+
+                    self.pop();
+                }
+            },
+
+            Opcode::OP_1ADD | Opcode::OP_1SUB | Opcode::OP_NEGATE | Opcode::OP_ABS | Opcode::OP_NOT | Opcode::OP_0NOTEQUAL => {
+                self.require(1)?;
+                let n = self.num(1, MAX_NUM_SIZE)?;
+
+                let result = match opcode {
+                    Opcode::OP_1ADD => n + 1,
+                    Opcode::OP_1SUB => n - 1,
+                    Opcode::OP_NEGATE => -n,
+                    Opcode::OP_ABS => n.abs(),
+                    Opcode::OP_NOT => (n == 0) as i64,
+                    _ => (n != 0) as i64,
+                };
+
+                self.pop();
+                self.stack.push(encode_num(result));
+            },
+
+            Opcode::OP_ADD | Opcode::OP_SUB | Opcode::OP_BOOLAND | Opcode::OP_BOOLOR | Opcode::OP_NUMEQUAL | Opcode::OP_NUMEQUALVERIFY | Opcode::OP_NUMNOTEQUAL
+                | Opcode::OP_LESSTHAN | Opcode::OP_GREATERTHAN | Opcode::OP_LESSTHANOREQUAL | Opcode::OP_GREATERTHANOREQUAL | Opcode::OP_MIN | Opcode::OP_MAX => {
+                self.require(2)?;
+                let a = self.num(2, MAX_NUM_SIZE)?;
+                let b = self.num(1, MAX_NUM_SIZE)?;
+
+                let result = match opcode {
+                    Opcode::OP_ADD => a + b,
+                    Opcode::OP_SUB => a - b,
+                    Opcode::OP_BOOLAND => (a != 0 && b != 0) as i64,
+                    Opcode::OP_BOOLOR => (a != 0 || b != 0) as i64,
+                    Opcode::OP_NUMEQUAL | Opcode::OP_NUMEQUALVERIFY => (a == b) as i64,
+                    Opcode::OP_NUMNOTEQUAL => (a != b) as i64,
+                    Opcode::OP_LESSTHAN => (a < b) as i64,
+                    Opcode::OP_GREATERTHAN => (a > b) as i64,
+                    Opcode::OP_LESSTHANOREQUAL => (a <= b) as i64,
+                    Opcode::OP_GREATERTHANOREQUAL => (a >= b) as i64,
+                    Opcode::OP_MIN => a.min(b),
+                    _ => a.max(b),
+                };
+
+                self.pop();
+                self.pop();
+                self.stack.push(encode_num(result));
+
+                if opcode == Opcode::OP_NUMEQUALVERIFY {
+                    if !cast_to_bool(self.top(1)) {
+                        return Err(InterpreterError::NumEqualVerify);
+                    }
+
+                    self.pop();
+                }
+            },
+
+            Opcode::OP_WITHIN => {
+                self.require(3)?;
+                let x = self.num(3, MAX_NUM_SIZE)?;
+                let min = self.num(2, MAX_NUM_SIZE)?;
+                let max = self.num(1, MAX_NUM_SIZE)?;
+
+                self.stack.truncate(self.stack.len() - 3);
+                self.push_bool(min <= x && x < max);
+            },
+
+            Opcode::OP_RIPEMD160 | Opcode::OP_SHA1 | Opcode::OP_SHA256 | Opcode::OP_HASH160 | Opcode::OP_HASH256 => {
+                self.require(1)?;
+                let item = self.pop();
+
+                let hash = match opcode {
+                    Opcode::OP_RIPEMD160 => ripemd160(&item).to_vec(),
+                    Opcode::OP_SHA1 => sha1(&item).to_vec(),
+                    Opcode::OP_SHA256 => sha256(&item).to_vec(),
+                    Opcode::OP_HASH160 => hash160(&item).to_vec(),
+                    _ => hash256(&item).to_vec(),
+                };
+
+                self.stack.push(hash);
+            },
+
+            Opcode::OP_CODESEPARATOR => {
+                self.code_separator = self.position();
+                self.code_separator_position = self.opcode_position;
+            },
+
+            Opcode::OP_CHECKSIG | Opcode::OP_CHECKSIGVERIFY => {
+                self.require(2)?;
+                let (signature, public_key) = (self.top(2).clone(), self.top(1).clone());
+
+                let success = match self.sig_version {
+                    SigVersion::Tapscript => self.check_sig_tapscript(&signature, &public_key)?,
+                    _ => self.check_sig(&signature, &public_key)?,
+                };
+
+                self.pop();
+                self.pop();
+                self.push_bool(success);
+
+                if opcode == Opcode::OP_CHECKSIGVERIFY {
+                    if !success {
+                        return Err(InterpreterError::CheckSigVerify);
+                    }
+
+                    self.pop();
+                }
+            },
+
+            Opcode::OP_CHECKSIGADD => {
+                if matches!(self.sig_version, SigVersion::Base | SigVersion::WitnessV0) {
+                    return Err(InterpreterError::BadOpcode);
+                }
+
+                // <sig> <n> <pubkey>, leaving n + 1 if the signature is valid.
+                self.require(3)?;
+                let n = self.num(2, MAX_NUM_SIZE)?;
+                let (signature, public_key) = (self.top(3).clone(), self.top(1).clone());
+                let success = self.check_sig_tapscript(&signature, &public_key)?;
+
+                self.stack.truncate(self.stack.len() - 3);
+                self.stack.push(encode_num(n + success as i64));
+            },
+
+            Opcode::OP_CHECKMULTISIG | Opcode::OP_CHECKMULTISIGVERIFY => {
+                // Replaced by OP_CHECKSIGADD, which allows batch verification.
+                if self.sig_version == SigVersion::Tapscript {
+                    return Err(InterpreterError::TapscriptCheckMultisig);
+                }
+
+                let success = self.check_multisig()?;
+                self.push_bool(success);
+
+                if opcode == Opcode::OP_CHECKMULTISIGVERIFY {
+                    if !success {
+                        return Err(InterpreterError::CheckMultisigVerify);
+                    }
+
+                    self.pop();
+                }
+            },
+
+            _ => return Err(InterpreterError::BadOpcode),
+        }
+
+        Ok(())
+    }
+
+    fn upgradable_nop(&self) -> Result<(), InterpreterError> {
+        match self.flags.contains(VerifyFlags::DISCOURAGE_UPGRADABLE_NOPS) {
+            true => Err(InterpreterError::DiscourageUpgradableNops),
+            false => Ok(()),
+        }
+    }
+
+    /// The script from the last executed OP_CODESEPARATOR on, with legacy
+    ///  scripts losing any pushes of `signatures`.
+    fn script_code(&self, signatures: &[&Vec<u8>]) -> Result<Vec<u8>, InterpreterError> {
+        let mut script_code = self.script[self.code_separator..].to_vec();
+
+        if self.sig_version == SigVersion::Base {
+            for signature in signatures {
+                if find_and_delete(&mut script_code, signature) > 0 && self.flags.contains(VerifyFlags::CONST_SCRIPTCODE) {
+                    return Err(InterpreterError::SigFindAndDelete);
+                }
+            }
+        }
+
+        Ok(script_code)
+    }
+
+    fn check_sig(&self, signature: &Vec<u8>, public_key: &[u8]) -> Result<bool, InterpreterError> {
+        let script_code = self.script_code(&[signature])?;
+
+        check_signature_encoding(signature, self.flags)?;
+        check_public_key_encoding(public_key, self.flags, self.sig_version)?;
+
+        let success = self.checker.check_ecdsa_signature(signature, public_key, &script_code, self.sig_version);
+
+        if !success && self.flags.contains(VerifyFlags::NULLFAIL) && !signature.is_empty() {
+            return Err(InterpreterError::NullFail);
+        }
+
+        Ok(success)
+    }
+
+    /// BIP342 signature check: empty signatures fail quietly, every other
+    ///  one must be valid and spends validation weight. Keys that are neither
+    ///  empty nor 32 bytes are left for future soft forks and always pass.
+    fn check_sig_tapscript(&mut self, signature: &[u8], public_key: &[u8]) -> Result<bool, InterpreterError> {
+        let success = !signature.is_empty();
+
+        if success {
+            self.tapscript.validation_weight_left -= VALIDATION_WEIGHT_PER_SIGOP_PASSED;
+
+            if self.tapscript.validation_weight_left < 0 {
+                return Err(InterpreterError::TapscriptValidationWeight);
+            }
+        }
+
+        match public_key.len() {
+            0 => return Err(InterpreterError::TapscriptEmptyPubkey),
+            32 if success => {
+                let script_path = ScriptPath {
+                    leaf_hash: self.tapscript.leaf_hash,
+                    code_separator_position: self.code_separator_position,
+                };
+
+                self.checker.check_schnorr_signature(signature, public_key.try_into().unwrap(), self.tapscript.annex.as_deref(), Some(&script_path))?;
+            },
+            32 => { },
+            _ => {
+                if self.flags.contains(VerifyFlags::DISCOURAGE_UPGRADABLE_PUBKEYTYPE) {
+                    return Err(InterpreterError::DiscourageUpgradablePubkeyType);
+                }
+            },
+        }
+
+        Ok(success)
+    }
+
+    /// `<dummy> <sig>... <m> <pubkey>... <n>`, consuming all of it. Keys are
+    ///  tried in order, so signatures must be in the order of their keys.
+    fn check_multisig(&mut self) -> Result<bool, InterpreterError> {
+        let mut i = 1;
+        self.require(i)?;
+
+        let key_count = self.num(i, MAX_NUM_SIZE)?;
+
+        if !(0..=MAX_PUBKEYS_PER_MULTISIG).contains(&key_count) {
+            return Err(InterpreterError::PubkeyCount);
+        }
+
+        self.op_count += key_count as usize;
+
+        if self.op_count > MAX_OPS_PER_SCRIPT {
+            return Err(InterpreterError::OpCount);
+        }
+
+        let mut key_count = key_count as usize;
+        i += 1;
+        let mut key = i;
+        // The keys and both counts, popped before the signatures that
+        //  NULLFAIL requires to be empty on failure.
+        let mut last_key = key_count + 2;
+        i += key_count;
+        self.require(i)?;
+
+        let signature_count = self.num(i, MAX_NUM_SIZE)?;
+
+        if signature_count < 0 || signature_count > key_count as i64 {
+            return Err(InterpreterError::SigCount);
+        }
+
+        let mut signature_count = signature_count as usize;
+        i += 1;
+        let mut signature = i;
+        i += signature_count;
+        self.require(i)?;
+
+        let signatures: Vec<_> = (0..signature_count).map(|k| self.top(signature + k)).collect();
+        let script_code = self.script_code(&signatures)?;
+
+        let mut success = true;
+
+        while success && signature_count > 0 {
+            let sig = self.top(signature);
+            let public_key = self.top(key);
+
+            check_signature_encoding(sig, self.flags)?;
+            check_public_key_encoding(public_key, self.flags, self.sig_version)?;
+
+            if self.checker.check_ecdsa_signature(sig, public_key, &script_code, self.sig_version) {
+                signature += 1;
+                signature_count -= 1;
+            }
+
+            key += 1;
+            key_count -= 1;
+
+            // Too many failures for the remaining keys to make up for.
+            if signature_count > key_count {
+                success = false;
+            }
+        }
+
+        for _ in 1..i {
+            if !success && self.flags.contains(VerifyFlags::NULLFAIL) && last_key == 0 && !self.top(1).is_empty() {
+                return Err(InterpreterError::NullFail);
+            }
+
+            last_key = last_key.saturating_sub(1);
+            self.pop();
+        }
+
+        // The original implementation pops one item too many, which
+        //  NULLDUMMY requires to be empty.
+        self.require(1)?;
+
+        if self.flags.contains(VerifyFlags::NULLDUMMY) && !self.top(1).is_empty() {
+            return Err(InterpreterError::SigNullDummy);
+        }
+
+        self.pop();
+
+        Ok(success)
+    }
+}
+
+/// Evaluates `script` on top of `stack`, returning the resulting stack.
+pub fn eval_script(stack: Vec<Vec<u8>>, script: &[u8], flags: VerifyFlags, checker: &dyn SignatureChecker, sig_version: SigVersion) -> Result<Vec<Vec<u8>>, InterpreterError> {
+    Execution::new(script, stack, flags, sig_version, checker)?.run()
+}
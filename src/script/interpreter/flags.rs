@@ -0,0 +1,152 @@
+use std::{fmt::Display, ops::{BitOr, BitOrAssign}};
+
+/// Script verification flags, with the bit values Bitcoin Core uses.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct VerifyFlags(u32);
+
+impl VerifyFlags {
+    pub const NONE: VerifyFlags = VerifyFlags(0);
+    /// BIP16.
+    pub const P2SH: VerifyFlags = VerifyFlags(1 << 0);
+    /// Signatures must be strict DER with a defined hash type, and public
+    ///  keys compressed or uncompressed SEC.
+    pub const STRICTENC: VerifyFlags = VerifyFlags(1 << 1);
+    /// BIP66.
+    pub const DERSIG: VerifyFlags = VerifyFlags(1 << 2);
+    pub const LOW_S: VerifyFlags = VerifyFlags(1 << 3);
+    /// BIP147: CHECKMULTISIG's extra argument must be empty.
+    pub const NULLDUMMY: VerifyFlags = VerifyFlags(1 << 4);
+    pub const SIGPUSHONLY: VerifyFlags = VerifyFlags(1 << 5);
+    pub const MINIMALDATA: VerifyFlags = VerifyFlags(1 << 6);
+    pub const DISCOURAGE_UPGRADABLE_NOPS: VerifyFlags = VerifyFlags(1 << 7);
+    /// Exactly one stack item must be left. Requires P2SH and WITNESS.
+    pub const CLEANSTACK: VerifyFlags = VerifyFlags(1 << 8);
+    /// BIP65.
+    pub const CHECKLOCKTIMEVERIFY: VerifyFlags = VerifyFlags(1 << 9);
+    /// BIP112.
+    pub const CHECKSEQUENCEVERIFY: VerifyFlags = VerifyFlags(1 << 10);
+    /// BIP141. Requires P2SH.
+    pub const WITNESS: VerifyFlags = VerifyFlags(1 << 11);
+    pub const DISCOURAGE_UPGRADABLE_WITNESS_PROGRAM: VerifyFlags = VerifyFlags(1 << 12);
+    pub const MINIMALIF: VerifyFlags = VerifyFlags(1 << 13);
+    /// Failed signature checks must have been given empty signatures.
+    pub const NULLFAIL: VerifyFlags = VerifyFlags(1 << 14);
+    pub const WITNESS_PUBKEYTYPE: VerifyFlags = VerifyFlags(1 << 15);
+    pub const CONST_SCRIPTCODE: VerifyFlags = VerifyFlags(1 << 16);
+    /// BIP341 and BIP342.
+    pub const TAPROOT: VerifyFlags = VerifyFlags(1 << 17);
+    pub const DISCOURAGE_UPGRADABLE_TAPROOT_VERSION: VerifyFlags = VerifyFlags(1 << 18);
+    pub const DISCOURAGE_OP_SUCCESS: VerifyFlags = VerifyFlags(1 << 19);
+    /// Tapscript public keys of a length other than 0 or 32 bytes.
+    pub const DISCOURAGE_UPGRADABLE_PUBKEYTYPE: VerifyFlags = VerifyFlags(1 << 20);
+
+    /// The rules every block on mainnet follows today.
+    pub const CONSENSUS: VerifyFlags = VerifyFlags(
+        Self::P2SH.0 | Self::DERSIG.0 | Self::NULLDUMMY.0 | Self::CHECKLOCKTIMEVERIFY.0 | Self::CHECKSEQUENCEVERIFY.0 | Self::WITNESS.0 | Self::TAPROOT.0
+    );
+
+    /// Core's relay policy, which is what wallets should sign for.
+    pub const STANDARD: VerifyFlags = VerifyFlags(
+        Self::CONSENSUS.0 | Self::STRICTENC.0 | Self::MINIMALDATA.0 | Self::DISCOURAGE_UPGRADABLE_NOPS.0 | Self::CLEANSTACK.0 | Self::MINIMALIF.0
+            | Self::NULLFAIL.0 | Self::LOW_S.0 | Self::DISCOURAGE_UPGRADABLE_WITNESS_PROGRAM.0 | Self::WITNESS_PUBKEYTYPE.0 | Self::CONST_SCRIPTCODE.0
+            | Self::DISCOURAGE_UPGRADABLE_TAPROOT_VERSION.0 | Self::DISCOURAGE_OP_SUCCESS.0 | Self::DISCOURAGE_UPGRADABLE_PUBKEYTYPE.0
+    );
+
+    /// Every flag with the name Core's tests refer to it by.
+    pub const NAMED: [(&'static str, VerifyFlags); 21] = [
+        ("P2SH", Self::P2SH),
+        ("STRICTENC", Self::STRICTENC),
+        ("DERSIG", Self::DERSIG),
+        ("LOW_S", Self::LOW_S),
+        ("NULLDUMMY", Self::NULLDUMMY),
+        ("SIGPUSHONLY", Self::SIGPUSHONLY),
+        ("MINIMALDATA", Self::MINIMALDATA),
+        ("DISCOURAGE_UPGRADABLE_NOPS", Self::DISCOURAGE_UPGRADABLE_NOPS),
+        ("CLEANSTACK", Self::CLEANSTACK),
+        ("CHECKLOCKTIMEVERIFY", Self::CHECKLOCKTIMEVERIFY),
+        ("CHECKSEQUENCEVERIFY", Self::CHECKSEQUENCEVERIFY),
+        ("WITNESS", Self::WITNESS),
+        ("DISCOURAGE_UPGRADABLE_WITNESS_PROGRAM", Self::DISCOURAGE_UPGRADABLE_WITNESS_PROGRAM),
+        ("MINIMALIF", Self::MINIMALIF),
+        ("NULLFAIL", Self::NULLFAIL),
+        ("WITNESS_PUBKEYTYPE", Self::WITNESS_PUBKEYTYPE),
+        ("CONST_SCRIPTCODE", Self::CONST_SCRIPTCODE),
+        ("TAPROOT", Self::TAPROOT),
+        ("DISCOURAGE_UPGRADABLE_TAPROOT_VERSION", Self::DISCOURAGE_UPGRADABLE_TAPROOT_VERSION),
+        ("DISCOURAGE_OP_SUCCESS", Self::DISCOURAGE_OP_SUCCESS),
+        ("DISCOURAGE_UPGRADABLE_PUBKEYTYPE", Self::DISCOURAGE_UPGRADABLE_PUBKEYTYPE),
+    ];
+
+    pub fn from_u32(value: u32) -> Self {
+        Self(value)
+    }
+
+    pub fn to_u32(self) -> u32 {
+        self.0
+    }
+
+    /// Parses a comma separated list of flag names, like `"P2SH,DERSIG"`.
+    ///  An empty string and `"NONE"` are no flags.
+    pub fn from_names(names: &str) -> Option<Self> {
+        let mut flags = Self::NONE;
+
+        for name in names.split(',').map(str::trim).filter(|name| !name.is_empty() && *name != "NONE") {
+            let (_, flag) = Self::NAMED.iter().find(|(named, _)| *named == name)?;
+
+            flags |= *flag;
+        }
+
+        Some(flags)
+    }
+
+    pub fn contains(self, other: VerifyFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn without(self, other: VerifyFlags) -> Self {
+        Self(self.0 & !other.0)
+    }
+}
+
+impl BitOr for VerifyFlags {
+    type Output = VerifyFlags;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for VerifyFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// The names of the set flags, comma separated, in Core's order.
+impl Display for VerifyFlags {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names: Vec<_> = Self::NAMED.iter().filter(|(_, flag)| self.contains(*flag)).map(|(name, _)| *name).collect();
+
+        match names.is_empty() {
+            true => write!(f, "NONE"),
+            false => write!(f, "{}", names.join(",")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::VerifyFlags;
+
+    #[test]
+    pub fn test_names() {
+        let flags = VerifyFlags::from_names("P2SH,WITNESS,DERSIG").unwrap();
+
+        assert_eq!(flags, VerifyFlags::P2SH | VerifyFlags::DERSIG | VerifyFlags::WITNESS);
+        assert_eq!(flags.to_string(), "P2SH,DERSIG,WITNESS");
+        assert_eq!(VerifyFlags::from_names(&VerifyFlags::STANDARD.to_string()), Some(VerifyFlags::STANDARD));
+        assert_eq!(VerifyFlags::from_names(""), Some(VerifyFlags::NONE));
+        assert_eq!(VerifyFlags::NONE.to_string(), "NONE");
+        assert_eq!(VerifyFlags::from_names("P2SH,SEGWIT"), None);
+    }
+}
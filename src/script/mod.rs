@@ -0,0 +1,280 @@
+use std::{fmt::{Debug, Display}, str::FromStr};
+use self::{error::ScriptError, instruction::{Instruction, Instructions}, interpreter::execution::MAX_PUBKEYS_PER_MULTISIG, num::encode_num, opcode::Opcode};
+
+mod asm;
+pub mod error;
+pub mod instruction;
+pub mod interpreter;
+pub mod num;
+pub mod opcode;
+pub mod standard;
+
+/// Largest script consensus allows to be executed.
+pub const MAX_SCRIPT_SIZE: usize = 10_000;
+/// Largest single push, in bytes, outside of tapscript.
+pub const MAX_SCRIPT_ELEMENT_SIZE: usize = 520;
+
+/// A serialized script. Any bytes are a script, even ones that do not parse,
+///  so construction never fails and problems surface while iterating.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct Script(Vec<u8>);
+
+impl Script {
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+
+    /// Parses Bitcoin Core's ASM representation, see `Display`.
+    pub fn from_asm(s: &str) -> Result<Self, ScriptError> {
+        asm::parse(s)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn instructions(&self) -> Instructions<'_> {
+        Instructions::new(&self.0)
+    }
+
+    pub fn push_opcode(&mut self, opcode: Opcode) {
+        self.0.push(opcode.to_u8());
+    }
+
+    /// Pushes `data` with the smallest push opcode for its length. Unlike
+    ///  `push_int`, single bytes are never turned into OP_1 through OP_16,
+    ///  matching what Core's `CScript << std::vector` produces.
+    pub fn push_slice(&mut self, data: &[u8]) {
+        match data.len() {
+            len @ 0..=0x4b => self.0.push(len as u8),
+            len @ 0x4c..=0xff => self.0.extend_from_slice(&[Opcode::OP_PUSHDATA1.to_u8(), len as u8]),
+            len @ 0x100..=0xffff => {
+                self.0.push(Opcode::OP_PUSHDATA2.to_u8());
+                self.0.extend_from_slice(&(len as u16).to_le_bytes());
+            },
+            len => {
+                self.0.push(Opcode::OP_PUSHDATA4.to_u8());
+                self.0.extend_from_slice(&(len as u32).to_le_bytes());
+            },
+        }
+
+        self.0.extend_from_slice(data);
+    }
+
+    /// Pushes `value` as a script number, using OP_1NEGATE and OP_0 through
+    ///  OP_16 where possible.
+    pub fn push_int(&mut self, value: i64) {
+        match value {
+            -1 => self.push_opcode(Opcode::OP_1NEGATE),
+            0..=16 => self.push_opcode(Opcode::from_small_int(value as u8).unwrap()),
+            _ => self.push_slice(&encode_num(value)),
+        }
+    }
+
+    /// Whether the script only pushes data, which scriptSigs must under
+    ///  P2SH and SIGPUSHONLY. Like Core, every opcode up to OP_16 counts,
+    ///  OP_RESERVED included since executing it fails anyway, and a
+    ///  malformed script is not push only.
+    pub fn is_push_only(&self) -> bool {
+        self.instructions().all(|instruction| match instruction {
+            Ok(Instruction::Push(..)) => true,
+            Ok(Instruction::Op(opcode)) => opcode <= Opcode::OP_16,
+            Err(_) => false,
+        })
+    }
+
+    /// Core's `GetSigOpCount`. Inaccurate counting, used for scriptSigs and
+    ///  scriptPubKeys, charges every CHECKMULTISIG the maximum of 20 keys;
+    ///  accurate counting, used for redeem and witness scripts, charges the
+    ///  key count pushed right before it. Counting stops at a malformed push.
+    pub fn sigop_count(&self, accurate: bool) -> usize {
+        let mut count = 0;
+        let mut last = Opcode::OP_INVALIDOPCODE;
+
+        for instruction in self.instructions() {
+            let Ok(instruction) = instruction else {
+                break;
+            };
+
+            let opcode = instruction.opcode();
+
+            match opcode {
+                Opcode::OP_CHECKSIG | Opcode::OP_CHECKSIGVERIFY => count += 1,
+                Opcode::OP_CHECKMULTISIG | Opcode::OP_CHECKMULTISIGVERIFY => {
+                    count += match last.small_int() {
+                        Some(n) if accurate && n > 0 => n as usize,
+                        _ => MAX_PUBKEYS_PER_MULTISIG as usize,
+                    };
+                },
+                _ => { },
+            }
+
+            last = opcode;
+        }
+
+        count
+    }
+}
+
+impl From<Vec<u8>> for Script {
+    fn from(value: Vec<u8>) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&[u8]> for Script {
+    fn from(value: &[u8]) -> Self {
+        Self(value.to_vec())
+    }
+}
+
+impl AsRef<[u8]> for Script {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Bitcoin Core's ASM, as shown by `decodescript`.
+impl Display for Script {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        asm::format(&self.0, f)
+    }
+}
+
+impl Debug for Script {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Script({})", self)
+    }
+}
+
+impl FromStr for Script {
+    type Err = ScriptError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_asm(s)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+    use crate::script::{error::ScriptError, opcode::Opcode};
+    use super::Script;
+
+    // Scripts and the `asm` Bitcoin Core's `decodescript` gives for them.
+    const ASM_CASES: [(&str, &str); 10] = [
+        ("76a91489abcdefabbaabbaabbaabbaabbaabbaabbaabba88ac", "OP_DUP OP_HASH160 89abcdefabbaabbaabbaabbaabbaabbaabbaabba OP_EQUALVERIFY OP_CHECKSIG"),
+        ("a9145a6e2a7a0b5ec9b2b0fd4bdac6ed7c6a5b9f3dba87", "OP_HASH160 5a6e2a7a0b5ec9b2b0fd4bdac6ed7c6a5b9f3dba OP_EQUAL"),
+        ("00141d0f172a0ecb48aee1be1f2687d2963ae33f71a1", "0 1d0f172a0ecb48aee1be1f2687d2963ae33f71a1"),
+        ("5221022626e955ea6ea6d98850c994f9107b036b1334f18ca8830bfff1295d21cfdb702103b287eaf122eea69030a0e9feed096bed8045c8b98bec453e1ffac7fbdbd4bb7152ae", "2 022626e955ea6ea6d98850c994f9107b036b1334f18ca8830bfff1295d21cfdb70 03b287eaf122eea69030a0e9feed096bed8045c8b98bec453e1ffac7fbdbd4bb71 2 OP_CHECKMULTISIG"),
+        ("6a0b68656c6c6f20776f726c64", "OP_RETURN 68656c6c6f20776f726c64"),
+        ("63ac6703a0860db17568", "OP_IF OP_CHECKSIG OP_ELSE 886432 OP_CHECKLOCKTIMEVERIFY OP_DROP OP_ENDIF"),
+        ("4f51016002900000", "-1 1 96 144 0"),
+        ("0181020080", "-1 0"),
+        ("ba62bbff", "OP_CHECKSIGADD OP_VER OP_UNKNOWN OP_INVALIDOPCODE"),
+        ("51024c", "1 [error]"),
+    ];
+
+    #[test]
+    pub fn test_asm() {
+        for (script, asm) in ASM_CASES {
+            assert_eq!(Script::from_bytes(hex::decode(script).unwrap()).to_string(), asm);
+        }
+    }
+
+    #[test]
+    pub fn test_asm_round_trip() {
+        // The cases above apart from the ones with non-minimal pushes, the
+        //  two unknown opcodes and the malformed tail.
+        for (script, asm) in ASM_CASES.iter().take(6) {
+            assert_eq!(hex::encode(Script::from_asm(asm).unwrap().as_bytes()), *script);
+        }
+
+        assert_eq!(hex::encode(Script::from_str("CHECKSIGADD OP_NOP2 NOP3 2147483647 -2147483647 1234567890").unwrap().as_bytes()), "bab1b204ffffff7f04ffffffff04d2029649");
+        assert_eq!(hex::encode(Script::from_str("3234567890").unwrap().as_bytes()), "053234567890");
+    }
+
+    #[test]
+    pub fn test_asm_invalid() {
+        for token in ["OP_FOO", "abc", "0x51", "012345", "123456789g", "[error]"] {
+            assert_eq!(Script::from_asm(token), Err(ScriptError::InvalidAsmToken(token.to_string())));
+        }
+    }
+
+    #[test]
+    pub fn test_push_slice() {
+        let mut script = Script::new();
+
+        for len in [0, 1, 75, 76, 255, 256, 65535, 65536] {
+            script.push_slice(&vec![0x01; len]);
+        }
+
+        let lengths: Vec<_> = script.instructions().map(|instruction| instruction.unwrap().push_data().unwrap().len()).collect();
+        assert_eq!(lengths, [0, 1, 75, 76, 255, 256, 65535, 65536]);
+
+        let opcodes: Vec<_> = script.instructions().map(|instruction| instruction.unwrap().opcode().to_u8()).collect();
+        assert_eq!(opcodes, [0x00, 0x01, 0x4b, 0x4c, 0x4c, 0x4d, 0x4d, 0x4e]);
+
+        assert!(!script.instructions().all(|instruction| instruction.unwrap().is_minimal_push()));
+    }
+
+    #[test]
+    pub fn test_push_int() {
+        let mut script = Script::new();
+
+        for value in [-1, 0, 1, 16, 17, -2, 128, 0x7fffffff] {
+            script.push_int(value);
+        }
+
+        assert_eq!(hex::encode(script.as_bytes()), "4f0051600111018202800004ffffff7f");
+        assert!(script.instructions().all(|instruction| instruction.unwrap().is_minimal_push()));
+    }
+
+    #[test]
+    pub fn test_push_only() {
+        assert!(Script::from_bytes(hex::decode("004f5160020102").unwrap()).is_push_only());
+        assert!(Script::from_bytes(hex::decode("0050").unwrap()).is_push_only());
+        assert!(!Script::from_bytes(hex::decode("0061").unwrap()).is_push_only());
+        assert!(!Script::from_bytes(hex::decode("004c").unwrap()).is_push_only());
+        assert_eq!(Opcode::OP_RESERVED.name(), "OP_RESERVED");
+    }
+
+    #[test]
+    pub fn test_sigop_count() {
+        let cases = [
+            ("OP_DUP OP_HASH160 89abcdefabbaabbaabbaabbaabbaabbaabbaabba OP_EQUALVERIFY OP_CHECKSIG", 1, 1),
+            ("2 022626e955ea6ea6d98850c994f9107b036b1334f18ca8830bfff1295d21cfdb70 03b287eaf122eea69030a0e9feed096bed8045c8b98bec453e1ffac7fbdbd4bb71 2 OP_CHECKMULTISIG", 20, 2),
+            ("OP_CHECKSIGVERIFY OP_CHECKMULTISIGVERIFY 16 OP_CHECKMULTISIG", 41, 37),
+            // Zero keys isn't a count Core recognizes.
+            ("0 OP_CHECKMULTISIG", 20, 20),
+        ];
+
+        for (asm, inaccurate, accurate) in cases {
+            let script = Script::from_asm(asm).unwrap();
+
+            assert_eq!(script.sigop_count(false), inaccurate, "{}", asm);
+            assert_eq!(script.sigop_count(true), accurate, "{}", asm);
+        }
+
+        // Pushed data is never counted, and counting stops at a push
+        //  running past the end.
+        assert_eq!(Script::from_bytes(hex::decode("04acadaeaf").unwrap()).sigop_count(false), 0);
+        assert_eq!(Script::from_bytes(hex::decode("ac4cffac").unwrap()).sigop_count(true), 1);
+    }
+}
@@ -0,0 +1,130 @@
+use super::error::ScriptError;
+
+/// Largest script number arithmetic opcodes accept as input. Results may
+///  be longer, but cannot be used as numbers again.
+pub const MAX_NUM_SIZE: usize = 4;
+
+/// Little-endian sign-magnitude encoding, with zero as the empty vector.
+pub fn encode_num(value: i64) -> Vec<u8> {
+    let mut encoded = Vec::new();
+
+    if value == 0 {
+        return encoded;
+    }
+
+    let negative = value < 0;
+    let mut magnitude = value.unsigned_abs();
+
+    while magnitude > 0 {
+        encoded.push((magnitude & 0xff) as u8);
+        magnitude >>= 8;
+    }
+
+    // The top bit is the sign, so add a byte for it if the magnitude uses it.
+    if encoded.last().unwrap() & 0x80 != 0 {
+        encoded.push(if negative { 0x80 } else { 0x00 });
+    } else if negative {
+        *encoded.last_mut().unwrap() |= 0x80;
+    }
+
+    encoded
+}
+
+/// Decodes a script number of at most `max_size` bytes. With
+///  `require_minimal`, encodings padded with zero bytes are rejected, like
+///  Core does under MINIMALDATA.
+pub fn decode_num(bytes: &[u8], max_size: usize, require_minimal: bool) -> Result<i64, ScriptError> {
+    if bytes.len() > max_size {
+        return Err(ScriptError::NumberOverflow(bytes.len()));
+    }
+
+    if require_minimal && !is_minimal_num(bytes) {
+        return Err(ScriptError::NonMinimalNumber);
+    }
+
+    let Some((last, _)) = bytes.split_last() else {
+        return Ok(0);
+    };
+
+    let mut value = bytes.iter().rev().fold(0i64, |value, byte| value << 8 | *byte as i64);
+
+    if last & 0x80 != 0 {
+        value &= !(0x80 << (8 * (bytes.len() - 1)));
+        value = -value;
+    }
+
+    Ok(value)
+}
+
+/// Whether `bytes` is the shortest encoding of its value: the last byte
+///  can only be zero apart from its sign bit if the byte before it needs
+///  its top bit cleared.
+pub fn is_minimal_num(bytes: &[u8]) -> bool {
+    match bytes {
+        [] => true,
+        [.., last] if last & 0x7f != 0 => true,
+        [_] => false,
+        [.., second_last, _] => second_last & 0x80 != 0,
+    }
+}
+
+/// Script booleans: anything but zero, including negative zero, is true.
+pub fn cast_to_bool(bytes: &[u8]) -> bool {
+    match bytes.split_last() {
+        Some((last, rest)) => rest.iter().any(|byte| *byte != 0) || (last & 0x7f) != 0,
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::script::error::ScriptError;
+    use super::{cast_to_bool, decode_num, encode_num, MAX_NUM_SIZE};
+
+    #[test]
+    pub fn test_encode_num() {
+        let cases: [(i64, &str); 10] = [
+            (0, ""),
+            (1, "01"),
+            (-1, "81"),
+            (127, "7f"),
+            (128, "8000"),
+            (-128, "8080"),
+            (255, "ff00"),
+            (256, "0001"),
+            (-32768, "008080"),
+            (2147483647, "ffffff7f"),
+        ];
+
+        for (value, encoded) in cases {
+            assert_eq!(hex::encode(encode_num(value)), encoded);
+            assert_eq!(decode_num(&hex::decode(encoded).unwrap(), MAX_NUM_SIZE, true), Ok(value));
+        }
+
+        assert_eq!(hex::encode(encode_num(i64::MIN + 1)), "ffffffffffffffff");
+    }
+
+    #[test]
+    pub fn test_decode_num() {
+        assert_eq!(decode_num(&[0x00], MAX_NUM_SIZE, false), Ok(0));
+        assert_eq!(decode_num(&[0x80], MAX_NUM_SIZE, false), Ok(0));
+        assert_eq!(decode_num(&[0x01, 0x00], MAX_NUM_SIZE, false), Ok(1));
+        assert_eq!(decode_num(&[0xff, 0xff, 0xff, 0xff], MAX_NUM_SIZE, false), Ok(-2147483647));
+
+        assert_eq!(decode_num(&[0x00], MAX_NUM_SIZE, true), Err(ScriptError::NonMinimalNumber));
+        assert_eq!(decode_num(&[0x80], MAX_NUM_SIZE, true), Err(ScriptError::NonMinimalNumber));
+        assert_eq!(decode_num(&[0x01, 0x00], MAX_NUM_SIZE, true), Err(ScriptError::NonMinimalNumber));
+        assert_eq!(decode_num(&[0xff, 0x00], MAX_NUM_SIZE, true), Ok(255));
+        assert_eq!(decode_num(&[0x00, 0x00, 0x00, 0x00, 0x01], MAX_NUM_SIZE, false), Err(ScriptError::NumberOverflow(5)));
+        assert_eq!(decode_num(&[0x00, 0x00, 0x00, 0x00, 0x01], 5, false), Ok(0x0100000000));
+    }
+
+    #[test]
+    pub fn test_cast_to_bool() {
+        assert!(!cast_to_bool(&[]));
+        assert!(!cast_to_bool(&[0x00, 0x00]));
+        assert!(!cast_to_bool(&[0x00, 0x80]));
+        assert!(cast_to_bool(&[0x80, 0x00]));
+        assert!(cast_to_bool(&[0x01]));
+    }
+}
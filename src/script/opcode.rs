@@ -0,0 +1,291 @@
+use std::fmt::Display;
+
+/// A script opcode. Every byte is one, so like `SighashType` this wraps the
+///  raw value, with the named opcodes as associated constants. The direct
+///  pushes 0x01..=0x4b have no constants, see `Opcode::push_bytes`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Opcode(u8);
+
+/// Declares the named opcodes along with the names Bitcoin Core gives them.
+macro_rules! opcodes {
+    ($($name:ident = $value:expr),* $(,)?) => {
+        impl Opcode {
+            $(pub const $name: Opcode = Opcode($value);)*
+
+            /// Bitcoin Core's name for the opcode, or `OP_UNKNOWN` for the
+            ///  unassigned ones. Direct pushes all share `OP_PUSHBYTES`.
+            pub fn name(self) -> &'static str {
+                match self.0 {
+                    $($value => stringify!($name),)*
+                    0x01..=0x4b => "OP_PUSHBYTES",
+                    _ => "OP_UNKNOWN",
+                }
+            }
+
+            /// Looks up an opcode by name, with or without the `OP_` prefix.
+            pub fn from_name(name: &str) -> Option<Opcode> {
+                let name = name.strip_prefix("OP_").unwrap_or(name);
+
+                match name {
+                    $(_ if name == &stringify!($name)[3..] => Some(Opcode::$name),)*
+                    "FALSE" => Some(Opcode::OP_FALSE),
+                    "TRUE" => Some(Opcode::OP_TRUE),
+                    "NOP2" => Some(Opcode::OP_NOP2),
+                    "NOP3" => Some(Opcode::OP_NOP3),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+opcodes! {
+    OP_0 = 0x00,
+    OP_PUSHDATA1 = 0x4c,
+    OP_PUSHDATA2 = 0x4d,
+    OP_PUSHDATA4 = 0x4e,
+    OP_1NEGATE = 0x4f,
+    OP_RESERVED = 0x50,
+    OP_1 = 0x51,
+    OP_2 = 0x52,
+    OP_3 = 0x53,
+    OP_4 = 0x54,
+    OP_5 = 0x55,
+    OP_6 = 0x56,
+    OP_7 = 0x57,
+    OP_8 = 0x58,
+    OP_9 = 0x59,
+    OP_10 = 0x5a,
+    OP_11 = 0x5b,
+    OP_12 = 0x5c,
+    OP_13 = 0x5d,
+    OP_14 = 0x5e,
+    OP_15 = 0x5f,
+    OP_16 = 0x60,
+
+    OP_NOP = 0x61,
+    OP_VER = 0x62,
+    OP_IF = 0x63,
+    OP_NOTIF = 0x64,
+    OP_VERIF = 0x65,
+    OP_VERNOTIF = 0x66,
+    OP_ELSE = 0x67,
+    OP_ENDIF = 0x68,
+    OP_VERIFY = 0x69,
+    OP_RETURN = 0x6a,
+
+    OP_TOALTSTACK = 0x6b,
+    OP_FROMALTSTACK = 0x6c,
+    OP_2DROP = 0x6d,
+    OP_2DUP = 0x6e,
+    OP_3DUP = 0x6f,
+    OP_2OVER = 0x70,
+    OP_2ROT = 0x71,
+    OP_2SWAP = 0x72,
+    OP_IFDUP = 0x73,
+    OP_DEPTH = 0x74,
+    OP_DROP = 0x75,
+    OP_DUP = 0x76,
+    OP_NIP = 0x77,
+    OP_OVER = 0x78,
+    OP_PICK = 0x79,
+    OP_ROLL = 0x7a,
+    OP_ROT = 0x7b,
+    OP_SWAP = 0x7c,
+    OP_TUCK = 0x7d,
+
+    OP_CAT = 0x7e,
+    OP_SUBSTR = 0x7f,
+    OP_LEFT = 0x80,
+    OP_RIGHT = 0x81,
+    OP_SIZE = 0x82,
+
+    OP_INVERT = 0x83,
+    OP_AND = 0x84,
+    OP_OR = 0x85,
+    OP_XOR = 0x86,
+    OP_EQUAL = 0x87,
+    OP_EQUALVERIFY = 0x88,
+    OP_RESERVED1 = 0x89,
+    OP_RESERVED2 = 0x8a,
+
+    OP_1ADD = 0x8b,
+    OP_1SUB = 0x8c,
+    OP_2MUL = 0x8d,
+    OP_2DIV = 0x8e,
+    OP_NEGATE = 0x8f,
+    OP_ABS = 0x90,
+    OP_NOT = 0x91,
+    OP_0NOTEQUAL = 0x92,
+    OP_ADD = 0x93,
+    OP_SUB = 0x94,
+    OP_MUL = 0x95,
+    OP_DIV = 0x96,
+    OP_MOD = 0x97,
+    OP_LSHIFT = 0x98,
+    OP_RSHIFT = 0x99,
+    OP_BOOLAND = 0x9a,
+    OP_BOOLOR = 0x9b,
+    OP_NUMEQUAL = 0x9c,
+    OP_NUMEQUALVERIFY = 0x9d,
+    OP_NUMNOTEQUAL = 0x9e,
+    OP_LESSTHAN = 0x9f,
+    OP_GREATERTHAN = 0xa0,
+    OP_LESSTHANOREQUAL = 0xa1,
+    OP_GREATERTHANOREQUAL = 0xa2,
+    OP_MIN = 0xa3,
+    OP_MAX = 0xa4,
+    OP_WITHIN = 0xa5,
+
+    OP_RIPEMD160 = 0xa6,
+    OP_SHA1 = 0xa7,
+    OP_SHA256 = 0xa8,
+    OP_HASH160 = 0xa9,
+    OP_HASH256 = 0xaa,
+    OP_CODESEPARATOR = 0xab,
+    OP_CHECKSIG = 0xac,
+    OP_CHECKSIGVERIFY = 0xad,
+    OP_CHECKMULTISIG = 0xae,
+    OP_CHECKMULTISIGVERIFY = 0xaf,
+
+    OP_NOP1 = 0xb0,
+    OP_CHECKLOCKTIMEVERIFY = 0xb1,
+    OP_CHECKSEQUENCEVERIFY = 0xb2,
+    OP_NOP4 = 0xb3,
+    OP_NOP5 = 0xb4,
+    OP_NOP6 = 0xb5,
+    OP_NOP7 = 0xb6,
+    OP_NOP8 = 0xb7,
+    OP_NOP9 = 0xb8,
+    OP_NOP10 = 0xb9,
+
+    OP_CHECKSIGADD = 0xba,
+
+    OP_INVALIDOPCODE = 0xff,
+}
+
+impl Opcode {
+    pub const OP_FALSE: Opcode = Opcode::OP_0;
+    pub const OP_TRUE: Opcode = Opcode::OP_1;
+    pub const OP_NOP2: Opcode = Opcode::OP_CHECKLOCKTIMEVERIFY;
+    pub const OP_NOP3: Opcode = Opcode::OP_CHECKSEQUENCEVERIFY;
+
+    pub const fn from_u8(value: u8) -> Self {
+        Self(value)
+    }
+
+    pub const fn to_u8(self) -> u8 {
+        self.0
+    }
+
+    /// The opcode that pushes the next `len` bytes directly, for
+    ///  `1..=75`.
+    pub fn push_bytes(len: usize) -> Option<Self> {
+        match len {
+            1..=0x4b => Some(Self(len as u8)),
+            _ => None,
+        }
+    }
+
+    /// Whether the opcode is followed by data in the script, i.e. OP_0, a
+    ///  direct push or one of the OP_PUSHDATAs.
+    pub fn is_push(self) -> bool {
+        self <= Self::OP_PUSHDATA4
+    }
+
+    /// Opcodes disabled after CVE-2010-5137, which fail a script even in an
+    ///  unexecuted branch.
+    pub fn is_disabled(self) -> bool {
+        matches!(
+            self,
+            Self::OP_CAT | Self::OP_SUBSTR | Self::OP_LEFT | Self::OP_RIGHT
+                | Self::OP_INVERT | Self::OP_AND | Self::OP_OR | Self::OP_XOR
+                | Self::OP_2MUL | Self::OP_2DIV | Self::OP_MUL | Self::OP_DIV | Self::OP_MOD | Self::OP_LSHIFT | Self::OP_RSHIFT
+        )
+    }
+
+    /// BIP342's OP_SUCCESSx: any of these in a tapscript makes it succeed
+    ///  without being run, leaving them free for soft forks to redefine.
+    pub fn is_success(self) -> bool {
+        matches!(self.0, 0x50 | 0x62 | 0x7e..=0x81 | 0x83..=0x86 | 0x89..=0x8a | 0x8d..=0x8e | 0x95..=0x99 | 0xbb..=0xfe)
+    }
+
+    /// The number OP_0 and OP_1 through OP_16 push.
+    pub fn small_int(self) -> Option<u8> {
+        match self.0 {
+            0x00 => Some(0),
+            0x51..=0x60 => Some(self.0 - 0x50),
+            _ => None,
+        }
+    }
+
+    /// OP_0 through OP_16 for `0..=16`.
+    pub fn from_small_int(n: u8) -> Option<Self> {
+        match n {
+            0 => Some(Self::OP_0),
+            1..=16 => Some(Self(Self::OP_1.0 + n - 1)),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Opcode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            0x01..=0x4b => write!(f, "OP_PUSHBYTES_{}", self.0),
+            _ => write!(f, "{}", self.name()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Opcode;
+
+    #[test]
+    pub fn test_names() {
+        assert_eq!(Opcode::OP_CHECKSIG.name(), "OP_CHECKSIG");
+        assert_eq!(Opcode::OP_NOP2.name(), "OP_CHECKLOCKTIMEVERIFY");
+        assert_eq!(Opcode::from_u8(0xbb).name(), "OP_UNKNOWN");
+        assert_eq!(Opcode::from_u8(0x14).to_string(), "OP_PUSHBYTES_20");
+
+        assert_eq!(Opcode::from_name("OP_CHECKMULTISIG"), Some(Opcode::OP_CHECKMULTISIG));
+        assert_eq!(Opcode::from_name("HASH160"), Some(Opcode::OP_HASH160));
+        assert_eq!(Opcode::from_name("NOP3"), Some(Opcode::OP_CHECKSEQUENCEVERIFY));
+        assert_eq!(Opcode::from_name("OP_TRUE"), Some(Opcode::OP_1));
+        assert_eq!(Opcode::from_name("OP_UNKNOWN"), None);
+
+        // Every named opcode is found again by its name.
+        for byte in 0..=u8::MAX {
+            let opcode = Opcode::from_u8(byte);
+
+            if !matches!(opcode.name(), "OP_UNKNOWN" | "OP_PUSHBYTES") {
+                assert_eq!(Opcode::from_name(opcode.name()), Some(opcode));
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_small_ints() {
+        for n in 0..=16 {
+            assert_eq!(Opcode::from_small_int(n).unwrap().small_int(), Some(n));
+        }
+
+        assert_eq!(Opcode::from_small_int(16), Some(Opcode::OP_16));
+        assert_eq!(Opcode::from_small_int(17), None);
+        assert_eq!(Opcode::OP_1NEGATE.small_int(), None);
+    }
+
+    #[test]
+    pub fn test_op_success() {
+        let successes = (0..=0xff).map(Opcode::from_u8).filter(|opcode| opcode.is_success()).count();
+
+        // BIP342 lists 87 of them.
+        assert_eq!(successes, 87);
+        assert!(Opcode::OP_RESERVED.is_success());
+        assert!(Opcode::OP_CAT.is_success());
+        assert!(!Opcode::OP_CHECKSIGADD.is_success());
+        assert!(Opcode::from_u8(0xbb).is_success());
+        assert!(!Opcode::OP_INVALIDOPCODE.is_success());
+    }
+}
@@ -0,0 +1,376 @@
+use crate::cryptography::hash::digest::{PubkeyHash, ScriptHash};
+use super::{error::ScriptError, instruction::{Instruction, Instructions}, opcode::Opcode, Script};
+
+/// Most public keys bare multisig can commit to, as OP_16 is the largest
+///  key count that fits in a single opcode.
+pub const MAX_PUBKEYS_PER_BARE_MULTISIG: usize = 16;
+
+/// Output script templates, as classified by Bitcoin Core's `Solver`, along
+///  with the keys or hashes they commit to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StandardScript {
+    /// `<pubkey> OP_CHECKSIG`, compressed or not.
+    P2PK(Vec<u8>),
+    P2PKH(PubkeyHash),
+    P2SH(ScriptHash),
+    /// Bare `m <pubkey>... n OP_CHECKMULTISIG`.
+    P2MS {
+        required: usize,
+        public_keys: Vec<Vec<u8>>,
+    },
+    P2WPKH(PubkeyHash),
+    P2WSH([u8; 32]),
+    P2TR([u8; 32]),
+    /// A witness program of a version or length without rules yet, which
+    ///  anyone can spend until a soft fork gives it some.
+    WitnessUnknown {
+        version: u8,
+        program: Vec<u8>,
+    },
+    /// `OP_RETURN` followed by pushes only, with the data they push.
+    NullData(Vec<Vec<u8>>),
+    NonStandard,
+}
+
+impl Script {
+    pub fn p2pk(public_key: &[u8]) -> Self {
+        let mut script = Self::new();
+        script.push_slice(public_key);
+        script.push_opcode(Opcode::OP_CHECKSIG);
+
+        script
+    }
+
+    pub fn p2pkh(hash: &PubkeyHash) -> Self {
+        let mut script = Self::new();
+        script.push_opcode(Opcode::OP_DUP);
+        script.push_opcode(Opcode::OP_HASH160);
+        script.push_slice(hash.as_ref());
+        script.push_opcode(Opcode::OP_EQUALVERIFY);
+        script.push_opcode(Opcode::OP_CHECKSIG);
+
+        script
+    }
+
+    pub fn p2sh(hash: &ScriptHash) -> Self {
+        let mut script = Self::new();
+        script.push_opcode(Opcode::OP_HASH160);
+        script.push_slice(hash.as_ref());
+        script.push_opcode(Opcode::OP_EQUAL);
+
+        script
+    }
+
+    /// `required`-of-`public_keys.len()` bare multisig, with the keys in the
+    ///  order given.
+    pub fn p2ms<K: AsRef<[u8]>>(required: usize, public_keys: &[K]) -> Result<Self, ScriptError> {
+        if required == 0 || required > public_keys.len() || public_keys.len() > MAX_PUBKEYS_PER_BARE_MULTISIG {
+            return Err(ScriptError::InvalidMultisig(required, public_keys.len()));
+        }
+
+        let mut script = Self::new();
+        script.push_int(required as i64);
+
+        for public_key in public_keys {
+            script.push_slice(public_key.as_ref());
+        }
+
+        script.push_int(public_keys.len() as i64);
+        script.push_opcode(Opcode::OP_CHECKMULTISIG);
+
+        Ok(script)
+    }
+
+    pub fn p2wpkh(hash: &PubkeyHash) -> Self {
+        Self::witness_program(0, hash.as_ref())
+    }
+
+    pub fn p2wsh(hash: &[u8; 32]) -> Self {
+        Self::witness_program(0, hash)
+    }
+
+    /// Pays to an already tweaked taproot output key.
+    pub fn p2tr(output_key: &[u8; 32]) -> Self {
+        Self::witness_program(1, output_key)
+    }
+
+    /// `OP_n <program>`. Neither the version nor the program length are
+    ///  checked, see `WitnessProgram` for that.
+    pub fn witness_program(version: u8, program: &[u8]) -> Self {
+        let mut script = Self::new();
+        script.push_int(version as i64);
+        script.push_slice(program);
+
+        script
+    }
+
+    /// `OP_RETURN <data>`, which is provably unspendable. How much data
+    ///  nodes relay is policy and not checked here.
+    pub fn null_data(data: &[u8]) -> Self {
+        let mut script = Self::new();
+        script.push_opcode(Opcode::OP_RETURN);
+        script.push_slice(data);
+
+        script
+    }
+
+    /// Which template the script follows. Like Core, only the exact byte
+    ///  patterns match, so e.g. a P2PKH hash pushed with OP_PUSHDATA1 is
+    ///  non-standard.
+    pub fn classify(&self) -> StandardScript {
+        let script = self.as_bytes();
+
+        if is_p2sh(script) {
+            return StandardScript::P2SH(ScriptHash::from_slice(&script[2..22]).unwrap());
+        }
+
+        if let Some((version, program)) = witness_program(script) {
+            return match (version, program.len()) {
+                (0, 20) => StandardScript::P2WPKH(PubkeyHash::from_slice(program).unwrap()),
+                (0, 32) => StandardScript::P2WSH(program.try_into().unwrap()),
+                (0, _) => StandardScript::NonStandard,
+                (1, 32) => StandardScript::P2TR(program.try_into().unwrap()),
+                _ => StandardScript::WitnessUnknown {
+                    version,
+                    program: program.to_vec(),
+                },
+            };
+        }
+
+        if script.first() == Some(&Opcode::OP_RETURN.to_u8()) {
+            return match null_data(&script[1..]) {
+                Some(data) => StandardScript::NullData(data),
+                None => StandardScript::NonStandard,
+            };
+        }
+
+        if let Some(public_key) = p2pk(script) {
+            return StandardScript::P2PK(public_key.to_vec());
+        }
+
+        if is_p2pkh(script) {
+            return StandardScript::P2PKH(PubkeyHash::from_slice(&script[3..23]).unwrap());
+        }
+
+        match p2ms(script) {
+            Some((required, public_keys)) => StandardScript::P2MS {
+                required,
+                public_keys,
+            },
+            None => StandardScript::NonStandard,
+        }
+    }
+
+    /// The version and program of a witness program: a version opcode
+    ///  followed by a direct push of 2 to 40 bytes and nothing else.
+    pub fn witness_version_and_program(&self) -> Option<(u8, &[u8])> {
+        witness_program(self.as_bytes())
+    }
+
+    pub fn is_p2sh(&self) -> bool {
+        is_p2sh(self.as_bytes())
+    }
+
+    /// Whether the script starts with OP_RETURN and so can never be spent,
+    ///  whatever follows it.
+    pub fn is_op_return(&self) -> bool {
+        self.as_bytes().first() == Some(&Opcode::OP_RETURN.to_u8())
+    }
+}
+
+/// BIP141 witness programs, matched byte for byte as consensus does.
+pub(crate) fn witness_program(script: &[u8]) -> Option<(u8, &[u8])> {
+    if !(4..=42).contains(&script.len()) || script[1] as usize + 2 != script.len() {
+        return None;
+    }
+
+    let version = Opcode::from_u8(script[0]);
+
+    match version {
+        Opcode::OP_0 => Some((0, &script[2..])),
+        _ => version.small_int().filter(|n| *n > 0).map(|n| (n, &script[2..])),
+    }
+}
+
+/// `OP_HASH160 <20 bytes> OP_EQUAL`, matched byte for byte.
+pub(crate) fn is_p2sh(script: &[u8]) -> bool {
+    script.len() == 23 && script[0] == Opcode::OP_HASH160.to_u8() && script[1] == 0x14 && script[22] == Opcode::OP_EQUAL.to_u8()
+}
+
+/// `OP_DUP OP_HASH160 <20 bytes> OP_EQUALVERIFY OP_CHECKSIG`.
+fn is_p2pkh(script: &[u8]) -> bool {
+    script.len() == 25
+        && script[0] == Opcode::OP_DUP.to_u8()
+        && script[1] == Opcode::OP_HASH160.to_u8()
+        && script[2] == 0x14
+        && script[23] == Opcode::OP_EQUALVERIFY.to_u8()
+        && script[24] == Opcode::OP_CHECKSIG.to_u8()
+}
+
+/// A direct push of a compressed or uncompressed key, then OP_CHECKSIG.
+fn p2pk(script: &[u8]) -> Option<&[u8]> {
+    match script {
+        [len, public_key @ .., checksig] if *len as usize == public_key.len() && *checksig == Opcode::OP_CHECKSIG.to_u8() && has_public_key_size(public_key) => Some(public_key),
+        _ => None,
+    }
+}
+
+/// Core's `CPubKey::ValidSize`: the length the first byte calls for,
+///  without checking the key is on the curve. Hybrid keys count as
+///  uncompressed.
+fn has_public_key_size(public_key: &[u8]) -> bool {
+    match public_key.first() {
+        Some(0x02 | 0x03) => public_key.len() == 33,
+        Some(0x04 | 0x06 | 0x07) => public_key.len() == 65,
+        _ => false,
+    }
+}
+
+/// Follows Core's `MatchMultisig`, where the keys may be pushed any way
+///  but the counts must be OP_1 through OP_16.
+fn p2ms(script: &[u8]) -> Option<(usize, Vec<Vec<u8>>)> {
+    let (last, rest) = script.split_last()?;
+
+    if *last != Opcode::OP_CHECKMULTISIG.to_u8() {
+        return None;
+    }
+
+    let mut instructions = Instructions::new(rest).collect::<Result<Vec<_>, _>>().ok()?.into_iter();
+
+    let required = match instructions.next()? {
+        Instruction::Op(opcode) => opcode.small_int().filter(|n| *n > 0)?,
+        Instruction::Push(..) => return None,
+    };
+
+    let mut public_keys = Vec::new();
+
+    let count = loop {
+        match instructions.next()? {
+            Instruction::Push(_, public_key) if has_public_key_size(public_key) => public_keys.push(public_key.to_vec()),
+            Instruction::Op(opcode) => break opcode.small_int().filter(|n| *n > 0)?,
+            Instruction::Push(..) => return None,
+        }
+    };
+
+    if instructions.next().is_some() || count as usize != public_keys.len() || count < required {
+        return None;
+    }
+
+    Some((required as usize, public_keys))
+}
+
+/// The data pushed by what follows OP_RETURN, if it is push only. Like
+///  Core, OP_RESERVED counts as a push, though it adds no data.
+fn null_data(script: &[u8]) -> Option<Vec<Vec<u8>>> {
+    let mut data = Vec::new();
+
+    for instruction in Instructions::new(script) {
+        match instruction {
+            Ok(Instruction::Push(_, pushed)) => data.push(pushed.to_vec()),
+            Ok(Instruction::Op(Opcode::OP_1NEGATE)) => data.push(vec![0x81]),
+            Ok(Instruction::Op(Opcode::OP_RESERVED)) => (),
+            Ok(Instruction::Op(opcode)) => data.push(opcode.small_int().filter(|n| *n > 0).map(|n| vec![n])?),
+            Err(_) => return None,
+        }
+    }
+
+    Some(data)
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+    use crate::{cryptography::hash::digest::{PubkeyHash, ScriptHash}, script::{error::ScriptError, Script}};
+    use super::StandardScript;
+
+    const KEY_1: &str = "022626e955ea6ea6d98850c994f9107b036b1334f18ca8830bfff1295d21cfdb70";
+    const KEY_2: &str = "03b287eaf122eea69030a0e9feed096bed8045c8b98bec453e1ffac7fbdbd4bb71";
+
+    fn script(hex: &str) -> Script {
+        Script::from_bytes(hex::decode(hex).unwrap())
+    }
+
+    #[test]
+    pub fn test_constructors() {
+        let hash = PubkeyHash::from_str("89abcdefabbaabbaabbaabbaabbaabbaabbaabba").unwrap();
+
+        assert_eq!(Script::p2pkh(&hash), script("76a91489abcdefabbaabbaabbaabbaabbaabbaabbaabba88ac"));
+        assert_eq!(Script::p2sh(&ScriptHash::from_str("5a6e2a7a0b5ec9b2b0fd4bdac6ed7c6a5b9f3dba").unwrap()), script("a9145a6e2a7a0b5ec9b2b0fd4bdac6ed7c6a5b9f3dba87"));
+        assert_eq!(Script::p2wpkh(&hash), script("001489abcdefabbaabbaabbaabbaabbaabbaabbaabba"));
+        assert_eq!(Script::p2wsh(&[0x11; 32]), script(&format!("0020{}", "11".repeat(32))));
+        assert_eq!(Script::p2tr(&[0x22; 32]), script(&format!("5120{}", "22".repeat(32))));
+        assert_eq!(Script::p2pk(&hex::decode(KEY_1).unwrap()), script(&format!("21{}ac", KEY_1)));
+        assert_eq!(Script::null_data(b"hello world"), script("6a0b68656c6c6f20776f726c64"));
+
+        let keys = [hex::decode(KEY_1).unwrap(), hex::decode(KEY_2).unwrap()];
+        assert_eq!(Script::p2ms(2, &keys).unwrap(), script(&format!("5221{}21{}52ae", KEY_1, KEY_2)));
+    }
+
+    #[test]
+    pub fn test_p2ms_invalid() {
+        let keys = vec![hex::decode(KEY_1).unwrap(); 17];
+
+        assert_eq!(Script::p2ms(0, &keys[..2]), Err(ScriptError::InvalidMultisig(0, 2)));
+        assert_eq!(Script::p2ms(3, &keys[..2]), Err(ScriptError::InvalidMultisig(3, 2)));
+        assert_eq!(Script::p2ms(1, &keys), Err(ScriptError::InvalidMultisig(1, 17)));
+        assert!(Script::p2ms(16, &keys[..16]).is_ok());
+    }
+
+    #[test]
+    pub fn test_classify() {
+        let hash = PubkeyHash::from_str("89abcdefabbaabbaabbaabbaabbaabbaabbaabba").unwrap();
+        let keys = vec![hex::decode(KEY_1).unwrap(), hex::decode(KEY_2).unwrap()];
+
+        assert_eq!(Script::p2pkh(&hash).classify(), StandardScript::P2PKH(hash));
+        assert_eq!(Script::p2wpkh(&hash).classify(), StandardScript::P2WPKH(hash));
+        assert_eq!(Script::p2sh(&ScriptHash::from_byte_array([0x33; 20])).classify(), StandardScript::P2SH(ScriptHash::from_byte_array([0x33; 20])));
+        assert_eq!(Script::p2wsh(&[0x11; 32]).classify(), StandardScript::P2WSH([0x11; 32]));
+        assert_eq!(Script::p2tr(&[0x22; 32]).classify(), StandardScript::P2TR([0x22; 32]));
+        assert_eq!(Script::p2pk(&keys[0]).classify(), StandardScript::P2PK(keys[0].clone()));
+        assert_eq!(Script::p2ms(1, &keys).unwrap().classify(), StandardScript::P2MS { required: 1, public_keys: keys.clone() });
+        assert_eq!(Script::witness_program(2, &[0x44; 2]).classify(), StandardScript::WitnessUnknown { version: 2, program: vec![0x44; 2] });
+        assert_eq!(Script::witness_program(1, &[0x44; 20]).classify(), StandardScript::WitnessUnknown { version: 1, program: vec![0x44; 20] });
+        assert_eq!(Script::null_data(b"hello world").classify(), StandardScript::NullData(vec![b"hello world".to_vec()]));
+        assert_eq!(script("6a").classify(), StandardScript::NullData(vec![]));
+        assert_eq!(script("6a4f0051").classify(), StandardScript::NullData(vec![vec![0x81], vec![], vec![0x01]]));
+        assert_eq!(script("6a50").classify(), StandardScript::NullData(vec![]));
+        assert_eq!(script("6a50015151").classify(), StandardScript::NullData(vec![vec![0x51], vec![0x01]]));
+
+        // Close to a template but not quite: an empty script, non-pushes or a malformed push after OP_RETURN, a v0 program of
+        //  the wrong length, a non-minimal P2PKH push, keys of the wrong
+        //  size and multisig counts that don't add up.
+        let key = format!("02{}", "00".repeat(32));
+
+        for hex in [
+            "".to_string(),
+            "6a61".to_string(),
+            "6a4c".to_string(),
+            format!("0015{}", "00".repeat(21)),
+            "76a94c1489abcdefabbaabbaabbaabbaabbaabbaabbaabba88ac".to_string(),
+            format!("21{}00ac", key),
+            format!("21{}ac", key.replacen("02", "04", 1)),
+            format!("5221{}51ae", key),
+            format!("0021{}51ae", key),
+            format!("5121{}51ae51", key),
+            format!("5121{}0151ae", key),
+        ] {
+            assert_eq!(script(&hex).classify(), StandardScript::NonStandard, "{}", hex);
+        }
+
+        assert_eq!(script(&format!("5121{}51ae", key)).classify(), StandardScript::P2MS { required: 1, public_keys: vec![hex::decode(key).unwrap()] });
+    }
+
+    #[test]
+    pub fn test_witness_programs() {
+        assert_eq!(script("0014751e76e8199196d454941c45d1b3a323f1433bd6").witness_version_and_program(), Some((0, &hex::decode("751e76e8199196d454941c45d1b3a323f1433bd6").unwrap()[..])));
+        assert_eq!(script("6002751e").witness_version_and_program(), Some((16, &[0x75, 0x1e][..])));
+        // OP_1NEGATE is not a version and programs need a direct push.
+        assert_eq!(script("4f02751e").witness_version_and_program(), None);
+        assert_eq!(script("514c02751e").witness_version_and_program(), None);
+        assert_eq!(script("0001ff").witness_version_and_program(), None);
+
+        assert!(script("a9145a6e2a7a0b5ec9b2b0fd4bdac6ed7c6a5b9f3dba87").is_p2sh());
+        assert!(script("6a").is_op_return());
+        assert!(!script("516a").is_op_return());
+    }
+}
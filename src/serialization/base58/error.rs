@@ -0,0 +1,20 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Base58Error {
+    InvalidCharacter(char, usize),
+    TooShort(usize),
+    InvalidChecksum([u8; 4], [u8; 4]),
+}
+
+impl Error for Base58Error { }
+
+impl Display for Base58Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Base58Error::InvalidCharacter(c, i) => write!(f, "Base58Error::InvalidCharacter({:?} at position {})", c, i),
+            Base58Error::TooShort(len) => write!(f, "Base58Error::TooShort({} bytes, checksum needs at least 4)", len),
+            Base58Error::InvalidChecksum(expected, actual) => write!(f, "Base58Error::InvalidChecksum(expected {}, got {})", hex::encode(expected), hex::encode(actual)),
+        }
+    }
+}
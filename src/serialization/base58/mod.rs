@@ -0,0 +1,169 @@
+use crate::cryptography::hash::hash256;
+use self::error::Base58Error;
+
+pub mod error;
+
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+pub fn encode(bytes: &[u8]) -> String {
+    // Leading zero bytes carry no numeric value, so they are written as
+    //  one leading '1' each.
+    let zeros = bytes.iter().take_while(|b| **b == 0).count();
+
+    // Base 58 digits, least significant first.
+    let mut digits: Vec<u8> = Vec::with_capacity(bytes.len() * 138 / 100 + 1);
+
+    for byte in &bytes[zeros..] {
+        let mut carry = *byte as u32;
+
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    let mut encoded = String::with_capacity(zeros + digits.len());
+
+    encoded.extend(std::iter::repeat_n('1', zeros));
+    encoded.extend(digits.iter().rev().map(|d| ALPHABET[*d as usize] as char));
+
+    encoded
+}
+
+pub fn decode(s: &str) -> Result<Vec<u8>, Base58Error> {
+    let zeros = s.chars().take_while(|c| *c == '1').count();
+
+    // Base 256 digits, least significant first.
+    let mut bytes: Vec<u8> = Vec::with_capacity(s.len() * 733 / 1000 + 1);
+
+    for (i, c) in s.chars().enumerate().skip(zeros) {
+        let value = ALPHABET
+            .iter()
+            .position(|a| *a as char == c)
+            .ok_or(Base58Error::InvalidCharacter(c, i))?;
+
+        let mut carry = value as u32;
+
+        for byte in bytes.iter_mut() {
+            carry += (*byte as u32) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    let mut decoded = vec![0u8; zeros];
+    decoded.extend(bytes.iter().rev());
+
+    Ok(decoded)
+}
+
+/// Base58 with the first four bytes of the payload's hash256 appended.
+pub fn encode_check(payload: &[u8]) -> String {
+    let checksum = hash256(payload);
+
+    let mut bytes = payload.to_vec();
+    bytes.extend_from_slice(&checksum[..4]);
+
+    encode(&bytes)
+}
+
+/// Decodes a Base58Check string, returning the payload without its checksum.
+pub fn decode_check(s: &str) -> Result<Vec<u8>, Base58Error> {
+    let mut bytes = decode(s)?;
+
+    if bytes.len() < 4 {
+        return Err(Base58Error::TooShort(bytes.len()));
+    }
+
+    let checksum = bytes.split_off(bytes.len() - 4);
+    let expected = hash256(&bytes);
+
+    if expected[..4] != checksum[..] {
+        return Err(Base58Error::InvalidChecksum(
+            expected[..4].try_into().unwrap(),
+            checksum[..].try_into().unwrap(),
+        ));
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{decode, decode_check, encode, encode_check, error::Base58Error};
+
+    #[test]
+    pub fn test_encode() {
+        let cases = [
+            ("", ""),
+            ("61", "2g"),
+            ("626262", "a3gV"),
+            ("636363", "aPEr"),
+            ("73696d706c792061206c6f6e6720737472696e67", "2cFupjhnEsSn59qHXstmK2ffpLv2"),
+            ("00eb15231dfceb60925886b67d065299925915aeb172c06647", "1NS17iag9jJgTHD1VXjvLCEnZuQ3rJDE9L"),
+            ("516b6fcd0f", "ABnLTmg"),
+            ("bf4f89001e670274dd", "3SEo3LWLoPntC"),
+            ("572e4794", "3EFU7m"),
+            ("ecac89cad93923c02321", "EJDM8drfXA6uyA"),
+            ("10c8511e", "Rt5zm"),
+            ("00000000000000000000", "1111111111"),
+        ];
+
+        for (h, expected) in cases {
+            let bytes = hex::decode(h).unwrap();
+
+            assert_eq!(encode(&bytes), expected);
+            assert_eq!(decode(expected).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    pub fn test_leading_zeros() {
+        let bytes = [0u8, 0, 0, 1, 2, 3];
+
+        let encoded = encode(&bytes);
+
+        assert!(encoded.starts_with("111"));
+        assert_eq!(decode(&encoded).unwrap(), bytes);
+    }
+
+    #[test]
+    pub fn test_invalid_character() {
+        assert_eq!(decode("3SEo3L0LoPntC"), Err(Base58Error::InvalidCharacter('0', 6)));
+        assert_eq!(decode("Il"), Err(Base58Error::InvalidCharacter('I', 0)));
+    }
+
+    #[test]
+    pub fn test_check() {
+        let payload = hex::decode("00f54a5851e9372b87810a8e60cdd2e7cfd80b6e31").unwrap();
+
+        let encoded = encode_check(&payload);
+
+        assert_eq!(encoded, "1PMycacnJaSqwwJqjawXBErnLsZ7RkXUAs");
+        assert_eq!(decode_check(&encoded).unwrap(), payload);
+    }
+
+    #[test]
+    pub fn test_check_invalid_checksum() {
+        assert!(matches!(
+            decode_check("1PMycacnJaSqwwJqjawXBErnLsZ7RkXUAt"),
+            Err(Base58Error::InvalidChecksum(_, _))
+        ));
+    }
+
+    #[test]
+    pub fn test_check_too_short() {
+        assert_eq!(decode_check("1"), Err(Base58Error::TooShort(1)));
+    }
+}
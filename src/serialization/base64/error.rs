@@ -0,0 +1,20 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Base64Error {
+    InvalidCharacter(char, usize),
+    InvalidLength(usize),
+    InvalidPadding,
+}
+
+impl Error for Base64Error { }
+
+impl Display for Base64Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Base64Error::InvalidCharacter(c, i) => write!(f, "Base64Error::InvalidCharacter({:?} at position {})", c, i),
+            Base64Error::InvalidLength(len) => write!(f, "Base64Error::InvalidLength({} is not a multiple of 4)", len),
+            Base64Error::InvalidPadding => write!(f, "Base64Error::InvalidPadding"),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use self::error::Base64Error;
+
+pub mod error;
+
+/// RFC 4648's standard alphabet, which BIP174 uses for PSBTs.
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+const PADDING: char = '=';
+
+pub fn encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let mut group = [0u8; 3];
+        group[..chunk.len()].copy_from_slice(chunk);
+
+        let n = u32::from_be_bytes([0, group[0], group[1], group[2]]);
+
+        // Three bytes make four digits, one and two bytes two and three.
+        for i in 0..chunk.len() + 1 {
+            encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+        }
+
+        encoded.extend(std::iter::repeat_n(PADDING, 3 - chunk.len()));
+    }
+
+    encoded
+}
+
+/// Decodes padded base64, rejecting anything but the one encoding `encode`
+///  would give: no whitespace, and no bits set past the last byte.
+pub fn decode(s: &str) -> Result<Vec<u8>, Base64Error> {
+    if !s.len().is_multiple_of(4) {
+        return Err(Base64Error::InvalidLength(s.len()));
+    }
+
+    let padding = s.bytes().rev().take_while(|b| *b as char == PADDING).count();
+
+    if padding > 2 {
+        return Err(Base64Error::InvalidPadding);
+    }
+
+    let mut decoded = Vec::with_capacity(s.len() / 4 * 3);
+    let mut n = 0u32;
+
+    for (i, c) in s.chars().take(s.len() - padding).enumerate() {
+        let value = ALPHABET
+            .iter()
+            .position(|a| *a as char == c)
+            .ok_or(Base64Error::InvalidCharacter(c, i))?;
+
+        n = n << 6 | value as u32;
+
+        if i % 4 == 3 {
+            decoded.extend_from_slice(&n.to_be_bytes()[1..]);
+            n = 0;
+        }
+    }
+
+    match padding {
+        1 => {
+            if n & 0x3 != 0 {
+                return Err(Base64Error::InvalidPadding);
+            }
+
+            decoded.extend_from_slice(&(n >> 2).to_be_bytes()[2..]);
+        },
+        2 => {
+            if n & 0xf != 0 {
+                return Err(Base64Error::InvalidPadding);
+            }
+
+            decoded.push((n >> 4) as u8);
+        },
+        _ => { },
+    }
+
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{decode, encode, error::Base64Error};
+
+    #[test]
+    pub fn test_rfc4648() {
+        let cases = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+
+        for (plain, expected) in cases {
+            assert_eq!(encode(plain.as_bytes()), expected);
+            assert_eq!(decode(expected).unwrap(), plain.as_bytes());
+        }
+
+        let bytes: Vec<u8> = (0..=255).collect();
+        assert_eq!(decode(&encode(&bytes)).unwrap(), bytes);
+        assert_eq!(encode(&[0xfb, 0xff, 0xbf]), "+/+/");
+    }
+
+    #[test]
+    pub fn test_decode_invalid() {
+        assert_eq!(decode("Zm9"), Err(Base64Error::InvalidLength(3)));
+        assert_eq!(decode("Zm9v\n"), Err(Base64Error::InvalidLength(5)));
+        assert_eq!(decode("Zm-v"), Err(Base64Error::InvalidCharacter('-', 2)));
+        assert_eq!(decode("Zm=v"), Err(Base64Error::InvalidCharacter('=', 2)));
+        assert_eq!(decode("Z==="), Err(Base64Error::InvalidPadding));
+
+        // Bits below the last whole byte have to be zero.
+        assert_eq!(decode("Zh=="), Err(Base64Error::InvalidPadding));
+        assert_eq!(decode("Zm9="), Err(Base64Error::InvalidPadding));
+    }
+}
@@ -0,0 +1,30 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Bech32Error {
+    MixedCase,
+    InvalidLength(usize),
+    MissingSeparator,
+    InvalidHrp,
+    InvalidCharacter(char),
+    InvalidChecksum,
+    InvalidPadding,
+    InvalidData(u8),
+}
+
+impl Error for Bech32Error { }
+
+impl Display for Bech32Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Bech32Error::MixedCase => write!(f, "Bech32Error::MixedCase"),
+            Bech32Error::InvalidLength(len) => write!(f, "Bech32Error::InvalidLength({})", len),
+            Bech32Error::MissingSeparator => write!(f, "Bech32Error::MissingSeparator"),
+            Bech32Error::InvalidHrp => write!(f, "Bech32Error::InvalidHrp"),
+            Bech32Error::InvalidCharacter(c) => write!(f, "Bech32Error::InvalidCharacter({:?})", c),
+            Bech32Error::InvalidChecksum => write!(f, "Bech32Error::InvalidChecksum"),
+            Bech32Error::InvalidPadding => write!(f, "Bech32Error::InvalidPadding"),
+            Bech32Error::InvalidData(value) => write!(f, "Bech32Error::InvalidData({} does not fit in 5 bits)", value),
+        }
+    }
+}
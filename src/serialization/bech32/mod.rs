@@ -0,0 +1,267 @@
+use self::error::Bech32Error;
+
+pub mod error;
+
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+
+const MAX_LENGTH: usize = 90;
+const CHECKSUM_LENGTH: usize = 6;
+
+/// BIP173 bech32 and its BIP350 replacement bech32m differ only in the
+///  constant the checksum is XORed with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    Bech32,
+    Bech32m,
+}
+
+impl Variant {
+    fn constant(&self) -> u32 {
+        match self {
+            Variant::Bech32 => 1,
+            Variant::Bech32m => 0x2bc830a3,
+        }
+    }
+}
+
+fn polymod(values: impl Iterator<Item = u8>) -> u32 {
+    let mut chk: u32 = 1;
+
+    for value in values {
+        let top = chk >> 25;
+        chk = ((chk & 0x1ffffff) << 5) ^ value as u32;
+
+        for (i, g) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= g;
+            }
+        }
+    }
+
+    chk
+}
+
+fn hrp_expand(hrp: &str) -> impl Iterator<Item = u8> + '_ {
+    hrp.bytes()
+        .map(|b| b >> 5)
+        .chain(std::iter::once(0))
+        .chain(hrp.bytes().map(|b| b & 0x1f))
+}
+
+fn create_checksum(hrp: &str, data: &[u8], variant: Variant) -> [u8; CHECKSUM_LENGTH] {
+    let values = hrp_expand(hrp)
+        .chain(data.iter().copied())
+        .chain([0u8; CHECKSUM_LENGTH]);
+
+    let polymod = polymod(values) ^ variant.constant();
+
+    let mut checksum = [0u8; CHECKSUM_LENGTH];
+
+    for (i, c) in checksum.iter_mut().enumerate() {
+        *c = ((polymod >> (5 * (5 - i))) & 0x1f) as u8;
+    }
+
+    checksum
+}
+
+fn valid_hrp(hrp: &str) -> bool {
+    !hrp.is_empty() && hrp.bytes().all(|b| (33..=126).contains(&b))
+}
+
+/// Encodes 5-bit `data` under `hrp`. The result is lowercase.
+pub fn encode(hrp: &str, data: &[u8], variant: Variant) -> Result<String, Bech32Error> {
+    let hrp = hrp.to_lowercase();
+
+    if !valid_hrp(&hrp) {
+        return Err(Bech32Error::InvalidHrp);
+    }
+
+    if let Some(value) = data.iter().find(|d| **d > 31) {
+        return Err(Bech32Error::InvalidData(*value));
+    }
+
+    let length = hrp.len() + 1 + data.len() + CHECKSUM_LENGTH;
+
+    if length > MAX_LENGTH {
+        return Err(Bech32Error::InvalidLength(length));
+    }
+
+    let checksum = create_checksum(&hrp, data, variant);
+
+    let mut encoded = String::with_capacity(length);
+
+    encoded.push_str(&hrp);
+    encoded.push('1');
+    encoded.extend(data.iter().chain(checksum.iter()).map(|d| CHARSET[*d as usize] as char));
+
+    Ok(encoded)
+}
+
+/// Decodes a bech32 or bech32m string into its lowercase HRP, the 5-bit
+///  data without checksum, and which checksum variant matched.
+pub fn decode(s: &str) -> Result<(String, Vec<u8>, Variant), Bech32Error> {
+    if s.len() > MAX_LENGTH {
+        return Err(Bech32Error::InvalidLength(s.len()));
+    }
+
+    if let Some(c) = s.chars().find(|c| !(33..=126).contains(&(*c as u32))) {
+        return Err(Bech32Error::InvalidCharacter(c));
+    }
+
+    let has_lower = s.chars().any(|c| c.is_ascii_lowercase());
+    let has_upper = s.chars().any(|c| c.is_ascii_uppercase());
+
+    if has_lower && has_upper {
+        return Err(Bech32Error::MixedCase);
+    }
+
+    let s = s.to_lowercase();
+
+    let separator = s.rfind('1').ok_or(Bech32Error::MissingSeparator)?;
+    let (hrp, data) = (&s[..separator], &s[separator + 1..]);
+
+    if !valid_hrp(hrp) {
+        return Err(Bech32Error::InvalidHrp);
+    }
+
+    if data.len() < CHECKSUM_LENGTH {
+        return Err(Bech32Error::InvalidLength(s.len()));
+    }
+
+    let values = data
+        .chars()
+        .map(|c| {
+            CHARSET
+                .iter()
+                .position(|a| *a as char == c)
+                .map(|p| p as u8)
+                .ok_or(Bech32Error::InvalidCharacter(c))
+        })
+        .collect::<Result<Vec<u8>, Bech32Error>>()?;
+
+    let variant = match polymod(hrp_expand(hrp).chain(values.iter().copied())) {
+        c if c == Variant::Bech32.constant() => Variant::Bech32,
+        c if c == Variant::Bech32m.constant() => Variant::Bech32m,
+        _ => return Err(Bech32Error::InvalidChecksum),
+    };
+
+    let data = values[..values.len() - CHECKSUM_LENGTH].to_vec();
+
+    Ok((hrp.to_string(), data, variant))
+}
+
+/// Regroups `data` from `from`-bit to `to`-bit values. Without `pad`, any
+///  leftover bits must be fewer than `from` and all zero.
+pub fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Result<Vec<u8>, Bech32Error> {
+    let mut acc: u32 = 0;
+    let mut bits: u32 = 0;
+    let max: u32 = (1 << to) - 1;
+
+    let mut converted = Vec::with_capacity(data.len() * from as usize / to as usize + 1);
+
+    for value in data {
+        let value = *value as u32;
+
+        if value >> from != 0 {
+            return Err(Bech32Error::InvalidData(value as u8));
+        }
+
+        acc = (acc << from) | value;
+        bits += from;
+
+        while bits >= to {
+            bits -= to;
+            converted.push(((acc >> bits) & max) as u8);
+        }
+    }
+
+    if pad {
+        if bits > 0 {
+            converted.push(((acc << (to - bits)) & max) as u8);
+        }
+    } else if bits >= from || ((acc << (to - bits)) & max) != 0 {
+        return Err(Bech32Error::InvalidPadding);
+    }
+
+    Ok(converted)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{convert_bits, decode, encode, error::Bech32Error, Variant};
+
+    #[test]
+    pub fn test_valid_bech32() {
+        let cases = [
+            "A12UEL5L",
+            "a12uel5l",
+            "an83characterlonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1tt5tgs",
+            "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw",
+            "11qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqc8247j",
+            "split1checkupstagehandshakeupstreamerranterredcaperred2y9e3w",
+            "?1ezyfcl",
+        ];
+
+        for case in cases {
+            let (hrp, data, variant) = decode(case).unwrap();
+
+            assert_eq!(variant, Variant::Bech32);
+            assert_eq!(encode(&hrp, &data, variant).unwrap(), case.to_lowercase());
+        }
+    }
+
+    #[test]
+    pub fn test_valid_bech32m() {
+        let cases = [
+            "A1LQFN3A",
+            "a1lqfn3a",
+            "an83characterlonghumanreadablepartthatcontainsthetheexcludedcharactersbioandnumber11sg7hg6",
+            "abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx",
+            "11llllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllludsr8",
+            "split1checkupstagehandshakeupstreamerranterredcaperredlc445v",
+            "?1v759aa",
+        ];
+
+        for case in cases {
+            let (hrp, data, variant) = decode(case).unwrap();
+
+            assert_eq!(variant, Variant::Bech32m);
+            assert_eq!(encode(&hrp, &data, variant).unwrap(), case.to_lowercase());
+        }
+    }
+
+    #[test]
+    pub fn test_invalid() {
+        let cases = [
+            ("\x201nwldj5", Bech32Error::InvalidCharacter(' ')),
+            ("\x7f1axkwrx", Bech32Error::InvalidCharacter('\x7f')),
+            ("\u{80}1eym55h", Bech32Error::InvalidCharacter('\u{80}')),
+            ("an84characterslonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1569pvx", Bech32Error::InvalidLength(91)),
+            ("pzry9x0s0muk", Bech32Error::MissingSeparator),
+            ("1pzry9x0s0muk", Bech32Error::InvalidHrp),
+            ("x1b4n0q5v", Bech32Error::InvalidCharacter('b')),
+            ("li1dgmt3", Bech32Error::InvalidLength(8)),
+            ("A1G7SGD8", Bech32Error::InvalidChecksum),
+            ("10a06t8", Bech32Error::InvalidHrp),
+            ("1qzzfhee", Bech32Error::InvalidHrp),
+            ("M1VUXWEZ", Bech32Error::InvalidChecksum),
+            ("a12UEL5L", Bech32Error::MixedCase),
+        ];
+
+        for (case, expected) in cases {
+            assert_eq!(decode(case), Err(expected), "{}", case);
+        }
+    }
+
+    #[test]
+    pub fn test_convert_bits() {
+        let bytes = hex::decode("751e76e8199196d454941c45d1b3a323f1433bd6").unwrap();
+
+        let five = convert_bits(&bytes, 8, 5, true).unwrap();
+        assert_eq!(five.len(), 32);
+        assert_eq!(convert_bits(&five, 5, 8, false).unwrap(), bytes);
+
+        assert_eq!(convert_bits(&[0x1f], 5, 8, false), Err(Bech32Error::InvalidPadding));
+    }
+}
@@ -0,0 +1,86 @@
+use std::io::Read;
+use crate::{block::{header::BlockHeader, merkle_block::{MerkleBlock, PartialMerkleTree}, Block}, cryptography::hash::digest::{BlockHash, TxMerkleNode}};
+use super::{deserialize::{DeserializeConsensus, DeserializeConsensusError}, serialize::SerializeConsensus};
+
+impl SerializeConsensus for BlockHeader {
+    fn serialize_consensus_to(&self, buffer: &mut Vec<u8>) {
+        self.version.serialize_consensus_to(buffer);
+        self.prev_blockhash.serialize_consensus_to(buffer);
+        self.merkle_root.serialize_consensus_to(buffer);
+        self.time.serialize_consensus_to(buffer);
+        self.bits.serialize_consensus_to(buffer);
+        self.nonce.serialize_consensus_to(buffer);
+    }
+}
+
+impl DeserializeConsensus for BlockHeader {
+    fn deserialize_consensus_from<R: Read>(reader: &mut R) -> Result<Self, DeserializeConsensusError> {
+        Ok(BlockHeader {
+            version: i32::deserialize_consensus_from(reader)?,
+            prev_blockhash: BlockHash::deserialize_consensus_from(reader)?,
+            merkle_root: TxMerkleNode::deserialize_consensus_from(reader)?,
+            time: u32::deserialize_consensus_from(reader)?,
+            bits: u32::deserialize_consensus_from(reader)?,
+            nonce: u32::deserialize_consensus_from(reader)?,
+        })
+    }
+}
+
+impl SerializeConsensus for Block {
+    fn serialize_consensus_to(&self, buffer: &mut Vec<u8>) {
+        self.header.serialize_consensus_to(buffer);
+        self.transactions.serialize_consensus_to(buffer);
+    }
+}
+
+impl DeserializeConsensus for Block {
+    fn deserialize_consensus_from<R: Read>(reader: &mut R) -> Result<Self, DeserializeConsensusError> {
+        Ok(Block::new(BlockHeader::deserialize_consensus_from(reader)?, Vec::deserialize_consensus_from(reader)?))
+    }
+}
+
+impl SerializeConsensus for PartialMerkleTree {
+    fn serialize_consensus_to(&self, buffer: &mut Vec<u8>) {
+        self.transaction_count.serialize_consensus_to(buffer);
+        self.hashes.serialize_consensus_to(buffer);
+
+        // Flag bits are packed least significant first.
+        let mut bytes = vec![0u8; self.bits.len().div_ceil(8)];
+
+        for (position, bit) in self.bits.iter().enumerate() {
+            bytes[position / 8] |= (*bit as u8) << (position % 8);
+        }
+
+        bytes.serialize_consensus_to(buffer);
+    }
+}
+
+impl DeserializeConsensus for PartialMerkleTree {
+    fn deserialize_consensus_from<R: Read>(reader: &mut R) -> Result<Self, DeserializeConsensusError> {
+        let transaction_count = u32::deserialize_consensus_from(reader)?;
+        let hashes = Vec::deserialize_consensus_from(reader)?;
+        let bytes = Vec::<u8>::deserialize_consensus_from(reader)?;
+
+        Ok(PartialMerkleTree {
+            transaction_count,
+            hashes,
+            bits: (0..bytes.len() * 8).map(|position| bytes[position / 8] >> (position % 8) & 1 == 1).collect(),
+        })
+    }
+}
+
+impl SerializeConsensus for MerkleBlock {
+    fn serialize_consensus_to(&self, buffer: &mut Vec<u8>) {
+        self.header.serialize_consensus_to(buffer);
+        self.txn.serialize_consensus_to(buffer);
+    }
+}
+
+impl DeserializeConsensus for MerkleBlock {
+    fn deserialize_consensus_from<R: Read>(reader: &mut R) -> Result<Self, DeserializeConsensusError> {
+        Ok(MerkleBlock {
+            header: BlockHeader::deserialize_consensus_from(reader)?,
+            txn: PartialMerkleTree::deserialize_consensus_from(reader)?,
+        })
+    }
+}
@@ -0,0 +1,113 @@
+use std::io::Read;
+use super::deserialize::DeserializeConsensusError;
+
+/// Largest length Core will accept for any serialized vector.
+pub const MAX_SIZE: u64 = 0x0200_0000;
+
+pub fn write_compact_size(buffer: &mut Vec<u8>, n: u64) {
+    match n {
+        0..=0xfc => buffer.push(n as u8),
+        0xfd..=0xffff => {
+            buffer.push(0xfd);
+            buffer.extend_from_slice(&(n as u16).to_le_bytes());
+        },
+        0x10000..=0xffff_ffff => {
+            buffer.push(0xfe);
+            buffer.extend_from_slice(&(n as u32).to_le_bytes());
+        },
+        _ => {
+            buffer.push(0xff);
+            buffer.extend_from_slice(&n.to_le_bytes());
+        },
+    }
+}
+
+/// Reads a CompactSize, rejecting non-minimal encodings and values over
+///  `MAX_SIZE` the same way Core's `ReadCompactSize` does.
+pub fn read_compact_size<R: Read>(reader: &mut R) -> Result<u64, DeserializeConsensusError> {
+    let mut prefix = [0u8; 1];
+    reader.read_exact(&mut prefix)?;
+
+    let (n, min) = match prefix[0] {
+        0xfd => {
+            let mut bytes = [0u8; 2];
+            reader.read_exact(&mut bytes)?;
+
+            (u16::from_le_bytes(bytes) as u64, 0xfd)
+        },
+        0xfe => {
+            let mut bytes = [0u8; 4];
+            reader.read_exact(&mut bytes)?;
+
+            (u32::from_le_bytes(bytes) as u64, 0x10000)
+        },
+        0xff => {
+            let mut bytes = [0u8; 8];
+            reader.read_exact(&mut bytes)?;
+
+            (u64::from_le_bytes(bytes), 0x1_0000_0000)
+        },
+        n => (n as u64, 0),
+    };
+
+    if n < min {
+        return Err(DeserializeConsensusError::NonCanonicalCompactSize(n));
+    }
+
+    if n > MAX_SIZE {
+        return Err(DeserializeConsensusError::OversizedCompactSize(n));
+    }
+
+    Ok(n)
+}
+
+pub fn compact_size_len(n: u64) -> usize {
+    match n {
+        0..=0xfc => 1,
+        0xfd..=0xffff => 3,
+        0x10000..=0xffff_ffff => 5,
+        _ => 9,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::serialization::consensus::deserialize::DeserializeConsensusError;
+    use super::{compact_size_len, read_compact_size, write_compact_size};
+
+    #[test]
+    pub fn test_compact_size_round_trip() {
+        for (n, encoded) in [
+            (0u64, "00"),
+            (0xfc, "fc"),
+            (0xfd, "fdfd00"),
+            (0xffff, "fdffff"),
+            (0x10000, "fe00000100"),
+            (0x0200_0000, "fe00000002"),
+        ] {
+            let mut buffer = Vec::new();
+            write_compact_size(&mut buffer, n);
+
+            assert_eq!(hex::encode(&buffer), encoded);
+            assert_eq!(compact_size_len(n), buffer.len());
+            assert_eq!(read_compact_size(&mut &buffer[..]).unwrap(), n);
+        }
+    }
+
+    #[test]
+    pub fn test_compact_size_non_canonical() {
+        for encoded in ["fdfc00", "feffff0000"] {
+            let bytes = hex::decode(encoded).unwrap();
+
+            assert!(matches!(read_compact_size(&mut &bytes[..]), Err(DeserializeConsensusError::NonCanonicalCompactSize(_))));
+        }
+
+        let bytes = hex::decode("ff0000000001000000").unwrap();
+        assert!(matches!(read_compact_size(&mut &bytes[..]), Err(DeserializeConsensusError::OversizedCompactSize(_))));
+
+        let bytes = hex::decode("fe01000002").unwrap();
+        assert!(matches!(read_compact_size(&mut &bytes[..]), Err(DeserializeConsensusError::OversizedCompactSize(0x0200_0001))));
+
+        assert!(matches!(read_compact_size(&mut &[0xfd, 0x00][..]), Err(DeserializeConsensusError::Io(_))));
+    }
+}
@@ -0,0 +1,48 @@
+use std::{error::Error, fmt::Display, io::Read};
+
+#[derive(Debug)]
+pub enum DeserializeConsensusError {
+    Io(std::io::Error),
+    NonCanonicalCompactSize(u64),
+    OversizedCompactSize(u64),
+    UnknownSegwitFlag(u8),
+    SuperfluousWitness,
+    TrailingBytes(usize),
+}
+
+impl Error for DeserializeConsensusError { }
+
+impl Display for DeserializeConsensusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeserializeConsensusError::Io(e) => write!(f, "DeserializeConsensusError::Io({})", e),
+            DeserializeConsensusError::NonCanonicalCompactSize(n) => write!(f, "DeserializeConsensusError::NonCanonicalCompactSize({} has a shorter encoding)", n),
+            DeserializeConsensusError::OversizedCompactSize(n) => write!(f, "DeserializeConsensusError::OversizedCompactSize({})", n),
+            DeserializeConsensusError::UnknownSegwitFlag(flag) => write!(f, "DeserializeConsensusError::UnknownSegwitFlag({:#04x})", flag),
+            DeserializeConsensusError::SuperfluousWitness => write!(f, "DeserializeConsensusError::SuperfluousWitness(witness flag set but every witness is empty)"),
+            DeserializeConsensusError::TrailingBytes(len) => write!(f, "DeserializeConsensusError::TrailingBytes({})", len),
+        }
+    }
+}
+
+pub trait DeserializeConsensus: Sized {
+    fn deserialize_consensus_from<R: Read>(reader: &mut R) -> Result<Self, DeserializeConsensusError>;
+
+    /// Decodes exactly one value from `bytes`, rejecting anything left over.
+    fn deserialize_consensus(bytes: &[u8]) -> Result<Self, DeserializeConsensusError> {
+        let mut reader = bytes;
+        let value = Self::deserialize_consensus_from(&mut reader)?;
+
+        if !reader.is_empty() {
+            return Err(DeserializeConsensusError::TrailingBytes(reader.len()));
+        }
+
+        Ok(value)
+    }
+}
+
+impl From<std::io::Error> for DeserializeConsensusError {
+    fn from(value: std::io::Error) -> Self {
+        DeserializeConsensusError::Io(value)
+    }
+}
@@ -0,0 +1,6 @@
+mod block;
+mod primitives;
+mod transaction;
+pub mod compact_size;
+pub mod serialize;
+pub mod deserialize;
@@ -0,0 +1,101 @@
+use std::io::Read;
+use crate::cryptography::hash::digest::{BlockHash, Txid, TxMerkleNode};
+use super::{compact_size::{read_compact_size, write_compact_size}, deserialize::{DeserializeConsensus, DeserializeConsensusError}, serialize::SerializeConsensus};
+
+macro_rules! impl_le_integer {
+    ($t:ty) => {
+        impl SerializeConsensus for $t {
+            fn serialize_consensus_to(&self, buffer: &mut Vec<u8>) {
+                buffer.extend_from_slice(&self.to_le_bytes());
+            }
+        }
+
+        impl DeserializeConsensus for $t {
+            fn deserialize_consensus_from<R: Read>(reader: &mut R) -> Result<Self, DeserializeConsensusError> {
+                let mut bytes = [0u8; std::mem::size_of::<$t>()];
+                reader.read_exact(&mut bytes)?;
+
+                Ok(<$t>::from_le_bytes(bytes))
+            }
+        }
+    };
+}
+
+impl_le_integer!(u16);
+impl_le_integer!(u32);
+impl_le_integer!(i32);
+impl_le_integer!(u64);
+impl_le_integer!(i64);
+
+macro_rules! impl_digest {
+    ($t:ty) => {
+        impl SerializeConsensus for $t {
+            fn serialize_consensus_to(&self, buffer: &mut Vec<u8>) {
+                buffer.extend_from_slice(self.as_bytes());
+            }
+        }
+
+        impl DeserializeConsensus for $t {
+            fn deserialize_consensus_from<R: Read>(reader: &mut R) -> Result<Self, DeserializeConsensusError> {
+                let mut bytes = [0u8; 32];
+                reader.read_exact(&mut bytes)?;
+
+                Ok(<$t>::from_byte_array(bytes))
+            }
+        }
+    };
+}
+
+impl_digest!(Txid);
+impl_digest!(BlockHash);
+impl_digest!(TxMerkleNode);
+
+// Byte strings (scripts, witness items) are length-prefixed and copied
+//  wholesale rather than going through the element-wise `Vec<T>` impl.
+impl SerializeConsensus for Vec<u8> {
+    fn serialize_consensus_to(&self, buffer: &mut Vec<u8>) {
+        write_compact_size(buffer, self.len() as u64);
+        buffer.extend_from_slice(self);
+    }
+}
+
+impl DeserializeConsensus for Vec<u8> {
+    fn deserialize_consensus_from<R: Read>(reader: &mut R) -> Result<Self, DeserializeConsensusError> {
+        let len = read_compact_size(reader)?;
+
+        let mut bytes = Vec::new();
+        reader.take(len).read_to_end(&mut bytes)?;
+
+        if bytes.len() as u64 != len {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+        }
+
+        Ok(bytes)
+    }
+}
+
+impl<T: SerializeConsensus> SerializeConsensus for Vec<T> {
+    fn serialize_consensus_to(&self, buffer: &mut Vec<u8>) {
+        write_compact_size(buffer, self.len() as u64);
+
+        for item in self {
+            item.serialize_consensus_to(buffer);
+        }
+    }
+}
+
+impl<T: DeserializeConsensus> DeserializeConsensus for Vec<T> {
+    fn deserialize_consensus_from<R: Read>(reader: &mut R) -> Result<Self, DeserializeConsensusError> {
+        let len = read_compact_size(reader)?;
+
+        // The count is attacker controlled, so let the vector grow with what
+        //  actually decodes instead of reserving `len` up front.
+        let mut items = Vec::new();
+
+        for _ in 0..len {
+            items.push(T::deserialize_consensus_from(reader)?);
+        }
+
+        Ok(items)
+    }
+}
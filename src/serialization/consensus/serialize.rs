@@ -0,0 +1,11 @@
+/// Bitcoin's wire and disk encoding, as hashed for txids and block hashes.
+pub trait SerializeConsensus {
+    fn serialize_consensus_to(&self, buffer: &mut Vec<u8>);
+
+    fn serialize_consensus(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        self.serialize_consensus_to(&mut buffer);
+
+        buffer
+    }
+}
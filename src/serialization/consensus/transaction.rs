@@ -0,0 +1,215 @@
+use std::io::Read;
+use crate::{cryptography::hash::digest::Txid, transaction::{out_point::OutPoint, tx_in::TxIn, tx_out::TxOut, witness::Witness, Transaction}};
+use super::{compact_size::write_compact_size, deserialize::{DeserializeConsensus, DeserializeConsensusError}, serialize::SerializeConsensus};
+
+/// BIP144 marker byte, standing where a legacy transaction's input count would be.
+const SEGWIT_MARKER: u8 = 0x00;
+const SEGWIT_FLAG: u8 = 0x01;
+
+impl SerializeConsensus for OutPoint {
+    fn serialize_consensus_to(&self, buffer: &mut Vec<u8>) {
+        self.txid.serialize_consensus_to(buffer);
+        self.vout.serialize_consensus_to(buffer);
+    }
+}
+
+impl DeserializeConsensus for OutPoint {
+    fn deserialize_consensus_from<R: Read>(reader: &mut R) -> Result<Self, DeserializeConsensusError> {
+        Ok(OutPoint::new(Txid::deserialize_consensus_from(reader)?, u32::deserialize_consensus_from(reader)?))
+    }
+}
+
+// Witnesses are not part of the input encoding; `Transaction` writes them
+//  after the outputs.
+impl SerializeConsensus for TxIn {
+    fn serialize_consensus_to(&self, buffer: &mut Vec<u8>) {
+        self.previous_output.serialize_consensus_to(buffer);
+        self.script_sig.serialize_consensus_to(buffer);
+        self.sequence.serialize_consensus_to(buffer);
+    }
+}
+
+impl DeserializeConsensus for TxIn {
+    fn deserialize_consensus_from<R: Read>(reader: &mut R) -> Result<Self, DeserializeConsensusError> {
+        Ok(TxIn {
+            previous_output: OutPoint::deserialize_consensus_from(reader)?,
+            script_sig: Vec::deserialize_consensus_from(reader)?,
+            sequence: u32::deserialize_consensus_from(reader)?,
+            witness: Witness::new(),
+        })
+    }
+}
+
+impl SerializeConsensus for TxOut {
+    fn serialize_consensus_to(&self, buffer: &mut Vec<u8>) {
+        self.value.serialize_consensus_to(buffer);
+        self.script_pubkey.serialize_consensus_to(buffer);
+    }
+}
+
+impl DeserializeConsensus for TxOut {
+    fn deserialize_consensus_from<R: Read>(reader: &mut R) -> Result<Self, DeserializeConsensusError> {
+        Ok(TxOut::new(u64::deserialize_consensus_from(reader)?, Vec::deserialize_consensus_from(reader)?))
+    }
+}
+
+impl SerializeConsensus for Witness {
+    fn serialize_consensus_to(&self, buffer: &mut Vec<u8>) {
+        write_compact_size(buffer, self.len() as u64);
+
+        for item in self.items() {
+            item.serialize_consensus_to(buffer);
+        }
+    }
+}
+
+impl DeserializeConsensus for Witness {
+    fn deserialize_consensus_from<R: Read>(reader: &mut R) -> Result<Self, DeserializeConsensusError> {
+        Ok(Witness::from_items(Vec::deserialize_consensus_from(reader)?))
+    }
+}
+
+impl SerializeConsensus for Transaction {
+    fn serialize_consensus_to(&self, buffer: &mut Vec<u8>) {
+        if !self.has_witness() {
+            buffer.extend_from_slice(&self.serialize_without_witness());
+            return;
+        }
+
+        self.version.serialize_consensus_to(buffer);
+        buffer.push(SEGWIT_MARKER);
+        buffer.push(SEGWIT_FLAG);
+        self.inputs.serialize_consensus_to(buffer);
+        self.outputs.serialize_consensus_to(buffer);
+
+        for input in &self.inputs {
+            input.witness.serialize_consensus_to(buffer);
+        }
+
+        self.lock_time.serialize_consensus_to(buffer);
+    }
+}
+
+// Mirrors Core's `UnserializeTransaction`: an empty input vector is read as
+//  the segwit marker, after which the flag byte decides what follows.
+impl DeserializeConsensus for Transaction {
+    fn deserialize_consensus_from<R: Read>(reader: &mut R) -> Result<Self, DeserializeConsensusError> {
+        let version = i32::deserialize_consensus_from(reader)?;
+
+        let mut flags = 0u8;
+        let mut inputs = Vec::<TxIn>::deserialize_consensus_from(reader)?;
+        let mut outputs = Vec::new();
+
+        if inputs.is_empty() {
+            let mut flag = [0u8; 1];
+            reader.read_exact(&mut flag)?;
+            flags = flag[0];
+
+            if flags != 0 {
+                inputs = Vec::deserialize_consensus_from(reader)?;
+                outputs = Vec::deserialize_consensus_from(reader)?;
+            }
+        } else {
+            outputs = Vec::deserialize_consensus_from(reader)?;
+        }
+
+        if flags & SEGWIT_FLAG != 0 {
+            flags ^= SEGWIT_FLAG;
+
+            for input in inputs.iter_mut() {
+                input.witness = Witness::deserialize_consensus_from(reader)?;
+            }
+
+            if inputs.iter().all(|input| input.witness.is_empty()) {
+                return Err(DeserializeConsensusError::SuperfluousWitness);
+            }
+        }
+
+        if flags != 0 {
+            return Err(DeserializeConsensusError::UnknownSegwitFlag(flags));
+        }
+
+        let lock_time = u32::deserialize_consensus_from(reader)?;
+
+        Ok(Transaction::new(version, inputs, outputs, lock_time))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+    use crate::{cryptography::hash::digest::Txid, serialization::consensus::{deserialize::{DeserializeConsensus, DeserializeConsensusError}, serialize::SerializeConsensus}, transaction::Transaction};
+
+    const MAINNET_TRANSACTIONS: &str = include_str!("../../../test_data/mainnet_transactions.txt");
+
+    #[test]
+    pub fn test_mainnet_round_trip() {
+        let fixtures = MAINNET_TRANSACTIONS.lines().filter(|line| !line.starts_with('#'));
+
+        for line in fixtures {
+            let fields: Vec<&str> = line.split(' ').collect();
+            let bytes = hex::decode(fields[3]).unwrap();
+
+            let tx = Transaction::deserialize_consensus(&bytes).unwrap();
+
+            assert_eq!(tx.serialize_consensus(), bytes);
+            assert_eq!(tx.txid().to_string(), fields[0]);
+            assert_eq!(tx.wtxid().to_string(), fields[1]);
+            assert_eq!(tx.weight().to_string(), fields[2]);
+            assert_eq!(tx.total_size(), bytes.len());
+        }
+    }
+
+    #[test]
+    pub fn test_deserialize_legacy() {
+        let bytes = hex::decode("0100000001a15d57094aa7a21a28cb20b59aab8fc7d1149a3bdbcddba9c622e4f5f6a99ece010000006c493046022100f93bb0e7d8db7bd46e40132d1f8242026e045f03a0efe71bbb8e3f475e970d790221009337cd7f1f929f00cc6ff01f03729b069a7c21b59b1736ddfee5db5946c5da8c0121033b9b137ee87d5a812d6f506efdd37f0affa7ffc310711c06c7f3e097c9447c52ffffffff0100e1f505000000001976a9140389035a9225b3839e2bbf32d826a1e222031fd888ac00000000").unwrap();
+        let tx = Transaction::deserialize_consensus(&bytes).unwrap();
+
+        assert_eq!(tx.version, 1);
+        assert_eq!(tx.lock_time, 0);
+        assert_eq!(tx.inputs.len(), 1);
+        assert_eq!(tx.inputs[0].previous_output.txid, Txid::from_str("ce9ea9f6f5e422c6a9dbcddb3b9a14d1c78fab9ab520cb281aa2a74a09575da1").unwrap());
+        assert_eq!(tx.inputs[0].previous_output.vout, 1);
+        assert_eq!(tx.inputs[0].script_sig.len(), 0x6c);
+        assert_eq!(tx.inputs[0].sequence, 0xffffffff);
+        assert_eq!(tx.outputs[0].value, 100_000_000);
+        assert_eq!(hex::encode(&tx.outputs[0].script_pubkey), "76a9140389035a9225b3839e2bbf32d826a1e222031fd888ac");
+        assert!(!tx.has_witness());
+        assert_eq!(tx.txid().to_string(), "a6eab3c14ab5272a58a5ba91505ba1a4b6d7a3a9fcbd187b6cd99a7b6d548cb7");
+    }
+
+    #[test]
+    pub fn test_deserialize_segwit() {
+        let bytes = hex::decode("02000000000101595895ea20179de87052b4046dfe6fd515860505d6511a9004cf12a1f93cac7c0100000000ffffffff01deb807000000000017a9140f3444e271620c736808aa7b33e370bd87cb5a078702483045022100fb60dad8df4af2841adc0346638c16d0b8035f5e3f3753b88db122e70c79f9370220756e6633b17fd2710e626347d28d60b0a2d6cbb41de51740644b9fb3ba7751040121028fa937ca8cba2197a37c007176ed8941055d3bcb8627d085e94553e62f057dcc00000000").unwrap();
+        let tx = Transaction::deserialize_consensus(&bytes).unwrap();
+
+        assert!(tx.has_witness());
+        assert_eq!(tx.inputs[0].witness.len(), 2);
+        assert_eq!(tx.inputs[0].witness.last().unwrap().len(), 33);
+        assert_eq!(tx.txid().to_string(), "f5864806e3565c34d1b41e716f72609d00b55ea5eac5b924c9719a842ef42206");
+        assert_eq!(tx.wtxid().to_string(), "80b7d8a82d5d5bf92905b06f2014dd699e03837ca172e3a59d51426ebbe3e7f5");
+        assert_eq!(tx.weight(), 442);
+        assert_eq!(tx.vsize(), 111);
+    }
+
+    #[test]
+    pub fn test_deserialize_invalid() {
+        // Witness flag set, but the only witness is empty.
+        let bytes = hex::decode("0000fd000001021921212121212121212121f8b372b0239cc1dff600000000004f4f4f4f4f4f4f4f000000000000000000000000000000333732343133380d000000000000000000000000000000ff000000000009000dff000000000000000800000000000000000d").unwrap();
+        assert!(Transaction::deserialize_consensus(&bytes).is_err());
+
+        let bytes = hex::decode("020000000001010000000000000000000000000000000000000000000000000000000000000000ffffffff00ffffffff0000000000000000").unwrap();
+        assert!(matches!(Transaction::deserialize_consensus(&bytes), Err(DeserializeConsensusError::SuperfluousWitness)));
+
+        let bytes = hex::decode("020000000002010000000000000000000000000000000000000000000000000000000000000000ffffffff00ffffffff000000000000").unwrap();
+        assert!(matches!(Transaction::deserialize_consensus(&bytes), Err(DeserializeConsensusError::UnknownSegwitFlag(0x02))));
+
+        let legacy = "0100000001a15d57094aa7a21a28cb20b59aab8fc7d1149a3bdbcddba9c622e4f5f6a99ece010000006c493046022100f93bb0e7d8db7bd46e40132d1f8242026e045f03a0efe71bbb8e3f475e970d790221009337cd7f1f929f00cc6ff01f03729b069a7c21b59b1736ddfee5db5946c5da8c0121033b9b137ee87d5a812d6f506efdd37f0affa7ffc310711c06c7f3e097c9447c52ffffffff0100e1f505000000001976a9140389035a9225b3839e2bbf32d826a1e222031fd888ac00000000";
+
+        let bytes = hex::decode(format!("{}00", legacy)).unwrap();
+        assert!(matches!(Transaction::deserialize_consensus(&bytes), Err(DeserializeConsensusError::TrailingBytes(1))));
+
+        let bytes = hex::decode(&legacy[..legacy.len() - 2]).unwrap();
+        assert!(matches!(Transaction::deserialize_consensus(&bytes), Err(DeserializeConsensusError::Io(_))));
+    }
+}
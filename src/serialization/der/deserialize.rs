@@ -0,0 +1,27 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Debug)]
+pub enum DeserializeDERError {
+    InvalidFormat,
+    InvalidValue,
+}
+
+impl Error for DeserializeDERError { }
+
+impl Display for DeserializeDERError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeserializeDERError::InvalidFormat => write!(f, "DeserializeDERError(Invalid Format)"),
+            DeserializeDERError::InvalidValue => write!(f, "DeserializeDERError(Invalid Value)"),
+        }
+    }
+}
+
+pub trait DeserializeDER: Sized {
+    /// Strict DER, as BIP66 requires of signatures.
+    fn deserialize_der<'a>(s: impl Into<&'a [u8]>) -> Result<Self, DeserializeDERError>;
+
+    /// The lenient parsing consensus falls back to for signatures that
+    ///  predate BIP66, accepting e.g. padded integers and long-form lengths.
+    fn deserialize_der_lax<'a>(s: impl Into<&'a [u8]>) -> Result<Self, DeserializeDERError>;
+}
@@ -0,0 +1,3 @@
+mod signature;
+pub mod serialize;
+pub mod deserialize;
@@ -0,0 +1,3 @@
+pub trait SerializeDER {
+    fn serialize_der(&self) -> Vec<u8>;
+}
@@ -0,0 +1,243 @@
+use num_bigint::BigUint;
+use crate::cryptography::signature::Signature;
+use super::{deserialize::{DeserializeDER, DeserializeDERError}, serialize::SerializeDER};
+
+impl SerializeDER for Signature {
+    fn serialize_der(&self) -> Vec<u8> {
+        let r = encode_integer(self.r());
+        let s = encode_integer(self.s());
+
+        let mut der = vec![0x30, (r.len() + s.len()) as u8];
+        der.extend(r);
+        der.extend(s);
+
+        der
+    }
+}
+
+impl DeserializeDER for Signature {
+    fn deserialize_der<'a>(s: impl Into<&'a [u8]>) -> Result<Self, DeserializeDERError> {
+        let der = s.into();
+
+        let (tag, len, rest) = match der {
+            [tag, len, rest @ ..] => (*tag, *len as usize, rest),
+            _ => return Err(DeserializeDERError::InvalidFormat),
+        };
+
+        if tag != 0x30 || len != rest.len() {
+            return Err(DeserializeDERError::InvalidFormat);
+        }
+
+        let (r, rest) = decode_integer(rest)?;
+        let (s, rest) = decode_integer(rest)?;
+
+        if !rest.is_empty() {
+            return Err(DeserializeDERError::InvalidFormat);
+        }
+
+        Ok(Signature::new(r, s))
+    }
+
+    // Follows libsecp256k1's `ecdsa_signature_parse_der_lax`, which Core
+    //  uses for every signature. Integers too large for 32 bytes parse, but
+    //  as zero, so the signature never verifies.
+    fn deserialize_der_lax<'a>(s: impl Into<&'a [u8]>) -> Result<Self, DeserializeDERError> {
+        let der = s.into();
+        let mut pos = 0;
+
+        let byte = |pos: usize| der.get(pos).copied().ok_or(DeserializeDERError::InvalidFormat);
+
+        if byte(pos)? != 0x30 {
+            return Err(DeserializeDERError::InvalidFormat);
+        }
+        pos += 1;
+
+        // The sequence length is ignored, apart from skipping a long form.
+        let len = byte(pos)? as usize;
+        pos += 1;
+
+        if len & 0x80 != 0 {
+            if len - 0x80 > der.len() - pos {
+                return Err(DeserializeDERError::InvalidFormat);
+            }
+
+            pos += len - 0x80;
+        }
+
+        let (r, next) = lax_integer(der, pos)?;
+        let (s, _) = lax_integer(der, next)?;
+
+        let r = lax_value(r);
+        let s = lax_value(s);
+
+        match (r, s) {
+            (Some(r), Some(s)) => Ok(Signature::new(r, s)),
+            _ => Ok(Signature::new(0u32, 0u32)),
+        }
+    }
+}
+
+fn encode_integer(n: &BigUint) -> Vec<u8> {
+    let mut bytes = n.to_bytes_be();
+
+    // A set top bit would make the integer negative.
+    if bytes[0] & 0x80 != 0 {
+        bytes.insert(0, 0x00);
+    }
+
+    let mut encoded = vec![0x02, bytes.len() as u8];
+    encoded.extend(bytes);
+
+    encoded
+}
+
+fn decode_integer(der: &[u8]) -> Result<(BigUint, &[u8]), DeserializeDERError> {
+    let (len, rest) = match der {
+        [0x02, len, rest @ ..] => (*len as usize, rest),
+        _ => return Err(DeserializeDERError::InvalidFormat),
+    };
+
+    if len == 0 || len > rest.len() {
+        return Err(DeserializeDERError::InvalidFormat);
+    }
+
+    let (bytes, rest) = rest.split_at(len);
+
+    if bytes[0] & 0x80 != 0 {
+        return Err(DeserializeDERError::InvalidValue);
+    }
+
+    // Only allowed to keep the value positive.
+    if len > 1 && bytes[0] == 0x00 && bytes[1] & 0x80 == 0 {
+        return Err(DeserializeDERError::InvalidFormat);
+    }
+
+    Ok((BigUint::from_bytes_be(bytes), rest))
+}
+
+/// An INTEGER at `pos` with any length encoding, returning its content and
+///  the position after it.
+fn lax_integer(der: &[u8], mut pos: usize) -> Result<(&[u8], usize), DeserializeDERError> {
+    if der.get(pos) != Some(&0x02) {
+        return Err(DeserializeDERError::InvalidFormat);
+    }
+    pos += 1;
+
+    let mut len_byte = *der.get(pos).ok_or(DeserializeDERError::InvalidFormat)? as usize;
+    pos += 1;
+
+    let len = if len_byte & 0x80 != 0 {
+        len_byte -= 0x80;
+
+        if len_byte > der.len() - pos {
+            return Err(DeserializeDERError::InvalidFormat);
+        }
+
+        while len_byte > 0 && der[pos] == 0 {
+            pos += 1;
+            len_byte -= 1;
+        }
+
+        if len_byte >= std::mem::size_of::<usize>() {
+            return Err(DeserializeDERError::InvalidFormat);
+        }
+
+        let mut len = 0usize;
+
+        for _ in 0..len_byte {
+            len = len << 8 | der[pos] as usize;
+            pos += 1;
+        }
+
+        len
+    } else {
+        len_byte
+    };
+
+    if len > der.len() - pos {
+        return Err(DeserializeDERError::InvalidFormat);
+    }
+
+    Ok((&der[pos..pos + len], pos + len))
+}
+
+/// Strips leading zeros, returning `None` if what is left exceeds 32 bytes.
+fn lax_value(mut bytes: &[u8]) -> Option<BigUint> {
+    while let [0, rest @ ..] = bytes {
+        bytes = rest;
+    }
+
+    (bytes.len() <= 32).then(|| BigUint::from_bytes_be(bytes))
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigUint;
+    use crate::{cryptography::signature::Signature, serialization::der::{deserialize::DeserializeDER, serialize::SerializeDER}};
+
+    // "Programming Bitcoin" chapter 4.
+    const DER: &str = "3045022037206a0610995c58074999cb9767b87af4c4978db68c06e8e6e81d282047a7c60221008ca63759c1157ebeaec0d03cecca119fc9a75bf8e6d0fa65c841c8e2738cdaec";
+
+    #[test]
+    pub fn test_serialize_der() {
+        let r = BigUint::parse_bytes(b"37206a0610995c58074999cb9767b87af4c4978db68c06e8e6e81d282047a7c6", 16).unwrap();
+        let s = BigUint::parse_bytes(b"8ca63759c1157ebeaec0d03cecca119fc9a75bf8e6d0fa65c841c8e2738cdaec", 16).unwrap();
+
+        assert_eq!(hex::encode(Signature::new(r, s).serialize_der()), DER);
+    }
+
+    #[test]
+    pub fn test_round_trip() {
+        let der = hex::decode(DER).unwrap();
+
+        for signature in [Signature::deserialize_der(&der[..]).unwrap(), Signature::deserialize_der_lax(&der[..]).unwrap()] {
+            assert_eq!(signature.serialize_der(), der);
+        }
+
+        let small = Signature::new(1u32, 0x80u32);
+        assert_eq!(hex::encode(small.serialize_der()), "300702010102020080");
+        assert_eq!(Signature::deserialize_der(&small.serialize_der()[..]).unwrap().s(), &BigUint::from(0x80u32));
+    }
+
+    #[test]
+    pub fn test_strict_rejects() {
+        let cases = [
+            "",
+            "30060201010201",
+            "3006020101020101ff",
+            "3106020101020101",
+            "3006020002010101",
+            "3006020181020101",
+            "300702020001020101",
+            "308106020101020101",
+        ];
+
+        for case in cases {
+            let der = hex::decode(case).unwrap();
+
+            assert!(Signature::deserialize_der(&der[..]).is_err(), "{}", case);
+        }
+    }
+
+    #[test]
+    pub fn test_lax() {
+        // Padded integers, a long-form sequence length and a wrong sequence
+        //  length are all fine.
+        for case in ["300702020001020101", "308106020101020101", "3000020101020101", "30080282000101020101"] {
+            let der = hex::decode(case).unwrap();
+            let signature = Signature::deserialize_der_lax(&der[..]).unwrap();
+
+            assert_eq!((signature.r(), signature.s()), (&BigUint::from(1u32), &BigUint::from(1u32)), "{}", case);
+        }
+
+        // An oversized integer parses as the zero signature.
+        let mut der = vec![0x30, 0x26, 0x02, 0x21];
+        der.extend_from_slice(&[0x01; 33]);
+        der.extend_from_slice(&[0x02, 0x01, 0x01]);
+
+        assert_eq!(Signature::deserialize_der_lax(&der[..]).unwrap().r(), &BigUint::from(0u32));
+
+        assert!(Signature::deserialize_der_lax(&[0x30, 0x06, 0x02, 0x01][..]).is_err());
+        assert!(Signature::deserialize_der_lax(&[0x31, 0x06][..]).is_err());
+    }
+}
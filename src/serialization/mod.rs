@@ -1 +1,8 @@
-mod sec;
\ No newline at end of file
+pub mod sec;
+pub mod base58;
+pub mod bech32;
+pub mod wif;
+pub mod consensus;
+pub mod der;
+pub mod base64;
+pub mod psbt;
\ No newline at end of file
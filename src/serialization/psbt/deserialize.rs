@@ -0,0 +1,64 @@
+use std::{error::Error, fmt::Display};
+use crate::serialization::{base64::error::Base64Error, consensus::deserialize::DeserializeConsensusError};
+
+#[derive(Debug)]
+pub enum DeserializePSBTError {
+    Consensus(DeserializeConsensusError),
+    Base64(Base64Error),
+    InvalidMagic,
+    MissingUnsignedTx,
+    UnsignedTxHasScriptSigs,
+    UnsupportedVersion(u32),
+    DuplicateKey(Vec<u8>),
+    MissingKey(Vec<u8>),
+    InvalidKey(Vec<u8>),
+    InvalidValue(Vec<u8>),
+    NonWitnessUtxoMismatch(usize),
+    LockTimeConflict,
+    TrailingBytes(usize),
+}
+
+impl Error for DeserializePSBTError { }
+
+impl Display for DeserializePSBTError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeserializePSBTError::Consensus(e) => write!(f, "DeserializePSBTError::Consensus({})", e),
+            DeserializePSBTError::Base64(e) => write!(f, "DeserializePSBTError::Base64({})", e),
+            DeserializePSBTError::InvalidMagic => write!(f, "DeserializePSBTError::InvalidMagic"),
+            DeserializePSBTError::MissingUnsignedTx => write!(f, "DeserializePSBTError::MissingUnsignedTx"),
+            DeserializePSBTError::UnsignedTxHasScriptSigs => write!(f, "DeserializePSBTError::UnsignedTxHasScriptSigs"),
+            DeserializePSBTError::UnsupportedVersion(version) => write!(f, "DeserializePSBTError::UnsupportedVersion({})", version),
+            DeserializePSBTError::DuplicateKey(key) => write!(f, "DeserializePSBTError::DuplicateKey({})", hex::encode(key)),
+            DeserializePSBTError::MissingKey(key) => write!(f, "DeserializePSBTError::MissingKey({})", hex::encode(key)),
+            DeserializePSBTError::InvalidKey(key) => write!(f, "DeserializePSBTError::InvalidKey({})", hex::encode(key)),
+            DeserializePSBTError::InvalidValue(key) => write!(f, "DeserializePSBTError::InvalidValue(for key {})", hex::encode(key)),
+            DeserializePSBTError::NonWitnessUtxoMismatch(index) => write!(f, "DeserializePSBTError::NonWitnessUtxoMismatch(input {})", index),
+            DeserializePSBTError::LockTimeConflict => write!(f, "DeserializePSBTError::LockTimeConflict(inputs require lock times of different types)"),
+            DeserializePSBTError::TrailingBytes(len) => write!(f, "DeserializePSBTError::TrailingBytes({})", len),
+        }
+    }
+}
+
+pub trait DeserializePSBT: Sized {
+    /// Decodes exactly one PSBT from `bytes`, rejecting anything left over.
+    fn deserialize_psbt(bytes: &[u8]) -> Result<Self, DeserializePSBTError>;
+}
+
+impl From<DeserializeConsensusError> for DeserializePSBTError {
+    fn from(value: DeserializeConsensusError) -> Self {
+        DeserializePSBTError::Consensus(value)
+    }
+}
+
+impl From<std::io::Error> for DeserializePSBTError {
+    fn from(value: std::io::Error) -> Self {
+        DeserializePSBTError::Consensus(value.into())
+    }
+}
+
+impl From<Base64Error> for DeserializePSBTError {
+    fn from(value: Base64Error) -> Self {
+        DeserializePSBTError::Base64(value)
+    }
+}
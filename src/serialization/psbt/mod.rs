@@ -0,0 +1,6 @@
+mod partially_signed_transaction;
+pub mod serialize;
+pub mod deserialize;
+
+/// "psbt" followed by 0xff, which no network transaction starts with.
+pub const MAGIC: [u8; 5] = [0x70, 0x73, 0x62, 0x74, 0xff];
@@ -0,0 +1,1197 @@
+use std::{collections::{BTreeMap, BTreeSet}, io::Read};
+use crate::{cryptography::{elliptic_curve::bitcoin_point::BitcoinPoint, hash::{digest::Txid, hash160, hash256, ripemd160, sha256}, taproot::{lift_x, ControlBlock, TAPROOT_CONTROL_MAX_NODE_COUNT, TAPROOT_LEAF_MASK}}, script::Script, serialization::{consensus::{compact_size::{read_compact_size, write_compact_size}, deserialize::DeserializeConsensus, serialize::SerializeConsensus}, sec::deserialize::DeserializeSEC}, transaction::{out_point::OutPoint, psbt::{determine_lock_time, input::Input, output::{is_complete_tree, Output, TapTreeLeaf}, spends_from, tx_modifiable::TxModifiable, KeySource, PartiallySignedTransaction, ProprietaryKey}, sighash::SighashType, tx_in::{TxIn, SEQUENCE_FINAL}, tx_out::TxOut, witness::Witness, Transaction, LOCKTIME_THRESHOLD}};
+use super::{deserialize::{DeserializePSBT, DeserializePSBTError}, serialize::SerializePSBT, MAGIC};
+
+const PSBT_GLOBAL_UNSIGNED_TX: u64 = 0x00;
+const PSBT_GLOBAL_XPUB: u64 = 0x01;
+const PSBT_GLOBAL_TX_VERSION: u64 = 0x02;
+const PSBT_GLOBAL_FALLBACK_LOCKTIME: u64 = 0x03;
+const PSBT_GLOBAL_INPUT_COUNT: u64 = 0x04;
+const PSBT_GLOBAL_OUTPUT_COUNT: u64 = 0x05;
+const PSBT_GLOBAL_TX_MODIFIABLE: u64 = 0x06;
+const PSBT_GLOBAL_VERSION: u64 = 0xfb;
+
+const PSBT_IN_NON_WITNESS_UTXO: u64 = 0x00;
+const PSBT_IN_WITNESS_UTXO: u64 = 0x01;
+const PSBT_IN_PARTIAL_SIG: u64 = 0x02;
+const PSBT_IN_SIGHASH_TYPE: u64 = 0x03;
+const PSBT_IN_REDEEM_SCRIPT: u64 = 0x04;
+const PSBT_IN_WITNESS_SCRIPT: u64 = 0x05;
+const PSBT_IN_BIP32_DERIVATION: u64 = 0x06;
+const PSBT_IN_FINAL_SCRIPTSIG: u64 = 0x07;
+const PSBT_IN_FINAL_SCRIPTWITNESS: u64 = 0x08;
+const PSBT_IN_POR_COMMITMENT: u64 = 0x09;
+const PSBT_IN_RIPEMD160: u64 = 0x0a;
+const PSBT_IN_SHA256: u64 = 0x0b;
+const PSBT_IN_HASH160: u64 = 0x0c;
+const PSBT_IN_HASH256: u64 = 0x0d;
+const PSBT_IN_PREVIOUS_TXID: u64 = 0x0e;
+const PSBT_IN_OUTPUT_INDEX: u64 = 0x0f;
+const PSBT_IN_SEQUENCE: u64 = 0x10;
+const PSBT_IN_REQUIRED_TIME_LOCKTIME: u64 = 0x11;
+const PSBT_IN_REQUIRED_HEIGHT_LOCKTIME: u64 = 0x12;
+const PSBT_IN_TAP_KEY_SIG: u64 = 0x13;
+const PSBT_IN_TAP_SCRIPT_SIG: u64 = 0x14;
+const PSBT_IN_TAP_LEAF_SCRIPT: u64 = 0x15;
+const PSBT_IN_TAP_BIP32_DERIVATION: u64 = 0x16;
+const PSBT_IN_TAP_INTERNAL_KEY: u64 = 0x17;
+const PSBT_IN_TAP_MERKLE_ROOT: u64 = 0x18;
+
+const PSBT_OUT_REDEEM_SCRIPT: u64 = 0x00;
+const PSBT_OUT_WITNESS_SCRIPT: u64 = 0x01;
+const PSBT_OUT_BIP32_DERIVATION: u64 = 0x02;
+const PSBT_OUT_AMOUNT: u64 = 0x03;
+const PSBT_OUT_SCRIPT: u64 = 0x04;
+const PSBT_OUT_TAP_INTERNAL_KEY: u64 = 0x05;
+const PSBT_OUT_TAP_TREE: u64 = 0x06;
+const PSBT_OUT_TAP_BIP32_DERIVATION: u64 = 0x07;
+
+/// The same in every map.
+const PSBT_PROPRIETARY: u64 = 0xfc;
+
+/// Length of a serialized BIP32 extended key.
+const XPUB_LEN: usize = 78;
+
+impl SerializePSBT for PartiallySignedTransaction {
+    fn serialize_psbt(&self) -> Vec<u8> {
+        let mut buffer = MAGIC.to_vec();
+
+        // Version 2 spreads the transaction over the maps instead.
+        if self.version != 2 {
+            write_pair(&mut buffer, PSBT_GLOBAL_UNSIGNED_TX, &[], &self.unsigned_tx.serialize_without_witness());
+        }
+
+        for (xpub, source) in &self.xpubs {
+            write_pair(&mut buffer, PSBT_GLOBAL_XPUB, xpub, &serialize_key_source(source));
+        }
+
+        if self.version == 2 {
+            write_pair(&mut buffer, PSBT_GLOBAL_TX_VERSION, &[], &self.unsigned_tx.version.to_le_bytes());
+
+            if let Some(lock_time) = self.fallback_lock_time {
+                write_pair(&mut buffer, PSBT_GLOBAL_FALLBACK_LOCKTIME, &[], &lock_time.to_le_bytes());
+            }
+
+            write_pair(&mut buffer, PSBT_GLOBAL_INPUT_COUNT, &[], &serialize_compact_size(self.inputs.len()));
+            write_pair(&mut buffer, PSBT_GLOBAL_OUTPUT_COUNT, &[], &serialize_compact_size(self.outputs.len()));
+
+            // Nothing modifiable is implied when the field is left out.
+            if self.tx_modifiable != TxModifiable::NONE {
+                write_pair(&mut buffer, PSBT_GLOBAL_TX_MODIFIABLE, &[], &[self.tx_modifiable.to_u8()]);
+            }
+        }
+
+        // Version 0 is implied when the field is left out.
+        if self.version > 0 {
+            write_pair(&mut buffer, PSBT_GLOBAL_VERSION, &[], &self.version.to_le_bytes());
+        }
+
+        end_map(&mut buffer, &self.proprietary, &self.unknown);
+
+        for (input, tx_in) in self.inputs.iter().zip(&self.unsigned_tx.inputs) {
+            serialize_input(input, tx_in, self.version, &mut buffer);
+        }
+
+        for (output, tx_out) in self.outputs.iter().zip(&self.unsigned_tx.outputs) {
+            serialize_output(output, tx_out, self.version, &mut buffer);
+        }
+
+        buffer
+    }
+}
+
+fn serialize_input(input: &Input, tx_in: &TxIn, version: u32, buffer: &mut Vec<u8>) {
+    if let Some(tx) = &input.non_witness_utxo {
+        write_pair(buffer, PSBT_IN_NON_WITNESS_UTXO, &[], &tx.serialize_consensus());
+    }
+
+    if let Some(txout) = &input.witness_utxo {
+        write_pair(buffer, PSBT_IN_WITNESS_UTXO, &[], &txout.serialize_consensus());
+    }
+
+    for (public_key, signature) in &input.partial_sigs {
+        write_pair(buffer, PSBT_IN_PARTIAL_SIG, public_key, signature);
+    }
+
+    if let Some(sighash_type) = input.sighash_type {
+        write_pair(buffer, PSBT_IN_SIGHASH_TYPE, &[], &sighash_type.to_u32().to_le_bytes());
+    }
+
+    if let Some(script) = &input.redeem_script {
+        write_pair(buffer, PSBT_IN_REDEEM_SCRIPT, &[], script.as_bytes());
+    }
+
+    if let Some(script) = &input.witness_script {
+        write_pair(buffer, PSBT_IN_WITNESS_SCRIPT, &[], script.as_bytes());
+    }
+
+    for (public_key, source) in &input.bip32_derivation {
+        write_pair(buffer, PSBT_IN_BIP32_DERIVATION, public_key, &serialize_key_source(source));
+    }
+
+    if let Some(script_sig) = &input.final_script_sig {
+        write_pair(buffer, PSBT_IN_FINAL_SCRIPTSIG, &[], script_sig);
+    }
+
+    if let Some(witness) = &input.final_script_witness {
+        write_pair(buffer, PSBT_IN_FINAL_SCRIPTWITNESS, &[], &witness.serialize_consensus());
+    }
+
+    if let Some(commitment) = &input.por_commitment {
+        write_pair(buffer, PSBT_IN_POR_COMMITMENT, &[], commitment.as_bytes());
+    }
+
+    for (hash, preimage) in &input.ripemd160_preimages {
+        write_pair(buffer, PSBT_IN_RIPEMD160, hash, preimage);
+    }
+
+    for (hash, preimage) in &input.sha256_preimages {
+        write_pair(buffer, PSBT_IN_SHA256, hash, preimage);
+    }
+
+    for (hash, preimage) in &input.hash160_preimages {
+        write_pair(buffer, PSBT_IN_HASH160, hash, preimage);
+    }
+
+    for (hash, preimage) in &input.hash256_preimages {
+        write_pair(buffer, PSBT_IN_HASH256, hash, preimage);
+    }
+
+    if version == 2 {
+        write_pair(buffer, PSBT_IN_PREVIOUS_TXID, &[], tx_in.previous_output.txid.as_bytes());
+        write_pair(buffer, PSBT_IN_OUTPUT_INDEX, &[], &tx_in.previous_output.vout.to_le_bytes());
+
+        // A final sequence is implied when the field is left out.
+        if tx_in.sequence != SEQUENCE_FINAL {
+            write_pair(buffer, PSBT_IN_SEQUENCE, &[], &tx_in.sequence.to_le_bytes());
+        }
+
+        if let Some(lock_time) = input.required_time_lock_time {
+            write_pair(buffer, PSBT_IN_REQUIRED_TIME_LOCKTIME, &[], &lock_time.to_le_bytes());
+        }
+
+        if let Some(lock_time) = input.required_height_lock_time {
+            write_pair(buffer, PSBT_IN_REQUIRED_HEIGHT_LOCKTIME, &[], &lock_time.to_le_bytes());
+        }
+    }
+
+    if let Some(signature) = &input.tap_key_sig {
+        write_pair(buffer, PSBT_IN_TAP_KEY_SIG, &[], signature);
+    }
+
+    for ((public_key, leaf_hash), signature) in &input.tap_script_sigs {
+        write_pair(buffer, PSBT_IN_TAP_SCRIPT_SIG, &[&public_key[..], &leaf_hash[..]].concat(), signature);
+    }
+
+    for (control_block, (script, leaf_version)) in &input.tap_leaf_scripts {
+        write_pair(buffer, PSBT_IN_TAP_LEAF_SCRIPT, &control_block.serialize(), &[script.as_bytes(), &[*leaf_version]].concat());
+    }
+
+    for (public_key, (leaf_hashes, source)) in &input.tap_bip32_derivation {
+        write_pair(buffer, PSBT_IN_TAP_BIP32_DERIVATION, public_key, &serialize_tap_key_source(leaf_hashes, source));
+    }
+
+    if let Some(public_key) = &input.tap_internal_key {
+        write_pair(buffer, PSBT_IN_TAP_INTERNAL_KEY, &[], public_key);
+    }
+
+    if let Some(merkle_root) = &input.tap_merkle_root {
+        write_pair(buffer, PSBT_IN_TAP_MERKLE_ROOT, &[], merkle_root);
+    }
+
+    end_map(buffer, &input.proprietary, &input.unknown);
+}
+
+fn serialize_output(output: &Output, tx_out: &TxOut, version: u32, buffer: &mut Vec<u8>) {
+    if let Some(script) = &output.redeem_script {
+        write_pair(buffer, PSBT_OUT_REDEEM_SCRIPT, &[], script.as_bytes());
+    }
+
+    if let Some(script) = &output.witness_script {
+        write_pair(buffer, PSBT_OUT_WITNESS_SCRIPT, &[], script.as_bytes());
+    }
+
+    for (public_key, source) in &output.bip32_derivation {
+        write_pair(buffer, PSBT_OUT_BIP32_DERIVATION, public_key, &serialize_key_source(source));
+    }
+
+    if version == 2 {
+        write_pair(buffer, PSBT_OUT_AMOUNT, &[], &tx_out.value.to_le_bytes());
+        write_pair(buffer, PSBT_OUT_SCRIPT, &[], &tx_out.script_pubkey);
+    }
+
+    if let Some(public_key) = &output.tap_internal_key {
+        write_pair(buffer, PSBT_OUT_TAP_INTERNAL_KEY, &[], public_key);
+    }
+
+    if let Some(leaves) = &output.tap_tree {
+        let mut tree = Vec::new();
+
+        for leaf in leaves {
+            tree.extend_from_slice(&[leaf.depth, leaf.leaf_version]);
+            leaf.script.as_bytes().to_vec().serialize_consensus_to(&mut tree);
+        }
+
+        write_pair(buffer, PSBT_OUT_TAP_TREE, &[], &tree);
+    }
+
+    for (public_key, (leaf_hashes, source)) in &output.tap_bip32_derivation {
+        write_pair(buffer, PSBT_OUT_TAP_BIP32_DERIVATION, public_key, &serialize_tap_key_source(leaf_hashes, source));
+    }
+
+    end_map(buffer, &output.proprietary, &output.unknown);
+}
+
+/// Writes a key made of `key_type` and `key_data`, then `value`, each
+///  behind its length.
+fn write_pair(buffer: &mut Vec<u8>, key_type: u64, key_data: &[u8], value: &[u8]) {
+    let mut key = Vec::new();
+    write_compact_size(&mut key, key_type);
+    key.extend_from_slice(key_data);
+
+    key.serialize_consensus_to(buffer);
+    write_compact_size(buffer, value.len() as u64);
+    buffer.extend_from_slice(value);
+}
+
+/// Writes the pairs every map can have after the known ones, then the
+///  separator closing the map.
+fn end_map(buffer: &mut Vec<u8>, proprietary: &BTreeMap<ProprietaryKey, Vec<u8>>, unknown: &BTreeMap<Vec<u8>, Vec<u8>>) {
+    for (key, value) in proprietary {
+        let mut key_data = Vec::new();
+        write_compact_size(&mut key_data, key.prefix.len() as u64);
+        key_data.extend_from_slice(&key.prefix);
+        write_compact_size(&mut key_data, key.subtype);
+        key_data.extend_from_slice(&key.key);
+
+        write_pair(buffer, PSBT_PROPRIETARY, &key_data, value);
+    }
+
+    for (key, value) in unknown {
+        key.serialize_consensus_to(buffer);
+        value.serialize_consensus_to(buffer);
+    }
+
+    buffer.push(0x00);
+}
+
+fn serialize_compact_size(n: usize) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    write_compact_size(&mut bytes, n as u64);
+
+    bytes
+}
+
+fn serialize_key_source(source: &KeySource) -> Vec<u8> {
+    let mut bytes = source.fingerprint.to_vec();
+
+    for index in &source.path {
+        bytes.extend_from_slice(&index.to_le_bytes());
+    }
+
+    bytes
+}
+
+fn serialize_tap_key_source(leaf_hashes: &[[u8; 32]], source: &KeySource) -> Vec<u8> {
+    let mut bytes = serialize_compact_size(leaf_hashes.len());
+
+    for leaf_hash in leaf_hashes {
+        bytes.extend_from_slice(leaf_hash);
+    }
+
+    bytes.extend_from_slice(&serialize_key_source(source));
+
+    bytes
+}
+
+impl DeserializePSBT for PartiallySignedTransaction {
+    fn deserialize_psbt(bytes: &[u8]) -> Result<Self, DeserializePSBTError> {
+        if !bytes.starts_with(&MAGIC) {
+            return Err(DeserializePSBTError::InvalidMagic);
+        }
+
+        let mut reader = &bytes[MAGIC.len()..];
+
+        let pairs = read_map(&mut reader)?;
+        let version = deserialize_version(&pairs)?;
+
+        let mut unsigned_tx = None;
+        let mut xpubs = BTreeMap::new();
+        let mut tx_version = None;
+        let mut fallback_lock_time = None;
+        let mut input_count = None;
+        let mut output_count = None;
+        let mut tx_modifiable = TxModifiable::NONE;
+        let mut proprietary = BTreeMap::new();
+        let mut unknown = BTreeMap::new();
+
+        // BIP370 excludes the version 2 fields from version 0, as it does
+        //  the unsigned transaction from version 2. Pairs of their types
+        //  with key data aren't those fields, so are kept as unknown.
+        for (key, value) in pairs {
+            let (key_type, key_data) = split_key(&key)?;
+
+            match key_type {
+                PSBT_GLOBAL_UNSIGNED_TX if version == 0 => {
+                    expect_no_key_data(&key, key_data)?;
+                    unsigned_tx = Some(Transaction::deserialize_consensus(&value)?);
+                },
+                PSBT_GLOBAL_UNSIGNED_TX => {
+                    return Err(DeserializePSBTError::InvalidKey(key));
+                },
+                PSBT_GLOBAL_TX_VERSION | PSBT_GLOBAL_FALLBACK_LOCKTIME | PSBT_GLOBAL_INPUT_COUNT | PSBT_GLOBAL_OUTPUT_COUNT | PSBT_GLOBAL_TX_MODIFIABLE if version == 0 && key_data.is_empty() => {
+                    return Err(DeserializePSBTError::InvalidKey(key));
+                },
+                PSBT_GLOBAL_XPUB => {
+                    if key_data.len() != XPUB_LEN {
+                        return Err(DeserializePSBTError::InvalidKey(key));
+                    }
+
+                    xpubs.insert(key_data.to_vec(), deserialize_key_source(&key, &value)?);
+                },
+                PSBT_GLOBAL_TX_VERSION if version == 2 => {
+                    expect_no_key_data(&key, key_data)?;
+                    tx_version = Some(deserialize_u32(&key, &value)? as i32);
+                },
+                PSBT_GLOBAL_FALLBACK_LOCKTIME if version == 2 => {
+                    expect_no_key_data(&key, key_data)?;
+                    fallback_lock_time = Some(deserialize_u32(&key, &value)?);
+                },
+                PSBT_GLOBAL_INPUT_COUNT if version == 2 => {
+                    expect_no_key_data(&key, key_data)?;
+                    input_count = Some(deserialize_compact_size(&key, &value)?);
+                },
+                PSBT_GLOBAL_OUTPUT_COUNT if version == 2 => {
+                    expect_no_key_data(&key, key_data)?;
+                    output_count = Some(deserialize_compact_size(&key, &value)?);
+                },
+                PSBT_GLOBAL_TX_MODIFIABLE if version == 2 => {
+                    expect_no_key_data(&key, key_data)?;
+
+                    match value[..] {
+                        [flags] => tx_modifiable = TxModifiable::from_u8(flags),
+                        _ => return Err(DeserializePSBTError::InvalidValue(key)),
+                    }
+                },
+                // Read ahead of the other pairs.
+                PSBT_GLOBAL_VERSION => { },
+                PSBT_PROPRIETARY => {
+                    proprietary.insert(deserialize_proprietary_key(&key, key_data)?, value);
+                },
+                _ => {
+                    unknown.insert(key, value);
+                },
+            }
+        }
+
+        // In version 2 the transaction is put together from the maps below.
+        let (mut unsigned_tx, input_count, output_count) = match unsigned_tx {
+            Some(tx) => {
+                if tx.inputs.iter().any(|input| !input.script_sig.is_empty() || !input.witness.is_empty()) {
+                    return Err(DeserializePSBTError::UnsignedTxHasScriptSigs);
+                }
+
+                let (input_count, output_count) = (tx.inputs.len(), tx.outputs.len());
+
+                (tx, input_count, output_count)
+            },
+            None if version == 0 => return Err(DeserializePSBTError::MissingUnsignedTx),
+            None => {
+                let tx_version = required(tx_version, PSBT_GLOBAL_TX_VERSION)?;
+
+                (Transaction::new(tx_version, Vec::new(), Vec::new(), 0), required(input_count, PSBT_GLOBAL_INPUT_COUNT)?, required(output_count, PSBT_GLOBAL_OUTPUT_COUNT)?)
+            },
+        };
+
+        let mut inputs = Vec::new();
+
+        for input_index in 0..input_count {
+            let (input, tx_in) = deserialize_input(&mut reader, version)?;
+
+            if let Some(tx_in) = tx_in {
+                unsigned_tx.inputs.push(tx_in);
+            }
+
+            // Core's check: the transaction has to be the one spent from,
+            //  with an output at the index spent.
+            if input.non_witness_utxo.as_ref().is_some_and(|tx| !spends_from(&unsigned_tx.inputs[input_index], tx)) {
+                return Err(DeserializePSBTError::NonWitnessUtxoMismatch(input_index));
+            }
+
+            inputs.push(input);
+        }
+
+        let mut outputs = Vec::new();
+
+        for _ in 0..output_count {
+            let (output, tx_out) = deserialize_output(&mut reader, version)?;
+
+            if let Some(tx_out) = tx_out {
+                unsigned_tx.outputs.push(tx_out);
+            }
+
+            outputs.push(output);
+        }
+
+        if version == 2 {
+            unsigned_tx.lock_time = determine_lock_time(fallback_lock_time, inputs.iter()).ok_or(DeserializePSBTError::LockTimeConflict)?;
+        }
+
+        if !reader.is_empty() {
+            return Err(DeserializePSBTError::TrailingBytes(reader.len()));
+        }
+
+        Ok(PartiallySignedTransaction {
+            unsigned_tx,
+            xpubs,
+            version,
+            fallback_lock_time,
+            tx_modifiable,
+            proprietary,
+            unknown,
+            inputs,
+            outputs,
+        })
+    }
+}
+
+/// Reads the input map, along with the transaction input it describes in
+///  version 2.
+fn deserialize_input<R: Read>(reader: &mut R, version: u32) -> Result<(Input, Option<TxIn>), DeserializePSBTError> {
+    let mut input = Input::default();
+    let mut previous_txid = None;
+    let mut output_index = None;
+    let mut sequence = None;
+
+    for (key, value) in read_map(reader)? {
+        let (key_type, key_data) = split_key(&key)?;
+
+        match key_type {
+            PSBT_IN_NON_WITNESS_UTXO => {
+                expect_no_key_data(&key, key_data)?;
+                input.non_witness_utxo = Some(Transaction::deserialize_consensus(&value)?);
+            },
+            PSBT_IN_WITNESS_UTXO => {
+                expect_no_key_data(&key, key_data)?;
+                input.witness_utxo = Some(TxOut::deserialize_consensus(&value)?);
+            },
+            PSBT_IN_PARTIAL_SIG => {
+                expect_public_key(&key, key_data)?;
+                input.partial_sigs.insert(key_data.to_vec(), value);
+            },
+            PSBT_IN_SIGHASH_TYPE => {
+                expect_no_key_data(&key, key_data)?;
+                input.sighash_type = Some(SighashType::from_u32(deserialize_u32(&key, &value)?));
+            },
+            PSBT_IN_REDEEM_SCRIPT => {
+                expect_no_key_data(&key, key_data)?;
+                input.redeem_script = Some(Script::from_bytes(value));
+            },
+            PSBT_IN_WITNESS_SCRIPT => {
+                expect_no_key_data(&key, key_data)?;
+                input.witness_script = Some(Script::from_bytes(value));
+            },
+            PSBT_IN_BIP32_DERIVATION => {
+                expect_public_key(&key, key_data)?;
+                input.bip32_derivation.insert(key_data.to_vec(), deserialize_key_source(&key, &value)?);
+            },
+            PSBT_IN_FINAL_SCRIPTSIG => {
+                expect_no_key_data(&key, key_data)?;
+                input.final_script_sig = Some(value);
+            },
+            PSBT_IN_FINAL_SCRIPTWITNESS => {
+                expect_no_key_data(&key, key_data)?;
+                input.final_script_witness = Some(Witness::deserialize_consensus(&value)?);
+            },
+            PSBT_IN_POR_COMMITMENT => {
+                expect_no_key_data(&key, key_data)?;
+                input.por_commitment = Some(String::from_utf8(value).map_err(|_| DeserializePSBTError::InvalidValue(key.clone()))?);
+            },
+            PSBT_IN_RIPEMD160 => {
+                input.ripemd160_preimages.insert(deserialize_preimage(&key, key_data, &value, ripemd160)?, value);
+            },
+            PSBT_IN_SHA256 => {
+                input.sha256_preimages.insert(deserialize_preimage(&key, key_data, &value, sha256)?, value);
+            },
+            PSBT_IN_HASH160 => {
+                input.hash160_preimages.insert(deserialize_preimage(&key, key_data, &value, hash160)?, value);
+            },
+            PSBT_IN_HASH256 => {
+                input.hash256_preimages.insert(deserialize_preimage(&key, key_data, &value, hash256)?, value);
+            },
+            PSBT_IN_PREVIOUS_TXID | PSBT_IN_OUTPUT_INDEX | PSBT_IN_SEQUENCE | PSBT_IN_REQUIRED_TIME_LOCKTIME | PSBT_IN_REQUIRED_HEIGHT_LOCKTIME if version == 0 && key_data.is_empty() => {
+                return Err(DeserializePSBTError::InvalidKey(key));
+            },
+            PSBT_IN_PREVIOUS_TXID if version == 2 => {
+                expect_no_key_data(&key, key_data)?;
+                previous_txid = Some(Txid::from_slice(&value).map_err(|_| DeserializePSBTError::InvalidValue(key.clone()))?);
+            },
+            PSBT_IN_OUTPUT_INDEX if version == 2 => {
+                expect_no_key_data(&key, key_data)?;
+                output_index = Some(deserialize_u32(&key, &value)?);
+            },
+            PSBT_IN_SEQUENCE if version == 2 => {
+                expect_no_key_data(&key, key_data)?;
+                sequence = Some(deserialize_u32(&key, &value)?);
+            },
+            PSBT_IN_REQUIRED_TIME_LOCKTIME if version == 2 => {
+                expect_no_key_data(&key, key_data)?;
+
+                match deserialize_u32(&key, &value)? {
+                    lock_time if lock_time >= LOCKTIME_THRESHOLD => input.required_time_lock_time = Some(lock_time),
+                    _ => return Err(DeserializePSBTError::InvalidValue(key)),
+                }
+            },
+            PSBT_IN_REQUIRED_HEIGHT_LOCKTIME if version == 2 => {
+                expect_no_key_data(&key, key_data)?;
+
+                match deserialize_u32(&key, &value)? {
+                    lock_time if lock_time > 0 && lock_time < LOCKTIME_THRESHOLD => input.required_height_lock_time = Some(lock_time),
+                    _ => return Err(DeserializePSBTError::InvalidValue(key)),
+                }
+            },
+            PSBT_IN_TAP_KEY_SIG => {
+                expect_no_key_data(&key, key_data)?;
+                input.tap_key_sig = Some(deserialize_taproot_signature(&key, value)?);
+            },
+            PSBT_IN_TAP_SCRIPT_SIG => {
+                if key_data.len() != 64 {
+                    return Err(DeserializePSBTError::InvalidKey(key));
+                }
+
+                let public_key = deserialize_x_only(&key_data[..32]).ok_or(DeserializePSBTError::InvalidKey(key.clone()))?;
+                let leaf_hash = key_data[32..].try_into().unwrap();
+
+                input.tap_script_sigs.insert((public_key, leaf_hash), deserialize_taproot_signature(&key, value)?);
+            },
+            PSBT_IN_TAP_LEAF_SCRIPT => {
+                let control_block = ControlBlock::from_slice(key_data)
+                    .filter(|control_block| lift_x(&control_block.internal_key).is_some())
+                    .ok_or(DeserializePSBTError::InvalidKey(key.clone()))?;
+
+                // The script, then its leaf version.
+                let Some((leaf_version, script)) = value.split_last() else {
+                    return Err(DeserializePSBTError::InvalidValue(key));
+                };
+
+                input.tap_leaf_scripts.insert(control_block, (Script::from_bytes(script.to_vec()), *leaf_version));
+            },
+            PSBT_IN_TAP_BIP32_DERIVATION => {
+                let public_key = deserialize_x_only(key_data).ok_or(DeserializePSBTError::InvalidKey(key.clone()))?;
+                input.tap_bip32_derivation.insert(public_key, deserialize_tap_key_source(&key, &value)?);
+            },
+            PSBT_IN_TAP_INTERNAL_KEY => {
+                expect_no_key_data(&key, key_data)?;
+                input.tap_internal_key = Some(deserialize_x_only(&value).ok_or(DeserializePSBTError::InvalidValue(key))?);
+            },
+            PSBT_IN_TAP_MERKLE_ROOT => {
+                expect_no_key_data(&key, key_data)?;
+                input.tap_merkle_root = Some(value[..].try_into().map_err(|_| DeserializePSBTError::InvalidValue(key.clone()))?);
+            },
+            PSBT_PROPRIETARY => {
+                input.proprietary.insert(deserialize_proprietary_key(&key, key_data)?, value);
+            },
+            _ => {
+                input.unknown.insert(key, value);
+            },
+        }
+    }
+
+    let tx_in = match version {
+        2 => {
+            let previous_output = OutPoint::new(required(previous_txid, PSBT_IN_PREVIOUS_TXID)?, required(output_index, PSBT_IN_OUTPUT_INDEX)?);
+
+            let mut tx_in = TxIn::new(previous_output);
+            tx_in.sequence = sequence.unwrap_or(SEQUENCE_FINAL);
+
+            Some(tx_in)
+        },
+        _ => None,
+    };
+
+    Ok((input, tx_in))
+}
+
+/// Reads the output map, along with the transaction output it describes
+///  in version 2.
+fn deserialize_output<R: Read>(reader: &mut R, version: u32) -> Result<(Output, Option<TxOut>), DeserializePSBTError> {
+    let mut output = Output::default();
+    let mut amount = None;
+    let mut script = None;
+
+    for (key, value) in read_map(reader)? {
+        let (key_type, key_data) = split_key(&key)?;
+
+        match key_type {
+            PSBT_OUT_REDEEM_SCRIPT => {
+                expect_no_key_data(&key, key_data)?;
+                output.redeem_script = Some(Script::from_bytes(value));
+            },
+            PSBT_OUT_WITNESS_SCRIPT => {
+                expect_no_key_data(&key, key_data)?;
+                output.witness_script = Some(Script::from_bytes(value));
+            },
+            PSBT_OUT_BIP32_DERIVATION => {
+                expect_public_key(&key, key_data)?;
+                output.bip32_derivation.insert(key_data.to_vec(), deserialize_key_source(&key, &value)?);
+            },
+            PSBT_OUT_AMOUNT | PSBT_OUT_SCRIPT if version == 0 && key_data.is_empty() => {
+                return Err(DeserializePSBTError::InvalidKey(key));
+            },
+            PSBT_OUT_AMOUNT if version == 2 => {
+                expect_no_key_data(&key, key_data)?;
+
+                // A signed 64-bit integer, which can't be negative.
+                match <[u8; 8]>::try_from(&value[..]).map(i64::from_le_bytes) {
+                    Ok(value) if value >= 0 => amount = Some(value as u64),
+                    _ => return Err(DeserializePSBTError::InvalidValue(key)),
+                }
+            },
+            PSBT_OUT_SCRIPT if version == 2 => {
+                expect_no_key_data(&key, key_data)?;
+                script = Some(value);
+            },
+            PSBT_OUT_TAP_INTERNAL_KEY => {
+                expect_no_key_data(&key, key_data)?;
+                output.tap_internal_key = Some(deserialize_x_only(&value).ok_or(DeserializePSBTError::InvalidValue(key))?);
+            },
+            PSBT_OUT_TAP_TREE => {
+                expect_no_key_data(&key, key_data)?;
+                output.tap_tree = Some(deserialize_tap_tree(&key, &value)?);
+            },
+            PSBT_OUT_TAP_BIP32_DERIVATION => {
+                let public_key = deserialize_x_only(key_data).ok_or(DeserializePSBTError::InvalidKey(key.clone()))?;
+                output.tap_bip32_derivation.insert(public_key, deserialize_tap_key_source(&key, &value)?);
+            },
+            PSBT_PROPRIETARY => {
+                output.proprietary.insert(deserialize_proprietary_key(&key, key_data)?, value);
+            },
+            _ => {
+                output.unknown.insert(key, value);
+            },
+        }
+    }
+
+    let tx_out = match version {
+        2 => Some(TxOut::new(required(amount, PSBT_OUT_AMOUNT)?, required(script, PSBT_OUT_SCRIPT)?)),
+        _ => None,
+    };
+
+    Ok((output, tx_out))
+}
+
+/// A whole key, type included, and its value.
+type Pair = (Vec<u8>, Vec<u8>);
+
+/// Reads the pairs of one map up to its separator, an empty key. Keys
+///  have to be unique within the map.
+fn read_map<R: Read>(reader: &mut R) -> Result<Vec<Pair>, DeserializePSBTError> {
+    let mut pairs = Vec::new();
+    let mut keys = BTreeSet::new();
+
+    loop {
+        let key = Vec::<u8>::deserialize_consensus_from(reader)?;
+
+        if key.is_empty() {
+            return Ok(pairs);
+        }
+
+        let value = Vec::<u8>::deserialize_consensus_from(reader)?;
+
+        if !keys.insert(key.clone()) {
+            return Err(DeserializePSBTError::DuplicateKey(key));
+        }
+
+        pairs.push((key, value));
+    }
+}
+
+/// The version from the global map, which decides how the rest of the
+///  PSBT reads. Only 0 and 2 are defined.
+fn deserialize_version(pairs: &[Pair]) -> Result<u32, DeserializePSBTError> {
+    for (key, value) in pairs {
+        let (key_type, key_data) = split_key(key)?;
+
+        if key_type == PSBT_GLOBAL_VERSION {
+            expect_no_key_data(key, key_data)?;
+
+            return match deserialize_u32(key, value)? {
+                version @ (0 | 2) => Ok(version),
+                version => Err(DeserializePSBTError::UnsupportedVersion(version)),
+            };
+        }
+    }
+
+    Ok(0)
+}
+
+/// The key's type and the key data following it.
+fn split_key(key: &[u8]) -> Result<(u64, &[u8]), DeserializePSBTError> {
+    let mut key_data = key;
+    let key_type = read_compact_size(&mut key_data).map_err(|_| DeserializePSBTError::InvalidKey(key.to_vec()))?;
+
+    Ok((key_type, key_data))
+}
+
+/// `value` of a field the PSBT has to have, keyed by `key_type` alone.
+fn required<T>(value: Option<T>, key_type: u64) -> Result<T, DeserializePSBTError> {
+    value.ok_or_else(|| DeserializePSBTError::MissingKey(serialize_compact_size(key_type as usize)))
+}
+
+fn expect_no_key_data(key: &[u8], key_data: &[u8]) -> Result<(), DeserializePSBTError> {
+    match key_data.is_empty() {
+        true => Ok(()),
+        false => Err(DeserializePSBTError::InvalidKey(key.to_vec())),
+    }
+}
+
+fn expect_public_key(key: &[u8], key_data: &[u8]) -> Result<(), DeserializePSBTError> {
+    BitcoinPoint::deserialize_sec(key_data).map(|_| ()).map_err(|_| DeserializePSBTError::InvalidKey(key.to_vec()))
+}
+
+/// A BIP340 x-only public key, which has to be on the curve.
+fn deserialize_x_only(bytes: &[u8]) -> Option<[u8; 32]> {
+    let public_key = bytes.try_into().ok()?;
+
+    lift_x(&public_key).map(|_| public_key)
+}
+
+/// 64 bytes, or 65 with a sighash byte, which can't be an explicit
+///  SIGHASH_DEFAULT.
+fn deserialize_taproot_signature(key: &[u8], signature: Vec<u8>) -> Result<Vec<u8>, DeserializePSBTError> {
+    match signature.len() {
+        64 => Ok(signature),
+        65 if signature[64] != SighashType::DEFAULT.to_u32() as u8 => Ok(signature),
+        _ => Err(DeserializePSBTError::InvalidValue(key.to_vec())),
+    }
+}
+
+fn deserialize_u32(key: &[u8], value: &[u8]) -> Result<u32, DeserializePSBTError> {
+    let bytes = value.try_into().map_err(|_| DeserializePSBTError::InvalidValue(key.to_vec()))?;
+
+    Ok(u32::from_le_bytes(bytes))
+}
+
+/// A value that is a compact size and nothing more.
+fn deserialize_compact_size(key: &[u8], value: &[u8]) -> Result<usize, DeserializePSBTError> {
+    let mut reader = value;
+
+    match read_compact_size(&mut reader) {
+        Ok(n) if reader.is_empty() => Ok(n as usize),
+        _ => Err(DeserializePSBTError::InvalidValue(key.to_vec())),
+    }
+}
+
+/// A fingerprint followed by any number of little-endian indices.
+fn deserialize_key_source(key: &[u8], value: &[u8]) -> Result<KeySource, DeserializePSBTError> {
+    if value.len() < 4 || !value.len().is_multiple_of(4) {
+        return Err(DeserializePSBTError::InvalidValue(key.to_vec()));
+    }
+
+    let (fingerprint, path) = value.split_at(4);
+
+    Ok(KeySource {
+        fingerprint: fingerprint.try_into().unwrap(),
+        path: path.chunks(4).map(|index| u32::from_le_bytes(index.try_into().unwrap())).collect(),
+    })
+}
+
+/// The hashes of the leaves an x-only key appears in, behind their count,
+///  then the key's source.
+fn deserialize_tap_key_source(key: &[u8], value: &[u8]) -> Result<(Vec<[u8; 32]>, KeySource), DeserializePSBTError> {
+    let mut reader = value;
+
+    let count = match read_compact_size(&mut reader) {
+        Ok(count) if count <= reader.len() as u64 / 32 => count as usize,
+        _ => return Err(DeserializePSBTError::InvalidValue(key.to_vec())),
+    };
+
+    let (leaf_hashes, source) = reader.split_at(count * 32);
+
+    Ok((leaf_hashes.chunks(32).map(|leaf_hash| leaf_hash.try_into().unwrap()).collect(), deserialize_key_source(key, source)?))
+}
+
+/// Depth, leaf version and script of each leaf, depth first. The leaves
+///  have to make a whole tree no deeper than control blocks can prove.
+fn deserialize_tap_tree(key: &[u8], value: &[u8]) -> Result<Vec<TapTreeLeaf>, DeserializePSBTError> {
+    let invalid = || DeserializePSBTError::InvalidValue(key.to_vec());
+
+    let mut reader = value;
+    let mut leaves = Vec::new();
+
+    while let [depth, leaf_version, rest @ ..] = reader {
+        reader = rest;
+        let script = Vec::<u8>::deserialize_consensus_from(&mut reader).map_err(|_| invalid())?;
+
+        if *depth as usize > TAPROOT_CONTROL_MAX_NODE_COUNT || leaf_version & !TAPROOT_LEAF_MASK != 0 {
+            return Err(invalid());
+        }
+
+        leaves.push(TapTreeLeaf {
+            depth: *depth,
+            leaf_version: *leaf_version,
+            script: Script::from_bytes(script),
+        });
+    }
+
+    if !reader.is_empty() || !is_complete_tree(leaves.iter().map(|leaf| leaf.depth)) {
+        return Err(invalid());
+    }
+
+    Ok(leaves)
+}
+
+fn deserialize_proprietary_key(key: &[u8], key_data: &[u8]) -> Result<ProprietaryKey, DeserializePSBTError> {
+    let invalid = || DeserializePSBTError::InvalidKey(key.to_vec());
+
+    let mut reader = key_data;
+    let prefix = Vec::<u8>::deserialize_consensus_from(&mut reader).map_err(|_| invalid())?;
+    let subtype = read_compact_size(&mut reader).map_err(|_| invalid())?;
+
+    Ok(ProprietaryKey {
+        prefix,
+        subtype,
+        key: reader.to_vec(),
+    })
+}
+
+/// The hash keying a preimage, which has to be what `hash` gives for it.
+fn deserialize_preimage<const N: usize>(key: &[u8], key_data: &[u8], preimage: &[u8], hash: fn(&[u8]) -> [u8; N]) -> Result<[u8; N], DeserializePSBTError> {
+    let digest: [u8; N] = key_data.try_into().map_err(|_| DeserializePSBTError::InvalidKey(key.to_vec()))?;
+
+    if hash(preimage) != digest {
+        return Err(DeserializePSBTError::InvalidValue(key.to_vec()));
+    }
+
+    Ok(digest)
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+    use serde_json::Value;
+    use crate::{cryptography::{hash::{digest::Txid, sha256}, taproot::tap_leaf_hash}, script::Script, serialization::{consensus::deserialize::DeserializeConsensusError, psbt::{deserialize::{DeserializePSBT, DeserializePSBTError}, serialize::SerializePSBT}}, transaction::{out_point::OutPoint, psbt::{input::Input, output::{Output, TapTreeLeaf}, PartiallySignedTransaction, ProprietaryKey}, tx_in::{TxIn, SEQUENCE_FINAL, SEQUENCE_MAX_NONFINAL}, tx_out::TxOut}};
+
+    const BIP174_VECTORS: &str = include_str!("../../../test_data/bip174_vectors.json");
+    const BIP371_VECTORS: &str = include_str!("../../../test_data/bip371_vectors.json");
+    const BIP370_VECTORS: &str = include_str!("../../../test_data/bip370_vectors.json");
+
+    fn vectors(kind: &str) -> Vec<Value> {
+        let vectors: Value = serde_json::from_str(BIP174_VECTORS).unwrap();
+        vectors[kind].as_array().unwrap().clone()
+    }
+
+    fn taproot_vectors(kind: &str) -> Vec<Value> {
+        let vectors: Value = serde_json::from_str(BIP371_VECTORS).unwrap();
+        vectors[kind].as_array().unwrap().clone()
+    }
+
+    fn version_2_vectors(kind: &str) -> Vec<Value> {
+        let vectors: Value = serde_json::from_str(BIP370_VECTORS).unwrap();
+        vectors[kind].as_array().unwrap().clone()
+    }
+
+    fn deserialize_hex(h: &str) -> Result<PartiallySignedTransaction, DeserializePSBTError> {
+        PartiallySignedTransaction::deserialize_psbt(&hex::decode(h).unwrap())
+    }
+
+    #[test]
+    pub fn test_valid_vectors() {
+        for vector in vectors("valid") {
+            let h = vector["hex"].as_str().unwrap();
+            let psbt = deserialize_hex(h).unwrap();
+
+            assert_eq!(hex::encode(psbt.serialize_psbt()), h, "{}", vector["description"]);
+            assert_eq!(PartiallySignedTransaction::from_str(&psbt.to_string()).unwrap(), psbt);
+
+            if let Some(s) = vector["base64"].as_str() {
+                assert_eq!(psbt.to_string(), s);
+            }
+        }
+
+        let valid = vectors("valid");
+
+        // P2PKH input with a sighash type.
+        let psbt = deserialize_hex(valid[2]["hex"].as_str().unwrap()).unwrap();
+        assert_eq!(psbt.inputs[0].sighash_type.unwrap().to_u32(), 1);
+        assert!(psbt.inputs[0].non_witness_utxo.is_some());
+
+        // 2-of-2 P2SH-P2WSH input with one signature and both key paths.
+        let psbt = deserialize_hex(valid[4]["hex"].as_str().unwrap()).unwrap();
+        let input = &psbt.inputs[0];
+        assert_eq!(input.partial_sigs.len(), 1);
+        assert_eq!(input.bip32_derivation.len(), 2);
+        assert_eq!(input.bip32_derivation.values().next().unwrap().path, [0x8000_0000, 0x8000_0000, 0x8000_0004]);
+        assert_eq!(input.witness_script.as_ref().unwrap().to_string(), "2 03b1341ccba7683b6af4f1238cd6e97e7167d569fac47f1e48d47541844355bd46 03de55d1e1dac805e3f8a58c1fbf9b94c02f3dbaafe127fefca4995f26f82083bd 2 OP_CHECKMULTISIG");
+
+        // Unknown pairs are kept by their whole key.
+        let psbt = deserialize_hex(valid[5]["hex"].as_str().unwrap()).unwrap();
+        assert_eq!(psbt.inputs[0].unknown.get(&hex::decode("0f010203040506070809").unwrap()).unwrap(), &hex::decode("0102030405060708090a0b0c0d0e0f").unwrap());
+    }
+
+    #[test]
+    pub fn test_invalid_vectors() {
+        let invalid = vectors("invalid");
+
+        let errors: Vec<_> = invalid.iter()
+            .map(|vector| {
+                let error = deserialize_hex(vector["hex"].as_str().unwrap()).unwrap_err();
+
+                // The base64 encoding fails the same way.
+                let base64_error = PartiallySignedTransaction::from_str(vector["base64"].as_str().unwrap()).unwrap_err();
+                assert_eq!(base64_error.to_string(), error.to_string());
+
+                error
+            })
+            .collect();
+
+        assert!(matches!(errors[0], DeserializePSBTError::InvalidMagic));
+        assert!(matches!(errors[1], DeserializePSBTError::Consensus(DeserializeConsensusError::Io(_))));
+        assert!(matches!(errors[2], DeserializePSBTError::UnsignedTxHasScriptSigs));
+        assert!(matches!(errors[3], DeserializePSBTError::MissingUnsignedTx));
+        assert!(matches!(&errors[4], DeserializePSBTError::DuplicateKey(key) if key == &[0x00]));
+    }
+
+    #[test]
+    pub fn test_invalid_pairs() {
+        let valid = vectors("valid");
+        let psbt = deserialize_hex(valid[0]["hex"].as_str().unwrap()).unwrap();
+
+        // The vector with one extra pair in its input map.
+        let with_input_pair = |key: &str, value: &str| {
+            let mut bytes = psbt.serialize_psbt();
+            let input_start = bytes.len() - psbt.outputs.len() - 1;
+
+            let mut pair = hex::decode(format!("{:02x}{}{:02x}{}", key.len() / 2, key, value.len() / 2, value)).unwrap();
+            pair.reverse();
+
+            for byte in pair {
+                bytes.insert(input_start, byte);
+            }
+
+            PartiallySignedTransaction::deserialize_psbt(&bytes)
+        };
+
+        let sighash_key = "03";
+        assert!(with_input_pair(sighash_key, "01000000").is_ok());
+        assert!(matches!(with_input_pair(sighash_key, "010000"), Err(DeserializePSBTError::InvalidValue(_))));
+        assert!(matches!(with_input_pair("0300", "01000000"), Err(DeserializePSBTError::InvalidKey(_))));
+
+        // Partial signatures and key paths are keyed by valid public keys.
+        assert!(matches!(with_input_pair("0203b1341ccba7683b6af4f1238cd6e97e7167d569fac47f1e48d47541844355bd", "30"), Err(DeserializePSBTError::InvalidKey(_))));
+        assert!(matches!(with_input_pair("0604b1341ccba7683b6af4f1238cd6e97e7167d569fac47f1e48d47541844355bd46", "b4a6ba67"), Err(DeserializePSBTError::InvalidKey(_))));
+        assert!(matches!(with_input_pair("0603b1341ccba7683b6af4f1238cd6e97e7167d569fac47f1e48d47541844355bd46", "b4a6ba6700"), Err(DeserializePSBTError::InvalidValue(_))));
+
+        let mut bytes = psbt.serialize_psbt();
+        bytes.push(0x00);
+        assert!(matches!(PartiallySignedTransaction::deserialize_psbt(&bytes), Err(DeserializePSBTError::TrailingBytes(1))));
+    }
+
+    #[test]
+    pub fn test_preimages_and_proprietary() {
+        let valid = vectors("valid");
+        let mut psbt = deserialize_hex(valid[0]["hex"].as_str().unwrap()).unwrap();
+
+        psbt.inputs[0].sha256_preimages.insert(sha256(b"preimage"), b"preimage".to_vec());
+        psbt.inputs[0].por_commitment = Some("proof of reserves".to_string());
+        psbt.proprietary.insert(ProprietaryKey { prefix: b"rust".to_vec(), subtype: 0x1234, key: vec![0x01] }, vec![0x02]);
+        psbt.outputs[1].proprietary.insert(ProprietaryKey { prefix: Vec::new(), subtype: 0, key: Vec::new() }, Vec::new());
+
+        let bytes = psbt.serialize_psbt();
+        assert_eq!(PartiallySignedTransaction::deserialize_psbt(&bytes).unwrap(), psbt);
+
+        // A preimage that doesn't hash to its key.
+        psbt.inputs[0].sha256_preimages.insert([0x00; 32], b"preimage".to_vec());
+        assert!(matches!(PartiallySignedTransaction::deserialize_psbt(&psbt.serialize_psbt()), Err(DeserializePSBTError::InvalidValue(_))));
+    }
+
+    #[test]
+    pub fn test_taproot_vectors() {
+        let psbts: Vec<_> = taproot_vectors("valid").iter()
+            .map(|vector| {
+                let h = vector["hex"].as_str().unwrap();
+                let psbt = deserialize_hex(h).unwrap();
+
+                assert_eq!(hex::encode(psbt.serialize_psbt()), h, "{}", vector["description"]);
+
+                psbt
+            })
+            .collect();
+
+        let input = &psbts[0].inputs[0];
+        assert!(input.tap_bip32_derivation.contains_key(&input.tap_internal_key.unwrap()));
+        assert_eq!(psbts[1].inputs[0].tap_key_sig.as_ref().unwrap().len(), 64);
+
+        let output = &psbts[2].outputs[0];
+        assert_eq!(output.tap_bip32_derivation[&output.tap_internal_key.unwrap()].1.path, [0x8000_0056, 0x8000_0001, 0x8000_0000, 0, 5]);
+
+        // Every leaf script is committed to by the output spent, under the
+        //  input's merkle root.
+        let input = &psbts[3].inputs[0];
+        let output_key = input.witness_utxo.as_ref().unwrap().script_pubkey[2..].try_into().unwrap();
+        assert_eq!(input.tap_leaf_scripts.len(), 3);
+
+        for (control_block, (script, leaf_version)) in &input.tap_leaf_scripts {
+            let leaf_hash = tap_leaf_hash(*leaf_version, script.as_bytes());
+
+            assert!(control_block.verify_commitment(&output_key, &leaf_hash));
+            assert_eq!(Some(control_block.merkle_root(&leaf_hash)), input.tap_merkle_root);
+        }
+
+        let leaves = psbts[4].outputs[0].tap_tree.as_ref().unwrap();
+        assert_eq!(leaves.iter().map(|leaf| (leaf.depth, leaf.leaf_version)).collect::<Vec<_>>(), [(2, 0xc0), (2, 0xc0), (1, 0xc0)]);
+
+        let input = &psbts[5].inputs[0];
+        assert_eq!(input.tap_script_sigs.len(), 3);
+        assert!(input.tap_script_sigs.keys().all(|(public_key, _)| input.tap_bip32_derivation.contains_key(public_key)));
+
+        // A tree missing a leaf.
+        let mut psbt = psbts[4].clone();
+        psbt.outputs[0].tap_tree.as_mut().unwrap().pop();
+        assert!(matches!(PartiallySignedTransaction::deserialize_psbt(&psbt.serialize_psbt()), Err(DeserializePSBTError::InvalidValue(key)) if key == [0x06]));
+
+        psbt.outputs[0].tap_tree = Some(vec![TapTreeLeaf { depth: 0, leaf_version: 0xc1, script: Script::new() }]);
+        assert!(matches!(PartiallySignedTransaction::deserialize_psbt(&psbt.serialize_psbt()), Err(DeserializePSBTError::InvalidValue(_))));
+
+        // SIGHASH_DEFAULT can't be given explicitly.
+        let mut psbt = psbts[1].clone();
+        psbt.inputs[0].tap_key_sig.as_mut().unwrap().push(0x00);
+        assert!(matches!(PartiallySignedTransaction::deserialize_psbt(&psbt.serialize_psbt()), Err(DeserializePSBTError::InvalidValue(_))));
+    }
+
+    #[test]
+    pub fn test_invalid_taproot_vectors() {
+        let errors: Vec<_> = taproot_vectors("invalid").iter()
+            .map(|vector| deserialize_hex(vector["hex"].as_str().unwrap()).unwrap_err())
+            .collect();
+
+        let key_types: Vec<_> = errors.iter()
+            .map(|error| match error {
+                DeserializePSBTError::InvalidKey(key) => ("key", key[0]),
+                DeserializePSBTError::InvalidValue(key) => ("value", key[0]),
+                DeserializePSBTError::Consensus(DeserializeConsensusError::Io(_)) => ("end", 0x00),
+                _ => panic!("{}", error),
+            })
+            .collect();
+
+        assert_eq!(key_types, [
+            ("value", 0x17),
+            ("value", 0x13),
+            ("key", 0x16),
+            ("value", 0x05),
+            ("key", 0x07),
+            ("key", 0x14),
+            ("value", 0x14),
+            // The 57-byte signature is followed by the rest of a 64-byte
+            //  one, which throws off reading the maps that follow.
+            ("end", 0x00),
+            ("key", 0x15),
+            ("key", 0x15),
+        ]);
+    }
+
+    /// Two inputs and an output, with a fallback lock time.
+    fn version_2() -> PartiallySignedTransaction {
+        let mut psbt = PartiallySignedTransaction::new_v2(2, Some(100));
+
+        let mut tx_in = TxIn::new(OutPoint::new(Txid::from_byte_array([0x01; 32]), 1));
+        tx_in.sequence = SEQUENCE_MAX_NONFINAL;
+        psbt.add_input(tx_in, Input::default()).unwrap();
+
+        let input = Input {
+            required_height_lock_time: Some(800_000),
+            ..Input::default()
+        };
+        psbt.add_input(TxIn::new(OutPoint::new(Txid::from_byte_array([0x02; 32]), 0)), input).unwrap();
+
+        psbt.add_output(TxOut::new(50_000, vec![0x6a]), Output::default()).unwrap();
+
+        psbt
+    }
+
+    #[test]
+    pub fn test_version_2() {
+        let psbt = version_2();
+        let bytes = psbt.serialize_psbt();
+
+        // Transaction version, fallback lock time, input and output counts,
+        //  modifiable flags and the PSBT version, and no unsigned tx.
+        assert!(hex::encode(&bytes).starts_with("70736274ff010204020000000103046400000001040102010501010106010301fb040200000000"));
+
+        let deserialized = PartiallySignedTransaction::deserialize_psbt(&bytes).unwrap();
+        assert_eq!(deserialized, psbt);
+        assert_eq!(deserialized.unsigned_tx.lock_time, 800_000);
+        assert_eq!(deserialized.unsigned_tx.inputs[0].sequence, SEQUENCE_MAX_NONFINAL);
+        assert_eq!(deserialized.unsigned_tx.outputs[0].value, 50_000);
+
+        // Globals of a PSBT with one input and no outputs.
+        let globals = "70736274ff0102040200000001040101010501000106010301fb0402000000";
+        assert!(matches!(deserialize_hex(&format!("{}00010f040000000000", globals)), Err(DeserializePSBTError::MissingKey(key)) if key == [0x0e]));
+        assert!(matches!(deserialize_hex(&format!("{}00", globals.replace("01040101", ""))), Err(DeserializePSBTError::MissingKey(key)) if key == [0x04]));
+        assert_eq!(deserialize_hex(&format!("{}00", globals.replace("01040101", "01040100"))).unwrap(), PartiallySignedTransaction::new_v2(2, None));
+
+        // Version 2 has no unsigned tx, and there is no version 1.
+        assert!(matches!(deserialize_hex(&format!("{}010001000000", globals)), Err(DeserializePSBTError::InvalidKey(key)) if key == [0x00]));
+        assert!(matches!(deserialize_hex(&format!("{}00", globals.replace("01fb0402000000", "01fb0401000000"))), Err(DeserializePSBTError::UnsupportedVersion(1))));
+
+        let mut psbt = version_2();
+        psbt.inputs[0].required_time_lock_time = Some(1_700_000_000);
+        assert!(matches!(PartiallySignedTransaction::deserialize_psbt(&psbt.serialize_psbt()), Err(DeserializePSBTError::LockTimeConflict)));
+
+        psbt.inputs[0].required_time_lock_time = Some(800_000);
+        assert!(matches!(PartiallySignedTransaction::deserialize_psbt(&psbt.serialize_psbt()), Err(DeserializePSBTError::InvalidValue(key)) if key == [0x11]));
+    }
+
+    fn optional_u32(value: &Value) -> Option<u32> {
+        value.as_u64().map(|n| n as u32)
+    }
+
+    fn check_version_2_fields(psbt: &PartiallySignedTransaction, vector: &Value) {
+        let description = &vector["description"];
+        assert_eq!(psbt.unsigned_tx.version as u64, vector["tx_version"].as_u64().unwrap(), "{}", description);
+        assert_eq!(psbt.fallback_lock_time, optional_u32(&vector["fallback_lock_time"]), "{}", description);
+        assert_eq!(psbt.tx_modifiable.to_u8() as u64, vector["tx_modifiable"].as_u64().unwrap(), "{}", description);
+
+        let inputs = vector["inputs"].as_array().unwrap();
+        assert_eq!(psbt.inputs.len(), inputs.len(), "{}", description);
+
+        for ((input, tx_in), expected) in psbt.inputs.iter().zip(&psbt.unsigned_tx.inputs).zip(inputs) {
+            assert_eq!(tx_in.previous_output.txid, Txid::from_str(expected["previous_txid"].as_str().unwrap()).unwrap(), "{}", description);
+            assert_eq!(tx_in.previous_output.vout as u64, expected["output_index"].as_u64().unwrap(), "{}", description);
+            assert_eq!(tx_in.sequence, optional_u32(&expected["sequence"]).unwrap_or(SEQUENCE_FINAL), "{}", description);
+            assert_eq!(input.required_time_lock_time, optional_u32(&expected["required_time_lock_time"]), "{}", description);
+            assert_eq!(input.required_height_lock_time, optional_u32(&expected["required_height_lock_time"]), "{}", description);
+        }
+
+        let outputs = vector["outputs"].as_array().unwrap();
+        assert_eq!(psbt.unsigned_tx.outputs.len(), outputs.len(), "{}", description);
+
+        for (tx_out, expected) in psbt.unsigned_tx.outputs.iter().zip(outputs) {
+            assert_eq!(tx_out.value, expected["amount"].as_u64().unwrap(), "{}", description);
+            assert_eq!(hex::encode(&tx_out.script_pubkey), expected["script"].as_str().unwrap(), "{}", description);
+        }
+    }
+
+    #[test]
+    pub fn test_version_2_vectors() {
+        for vector in version_2_vectors("valid") {
+            let h = vector["hex"].as_str().unwrap();
+            let psbt = deserialize_hex(h).unwrap_or_else(|e| panic!("{}: {}", vector["description"], e));
+
+            assert_eq!(hex::encode(psbt.serialize_psbt()), h, "{}", vector["description"]);
+            assert_eq!(psbt.to_string(), vector["base64"].as_str().unwrap());
+            assert_eq!(psbt.unsigned_tx.lock_time as u64, vector["lock_time"].as_u64().unwrap(), "{}", vector["description"]);
+
+            if psbt.version == 2 {
+                check_version_2_fields(&psbt, &vector);
+            }
+        }
+
+        for vector in version_2_vectors("invalid") {
+            let error = deserialize_hex(vector["hex"].as_str().unwrap()).unwrap_err();
+            let expected = format!("DeserializePSBTError::{}", vector["error"].as_str().unwrap());
+
+            assert!(error.to_string().starts_with(&expected), "{}: {}", vector["description"], error);
+        }
+    }
+}
@@ -0,0 +1,4 @@
+/// BIP174's binary format, which base64 turns into the usual text form.
+pub trait SerializePSBT {
+    fn serialize_psbt(&self) -> Vec<u8>;
+}
@@ -1,18 +1,18 @@
 use num_bigint::BigUint;
-use crate::cryptography::{elliptic_curve::bitcoin_point::BitcoinPoint, field_element::bitcoin_field_element::BitcoinFieldElement, BITCOIN_SECP256K1_CONFIG};
+use crate::cryptography::{elliptic_curve::{bitcoin_curve::BitcoinCurve, bitcoin_point::BitcoinPoint, point::Point}, field_element::bitcoin_field_element::BitcoinFieldElement, BITCOIN_SECP256K1_CONFIG};
 use super::{deserialize::{DeserializeSEC, DeserializeSECError}, serialize::{SerializeSEC, SerializeSECError}};
 
 impl SerializeSEC for BitcoinPoint {
-    fn serialize_sec(&self, compressed: bool) -> Result<String, SerializeSECError> {
+    fn serialize_sec(&self, compressed: bool) -> Result<Vec<u8>, SerializeSECError> {
         let x = self.x().as_ref().ok_or(SerializeSECError)?.number();
         let y = self.y().as_ref().ok_or(SerializeSECError)?.number();
 
         let y_even = y % BigUint::from(2u32) == BigUint::from(0u32);
 
         let serialized = match (compressed, y_even) {
-            (true, true) => format!("\x02{0}", hex::encode(x.to_bytes_be())),
-            (true, false) => format!("\x03{0}", hex::encode(x.to_bytes_be())),
-            (false, _) => format!("\x04{0}{1}", hex::encode(x.to_bytes_be()), hex::encode(y.to_bytes_be())),
+            (true, true) => [&[0x02][..], &to_32_bytes(x)].concat(),
+            (true, false) => [&[0x03][..], &to_32_bytes(x)].concat(),
+            (false, _) => [&[0x04][..], &to_32_bytes(x), &to_32_bytes(y)].concat(),
         };
 
         Ok(serialized)
@@ -23,52 +23,103 @@ impl DeserializeSEC for BitcoinPoint {
     fn deserialize_sec<'a>(s: impl Into<&'a [u8]>) -> Result<Self, DeserializeSECError> {
         let s_bytes = s.into();
 
-        if s_bytes.len() == 0 {
+        if s_bytes.is_empty() {
             return Err(DeserializeSECError::InvalidFormat);
         }
 
-        match s_bytes[0] {
-            4 => {
+        match (s_bytes[0], s_bytes.len()) {
+            (4, 65) => {
                 let x = BitcoinFieldElement::new(BigUint::from_bytes_be(&s_bytes[1..33]))?;
                 let y = BitcoinFieldElement::new(BigUint::from_bytes_be(&s_bytes[33..65]))?;
 
-                Ok(BitcoinPoint::new(x.into(), y.into()))
+                on_curve(x, y)
             },
-            2 => {
+            (2, 33) | (3, 33) => {
                 let x = BitcoinFieldElement::new(BigUint::from_bytes_be(&s_bytes[1..]))?;
                 let b = BitcoinFieldElement::new(BigUint::from_slice(&BITCOIN_SECP256K1_CONFIG.b)).unwrap();
 
                 let alpha = (&x.pow(BigUint::from(3u32)) + &b)?;
                 let beta = alpha.sqrt();
 
-                if beta.number() % BigUint::from(2u32) == BigUint::from(0u32) {
-                    Ok(BitcoinPoint::new(x, beta))
+                let want_even = s_bytes[0] == 2;
+                let beta_even = beta.number() % BigUint::from(2u32) == BigUint::from(0u32);
+
+                if want_even == beta_even {
+                    on_curve(x, beta)
                 } else {
                     let prime = BigUint::from_slice(&BITCOIN_SECP256K1_CONFIG.p);
 
-                    let beta_inv = BitcoinFieldElement::new(beta.number() - prime)?;
+                    let beta_inv = BitcoinFieldElement::new(prime - beta.number())?;
 
-                    Ok(BitcoinPoint::new(x, beta_inv))
+                    on_curve(x, beta_inv)
                 }
             },
-            3 => {
-                let x = BitcoinFieldElement::new(BigUint::from_bytes_be(&s_bytes[1..]))?;
-                let b = BitcoinFieldElement::new(BigUint::from_slice(&BITCOIN_SECP256K1_CONFIG.b)).unwrap();
+            _ => Err(DeserializeSECError::InvalidFormat),
+        }
+    }
+}
 
-                let alpha = (&x.pow(BigUint::from(3u32)) + &b)?;
-                let beta = alpha.sqrt();
+/// `n` big endian, left padded with zeros to 32 bytes.
+pub(crate) fn to_32_bytes(n: &BigUint) -> [u8; 32] {
+    let bytes = n.to_bytes_be();
 
-                if beta.number() % BigUint::from(2u32) == BigUint::from(1u32) {
-                    Ok(BitcoinPoint::new(x, beta))
-                } else {
-                    let prime = BigUint::from_slice(&BITCOIN_SECP256K1_CONFIG.p);
+    let mut padded = [0u8; 32];
+    padded[32 - bytes.len()..].copy_from_slice(&bytes);
+
+    padded
+}
 
-                    let beta_inv = BitcoinFieldElement::new(beta.number() - prime)?;
+// `x` values with no square root still produce a `beta`, so the result
+//  has to be checked rather than handed to the panicking `BitcoinPoint::new`.
+fn on_curve(x: BitcoinFieldElement, y: BitcoinFieldElement) -> Result<BitcoinPoint, DeserializeSECError> {
+    let point = Point::new(x.into(), y.into(), BitcoinCurve::new().into())?;
 
-                    Ok(BitcoinPoint::new(x, beta_inv))
-                }
-            },
-            _ => Err(DeserializeSECError::InvalidFormat),
+    Ok(BitcoinPoint::from_point(point))
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigUint;
+    use crate::{cryptography::elliptic_curve::bitcoin_point::BitcoinPoint, serialization::sec::{deserialize::DeserializeSEC, serialize::SerializeSEC}};
+
+    #[test]
+    pub fn test_serialize_sec() {
+        let point = (BitcoinPoint::g() * &BigUint::from(5000u32)).unwrap();
+
+        assert_eq!(
+            hex::encode(point.serialize_sec(false).unwrap()),
+            "04ffe558e388852f0120e46af2d1b370f85854a8eb0841811ece0e3e03d282d57c315dc72890a4f10a1481c031b03b351b0dc79901ca18a00cf009dbdb157a1d10"
+        );
+
+        let point = (BitcoinPoint::g() * &BigUint::from(0xdeadbeef12345u64)).unwrap();
+
+        assert_eq!(
+            hex::encode(point.serialize_sec(true).unwrap()),
+            "03d90cd625ee87dd38656dd95cf79f65f60f7273b67d3096e68bd81e4f5342691f"
+        );
+    }
+
+    #[test]
+    pub fn test_round_trip() {
+        let point = (BitcoinPoint::g() * &BigUint::from(5001u32)).unwrap();
+
+        for compressed in [true, false] {
+            let sec = point.serialize_sec(compressed).unwrap();
+
+            assert!(BitcoinPoint::deserialize_sec(&sec[..]).unwrap() == point);
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    pub fn test_deserialize_invalid() {
+        assert!(BitcoinPoint::deserialize_sec(&[][..]).is_err());
+        assert!(BitcoinPoint::deserialize_sec(&[0x04, 0x01, 0x02][..]).is_err());
+
+        // x = 5 has no matching y on secp256k1.
+        let mut sec = [0u8; 33];
+        sec[0] = 0x02;
+        sec[32] = 0x05;
+
+        assert!(BitcoinPoint::deserialize_sec(&sec[..]).is_err());
+    }
+}
@@ -1,3 +1,3 @@
-mod bitcoin_point;
-mod serialize;
-mod deserialize;
\ No newline at end of file
+pub(crate) mod bitcoin_point;
+pub mod serialize;
+pub mod deserialize;
\ No newline at end of file
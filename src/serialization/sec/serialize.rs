@@ -12,5 +12,5 @@ impl Display for SerializeSECError {
 }
 
 pub trait SerializeSEC {
-    fn serialize_sec(&self, compressed: bool) -> Result<String, SerializeSECError>;
+    fn serialize_sec(&self, compressed: bool) -> Result<Vec<u8>, SerializeSECError>;
 }
\ No newline at end of file
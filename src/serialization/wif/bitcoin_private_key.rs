@@ -0,0 +1,116 @@
+use num_bigint::BigUint;
+use crate::{cryptography::{bitcoin_private_key::BitcoinPrivateKey, BITCOIN_SECP256K1_CONFIG}, network::Network, serialization::base58};
+use super::{deserialize::{DeserializeWIF, DeserializeWIFError}, serialize::SerializeWIF, COMPRESSED_FLAG};
+
+impl SerializeWIF for BitcoinPrivateKey {
+    fn serialize_wif(&self, network: Network) -> String {
+        let secret = self.secret().to_bytes_be();
+
+        let mut bytes = Vec::with_capacity(34);
+
+        bytes.push(network.params().wif_prefix);
+        bytes.extend(std::iter::repeat_n(0u8, 32 - secret.len()));
+        bytes.extend_from_slice(&secret);
+
+        if self.compressed() {
+            bytes.push(COMPRESSED_FLAG);
+        }
+
+        base58::encode_check(&bytes)
+    }
+}
+
+impl DeserializeWIF for BitcoinPrivateKey {
+    fn deserialize_wif(s: &str, network: Network) -> Result<Self, DeserializeWIFError> {
+        let bytes = base58::decode_check(s)?;
+
+        let compressed = match bytes.len() {
+            33 => false,
+            34 if bytes[33] == COMPRESSED_FLAG => true,
+            34 => return Err(DeserializeWIFError::InvalidCompressionFlag(bytes[33])),
+            len => return Err(DeserializeWIFError::InvalidLength(len)),
+        };
+
+        if Network::from_wif_prefix(bytes[0]).is_none() {
+            return Err(DeserializeWIFError::InvalidPrefix(bytes[0]));
+        }
+
+        if bytes[0] != network.params().wif_prefix {
+            return Err(DeserializeWIFError::WrongNetwork(bytes[0], network));
+        }
+
+        let secret = BigUint::from_bytes_be(&bytes[1..33]);
+        let n = BigUint::from_slice(&BITCOIN_SECP256K1_CONFIG.n);
+
+        if secret == BigUint::from(0u32) || secret >= n {
+            return Err(DeserializeWIFError::InvalidSecret);
+        }
+
+        Ok(BitcoinPrivateKey::new_with_compression(secret, compressed)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigUint;
+    use crate::{cryptography::bitcoin_private_key::BitcoinPrivateKey, network::Network, serialization::{base58, wif::{deserialize::{DeserializeWIF, DeserializeWIFError}, serialize::SerializeWIF}}};
+
+    #[test]
+    pub fn test_serialize_wif() {
+        let key = BitcoinPrivateKey::new(5003u32).unwrap();
+        assert_eq!(key.serialize_wif(Network::Testnet3), "cMahea7zqjxrtgAbB7LSGbcQUr1uX1ojuat9jZodMN8rFTv2sfUK");
+        assert_eq!(key.serialize_wif(Network::Regtest), key.serialize_wif(Network::Testnet3));
+
+        let key = BitcoinPrivateKey::new_with_compression(BigUint::from(2021u32).pow(5), false).unwrap();
+        assert_eq!(key.serialize_wif(Network::Testnet3), "91avARGdfge8E4tZfYLoxeJ5sGBdNJQH4kvjpWAxgzczjbCwxic");
+
+        let key = BitcoinPrivateKey::new(0x54321deadbeefu64).unwrap();
+        assert_eq!(key.serialize_wif(Network::Mainnet), "KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgiuQJv1h8Ytr2S53a");
+    }
+
+    #[test]
+    pub fn test_deserialize_wif() {
+        let key = BitcoinPrivateKey::deserialize_wif("KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgiuQJv1h8Ytr2S53a", Network::Mainnet).unwrap();
+
+        assert!(key.compressed());
+        assert_eq!(key.secret(), &BigUint::from(0x54321deadbeefu64));
+
+        let key = BitcoinPrivateKey::deserialize_wif("91avARGdfge8E4tZfYLoxeJ5sGBdNJQH4kvjpWAxgzczjbCwxic", Network::Regtest).unwrap();
+
+        assert!(!key.compressed());
+        assert_eq!(key.serialize_public_key().unwrap()[0], 0x04);
+    }
+
+    #[test]
+    pub fn test_deserialize_wif_wrong_network() {
+        assert!(matches!(
+            BitcoinPrivateKey::deserialize_wif("KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgiuQJv1h8Ytr2S53a", Network::Regtest),
+            Err(DeserializeWIFError::WrongNetwork(0x80, Network::Regtest))
+        ));
+        assert!(matches!(
+            BitcoinPrivateKey::deserialize_wif("cMahea7zqjxrtgAbB7LSGbcQUr1uX1ojuat9jZodMN8rFTv2sfUK", Network::Mainnet),
+            Err(DeserializeWIFError::WrongNetwork(0xef, Network::Mainnet))
+        ));
+    }
+
+    #[test]
+    pub fn test_deserialize_wif_invalid() {
+        // Valid checksum, unknown version byte.
+        let mut bytes = vec![0x81];
+        bytes.extend_from_slice(&[0x11; 32]);
+        assert!(matches!(BitcoinPrivateKey::deserialize_wif(&base58::encode_check(&bytes), Network::Mainnet), Err(DeserializeWIFError::InvalidPrefix(0x81))));
+
+        // Compression flag other than 0x01.
+        let mut bytes = vec![0x80];
+        bytes.extend_from_slice(&[0x11; 32]);
+        bytes.push(0x02);
+        assert!(matches!(BitcoinPrivateKey::deserialize_wif(&base58::encode_check(&bytes), Network::Mainnet), Err(DeserializeWIFError::InvalidCompressionFlag(0x02))));
+
+        // Secret of zero.
+        let mut bytes = vec![0x80];
+        bytes.extend_from_slice(&[0x00; 32]);
+        assert!(matches!(BitcoinPrivateKey::deserialize_wif(&base58::encode_check(&bytes), Network::Mainnet), Err(DeserializeWIFError::InvalidSecret)));
+
+        assert!(matches!(BitcoinPrivateKey::deserialize_wif("KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgiuQJv1h8Ytr2S53b", Network::Mainnet), Err(DeserializeWIFError::Base58(_))));
+    }
+}
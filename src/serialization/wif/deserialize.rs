@@ -0,0 +1,44 @@
+use std::{error::Error, fmt::Display};
+use crate::{cryptography::elliptic_curve::error::PointError, network::Network, serialization::base58::error::Base58Error};
+
+#[derive(Debug)]
+pub enum DeserializeWIFError {
+    Base58(Base58Error),
+    InvalidLength(usize),
+    InvalidPrefix(u8),
+    WrongNetwork(u8, Network),
+    InvalidCompressionFlag(u8),
+    InvalidSecret,
+}
+
+impl Error for DeserializeWIFError { }
+
+impl Display for DeserializeWIFError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeserializeWIFError::Base58(e) => write!(f, "DeserializeWIFError::Base58({})", e),
+            DeserializeWIFError::InvalidLength(len) => write!(f, "DeserializeWIFError::InvalidLength({} bytes, expected 33 or 34)", len),
+            DeserializeWIFError::InvalidPrefix(prefix) => write!(f, "DeserializeWIFError::InvalidPrefix({:#04x})", prefix),
+            DeserializeWIFError::WrongNetwork(prefix, network) => write!(f, "DeserializeWIFError::WrongNetwork({:#04x} is not a {} key)", prefix, network),
+            DeserializeWIFError::InvalidCompressionFlag(flag) => write!(f, "DeserializeWIFError::InvalidCompressionFlag({:#04x})", flag),
+            DeserializeWIFError::InvalidSecret => write!(f, "DeserializeWIFError::InvalidSecret(0 < secret < n)"),
+        }
+    }
+}
+
+/// Decodes a WIF string, rejecting keys exported for a different network.
+pub trait DeserializeWIF: Sized {
+    fn deserialize_wif(s: &str, network: Network) -> Result<Self, DeserializeWIFError>;
+}
+
+impl From<Base58Error> for DeserializeWIFError {
+    fn from(value: Base58Error) -> Self {
+        DeserializeWIFError::Base58(value)
+    }
+}
+
+impl From<PointError> for DeserializeWIFError {
+    fn from(_: PointError) -> Self {
+        DeserializeWIFError::InvalidSecret
+    }
+}
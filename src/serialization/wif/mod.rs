@@ -0,0 +1,6 @@
+mod bitcoin_private_key;
+pub mod serialize;
+pub mod deserialize;
+
+/// Trailing byte marking that the public key is serialized compressed.
+pub const COMPRESSED_FLAG: u8 = 0x01;
@@ -0,0 +1,5 @@
+use crate::network::Network;
+
+pub trait SerializeWIF {
+    fn serialize_wif(&self, network: Network) -> String;
+}
@@ -0,0 +1,35 @@
+use std::{error::Error, fmt::Display};
+use crate::transaction::coin_selection::error::SelectionError;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum BuilderError {
+    NoInputs,
+    NoOutputs,
+    UnknownSpendSize(usize),
+    DustOutput(usize),
+    ValueOverflow,
+    InsufficientFunds(u64, u64),
+    Selection(SelectionError),
+}
+
+impl Error for BuilderError { }
+
+impl Display for BuilderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuilderError::NoInputs => write!(f, "BuilderError::NoInputs"),
+            BuilderError::NoOutputs => write!(f, "BuilderError::NoOutputs"),
+            BuilderError::UnknownSpendSize(len) => write!(f, "BuilderError::UnknownSpendSize(can't tell how a {} byte script is spent)", len),
+            BuilderError::DustOutput(index) => write!(f, "BuilderError::DustOutput(output {} is below the dust threshold)", index),
+            BuilderError::ValueOverflow => write!(f, "BuilderError::ValueOverflow"),
+            BuilderError::InsufficientFunds(available, needed) => write!(f, "BuilderError::InsufficientFunds({} available, {} needed)", available, needed),
+            BuilderError::Selection(e) => write!(f, "BuilderError::Selection({})", e),
+        }
+    }
+}
+
+impl From<SelectionError> for BuilderError {
+    fn from(value: SelectionError) -> Self {
+        BuilderError::Selection(value)
+    }
+}
@@ -0,0 +1,608 @@
+use rand::RngCore;
+use crate::{address::Address, script::{standard::StandardScript, Script}, serialization::consensus::compact_size::compact_size_len};
+use self::error::BuilderError;
+use super::{coin_selection::{CoinSelection, SelectionParams}, fee::{dust_threshold, is_dust, FeeRate, DUST_RELAY_FEE}, out_point::OutPoint, signer::multisig::{Multisig, MultisigWrapper}, tx_in::{TxIn, SEQUENCE_FINAL, SEQUENCE_MAX_BIP125_RBF, SEQUENCE_MAX_NONFINAL}, tx_out::TxOut, Transaction, WITNESS_SCALE_FACTOR};
+
+pub mod error;
+
+/// Largest DER signature along with its sighash byte. Estimating with it
+///  means a signed transaction is never larger than its estimate.
+const MAX_ECDSA_SIGNATURE_SIZE: usize = 72;
+const COMPRESSED_PUBLIC_KEY_SIZE: usize = 33;
+
+/// The size of the scriptSig and witness an input will be spent with,
+///  which the fee has to account for before anything is signed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SpendSize {
+    /// Length of the scriptSig, without its length prefix.
+    pub script_sig: usize,
+    /// Serialized size of the witness including its item count, or zero
+    ///  for inputs without one.
+    pub witness: usize,
+}
+
+impl SpendSize {
+    /// `<signature> <compressed public key>`.
+    pub const P2PKH: SpendSize = SpendSize {
+        script_sig: 1 + MAX_ECDSA_SIGNATURE_SIZE + 1 + COMPRESSED_PUBLIC_KEY_SIZE,
+        witness: 0,
+    };
+
+    /// P2PKH outputs don't say whether their key is compressed, so spends
+    ///  with an uncompressed one have to be given this size.
+    pub const P2PKH_UNCOMPRESSED: SpendSize = SpendSize {
+        script_sig: 1 + MAX_ECDSA_SIGNATURE_SIZE + 1 + 65,
+        witness: 0,
+    };
+
+    pub const P2WPKH: SpendSize = SpendSize {
+        script_sig: 0,
+        witness: 1 + 1 + MAX_ECDSA_SIGNATURE_SIZE + 1 + COMPRESSED_PUBLIC_KEY_SIZE,
+    };
+
+    /// P2WPKH with the program pushed as the redeem script.
+    pub const P2SH_P2WPKH: SpendSize = SpendSize {
+        script_sig: 1 + 22,
+        ..SpendSize::P2WPKH
+    };
+
+    /// A key-path spend signed with SIGHASH_DEFAULT, whose signature is
+    ///  always 64 bytes.
+    pub const P2TR_KEY_PATH: SpendSize = SpendSize {
+        script_sig: 0,
+        witness: 1 + 1 + 64,
+    };
+
+    /// What spending `script_pubkey` takes, for the types where the
+    ///  script alone tells. P2SH and P2WSH outputs need their scripts.
+    pub fn for_script_pubkey(script_pubkey: &Script) -> Option<Self> {
+        match script_pubkey.classify() {
+            StandardScript::P2PKH(_) => Some(Self::P2PKH),
+            StandardScript::P2WPKH(_) => Some(Self::P2WPKH),
+            StandardScript::P2TR(_) => Some(Self::P2TR_KEY_PATH),
+            _ => None,
+        }
+    }
+
+    /// A dummy element, `required` signatures and the script, wrapped as
+    ///  `multisig` says.
+    pub fn multisig(multisig: &Multisig) -> Self {
+        let script = multisig.script().len();
+        let signatures = multisig.required() * (1 + MAX_ECDSA_SIGNATURE_SIZE);
+
+        let witness = compact_size_len(multisig.required() as u64 + 2) + 1 + signatures + compact_size_len(script as u64) + script;
+
+        match multisig.wrapper() {
+            MultisigWrapper::P2SH => Self {
+                script_sig: 1 + signatures + push_size(script) + script,
+                witness: 0,
+            },
+            MultisigWrapper::P2WSH => Self {
+                script_sig: 0,
+                witness,
+            },
+            MultisigWrapper::P2SHP2WSH => Self {
+                script_sig: 1 + 34,
+                witness,
+            },
+        }
+    }
+
+    /// Weight the signed input adds to a segwit transaction: outpoint,
+    ///  scriptSig and sequence at full weight plus the witness, which is a
+    ///  single zero byte for inputs without one.
+    pub fn input_weight(&self) -> usize {
+        let base = 32 + 4 + compact_size_len(self.script_sig as u64) + self.script_sig + 4;
+
+        base * WITNESS_SCALE_FACTOR + self.witness.max(1)
+    }
+}
+
+/// Bytes taken by the opcode pushing `len` bytes.
+fn push_size(len: usize) -> usize {
+    match len {
+        0..=0x4b => 1,
+        0x4c..=0xff => 2,
+        0x100..=0xffff => 3,
+        _ => 5,
+    }
+}
+
+/// A coin to spend: where it is, what it is and how it will be spent.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Utxo {
+    pub outpoint: OutPoint,
+    pub txout: TxOut,
+    pub spend_size: SpendSize,
+}
+
+impl Utxo {
+    /// For outputs `SpendSize::for_script_pubkey` knows how to spend.
+    pub fn new(outpoint: OutPoint, txout: TxOut) -> Result<Self, BuilderError> {
+        let spend_size = SpendSize::for_script_pubkey(&Script::from(&txout.script_pubkey[..])).ok_or(BuilderError::UnknownSpendSize(txout.script_pubkey.len()))?;
+
+        Ok(Self::with_spend_size(outpoint, txout, spend_size))
+    }
+
+    pub fn with_spend_size(outpoint: OutPoint, txout: TxOut, spend_size: SpendSize) -> Self {
+        Self {
+            outpoint,
+            txout,
+            spend_size,
+        }
+    }
+
+    pub fn input_weight(&self) -> usize {
+        self.spend_size.input_weight()
+    }
+
+    /// What the coin is worth once the fee for spending it at `fee_rate`
+    ///  is paid, which is negative for coins not worth spending.
+    pub fn effective_value(&self, fee_rate: FeeRate) -> i64 {
+        self.txout.value as i64 - fee_rate.fee_for_weight(self.input_weight()) as i64
+    }
+}
+
+/// An unsigned transaction along with what signing it takes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BuiltTransaction {
+    pub tx: Transaction,
+    /// The outputs spent, in input order.
+    pub prevouts: Vec<TxOut>,
+    pub fee: u64,
+    /// Expected weight once signed.
+    pub weight: usize,
+    /// Index of the change output, if one was worth adding.
+    pub change_index: Option<usize>,
+}
+
+impl BuiltTransaction {
+    pub fn vsize(&self) -> usize {
+        self.weight.div_ceil(WITNESS_SCALE_FACTOR)
+    }
+}
+
+/// Puts together a transaction paying for itself at a fee rate, with any
+///  change going to a change output.
+#[derive(Clone, Debug)]
+pub struct TransactionBuilder {
+    inputs: Vec<Utxo>,
+    outputs: Vec<TxOut>,
+    change_script: Option<Script>,
+    fee_rate: FeeRate,
+    long_term_fee_rate: Option<FeeRate>,
+    version: i32,
+    lock_time: u32,
+    sequence: Option<u32>,
+    rbf: bool,
+}
+
+impl TransactionBuilder {
+    /// A version 2 transaction without a locktime.
+    pub fn new(fee_rate: FeeRate) -> Self {
+        Self {
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            change_script: None,
+            fee_rate,
+            long_term_fee_rate: None,
+            version: 2,
+            lock_time: 0,
+            sequence: None,
+            rbf: false,
+        }
+    }
+
+    pub fn add_input(mut self, utxo: Utxo) -> Self {
+        self.inputs.push(utxo);
+        self
+    }
+
+    pub fn add_output(mut self, output: TxOut) -> Self {
+        self.outputs.push(output);
+        self
+    }
+
+    pub fn pay_to(self, address: &Address, value: u64) -> Self {
+        self.add_output(TxOut::new(value, address.script_pubkey().into_bytes()))
+    }
+
+    /// Without a change address whatever the outputs don't spend goes to
+    ///  the fee.
+    pub fn change_address(self, address: &Address) -> Self {
+        self.change_script(address.script_pubkey())
+    }
+
+    pub fn change_script(mut self, script_pubkey: Script) -> Self {
+        self.change_script = Some(script_pubkey);
+        self
+    }
+
+    /// The fee rate coins are expected to be spent at later, which decides
+    ///  whether change is worth making and how coins are selected. It
+    ///  defaults to the transaction's own fee rate.
+    pub fn long_term_fee_rate(mut self, long_term_fee_rate: FeeRate) -> Self {
+        self.long_term_fee_rate = Some(long_term_fee_rate);
+        self
+    }
+
+    pub fn version(mut self, version: i32) -> Self {
+        self.version = version;
+        self
+    }
+
+    pub fn lock_time(mut self, lock_time: u32) -> Self {
+        self.lock_time = lock_time;
+        self
+    }
+
+    /// Signals BIP125 replaceability on every input.
+    pub fn enable_rbf(mut self) -> Self {
+        self.rbf = true;
+        self
+    }
+
+    /// Uses `sequence` for every input, e.g. for a BIP68 relative locktime,
+    ///  instead of picking one from the locktime and RBF settings.
+    pub fn sequence(mut self, sequence: u32) -> Self {
+        self.sequence = Some(sequence);
+        self
+    }
+
+    fn input_sequence(&self) -> u32 {
+        match (self.sequence, self.rbf, self.lock_time) {
+            (Some(sequence), _, _) => sequence,
+            (None, true, _) => SEQUENCE_MAX_BIP125_RBF,
+            // A locktime is ignored when every input is final.
+            (None, false, 0) => SEQUENCE_FINAL,
+            (None, false, _) => SEQUENCE_MAX_NONFINAL,
+        }
+    }
+
+    /// What coin selection has to pay for on top of the inputs added so
+    ///  far. The change output, if any, is not part of the target.
+    pub fn selection_params(&self) -> Result<SelectionParams, BuilderError> {
+        let long_term_fee_rate = self.long_term_fee_rate.unwrap_or(self.fee_rate);
+
+        // Assumes the selected coins add the segwit marker and flag.
+        let fee = self.fee_rate.fee_for_weight(self.estimate_weight(&self.outputs) + 2);
+        let output_value = sum(self.outputs.iter().map(|output| output.value))?;
+        let input_value = sum(self.inputs.iter().map(|utxo| utxo.txout.value))?;
+        let needed = output_value.checked_add(fee).ok_or(BuilderError::ValueOverflow)?;
+
+        let cost_of_change = self.change_script.as_ref().map(|change_script| {
+            let change = TxOut::new(0, change_script.as_bytes().to_vec());
+            let weight = (8 + compact_size_len(change.script_pubkey.len() as u64) + change.script_pubkey.len()) * WITNESS_SCALE_FACTOR;
+
+            self.fee_rate.fee_for_weight(weight) + change_spend_fee(change_script, long_term_fee_rate)
+        });
+
+        Ok(SelectionParams {
+            target: needed.saturating_sub(input_value),
+            fee_rate: self.fee_rate,
+            long_term_fee_rate,
+            cost_of_change,
+        })
+    }
+
+    /// Adds the coins `algorithm` picks from `candidates` to fund the
+    ///  outputs, skipping any already added. Nothing is added when the
+    ///  inputs so far are enough.
+    pub fn select_coins(mut self, candidates: &[Utxo], algorithm: &dyn CoinSelection, rng: &mut dyn RngCore) -> Result<Self, BuilderError> {
+        let params = self.selection_params()?;
+
+        if params.target == 0 {
+            return Ok(self);
+        }
+
+        let candidates: Vec<Utxo> = candidates.iter()
+            .filter(|candidate| self.inputs.iter().all(|utxo| utxo.outpoint != candidate.outpoint))
+            .cloned()
+            .collect();
+
+        let selection = algorithm.select_coins(&candidates, &params, rng)?;
+        self.inputs.extend(selection.selected);
+
+        Ok(self)
+    }
+
+    /// Weight of the transaction with `outputs` once every input is signed.
+    pub fn estimate_weight(&self, outputs: &[TxOut]) -> usize {
+        let tx = Transaction::new(self.version, vec![], outputs.to_vec(), self.lock_time);
+
+        // The empty input vector's length prefix is already counted.
+        let mut weight = tx.weight() + (compact_size_len(self.inputs.len() as u64) - 1) * WITNESS_SCALE_FACTOR;
+        weight += self.inputs.iter().map(Utxo::input_weight).sum::<usize>();
+
+        // Marker and flag, unless no input has a witness and the per-input
+        //  zero bytes are not serialized either.
+        match self.inputs.iter().any(|utxo| utxo.spend_size.witness > 0) {
+            true => weight + 2,
+            false => weight - self.inputs.len(),
+        }
+    }
+
+    /// The unsigned transaction. Change is added last, and only when what
+    ///  is left for it once its own output is paid for is neither dust nor
+    ///  less than spending it at the long-term fee rate would cost;
+    ///  otherwise it goes to the fee.
+    pub fn build(&self) -> Result<BuiltTransaction, BuilderError> {
+        if self.inputs.is_empty() {
+            return Err(BuilderError::NoInputs);
+        }
+
+        if self.outputs.is_empty() && self.change_script.is_none() {
+            return Err(BuilderError::NoOutputs);
+        }
+
+        if let Some(index) = self.outputs.iter().position(|output| is_dust(output, DUST_RELAY_FEE)) {
+            return Err(BuilderError::DustOutput(index));
+        }
+
+        let input_value = sum(self.inputs.iter().map(|utxo| utxo.txout.value))?;
+        let output_value = sum(self.outputs.iter().map(|output| output.value))?;
+
+        let mut outputs = self.outputs.clone();
+        let mut weight = self.estimate_weight(&outputs);
+        let mut change_index = None;
+
+        let needed = output_value.checked_add(self.fee_rate.fee_for_weight(weight)).ok_or(BuilderError::ValueOverflow)?;
+
+        if input_value < needed {
+            return Err(BuilderError::InsufficientFunds(input_value, needed));
+        }
+
+        if let Some(change_script) = &self.change_script {
+            let mut with_change = outputs.clone();
+            with_change.push(TxOut::new(0, change_script.as_bytes().to_vec()));
+
+            let weight_with_change = self.estimate_weight(&with_change);
+            let fee = self.fee_rate.fee_for_weight(weight_with_change);
+
+            let spend_fee = change_spend_fee(change_script, self.long_term_fee_rate.unwrap_or(self.fee_rate));
+            let min_change = dust_threshold(with_change.last().unwrap(), DUST_RELAY_FEE).max(spend_fee + 1);
+
+            if let Some(change) = (input_value - output_value).checked_sub(fee) {
+                with_change.last_mut().unwrap().value = change;
+
+                if change >= min_change {
+                    change_index = Some(outputs.len());
+                    outputs = with_change;
+                    weight = weight_with_change;
+                }
+            }
+        }
+
+        let sequence = self.input_sequence();
+        let inputs = self.inputs.iter().map(|utxo| TxIn {
+            sequence,
+            ..TxIn::new(utxo.outpoint)
+        }).collect();
+
+        let fee = input_value - sum(outputs.iter().map(|output| output.value))?;
+
+        Ok(BuiltTransaction {
+            tx: Transaction::new(self.version, inputs, outputs, self.lock_time),
+            prevouts: self.inputs.iter().map(|utxo| utxo.txout.clone()).collect(),
+            fee,
+            weight,
+            change_index,
+        })
+    }
+}
+
+/// Fee for spending a change output later, where its type says how.
+fn change_spend_fee(change_script: &Script, long_term_fee_rate: FeeRate) -> u64 {
+    SpendSize::for_script_pubkey(change_script).map_or(0, |spend_size| long_term_fee_rate.fee_for_weight(spend_size.input_weight()))
+}
+
+fn sum(mut values: impl Iterator<Item = u64>) -> Result<u64, BuilderError> {
+    values.try_fold(0u64, |total, value| total.checked_add(value)).ok_or(BuilderError::ValueOverflow)
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{rngs::StdRng, SeedableRng};
+    use crate::{cryptography::{bitcoin_private_key::BitcoinPrivateKey, hash::digest::{PubkeyHash, Txid}}, script::Script, transaction::{coin_selection::{branch_and_bound::BranchAndBound, error::SelectionError, largest_first::LargestFirst, test::utxos as coins}, fee::FeeRate, out_point::OutPoint, sighash::{SighashCache, SighashType}, signer::{multisig::{Multisig, MultisigWrapper}, sign_transaction}, test::{keys, public_key_hash}, tx_in::{SEQUENCE_FINAL, SEQUENCE_MAX_BIP125_RBF, SEQUENCE_MAX_NONFINAL}, tx_out::TxOut}};
+    use super::{error::BuilderError, SpendSize, TransactionBuilder, Utxo};
+
+    /// A P2PKH, a P2WPKH and an uncompressed P2PKH coin.
+    fn utxos(keys: &[BitcoinPrivateKey]) -> Vec<Utxo> {
+        let txid = Txid::from_byte_array([0x42; 32]);
+
+        vec![
+            Utxo::new(OutPoint::new(txid, 0), TxOut::new(40_000, Script::p2pkh(&public_key_hash(&keys[1])).into_bytes())).unwrap(),
+            Utxo::new(OutPoint::new(txid, 1), TxOut::new(30_000, Script::p2wpkh(&public_key_hash(&keys[2])).into_bytes())).unwrap(),
+            Utxo::with_spend_size(OutPoint::new(txid, 2), TxOut::new(20_000, Script::p2pkh(&public_key_hash(&keys[0])).into_bytes()), SpendSize::P2PKH_UNCOMPRESSED),
+        ]
+    }
+
+    fn recipient() -> Script {
+        Script::p2wpkh(&PubkeyHash::from_byte_array([0x11; 20]))
+    }
+
+    #[test]
+    pub fn test_spend_sizes() {
+        let hash = PubkeyHash::from_byte_array([0x11; 20]);
+
+        assert_eq!(SpendSize::for_script_pubkey(&Script::p2pkh(&hash)), Some(SpendSize::P2PKH));
+        assert_eq!(SpendSize::for_script_pubkey(&Script::p2wpkh(&hash)), Some(SpendSize::P2WPKH));
+        assert_eq!(SpendSize::for_script_pubkey(&Script::p2tr(&[0x11; 32])), Some(SpendSize::P2TR_KEY_PATH));
+        assert_eq!(SpendSize::for_script_pubkey(&Script::p2wsh(&[0x11; 32])), None);
+
+        // The usual figures: 148 vbytes for P2PKH, 68 for P2WPKH, 91 for
+        //  P2SH-P2WPKH and 57.5 for a taproot key path.
+        let txout = TxOut::new(0, vec![]);
+        let weights: Vec<_> = [SpendSize::P2PKH, SpendSize::P2WPKH, SpendSize::P2SH_P2WPKH, SpendSize::P2TR_KEY_PATH].into_iter()
+            .map(|spend_size| Utxo::with_spend_size(OutPoint::null(), txout.clone(), spend_size).input_weight())
+            .collect();
+        assert_eq!(weights, [4 * 148 + 1, 4 * 68, 4 * 91, 230]);
+    }
+
+    #[test]
+    pub fn test_build_and_sign() {
+        let keys = keys();
+        let change = Script::p2wpkh(&public_key_hash(&keys[1]));
+        let fee_rate = FeeRate::from_sat_per_kvb(2_500);
+
+        let mut builder = TransactionBuilder::new(fee_rate).add_output(TxOut::new(60_000, recipient().into_bytes())).change_script(change.clone());
+        for utxo in utxos(&keys) {
+            builder = builder.add_input(utxo);
+        }
+
+        let mut built = builder.build().unwrap();
+        assert_eq!(built.change_index, Some(1));
+        assert_eq!(built.tx.outputs[1].script_pubkey, change.as_bytes());
+        assert_eq!(built.fee, fee_rate.fee(built.vsize()));
+        assert_eq!(built.tx.outputs[1].value, 90_000 - 60_000 - built.fee);
+
+        sign_transaction(&mut built.tx, &built.prevouts, &keys, SighashType::ALL).unwrap();
+
+        // Signatures are at most as long as the estimate assumes, and only
+        //  a byte or two shorter.
+        let weight = built.tx.weight();
+        assert!(weight <= built.weight);
+        assert!(built.weight - weight <= 3 * 2 * 4);
+    }
+
+    #[test]
+    pub fn test_multisig_estimate() {
+        let keys = keys();
+        let points: Vec<_> = keys[1..].iter().map(|key| key.public_key()).collect();
+
+        for wrapper in [MultisigWrapper::P2SH, MultisigWrapper::P2WSH, MultisigWrapper::P2SHP2WSH] {
+            let multisig = Multisig::sorted(2, &points, wrapper).unwrap();
+            let utxo = Utxo::with_spend_size(OutPoint::new(Txid::from_byte_array([0x42; 32]), 0), TxOut::new(50_000, multisig.script_pubkey().into_bytes()), SpendSize::multisig(&multisig));
+
+            let mut built = TransactionBuilder::new(FeeRate::from_sat_per_vb(1)).add_input(utxo).add_output(TxOut::new(40_000, recipient().into_bytes())).build().unwrap();
+            assert_eq!(built.change_index, None);
+            assert_eq!(built.fee, 10_000);
+
+            let signatures: Vec<_> = keys[1..].iter().map(|key| multisig.sign(&SighashCache::new(&built.tx), 0, 50_000, key, SighashType::ALL).unwrap()).collect();
+            multisig.finalize(&mut built.tx, 0, 50_000, &signatures).unwrap();
+
+            let weight = built.tx.weight();
+            assert!(weight <= built.weight);
+            assert!(built.weight - weight <= 2 * 2 * 4);
+        }
+    }
+
+    #[test]
+    pub fn test_dust_change() {
+        let keys = keys();
+        let utxo = utxos(&keys).swap_remove(1);
+
+        // 110 vbytes without change and 141 with it, at 1 sat/vB.
+        let builder = |value| TransactionBuilder::new(FeeRate::from_sat_per_vb(1))
+            .add_input(utxo.clone())
+            .add_output(TxOut::new(value, recipient().into_bytes()))
+            .change_script(recipient());
+
+        // 294 is the smallest P2WPKH output that isn't dust.
+        let built = builder(30_000 - 141 - 294).build().unwrap();
+        assert_eq!(built.change_index, Some(1));
+        assert_eq!(built.tx.outputs[1].value, 294);
+        assert_eq!(built.fee, 141);
+
+        let built = builder(30_000 - 141 - 293).build().unwrap();
+        assert_eq!(built.change_index, None);
+        assert_eq!(built.tx.outputs.len(), 1);
+        assert_eq!(built.fee, 141 + 293);
+        assert_eq!(built.vsize(), 110);
+
+        // Nor is change that would cost more to spend later than it's worth.
+        let built = builder(30_000 - 141 - 680).long_term_fee_rate(FeeRate::from_sat_per_vb(10)).build().unwrap();
+        assert_eq!(built.change_index, None);
+
+        let built = builder(30_000 - 141 - 681).long_term_fee_rate(FeeRate::from_sat_per_vb(10)).build().unwrap();
+        assert_eq!(built.change_index, Some(1));
+    }
+
+    #[test]
+    pub fn test_select_coins() {
+        // At 1 sat/vB the outputs need 20_042 sats with the fee for the
+        //  transaction's fixed parts.
+        let candidates = coins(&[5_000, 15_042, 20_100, 40_000]);
+        let builder = TransactionBuilder::new(FeeRate::from_sat_per_vb(1)).add_output(TxOut::new(20_000, recipient().into_bytes())).change_script(recipient());
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let params = builder.selection_params().unwrap();
+        assert_eq!(params.target, 20_042);
+        assert_eq!(params.cost_of_change, Some(31 + 68));
+
+        // Branch and bound matches the target exactly, which pays the fee
+        //  and needs no change.
+        let built = builder.clone().select_coins(&candidates, &BranchAndBound, &mut rng).unwrap().build().unwrap();
+        assert_eq!(built.tx.inputs.len(), 2);
+        assert_eq!(built.change_index, None);
+        assert_eq!(built.fee, FeeRate::from_sat_per_vb(1).fee(built.vsize()));
+
+        let built = builder.clone().select_coins(&candidates, &LargestFirst, &mut rng).unwrap().build().unwrap();
+        assert_eq!(built.prevouts, [candidates[3].txout.clone()]);
+        assert_eq!(built.change_index, Some(1));
+
+        // Inputs already added count towards the target.
+        let with_input = builder.clone().add_input(candidates[0].clone());
+        assert_eq!(with_input.selection_params().unwrap().target, 20_000 + 110 - 5_068);
+
+        let built = with_input.select_coins(&candidates, &BranchAndBound, &mut rng).unwrap().build().unwrap();
+        assert_eq!(built.prevouts, [candidates[0].txout.clone(), candidates[1].txout.clone()]);
+        assert_eq!(built.change_index, None);
+
+        let expensive = builder.clone().add_output(TxOut::new(100_000, recipient().into_bytes()));
+        assert_eq!(expensive.select_coins(&candidates, &LargestFirst, &mut rng).unwrap_err(), BuilderError::Selection(SelectionError::InsufficientFunds(80_142, 120_073)));
+
+        // Values adding up past `u64::MAX` are an error rather than a panic.
+        let overflowing = builder.clone().add_output(TxOut::new(u64::MAX, recipient().into_bytes()));
+        assert_eq!(overflowing.selection_params().unwrap_err(), BuilderError::ValueOverflow);
+        assert_eq!(overflowing.select_coins(&candidates, &LargestFirst, &mut rng).unwrap_err(), BuilderError::ValueOverflow);
+
+        let max_fee = builder.clone().add_output(TxOut::new(u64::MAX - 10, recipient().into_bytes()));
+        assert_eq!(max_fee.selection_params().unwrap_err(), BuilderError::ValueOverflow);
+
+        let huge_inputs = builder.add_input(Utxo::new(OutPoint::null(), TxOut::new(u64::MAX, recipient().into_bytes())).unwrap()).add_input(candidates[0].clone());
+        assert_eq!(huge_inputs.selection_params().unwrap_err(), BuilderError::ValueOverflow);
+    }
+
+    #[test]
+    pub fn test_sequence_and_lock_time() {
+        let keys = keys();
+        let builder = TransactionBuilder::new(FeeRate::from_sat_per_vb(1)).add_input(utxos(&keys).swap_remove(0)).add_output(TxOut::new(30_000, recipient().into_bytes()));
+
+        let built = builder.clone().build().unwrap();
+        assert_eq!((built.tx.version, built.tx.lock_time, built.tx.inputs[0].sequence), (2, 0, SEQUENCE_FINAL));
+        assert!(!built.tx.signals_rbf());
+
+        let built = builder.clone().version(1).lock_time(800_000).build().unwrap();
+        assert_eq!((built.tx.version, built.tx.lock_time, built.tx.inputs[0].sequence), (1, 800_000, SEQUENCE_MAX_NONFINAL));
+        assert!(!built.tx.signals_rbf());
+
+        let built = builder.clone().lock_time(800_000).enable_rbf().build().unwrap();
+        assert_eq!(built.tx.inputs[0].sequence, SEQUENCE_MAX_BIP125_RBF);
+        assert!(built.tx.signals_rbf());
+
+        // A relative locktime of 144 blocks.
+        let built = builder.enable_rbf().sequence(144).build().unwrap();
+        assert_eq!(built.tx.inputs[0].sequence, 144);
+        assert!(built.tx.signals_rbf());
+    }
+
+    #[test]
+    pub fn test_build_errors() {
+        let keys = keys();
+        let utxo = utxos(&keys).swap_remove(1);
+        let builder = TransactionBuilder::new(FeeRate::from_sat_per_vb(10));
+
+        assert_eq!(builder.clone().add_output(TxOut::new(1_000, recipient().into_bytes())).build(), Err(BuilderError::NoInputs));
+        assert_eq!(builder.clone().add_input(utxo.clone()).build(), Err(BuilderError::NoOutputs));
+        assert_eq!(builder.clone().add_input(utxo.clone()).add_output(TxOut::new(1_000, recipient().into_bytes())).add_output(TxOut::new(293, recipient().into_bytes())).build(), Err(BuilderError::DustOutput(1)));
+        assert_eq!(builder.clone().add_input(utxo.clone()).add_output(TxOut::new(30_000, recipient().into_bytes())).build(), Err(BuilderError::InsufficientFunds(30_000, 31_100)));
+        assert_eq!(Utxo::new(OutPoint::null(), TxOut::new(1_000, Script::p2wsh(&[0x11; 32]).into_bytes())), Err(BuilderError::UnknownSpendSize(34)));
+
+        // Sending everything to the change address works as long as that
+        //  covers the fee.
+        let sweep = builder.add_input(utxo).change_script(recipient()).build().unwrap();
+        assert_eq!(sweep.change_index, Some(0));
+        assert_eq!(sweep.tx.outputs[0].value, 30_000 - 1_100);
+    }
+}
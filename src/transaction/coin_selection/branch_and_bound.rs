@@ -0,0 +1,159 @@
+use std::cmp::Reverse;
+use rand::RngCore;
+use crate::transaction::builder::Utxo;
+use super::{error::SelectionError, CoinSelection, Selection, SelectionParams};
+
+/// Core gives up after this many steps through the search tree.
+const TOTAL_TRIES: usize = 100_000;
+
+/// Core's branch and bound: a depth-first search for the coins whose
+///  effective values land between the target and the target plus the cost
+///  of change, so that no change is needed, keeping the one with the least
+///  waste. Without a change output any excess is allowed.
+pub struct BranchAndBound;
+
+impl CoinSelection for BranchAndBound {
+    fn select_coins(&self, candidates: &[Utxo], params: &SelectionParams, _rng: &mut dyn RngCore) -> Result<Selection, SelectionError> {
+        let mut pool = params.spendable(candidates);
+        pool.sort_by_key(|&(_, value)| Reverse(value));
+
+        // What each coin adds to the waste when it is selected.
+        let timing_costs: Vec<i64> = pool.iter()
+            .map(|(utxo, value)| utxo.effective_value(params.long_term_fee_rate) - *value as i64)
+            .collect();
+
+        let target = params.target;
+        let upper_bound = target.saturating_add(params.cost_of_change.unwrap_or(u64::MAX));
+        let is_fee_rate_high = params.fee_rate > params.long_term_fee_rate;
+
+        let mut available: u64 = pool.iter().map(|(_, value)| value).sum();
+
+        if available < target {
+            return Err(params.insufficient_funds(&pool, target));
+        }
+
+        let mut value = 0;
+        let mut waste = 0;
+        let mut selected: Vec<usize> = Vec::new();
+
+        let mut best: Option<(Vec<usize>, i64)> = None;
+        let mut index = 0;
+
+        for _ in 0..TOTAL_TRIES {
+            let best_waste = best.as_ref().map_or(i64::MAX, |(_, waste)| *waste);
+
+            // With fees above the long-term rate every extra input only
+            //  adds waste, so a branch already worse than the best is done.
+            let backtrack = if value + available < target || value > upper_bound || (waste > best_waste && is_fee_rate_high) {
+                true
+            } else if value >= target {
+                let solution_waste = waste + (value - target) as i64;
+
+                if solution_waste <= best_waste {
+                    best = Some((selected.clone(), solution_waste));
+                }
+
+                true
+            } else {
+                false
+            };
+
+            if backtrack {
+                let Some(&last) = selected.last() else {
+                    break;
+                };
+
+                // Return the coins skipped since the last selected one, then
+                //  try the branch without it.
+                available += pool[last + 1..index].iter().map(|(_, value)| value).sum::<u64>();
+
+                index = last;
+                selected.pop();
+                value -= pool[index].1;
+                waste -= timing_costs[index];
+            } else {
+                available -= pool[index].1;
+
+                // Skipping a coin and selecting one just like it would
+                //  search the same branch again.
+                let is_duplicate = index > 0 && selected.last() != Some(&(index - 1)) && pool[index].1 == pool[index - 1].1 && timing_costs[index] == timing_costs[index - 1];
+
+                if !is_duplicate {
+                    selected.push(index);
+                    value += pool[index].1;
+                    waste += timing_costs[index];
+                }
+            }
+
+            index += 1;
+        }
+
+        match best {
+            Some((indices, _)) => Ok(params.selection(indices.into_iter().map(|index| pool[index].0.clone()).collect())),
+            None => Err(SelectionError::NoChangelessSolution),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{rngs::StdRng, SeedableRng};
+    use crate::transaction::{coin_selection::{error::SelectionError, test::{effective_values, params, utxos}, CoinSelection, SelectionParams}, fee::FeeRate};
+    use super::BranchAndBound;
+
+    fn select(values: &[u64], params: &SelectionParams) -> Result<Vec<u64>, SelectionError> {
+        BranchAndBound.select_coins(&utxos(values), params, &mut StdRng::seed_from_u64(0)).map(|selection| effective_values(&selection.selected))
+    }
+
+    #[test]
+    pub fn test_exact_match() {
+        // The pool from Core's coinselector tests, in whole coins.
+        let values: Vec<u64> = [1, 2, 3, 4].iter().map(|coins| coins * 100_000_000).collect();
+
+        assert_eq!(select(&values, &params(100_000_000, Some(500_000))).unwrap(), [100_000_000]);
+        assert_eq!(select(&values, &params(300_000_000, Some(500_000))).unwrap().iter().sum::<u64>(), 300_000_000);
+        assert_eq!(select(&values, &params(1_000_000_000, Some(500_000))).unwrap().len(), 4);
+        assert_eq!(select(&values, &params(700_000_000, Some(500_000))).unwrap().iter().sum::<u64>(), 700_000_000);
+
+        // Anything within the cost of change above the target will do.
+        assert_eq!(select(&values, &params(99_600_000, Some(500_000))).unwrap(), [100_000_000]);
+        assert_eq!(select(&values, &params(99_400_000, Some(500_000))), Err(SelectionError::NoChangelessSolution));
+        assert_eq!(select(&values, &params(1_100_000_000, Some(500_000))), Err(SelectionError::InsufficientFunds(1_000_000_000, 1_100_000_000)));
+    }
+
+    #[test]
+    pub fn test_least_waste() {
+        // Both 5 + 4 and 6 + 3.1 are within the window, the first exactly.
+        assert_eq!(select(&[6_000, 5_000, 4_000, 3_100], &params(9_000, Some(500))).unwrap(), [4_000, 5_000]);
+        assert_eq!(select(&[6_000, 5_200, 4_000, 3_100], &params(9_000, Some(500))).unwrap(), [3_100, 6_000]);
+
+        // Without change the closest match is best, however far.
+        assert_eq!(select(&[60_000, 50_000, 40_000], &params(45_000, None)).unwrap(), [50_000]);
+    }
+
+    #[test]
+    pub fn test_fee_rate_decides_input_count() {
+        let values = [5_000, 3_000, 2_000];
+        let mut params = params(5_000, Some(500));
+
+        // Spending more inputs while fees are low saves on spending them
+        //  later, the opposite when fees are high.
+        params.long_term_fee_rate = FeeRate::from_sat_per_vb(10);
+        assert_eq!(select(&values, &params).unwrap(), [2_000, 3_000]);
+
+        params.long_term_fee_rate = FeeRate::ZERO;
+        assert_eq!(select(&values, &params).unwrap(), [5_000]);
+    }
+
+    #[test]
+    pub fn test_duplicates() {
+        // Many equal coins short of the target exhaust the search quickly
+        //  rather than trying every combination.
+        let mut values = vec![1_000; 50];
+        values.push(25_500);
+
+        assert_eq!(select(&values, &params(25_500, Some(0))).unwrap(), [25_500]);
+        assert_eq!(select(&values[..50], &params(50_500, Some(0))), Err(SelectionError::InsufficientFunds(50_000, 50_500)));
+        assert_eq!(select(&values[..50], &params(30_500, Some(0))), Err(SelectionError::NoChangelessSolution));
+    }
+}
@@ -0,0 +1,18 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SelectionError {
+    InsufficientFunds(u64, u64),
+    NoChangelessSolution,
+}
+
+impl Error for SelectionError { }
+
+impl Display for SelectionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SelectionError::InsufficientFunds(available, needed) => write!(f, "SelectionError::InsufficientFunds({} available after fees, {} needed)", available, needed),
+            SelectionError::NoChangelessSolution => write!(f, "SelectionError::NoChangelessSolution(no set of coins avoids change)"),
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use std::cmp::Reverse;
+use rand::{seq::SliceRandom, Rng, RngCore};
+use crate::transaction::builder::Utxo;
+use super::{error::SelectionError, CoinSelection, Selection, SelectionParams};
+
+/// Change the knapsack solver aims for when there is a change output,
+///  Core's `MIN_CHANGE` of 0.01 BTC.
+pub const MIN_CHANGE: u64 = 1_000_000;
+
+/// Random subsets tried, as many as Core's `ApproximateBestSubset` does.
+const ITERATIONS: usize = 100_000;
+
+/// Core's original knapsack solver: a coin matching the target exactly,
+///  otherwise the closest of many random subsets of the smaller coins, or
+///  the smallest coin larger than the target when that is closer. With a
+///  change output subsets aim for `MIN_CHANGE` above the target, to keep
+///  change from being tiny.
+pub struct Knapsack;
+
+impl CoinSelection for Knapsack {
+    fn select_coins(&self, candidates: &[Utxo], params: &SelectionParams, rng: &mut dyn RngCore) -> Result<Selection, SelectionError> {
+        let mut pool = params.spendable(candidates);
+        pool.shuffle(rng);
+
+        let target = params.target;
+        let change_target = match params.cost_of_change {
+            Some(cost_of_change) => cost_of_change + MIN_CHANGE,
+            None => 0,
+        };
+
+        let mut lowest_larger: Option<(&Utxo, u64)> = None;
+        let mut applicable = Vec::new();
+
+        for &(utxo, value) in &pool {
+            if value == target {
+                return Ok(params.selection(vec![utxo.clone()]));
+            } else if value < target + change_target {
+                applicable.push((utxo, value));
+            } else if lowest_larger.is_none_or(|(_, lowest)| value < lowest) {
+                lowest_larger = Some((utxo, value));
+            }
+        }
+
+        let total_lower: u64 = applicable.iter().map(|(_, value)| value).sum();
+
+        if total_lower == target {
+            return Ok(params.selection(applicable.into_iter().map(|(utxo, _)| utxo.clone()).collect()));
+        }
+
+        if total_lower < target {
+            return match lowest_larger {
+                Some((utxo, _)) => Ok(params.selection(vec![utxo.clone()])),
+                None => Err(params.insufficient_funds(&pool, target)),
+            };
+        }
+
+        applicable.sort_by_key(|&(_, value)| Reverse(value));
+        let values: Vec<u64> = applicable.iter().map(|(_, value)| *value).collect();
+
+        let (mut best, mut best_value) = approximate_best_subset(&values, total_lower, target, rng);
+
+        if best_value != target && total_lower >= target + change_target {
+            (best, best_value) = approximate_best_subset(&values, total_lower, target + change_target, rng);
+        }
+
+        // A single larger coin wins over a subset that misses both targets,
+        //  or that isn't any smaller.
+        if let Some((utxo, value)) = lowest_larger {
+            if (best_value != target && best_value < target + change_target) || value <= best_value {
+                return Ok(params.selection(vec![utxo.clone()]));
+            }
+        }
+
+        let selected = applicable.into_iter().zip(best).filter(|(_, included)| *included).map(|((utxo, _), _)| utxo.clone()).collect();
+
+        Ok(params.selection(selected))
+    }
+}
+
+/// The smallest total of `values` reaching `target` that random tries
+///  find. Each includes values at random, then tops up with the rest, and
+///  whenever the total reaches `target` drops the last value again to look
+///  for a smaller one.
+fn approximate_best_subset(values: &[u64], total_lower: u64, target: u64, rng: &mut dyn RngCore) -> (Vec<bool>, u64) {
+    let mut best = vec![true; values.len()];
+    let mut best_value = total_lower;
+
+    for _ in 0..ITERATIONS {
+        if best_value == target {
+            break;
+        }
+
+        let mut included = vec![false; values.len()];
+        let mut total = 0;
+        let mut reached_target = false;
+
+        for pass in 0..2 {
+            if reached_target {
+                break;
+            }
+
+            for (i, value) in values.iter().enumerate() {
+                // The first pass picks at random, the second takes whatever
+                //  is left.
+                let include = match pass {
+                    0 => rng.gen_bool(0.5),
+                    _ => !included[i],
+                };
+
+                if include {
+                    total += value;
+                    included[i] = true;
+
+                    if total >= target {
+                        reached_target = true;
+
+                        if total < best_value {
+                            best_value = total;
+                            best = included.clone();
+                        }
+
+                        total -= value;
+                        included[i] = false;
+                    }
+                }
+            }
+        }
+    }
+
+    (best, best_value)
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{rngs::StdRng, SeedableRng};
+    use crate::transaction::coin_selection::{error::SelectionError, test::{effective_values, params, utxos}, CoinSelection, SelectionParams};
+    use super::{Knapsack, MIN_CHANGE};
+
+    fn select(values: &[u64], params: &SelectionParams, seed: u64) -> Result<Vec<u64>, SelectionError> {
+        Knapsack.select_coins(&utxos(values), params, &mut StdRng::seed_from_u64(seed)).map(|selection| effective_values(&selection.selected))
+    }
+
+    #[test]
+    pub fn test_exact_matches() {
+        for seed in 0..10 {
+            assert_eq!(select(&[5_000, 20_000, 7_000, 3_000], &params(7_000, None), seed).unwrap(), [7_000]);
+            assert_eq!(select(&[5_000, 2_000, 100_000], &params(7_000, None), seed).unwrap(), [2_000, 5_000]);
+            assert_eq!(select(&[1_000, 2_000, 3_000, 4_000, 500_000], &params(9_000, None), seed).unwrap().iter().sum::<u64>(), 9_000);
+        }
+    }
+
+    #[test]
+    pub fn test_lowest_larger() {
+        for seed in 0..10 {
+            // The smaller coins don't add up, so the next larger one it is.
+            assert_eq!(select(&[1_000, 2_000, 30_000, 20_000], &params(5_000, None), seed).unwrap(), [20_000]);
+
+            // A larger coin closer than any subset.
+            assert_eq!(select(&[6_000, 6_000, 11_000], &params(10_000, None), seed).unwrap(), [11_000]);
+        }
+
+        assert_eq!(select(&[1_000, 2_000], &params(5_000, None), 0), Err(SelectionError::InsufficientFunds(3_000, 5_000)));
+    }
+
+    #[test]
+    pub fn test_aims_for_change() {
+        let params = params(100_000, Some(1_000));
+
+        for seed in 0..10 {
+            // Change below `MIN_CHANGE` is avoided where there is a choice.
+            let selected = select(&[60_000, 50_000, 2 * MIN_CHANGE], &params, seed).unwrap();
+            assert_eq!(selected, [2 * MIN_CHANGE]);
+
+            let selected = select(&[60_000, 50_000, 600_000, 700_000], &params, seed).unwrap();
+            assert!(selected.iter().sum::<u64>() >= 100_000 + 1_000 + MIN_CHANGE);
+        }
+    }
+
+    #[test]
+    pub fn test_seeded() {
+        let values: Vec<u64> = (1..=40).map(|n| n * 1_009).collect();
+        let params = params(123_456, None);
+
+        // Deterministic for a seed, and good enough for any.
+        assert_eq!(select(&values, &params, 7), select(&values, &params, 7));
+
+        for seed in 0..5 {
+            let total: u64 = select(&values, &params, seed).unwrap().iter().sum();
+            assert!((123_456..123_456 + 1_009).contains(&total));
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use std::cmp::Reverse;
+use rand::RngCore;
+use crate::transaction::builder::Utxo;
+use super::{error::SelectionError, CoinSelection, Selection, SelectionParams};
+
+/// The coins with the highest effective values until they cover the
+///  target, which spends as few inputs as possible.
+pub struct LargestFirst;
+
+impl CoinSelection for LargestFirst {
+    fn select_coins(&self, candidates: &[Utxo], params: &SelectionParams, _rng: &mut dyn RngCore) -> Result<Selection, SelectionError> {
+        let mut pool = params.spendable(candidates);
+        pool.sort_by_key(|&(_, value)| Reverse(value));
+
+        let mut selected = Vec::new();
+        let mut value = 0;
+
+        for &(utxo, utxo_value) in &pool {
+            if value >= params.target {
+                break;
+            }
+
+            selected.push(utxo.clone());
+            value += utxo_value;
+        }
+
+        match value >= params.target {
+            true => Ok(params.selection(selected)),
+            false => Err(params.insufficient_funds(&pool, params.target)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{rngs::StdRng, SeedableRng};
+    use crate::transaction::coin_selection::{test::{effective_values, params, utxos}, CoinSelection};
+    use super::LargestFirst;
+
+    #[test]
+    pub fn test_largest_first() {
+        let utxos = utxos(&[3_000, 50_000, 1_000, 20_000, 40_000]);
+        let select = |target| effective_values(&LargestFirst.select_coins(&utxos, &params(target, None), &mut StdRng::seed_from_u64(0)).unwrap().selected);
+
+        assert_eq!(select(10_000), [50_000]);
+        assert_eq!(select(50_001), [40_000, 50_000]);
+        assert_eq!(select(112_000), [3_000, 20_000, 40_000, 50_000]);
+    }
+}
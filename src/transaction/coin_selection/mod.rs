@@ -0,0 +1,197 @@
+use rand::RngCore;
+use self::{branch_and_bound::BranchAndBound, error::SelectionError, knapsack::Knapsack, single_random_draw::SingleRandomDraw};
+use super::{builder::Utxo, fee::FeeRate};
+
+pub mod branch_and_bound;
+pub mod error;
+pub mod knapsack;
+pub mod largest_first;
+pub mod single_random_draw;
+
+/// What a selection has to pay for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SelectionParams {
+    /// Value the selected coins have to add up to once the fees for
+    ///  spending them are taken off: the outputs, plus the fee for
+    ///  everything in the transaction but the inputs.
+    pub target: u64,
+    pub fee_rate: FeeRate,
+    /// Fee rate the wallet expects to pay on average, at which coins not
+    ///  spent now would be spent later.
+    pub long_term_fee_rate: FeeRate,
+    /// Fee for adding a change output now and spending it later, or `None`
+    ///  when there is nowhere to send change.
+    pub cost_of_change: Option<u64>,
+}
+
+impl SelectionParams {
+    /// Core's waste metric, lower is better. Every input costs the
+    ///  difference between its fee now and at the long-term fee rate, and
+    ///  the transaction either pays for change or gives away the excess
+    ///  over `target` to the miners. Change is assumed whenever the excess
+    ///  covers its cost.
+    pub fn waste(&self, selected: &[Utxo]) -> i64 {
+        let timing_cost: i64 = selected.iter()
+            .map(|utxo| utxo.effective_value(self.long_term_fee_rate) - utxo.effective_value(self.fee_rate))
+            .sum();
+
+        let excess = selected.iter().map(|utxo| utxo.effective_value(self.fee_rate)).sum::<i64>() - self.target as i64;
+
+        match self.cost_of_change {
+            Some(cost_of_change) if excess > cost_of_change as i64 => timing_cost + cost_of_change as i64,
+            _ => timing_cost + excess,
+        }
+    }
+
+    fn selection(&self, selected: Vec<Utxo>) -> Selection {
+        Selection {
+            waste: self.waste(&selected),
+            selected,
+        }
+    }
+
+    /// The candidates worth more than it costs to spend them, along with
+    ///  that effective value.
+    fn spendable<'a>(&self, candidates: &'a [Utxo]) -> Vec<(&'a Utxo, u64)> {
+        candidates.iter()
+            .filter_map(|utxo| u64::try_from(utxo.effective_value(self.fee_rate)).ok().filter(|&value| value > 0).map(|value| (utxo, value)))
+            .collect()
+    }
+
+    fn insufficient_funds(&self, spendable: &[(&Utxo, u64)], needed: u64) -> SelectionError {
+        SelectionError::InsufficientFunds(spendable.iter().map(|(_, value)| value).sum(), needed)
+    }
+}
+
+/// The coins picked along with their waste.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Selection {
+    pub selected: Vec<Utxo>,
+    pub waste: i64,
+}
+
+/// A way of picking which of the wallet's coins fund a transaction. The
+///  randomized algorithms draw from `rng`, so a seeded one makes them
+///  deterministic.
+pub trait CoinSelection {
+    fn select_coins(&self, candidates: &[Utxo], params: &SelectionParams, rng: &mut dyn RngCore) -> Result<Selection, SelectionError>;
+}
+
+/// Runs every algorithm and keeps the selection with the least waste, as
+///  Core's wallet does. When none succeeds the last error is returned.
+pub struct LowestWaste(pub Vec<Box<dyn CoinSelection>>);
+
+/// Branch and bound, knapsack and single random draw, Core's choice.
+impl Default for LowestWaste {
+    fn default() -> Self {
+        Self(vec![Box::new(BranchAndBound), Box::new(Knapsack), Box::new(SingleRandomDraw)])
+    }
+}
+
+impl CoinSelection for LowestWaste {
+    fn select_coins(&self, candidates: &[Utxo], params: &SelectionParams, rng: &mut dyn RngCore) -> Result<Selection, SelectionError> {
+        let mut best: Option<Selection> = None;
+        let mut error = SelectionError::NoChangelessSolution;
+
+        for algorithm in &self.0 {
+            match algorithm.select_coins(candidates, params, rng) {
+                // Ties go to the fewer inputs.
+                Ok(selection) => match &best {
+                    Some(best) if (best.waste, best.selected.len()) <= (selection.waste, selection.selected.len()) => { },
+                    _ => best = Some(selection),
+                },
+                Err(e) => error = e,
+            }
+        }
+
+        best.ok_or(error)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use rand::{rngs::StdRng, SeedableRng};
+    use crate::{cryptography::hash::digest::{PubkeyHash, Txid}, script::Script, transaction::{builder::Utxo, fee::FeeRate, out_point::OutPoint, tx_out::TxOut}};
+    use super::{branch_and_bound::BranchAndBound, error::SelectionError, knapsack::Knapsack, largest_first::LargestFirst, single_random_draw::SingleRandomDraw, CoinSelection, LowestWaste, SelectionParams};
+
+    /// Fee rate at which a P2WPKH coin costs exactly 68 sats to spend.
+    pub const FEE_RATE: FeeRate = FeeRate::from_sat_per_vb(1);
+
+    /// P2WPKH coins whose effective values at `FEE_RATE` are `values`.
+    pub fn utxos(values: &[u64]) -> Vec<Utxo> {
+        let script_pubkey = Script::p2wpkh(&PubkeyHash::from_byte_array([0x11; 20])).into_bytes();
+
+        values.iter().enumerate()
+            .map(|(vout, value)| Utxo::new(OutPoint::new(Txid::from_byte_array([0x42; 32]), vout as u32), TxOut::new(value + 68, script_pubkey.clone())).unwrap())
+            .collect()
+    }
+
+    pub fn params(target: u64, cost_of_change: Option<u64>) -> SelectionParams {
+        SelectionParams {
+            target,
+            fee_rate: FEE_RATE,
+            long_term_fee_rate: FEE_RATE,
+            cost_of_change,
+        }
+    }
+
+    pub fn effective_values(selected: &[Utxo]) -> Vec<u64> {
+        let mut values: Vec<_> = selected.iter().map(|utxo| utxo.effective_value(FEE_RATE) as u64).collect();
+        values.sort_unstable();
+        values
+    }
+
+    #[test]
+    pub fn test_waste() {
+        let utxos = utxos(&[10_000, 20_000]);
+        let mut params = params(25_000, Some(1_000));
+
+        // Excess above the cost of change pays for change.
+        assert_eq!(params.waste(&utxos), 1_000);
+        params.target = 29_500;
+        assert_eq!(params.waste(&utxos), 500);
+        params.cost_of_change = None;
+        params.target = 25_000;
+        assert_eq!(params.waste(&utxos), 5_000);
+
+        // Spending two 68 vbyte inputs at 1 sat/vB instead of 10 later
+        //  saves 2 * 68 * 9 sats.
+        params.long_term_fee_rate = FeeRate::from_sat_per_vb(10);
+        params.target = 30_000;
+        assert_eq!(params.waste(&utxos), -2 * 68 * 9);
+
+        params.fee_rate = FeeRate::from_sat_per_vb(20);
+        assert_eq!(params.waste(&utxos[..1]), 68 * 10 + (10_000 + 68 - 20 * 68) - 30_000);
+    }
+
+    #[test]
+    pub fn test_lowest_waste() {
+        let utxos = utxos(&[1_000, 2_000, 5_000, 10_000, 50_000, 100_000]);
+        let mut rng = StdRng::seed_from_u64(1);
+
+        // Branch and bound finds the exact match, which wastes nothing.
+        let selection = LowestWaste::default().select_coins(&utxos, &params(7_000, Some(300)), &mut rng).unwrap();
+        assert_eq!(effective_values(&selection.selected), [2_000, 5_000]);
+        assert_eq!(selection.waste, 0);
+
+        // Without change the lowest excess wins.
+        let selection = LowestWaste::default().select_coins(&utxos, &params(60_500, None), &mut rng).unwrap();
+        assert_eq!(effective_values(&selection.selected), [1_000, 10_000, 50_000]);
+        assert_eq!(selection.waste, 500);
+
+        assert_eq!(LowestWaste::default().select_coins(&utxos, &params(200_000, Some(300)), &mut rng), Err(SelectionError::InsufficientFunds(168_000, 200_000)));
+    }
+
+    #[test]
+    pub fn test_skip_uneconomical() {
+        let mut utxos = utxos(&[5_000, 10_000]);
+        utxos[0].txout.value = 50;
+
+        let algorithms: [&dyn CoinSelection; 4] = [&BranchAndBound, &Knapsack, &SingleRandomDraw, &LargestFirst];
+
+        for algorithm in algorithms {
+            let mut rng = StdRng::seed_from_u64(2);
+            assert_eq!(algorithm.select_coins(&utxos, &params(12_000, None), &mut rng).unwrap_err(), SelectionError::InsufficientFunds(10_000, 12_000));
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use rand::{seq::SliceRandom, RngCore};
+use crate::transaction::builder::Utxo;
+use super::{error::SelectionError, CoinSelection, Selection, SelectionParams};
+
+/// Change single random draw aims for on top of the cost of change, Core's
+///  `CHANGE_LOWER`.
+pub const CHANGE_LOWER: u64 = 50_000;
+
+/// Core's single random draw: coins in random order until they cover the
+///  target, and with a change output leave at least `CHANGE_LOWER` for it
+///  when there is enough.
+pub struct SingleRandomDraw;
+
+impl CoinSelection for SingleRandomDraw {
+    fn select_coins(&self, candidates: &[Utxo], params: &SelectionParams, rng: &mut dyn RngCore) -> Result<Selection, SelectionError> {
+        let mut pool = params.spendable(candidates);
+
+        let available: u64 = pool.iter().map(|(_, value)| value).sum();
+
+        if available < params.target {
+            return Err(params.insufficient_funds(&pool, params.target));
+        }
+
+        let target = match params.cost_of_change {
+            Some(cost_of_change) => params.target + cost_of_change + CHANGE_LOWER,
+            None => params.target,
+        };
+
+        pool.shuffle(rng);
+
+        let mut selected = Vec::new();
+        let mut value = 0;
+
+        // Running out short of the change target still covers the target.
+        for (utxo, utxo_value) in pool {
+            selected.push(utxo.clone());
+            value += utxo_value;
+
+            if value >= target {
+                break;
+            }
+        }
+
+        Ok(params.selection(selected))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{rngs::StdRng, SeedableRng};
+    use crate::transaction::coin_selection::{error::SelectionError, test::{effective_values, params, utxos, FEE_RATE}, CoinSelection};
+    use super::{SingleRandomDraw, CHANGE_LOWER};
+
+    #[test]
+    pub fn test_single_random_draw() {
+        let utxos = utxos(&(1..=20).map(|n| n * 10_000).collect::<Vec<_>>());
+        let params = params(100_000, Some(1_000));
+
+        let select = |seed| SingleRandomDraw.select_coins(&utxos, &params, &mut StdRng::seed_from_u64(seed)).unwrap().selected;
+
+        // The same seed draws the same coins, and different ones usually
+        //  don't.
+        assert_eq!(select(3), select(3));
+        assert!((0..10).any(|seed| select(seed) != select(3)));
+
+        for seed in 0..10 {
+            // Coins come in the order drawn, up to the first reaching the
+            //  change target.
+            let values: Vec<u64> = select(seed).iter().map(|utxo| utxo.effective_value(FEE_RATE) as u64).collect();
+            let (last, drawn_before) = values.split_last().unwrap();
+
+            assert!(drawn_before.iter().sum::<u64>() < 100_000 + 1_000 + CHANGE_LOWER);
+            assert!(drawn_before.iter().sum::<u64>() + last >= 100_000 + 1_000 + CHANGE_LOWER);
+        }
+    }
+
+    #[test]
+    pub fn test_short_of_change() {
+        let utxos = utxos(&[40_000, 30_000, 35_000]);
+
+        // Everything covers the target, if not change as well.
+        let selection = SingleRandomDraw.select_coins(&utxos, &params(100_000, Some(1_000)), &mut StdRng::seed_from_u64(0)).unwrap();
+        assert_eq!(effective_values(&selection.selected), [30_000, 35_000, 40_000]);
+
+        assert_eq!(SingleRandomDraw.select_coins(&utxos, &params(110_000, Some(1_000)), &mut StdRng::seed_from_u64(0)), Err(SelectionError::InsufficientFunds(105_000, 110_000)));
+    }
+}
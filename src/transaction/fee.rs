@@ -0,0 +1,116 @@
+use std::fmt::Display;
+use crate::{script::{opcode::Opcode, standard::witness_program, MAX_SCRIPT_SIZE}, serialization::consensus::compact_size::compact_size_len};
+use super::{tx_out::TxOut, WITNESS_SCALE_FACTOR};
+
+/// Fee rate Core uses to decide which outputs are dust.
+pub const DUST_RELAY_FEE: FeeRate = FeeRate(3_000);
+
+/// A fee rate in satoshis per 1000 virtual bytes, the unit Core works in,
+///  so that fractional sat/vB rates are exact.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FeeRate(u64);
+
+impl FeeRate {
+    pub const ZERO: FeeRate = FeeRate(0);
+
+    pub const fn from_sat_per_kvb(sat_per_kvb: u64) -> Self {
+        Self(sat_per_kvb)
+    }
+
+    pub const fn from_sat_per_vb(sat_per_vb: u64) -> Self {
+        Self(sat_per_vb * 1000)
+    }
+
+    pub fn to_sat_per_kvb(self) -> u64 {
+        self.0
+    }
+
+    /// Fee for `vsize` virtual bytes, rounded up like Core's
+    ///  `CFeeRate::GetFee`.
+    pub fn fee(self, vsize: usize) -> u64 {
+        (self.0 * vsize as u64).div_ceil(1000)
+    }
+
+    pub fn fee_for_weight(self, weight: usize) -> u64 {
+        self.fee(weight.div_ceil(WITNESS_SCALE_FACTOR))
+    }
+}
+
+/// The rate in sat/vB, with up to three decimals.
+impl Display for FeeRate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 % 1000 {
+            0 => write!(f, "{} sat/vB", self.0 / 1000),
+            fraction => write!(f, "{}.{} sat/vB", self.0 / 1000, format!("{:03}", fraction).trim_end_matches('0')),
+        }
+    }
+}
+
+/// Core's `GetDustThreshold`: the smallest value worth more than the fee
+///  for spending it at `dust_relay_fee`, assuming a typical input for its
+///  type. Unspendable outputs are never dust.
+pub fn dust_threshold(output: &TxOut, dust_relay_fee: FeeRate) -> u64 {
+    let script_pubkey = &output.script_pubkey;
+
+    if script_pubkey.first() == Some(&Opcode::OP_RETURN.to_u8()) || script_pubkey.len() > MAX_SCRIPT_SIZE {
+        return 0;
+    }
+
+    // The output itself, then an outpoint, scriptSig length and sequence
+    //  with a 107 byte P2PKH scriptSig, discounted when it is a witness.
+    let output_size = 8 + compact_size_len(script_pubkey.len() as u64) + script_pubkey.len();
+
+    let input_size = match witness_program(script_pubkey) {
+        Some(_) => 32 + 4 + 1 + 107 / WITNESS_SCALE_FACTOR + 4,
+        None => 32 + 4 + 1 + 107 + 4,
+    };
+
+    dust_relay_fee.fee(output_size + input_size)
+}
+
+pub fn is_dust(output: &TxOut, dust_relay_fee: FeeRate) -> bool {
+    output.value < dust_threshold(output, dust_relay_fee)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{cryptography::hash::digest::{PubkeyHash, ScriptHash}, script::Script, transaction::tx_out::TxOut};
+    use super::{dust_threshold, is_dust, FeeRate, DUST_RELAY_FEE};
+
+    #[test]
+    pub fn test_fee() {
+        assert_eq!(FeeRate::from_sat_per_vb(2).fee(141), 282);
+        assert_eq!(FeeRate::from_sat_per_kvb(1500).fee(141), 212);
+        assert_eq!(FeeRate::from_sat_per_kvb(1).fee(1), 1);
+        assert_eq!(FeeRate::ZERO.fee(1000), 0);
+        assert_eq!(FeeRate::from_sat_per_vb(1).fee_for_weight(561), 141);
+    }
+
+    #[test]
+    pub fn test_display() {
+        assert_eq!(FeeRate::from_sat_per_vb(12).to_string(), "12 sat/vB");
+        assert_eq!(FeeRate::from_sat_per_kvb(1500).to_string(), "1.5 sat/vB");
+        assert_eq!(FeeRate::from_sat_per_kvb(1).to_string(), "0.001 sat/vB");
+    }
+
+    #[test]
+    pub fn test_dust_threshold() {
+        let hash = PubkeyHash::from_byte_array([0x11; 20]);
+
+        // The thresholds Core's policy is known for.
+        for (script_pubkey, threshold) in [
+            (Script::p2pkh(&hash), 546),
+            (Script::p2sh(&ScriptHash::from_byte_array([0x11; 20])), 540),
+            (Script::p2wpkh(&hash), 294),
+            (Script::p2wsh(&[0x11; 32]), 330),
+            (Script::p2tr(&[0x11; 32]), 330),
+            (Script::null_data(b"hello"), 0),
+        ] {
+            let output = TxOut::new(threshold, script_pubkey.into_bytes());
+
+            assert_eq!(dust_threshold(&output, DUST_RELAY_FEE), threshold);
+            assert!(!is_dust(&output, DUST_RELAY_FEE));
+            assert_eq!(is_dust(&TxOut::new(threshold.saturating_sub(1), output.script_pubkey), DUST_RELAY_FEE), threshold > 0);
+        }
+    }
+}
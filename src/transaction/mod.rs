@@ -0,0 +1,152 @@
+use crate::{cryptography::hash::digest::{Txid, Wtxid}, serialization::consensus::serialize::SerializeConsensus};
+use self::{tx_in::{TxIn, SEQUENCE_MAX_BIP125_RBF}, tx_out::TxOut};
+
+pub mod builder;
+pub mod coin_selection;
+pub mod fee;
+pub mod out_point;
+pub mod psbt;
+pub mod sighash;
+pub mod signer;
+pub mod sigops;
+pub mod tx_in;
+pub mod tx_out;
+pub mod witness;
+
+pub const WITNESS_SCALE_FACTOR: usize = 4;
+/// Locktimes below this are block heights, the rest are UNIX timestamps.
+pub const LOCKTIME_THRESHOLD: u32 = 500_000_000;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Transaction {
+    pub version: i32,
+    pub inputs: Vec<TxIn>,
+    pub outputs: Vec<TxOut>,
+    pub lock_time: u32,
+}
+
+impl Transaction {
+    pub fn new(version: i32, inputs: Vec<TxIn>, outputs: Vec<TxOut>, lock_time: u32) -> Self {
+        Self {
+            version,
+            inputs,
+            outputs,
+            lock_time,
+        }
+    }
+
+    /// Hash of the serialization without witness data, which segwit
+    ///  signatures cannot malleate.
+    pub fn txid(&self) -> Txid {
+        Txid::hash(&self.serialize_without_witness())
+    }
+
+    /// Hash of the full BIP144 serialization. Equal to the txid for
+    ///  transactions without witness data.
+    pub fn wtxid(&self) -> Wtxid {
+        Wtxid::hash(&self.serialize_consensus())
+    }
+
+    pub fn has_witness(&self) -> bool {
+        self.inputs.iter().any(|input| !input.witness.is_empty())
+    }
+
+    pub fn is_coinbase(&self) -> bool {
+        self.inputs.len() == 1 && self.inputs[0].previous_output.is_null()
+    }
+
+    /// BIP125 opt-in replace-by-fee, signalled by any input.
+    pub fn signals_rbf(&self) -> bool {
+        self.inputs.iter().any(|input| input.sequence <= SEQUENCE_MAX_BIP125_RBF)
+    }
+
+    /// Pre-segwit serialization, as hashed for the txid.
+    pub fn serialize_without_witness(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+
+        self.version.serialize_consensus_to(&mut buffer);
+        self.inputs.serialize_consensus_to(&mut buffer);
+        self.outputs.serialize_consensus_to(&mut buffer);
+        self.lock_time.serialize_consensus_to(&mut buffer);
+
+        buffer
+    }
+
+    /// Size in bytes without witness data.
+    pub fn base_size(&self) -> usize {
+        self.serialize_without_witness().len()
+    }
+
+    /// Size in bytes including witness data.
+    pub fn total_size(&self) -> usize {
+        self.serialize_consensus().len()
+    }
+
+    /// BIP141 weight: base bytes count four times, witness bytes once.
+    pub fn weight(&self) -> usize {
+        self.base_size() * (WITNESS_SCALE_FACTOR - 1) + self.total_size()
+    }
+
+    /// Virtual size in vbytes, rounded up.
+    pub fn vsize(&self) -> usize {
+        self.weight().div_ceil(WITNESS_SCALE_FACTOR)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use crate::{cryptography::{bitcoin_private_key::BitcoinPrivateKey, hash::digest::PubkeyHash}, network::Network, serialization::consensus::deserialize::DeserializeConsensus, transaction::{out_point::OutPoint, tx_in::TxIn, tx_out::TxOut, Transaction}};
+
+    /// Keys for the signing tests, the first of them uncompressed.
+    pub fn keys() -> Vec<BitcoinPrivateKey> {
+        vec![
+            BitcoinPrivateKey::new_with_compression(1001u32, false).unwrap(),
+            BitcoinPrivateKey::new(1002u32).unwrap(),
+            BitcoinPrivateKey::new(1003u32).unwrap(),
+        ]
+    }
+
+    pub fn public_key_hash(key: &BitcoinPrivateKey) -> PubkeyHash {
+        PubkeyHash::hash(&key.serialize_public_key().unwrap())
+    }
+
+    /// An unsigned transaction spending all of `prevouts`, which a single
+    ///  funding transaction creates, to `outputs`.
+    pub fn spending(prevouts: &[TxOut], outputs: Vec<TxOut>) -> Transaction {
+        let funding = Transaction::new(2, vec![TxIn::new(OutPoint::null())], prevouts.to_vec(), 0).txid();
+        let inputs = (0..prevouts.len() as u32).map(|vout| TxIn::new(OutPoint::new(funding, vout))).collect();
+
+        Transaction::new(2, inputs, outputs, 0)
+    }
+
+    #[test]
+    pub fn test_genesis_coinbase() {
+        let block = Network::Mainnet.genesis_block();
+        let coinbase = Transaction::deserialize_consensus(&block[81..]).unwrap();
+
+        assert!(coinbase.is_coinbase());
+        assert!(!coinbase.has_witness());
+        assert_eq!(coinbase.outputs[0].value, 50 * 100_000_000);
+
+        // A lone coinbase is its block's merkle root.
+        assert_eq!(coinbase.txid().as_bytes(), &block[36..68]);
+        assert_eq!(coinbase.weight(), coinbase.total_size() * 4);
+    }
+
+    #[test]
+    pub fn test_witness_changes_wtxid_only() {
+        let mut tx = Transaction::new(2, vec![TxIn::new(OutPoint::new(Default::default(), 0))], vec![TxOut::new(1000, vec![0x51])], 0);
+
+        let txid = tx.txid();
+        assert_eq!(tx.wtxid().as_bytes(), txid.as_bytes());
+        assert!(!tx.is_coinbase());
+
+        tx.inputs[0].witness.push(vec![0x01; 72]);
+
+        assert_eq!(tx.txid(), txid);
+        assert_ne!(tx.wtxid().as_bytes(), txid.as_bytes());
+        // Marker, flag, item count and the 1 + 72 byte item.
+        assert_eq!(tx.total_size(), tx.base_size() + 2 + 1 + 73);
+        assert_eq!(tx.vsize(), (tx.base_size() * 4 + 76).div_ceil(4));
+    }
+}
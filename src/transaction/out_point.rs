@@ -0,0 +1,33 @@
+use std::fmt::Display;
+use crate::cryptography::hash::digest::Txid;
+
+/// Reference to output `vout` of transaction `txid`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct OutPoint {
+    pub txid: Txid,
+    pub vout: u32,
+}
+
+impl OutPoint {
+    pub fn new(txid: Txid, vout: u32) -> Self {
+        Self {
+            txid,
+            vout,
+        }
+    }
+
+    /// The outpoint spent by coinbase inputs.
+    pub fn null() -> Self {
+        Self::new(Txid::default(), u32::MAX)
+    }
+
+    pub fn is_null(&self) -> bool {
+        *self == Self::null()
+    }
+}
+
+impl Display for OutPoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.txid, self.vout)
+    }
+}
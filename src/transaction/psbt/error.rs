@@ -0,0 +1,67 @@
+use std::{error::Error, fmt::Display};
+use crate::{script::interpreter::error::InterpreterError, transaction::{sighash::error::SighashError, signer::error::SignerError}};
+
+#[derive(Debug)]
+pub enum PsbtError {
+    UnsignedTxHasScriptSigs,
+    UnsignedTxMismatch,
+    VersionMismatch(u32, u32),
+    NotModifiable,
+    InvalidRequiredLockTime(usize),
+    LockTimeConflict(usize),
+    SignedLockTimeChange(usize),
+    MissingUtxo(usize),
+    MissingNonWitnessUtxo(usize),
+    NonWitnessUtxoMismatch(usize),
+    MissingRedeemScript(usize),
+    RedeemScriptMismatch(usize),
+    MissingWitnessScript(usize),
+    WitnessScriptMismatch(usize),
+    UnsupportedScript(usize),
+    MissingSignature(usize),
+    NotFinalized(usize),
+    Verification(usize, InterpreterError),
+    Sighash(SighashError),
+    Signer(SignerError),
+}
+
+impl Error for PsbtError { }
+
+impl Display for PsbtError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PsbtError::UnsignedTxHasScriptSigs => write!(f, "PsbtError::UnsignedTxHasScriptSigs(unsigned transaction has scriptSigs or witnesses)"),
+            PsbtError::UnsignedTxMismatch => write!(f, "PsbtError::UnsignedTxMismatch(PSBTs are for different transactions)"),
+            PsbtError::VersionMismatch(version, other) => write!(f, "PsbtError::VersionMismatch(cannot combine version {} and {} PSBTs)", version, other),
+            PsbtError::NotModifiable => write!(f, "PsbtError::NotModifiable(PSBT is not version 2 or its signatures don't allow the change)"),
+            PsbtError::InvalidRequiredLockTime(index) => write!(f, "PsbtError::InvalidRequiredLockTime(required lock time of input {} is of the wrong type)", index),
+            PsbtError::LockTimeConflict(index) => write!(f, "PsbtError::LockTimeConflict(input {} leaves no lock time every input accepts)", index),
+            PsbtError::SignedLockTimeChange(index) => write!(f, "PsbtError::SignedLockTimeChange(input {} changes the lock time of signed inputs)", index),
+            PsbtError::MissingUtxo(index) => write!(f, "PsbtError::MissingUtxo(input {} has no UTXO)", index),
+            PsbtError::MissingNonWitnessUtxo(index) => write!(f, "PsbtError::MissingNonWitnessUtxo(legacy input {} needs the transaction it spends from)", index),
+            PsbtError::NonWitnessUtxoMismatch(index) => write!(f, "PsbtError::NonWitnessUtxoMismatch(non-witness UTXO of input {} is not the transaction it spends from)", index),
+            PsbtError::MissingRedeemScript(index) => write!(f, "PsbtError::MissingRedeemScript(input {})", index),
+            PsbtError::RedeemScriptMismatch(index) => write!(f, "PsbtError::RedeemScriptMismatch(redeem script of input {} does not hash to the spent output)", index),
+            PsbtError::MissingWitnessScript(index) => write!(f, "PsbtError::MissingWitnessScript(input {})", index),
+            PsbtError::WitnessScriptMismatch(index) => write!(f, "PsbtError::WitnessScriptMismatch(witness script of input {} does not hash to the spent output)", index),
+            PsbtError::UnsupportedScript(index) => write!(f, "PsbtError::UnsupportedScript(input {} spends an output of unsupported type)", index),
+            PsbtError::MissingSignature(index) => write!(f, "PsbtError::MissingSignature(input {})", index),
+            PsbtError::NotFinalized(index) => write!(f, "PsbtError::NotFinalized(input {})", index),
+            PsbtError::Verification(index, e) => write!(f, "PsbtError::Verification(input {}: {})", index, e),
+            PsbtError::Sighash(e) => write!(f, "PsbtError::Sighash({})", e),
+            PsbtError::Signer(e) => write!(f, "PsbtError::Signer({})", e),
+        }
+    }
+}
+
+impl From<SighashError> for PsbtError {
+    fn from(value: SighashError) -> Self {
+        PsbtError::Sighash(value)
+    }
+}
+
+impl From<SignerError> for PsbtError {
+    fn from(value: SignerError) -> Self {
+        PsbtError::Signer(value)
+    }
+}
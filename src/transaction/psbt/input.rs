@@ -0,0 +1,105 @@
+use std::collections::BTreeMap;
+use crate::{cryptography::taproot::ControlBlock, script::Script, transaction::{sighash::SighashType, tx_out::TxOut, witness::Witness, Transaction}};
+use super::{KeySource, ProprietaryKey};
+
+/// What a PSBT knows about one input of its transaction.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Input {
+    /// The whole transaction the input spends from, which legacy inputs
+    ///  need for the signer to check the value spent.
+    pub non_witness_utxo: Option<Transaction>,
+    /// Just the output spent, enough for segwit inputs as their signatures
+    ///  commit to the value.
+    pub witness_utxo: Option<TxOut>,
+    /// Signatures with their sighash byte, by the public key that made them.
+    pub partial_sigs: BTreeMap<Vec<u8>, Vec<u8>>,
+    pub sighash_type: Option<SighashType>,
+    pub redeem_script: Option<Script>,
+    pub witness_script: Option<Script>,
+    pub bip32_derivation: BTreeMap<Vec<u8>, KeySource>,
+    pub final_script_sig: Option<Vec<u8>>,
+    pub final_script_witness: Option<Witness>,
+    /// BIP127 proof of reserves commitment, a UTF-8 message.
+    pub por_commitment: Option<String>,
+    /// Preimages by their hash, for scripts with hash locks.
+    pub ripemd160_preimages: BTreeMap<[u8; 20], Vec<u8>>,
+    pub sha256_preimages: BTreeMap<[u8; 32], Vec<u8>>,
+    pub hash160_preimages: BTreeMap<[u8; 20], Vec<u8>>,
+    pub hash256_preimages: BTreeMap<[u8; 32], Vec<u8>>,
+    /// Version 2 only: the lowest lock time in seconds the input can be
+    ///  spent with, for example because of an OP_CHECKLOCKTIMEVERIFY.
+    pub required_time_lock_time: Option<u32>,
+    /// Version 2 only: the same in blocks.
+    pub required_height_lock_time: Option<u32>,
+    /// BIP341 key-path signature, 64 bytes or 65 with a sighash byte.
+    pub tap_key_sig: Option<Vec<u8>>,
+    /// Script-path signatures by x-only public key and the hash of the leaf
+    ///  they were made for.
+    pub tap_script_sigs: BTreeMap<([u8; 32], [u8; 32]), Vec<u8>>,
+    /// Leaf scripts with their leaf version, by the control block proving
+    ///  them part of the output key.
+    pub tap_leaf_scripts: BTreeMap<ControlBlock, (Script, u8)>,
+    /// Key sources of x-only keys, with the hashes of the leaves each key
+    ///  appears in.
+    pub tap_bip32_derivation: BTreeMap<[u8; 32], (Vec<[u8; 32]>, KeySource)>,
+    pub tap_internal_key: Option<[u8; 32]>,
+    pub tap_merkle_root: Option<[u8; 32]>,
+    pub proprietary: BTreeMap<ProprietaryKey, Vec<u8>>,
+    /// Pairs of unknown type, by their whole key, which are kept so they
+    ///  can be passed on.
+    pub unknown: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+impl Input {
+    /// Whether the finalizer has been through, after which the input only
+    ///  needs extracting.
+    pub fn is_finalized(&self) -> bool {
+        self.final_script_sig.is_some() || self.final_script_witness.is_some()
+    }
+
+    /// Whether any signature commits to the transaction as it is.
+    pub fn is_signed(&self) -> bool {
+        self.is_finalized() || !self.partial_sigs.is_empty() || self.tap_key_sig.is_some() || !self.tap_script_sigs.is_empty()
+    }
+
+    /// Adds what `other` knows and `self` doesn't. Where both have a value
+    ///  for the same key `self`'s is kept.
+    pub fn combine(&mut self, other: Input) {
+        combine_option(&mut self.non_witness_utxo, other.non_witness_utxo);
+        combine_option(&mut self.witness_utxo, other.witness_utxo);
+        combine_map(&mut self.partial_sigs, other.partial_sigs);
+        combine_option(&mut self.sighash_type, other.sighash_type);
+        combine_option(&mut self.redeem_script, other.redeem_script);
+        combine_option(&mut self.witness_script, other.witness_script);
+        combine_map(&mut self.bip32_derivation, other.bip32_derivation);
+        combine_option(&mut self.final_script_sig, other.final_script_sig);
+        combine_option(&mut self.final_script_witness, other.final_script_witness);
+        combine_option(&mut self.por_commitment, other.por_commitment);
+        combine_map(&mut self.ripemd160_preimages, other.ripemd160_preimages);
+        combine_map(&mut self.sha256_preimages, other.sha256_preimages);
+        combine_map(&mut self.hash160_preimages, other.hash160_preimages);
+        combine_map(&mut self.hash256_preimages, other.hash256_preimages);
+        combine_option(&mut self.required_time_lock_time, other.required_time_lock_time);
+        combine_option(&mut self.required_height_lock_time, other.required_height_lock_time);
+        combine_option(&mut self.tap_key_sig, other.tap_key_sig);
+        combine_map(&mut self.tap_script_sigs, other.tap_script_sigs);
+        combine_map(&mut self.tap_leaf_scripts, other.tap_leaf_scripts);
+        combine_map(&mut self.tap_bip32_derivation, other.tap_bip32_derivation);
+        combine_option(&mut self.tap_internal_key, other.tap_internal_key);
+        combine_option(&mut self.tap_merkle_root, other.tap_merkle_root);
+        combine_map(&mut self.proprietary, other.proprietary);
+        combine_map(&mut self.unknown, other.unknown);
+    }
+}
+
+pub(super) fn combine_option<T>(value: &mut Option<T>, other: Option<T>) {
+    if value.is_none() {
+        *value = other;
+    }
+}
+
+pub(super) fn combine_map<K: Ord, V>(map: &mut BTreeMap<K, V>, other: BTreeMap<K, V>) {
+    for (key, value) in other {
+        map.entry(key).or_insert(value);
+    }
+}
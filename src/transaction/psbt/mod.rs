@@ -0,0 +1,807 @@
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+use crate::{cryptography::{bitcoin_private_key::BitcoinPrivateKey, hash::{digest::{PubkeyHash, ScriptHash}, sha256}}, script::{interpreter::{flags::VerifyFlags, verify_input}, standard::StandardScript, Script}, serialization::{base64, psbt::{deserialize::{DeserializePSBT, DeserializePSBTError}, serialize::SerializePSBT}}};
+use self::{error::PsbtError, input::Input, output::Output, tx_modifiable::TxModifiable};
+use super::{sighash::{error::SighashError, taproot::Prevouts, SighashCache, SighashType}, signer::{ecdsa_signature, error::SignerError, multisig::{Multisig, MultisigWrapper, PartialSignature}, sign_taproot_key_spend}, tx_in::TxIn, tx_out::TxOut, witness::Witness, Transaction, LOCKTIME_THRESHOLD};
+
+pub mod error;
+pub mod input;
+pub mod output;
+pub mod tx_modifiable;
+
+/// The master key fingerprint and BIP32 path a key was derived with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeySource {
+    pub fingerprint: [u8; 4],
+    pub path: Vec<u32>,
+}
+
+/// Key of a `0xfc` pair, whose meaning is up to whoever owns `prefix`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ProprietaryKey {
+    pub prefix: Vec<u8>,
+    pub subtype: u64,
+    pub key: Vec<u8>,
+}
+
+/// A BIP174 PSBT: a transaction along with what each party needs to sign
+///  it, passed between them until it can be finalized and extracted.
+///  Version 2 PSBTs (BIP370) describe the transaction field by field
+///  instead, so that constructors can add inputs and outputs to it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PartiallySignedTransaction {
+    /// The transaction with empty scriptSigs and witnesses. In version 2
+    ///  its lock time is the one the inputs determine.
+    pub unsigned_tx: Transaction,
+    /// Serialized BIP32 extended public keys, by which the signers may
+    ///  recognize their keys.
+    pub xpubs: BTreeMap<Vec<u8>, KeySource>,
+    /// 0 or 2.
+    pub version: u32,
+    /// Version 2 only: the lock time when no input requires one.
+    pub fallback_lock_time: Option<u32>,
+    /// Version 2 only.
+    pub tx_modifiable: TxModifiable,
+    pub proprietary: BTreeMap<ProprietaryKey, Vec<u8>>,
+    pub unknown: BTreeMap<Vec<u8>, Vec<u8>>,
+    pub inputs: Vec<Input>,
+    pub outputs: Vec<Output>,
+}
+
+/// How an input's spent output is unlocked, as the signer and finalizer
+///  see it.
+enum Spend {
+    P2PKH(PubkeyHash),
+    /// Native, or nested in P2SH when there is a redeem script.
+    P2WPKH {
+        hash: PubkeyHash,
+        redeem_script: Option<Script>,
+    },
+    Multisig(Multisig),
+    /// Key path only.
+    P2TR,
+}
+
+/// A signature the signer made, by where it goes in the input.
+enum InputSignature {
+    Ecdsa(PartialSignature),
+    /// With its sighash byte unless SIGHASH_DEFAULT.
+    TaprootKeyPath(Vec<u8>),
+}
+
+impl PartiallySignedTransaction {
+    /// Creator: a PSBT for `unsigned_tx`, knowing nothing about its inputs
+    ///  and outputs yet.
+    pub fn new(unsigned_tx: Transaction) -> Result<Self, PsbtError> {
+        if unsigned_tx.inputs.iter().any(|input| !input.script_sig.is_empty() || !input.witness.is_empty()) {
+            return Err(PsbtError::UnsignedTxHasScriptSigs);
+        }
+
+        Ok(Self {
+            inputs: vec![Input::default(); unsigned_tx.inputs.len()],
+            outputs: vec![Output::default(); unsigned_tx.outputs.len()],
+            unsigned_tx,
+            xpubs: BTreeMap::new(),
+            version: 0,
+            fallback_lock_time: None,
+            tx_modifiable: TxModifiable::NONE,
+            proprietary: BTreeMap::new(),
+            unknown: BTreeMap::new(),
+        })
+    }
+
+    /// Creator: an empty version 2 PSBT for constructors to add inputs and
+    ///  outputs to. Its lock time is `fallback_lock_time`, or 0, until an
+    ///  input requires another.
+    pub fn new_v2(tx_version: i32, fallback_lock_time: Option<u32>) -> Self {
+        Self {
+            unsigned_tx: Transaction::new(tx_version, Vec::new(), Vec::new(), fallback_lock_time.unwrap_or(0)),
+            xpubs: BTreeMap::new(),
+            version: 2,
+            fallback_lock_time,
+            tx_modifiable: TxModifiable::INPUTS | TxModifiable::OUTPUTS,
+            proprietary: BTreeMap::new(),
+            unknown: BTreeMap::new(),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+        }
+    }
+
+    /// Constructor: appends `tx_in`, described by `input`, to a version 2
+    ///  PSBT whose signatures allow it. The input's lock time requirements
+    ///  have to leave a lock time every input accepts, and can't change it
+    ///  once any input is signed.
+    pub fn add_input(&mut self, tx_in: TxIn, input: Input) -> Result<(), PsbtError> {
+        if self.version != 2 || !self.tx_modifiable.contains(TxModifiable::INPUTS) {
+            return Err(PsbtError::NotModifiable);
+        }
+
+        if !tx_in.script_sig.is_empty() || !tx_in.witness.is_empty() {
+            return Err(PsbtError::UnsignedTxHasScriptSigs);
+        }
+
+        let input_index = self.inputs.len();
+
+        if input.required_time_lock_time.is_some_and(|time| time < LOCKTIME_THRESHOLD) || input.required_height_lock_time.is_some_and(|height| height == 0 || height >= LOCKTIME_THRESHOLD) {
+            return Err(PsbtError::InvalidRequiredLockTime(input_index));
+        }
+
+        if input.non_witness_utxo.as_ref().is_some_and(|tx| !spends_from(&tx_in, tx)) {
+            return Err(PsbtError::NonWitnessUtxoMismatch(input_index));
+        }
+
+        let lock_time = determine_lock_time(self.fallback_lock_time, self.inputs.iter().chain([&input])).ok_or(PsbtError::LockTimeConflict(input_index))?;
+
+        if lock_time != self.unsigned_tx.lock_time && self.inputs.iter().any(Input::is_signed) {
+            return Err(PsbtError::SignedLockTimeChange(input_index));
+        }
+
+        self.unsigned_tx.inputs.push(tx_in);
+        self.unsigned_tx.lock_time = lock_time;
+        self.inputs.push(input);
+
+        Ok(())
+    }
+
+    /// Constructor: appends `tx_out`, described by `output`, to a version 2
+    ///  PSBT whose signatures allow it.
+    pub fn add_output(&mut self, tx_out: TxOut, output: Output) -> Result<(), PsbtError> {
+        if self.version != 2 || !self.tx_modifiable.contains(TxModifiable::OUTPUTS) {
+            return Err(PsbtError::NotModifiable);
+        }
+
+        self.unsigned_tx.outputs.push(tx_out);
+        self.outputs.push(output);
+
+        Ok(())
+    }
+
+    /// Updater: adds `tx`, the transaction input `input_index` spends from.
+    pub fn add_non_witness_utxo(&mut self, input_index: usize, tx: Transaction) -> Result<(), PsbtError> {
+        self.check_input_index(input_index)?;
+
+        if !spends_from(&self.unsigned_tx.inputs[input_index], &tx) {
+            return Err(PsbtError::NonWitnessUtxoMismatch(input_index));
+        }
+
+        self.inputs[input_index].non_witness_utxo = Some(tx);
+
+        Ok(())
+    }
+
+    /// Updater: adds the redeem and witness scripts of the multisig input
+    ///  `input_index` spends.
+    pub fn add_multisig(&mut self, input_index: usize, multisig: &Multisig) -> Result<(), PsbtError> {
+        self.check_input_index(input_index)?;
+
+        let input = &mut self.inputs[input_index];
+        input.redeem_script = multisig.redeem_script();
+        input.witness_script = multisig.witness_script().cloned();
+
+        Ok(())
+    }
+
+    /// The output input `input_index` spends, from the non-witness UTXO if
+    ///  there is one.
+    pub fn spent_output(&self, input_index: usize) -> Result<&TxOut, PsbtError> {
+        self.check_input_index(input_index)?;
+
+        let input = &self.inputs[input_index];
+        let previous_output = &self.unsigned_tx.inputs[input_index].previous_output;
+
+        match &input.non_witness_utxo {
+            Some(tx) if tx.txid() == previous_output.txid => tx.outputs.get(previous_output.vout as usize).ok_or(PsbtError::NonWitnessUtxoMismatch(input_index)),
+            Some(_) => Err(PsbtError::NonWitnessUtxoMismatch(input_index)),
+            None => input.witness_utxo.as_ref().ok_or(PsbtError::MissingUtxo(input_index)),
+        }
+    }
+
+    /// Signer: adds a signature by `key` to every input paying to it, and
+    ///  returns which inputs those were. Inputs that are finalized, lack the
+    ///  UTXO or scripts to tell what they spend, or spend outputs of unknown
+    ///  type are left for other signers. Taproot inputs are signed on the
+    ///  key path, with `key` as the internal key and the input's merkle
+    ///  root, and only once every UTXO is known unless ANYONECANPAY.
+    ///  Each input is signed with its sighash type, ALL when it has none or
+    ///  DEFAULT for taproot. Version 2 PSBTs get their modifiable flags
+    ///  updated for the signatures made.
+    pub fn sign(&mut self, key: &BitcoinPrivateKey) -> Result<Vec<usize>, PsbtError> {
+        let public_key = key.serialize_public_key().map_err(SignerError::from)?;
+        let cache = SighashCache::new(&self.unsigned_tx);
+        let prevouts: Option<Vec<TxOut>> = (0..self.inputs.len()).map(|input_index| self.spent_output(input_index).ok().cloned()).collect();
+
+        let mut signatures = Vec::new();
+
+        for (input_index, input) in self.inputs.iter().enumerate() {
+            if input.is_finalized() {
+                continue;
+            }
+
+            let spend = match self.spend(input_index) {
+                Ok(spend) => spend,
+                Err(PsbtError::MissingUtxo(_) | PsbtError::MissingRedeemScript(_) | PsbtError::MissingWitnessScript(_) | PsbtError::UnsupportedScript(_)) => continue,
+                Err(e) => return Err(e),
+            };
+
+            let sighash_type = input.sighash_type.unwrap_or(match spend {
+                Spend::P2TR => SighashType::DEFAULT,
+                _ => SighashType::ALL,
+            });
+            let value = self.spent_output(input_index)?.value;
+
+            let signature = match spend {
+                Spend::P2PKH(hash) if PubkeyHash::hash(&public_key) == hash => {
+                    self.expect_non_witness_utxo(input_index)?;
+                    let sighash = cache.legacy_sighash(input_index, Script::p2pkh(&hash).as_bytes(), sighash_type)?;
+
+                    InputSignature::Ecdsa(PartialSignature {
+                        public_key: public_key.clone(),
+                        signature: ecdsa_signature(key, &sighash, sighash_type)?,
+                    })
+                },
+                // Segwit v0 only spends with compressed keys.
+                Spend::P2WPKH { hash, .. } if public_key.len() == 33 && PubkeyHash::hash(&public_key) == hash => {
+                    let sighash = cache.p2wpkh_sighash(input_index, Script::p2wpkh(&hash).as_bytes(), value, sighash_type)?;
+
+                    InputSignature::Ecdsa(PartialSignature {
+                        public_key: public_key.clone(),
+                        signature: ecdsa_signature(key, &sighash, sighash_type)?,
+                    })
+                },
+                Spend::Multisig(multisig) => {
+                    if multisig.wrapper() == MultisigWrapper::P2SH {
+                        self.expect_non_witness_utxo(input_index)?;
+                    }
+
+                    match multisig.sign(&cache, input_index, value, key, sighash_type) {
+                        Ok(signature) => InputSignature::Ecdsa(signature),
+                        Err(SignerError::KeyNotInScript) => continue,
+                        Err(e) => return Err(e.into()),
+                    }
+                },
+                Spend::P2TR => {
+                    let prevouts = match (sighash_type.is_anyone_can_pay(), &prevouts) {
+                        (true, _) => Prevouts::One(input_index, self.spent_output(input_index)?),
+                        (false, Some(prevouts)) => Prevouts::All(prevouts),
+                        (false, None) => continue,
+                    };
+
+                    match sign_taproot_key_spend(&cache, input_index, &prevouts, key, input.tap_merkle_root.as_ref(), sighash_type, &rand::random()) {
+                        Ok(witness) => InputSignature::TaprootKeyPath(witness.into_items().swap_remove(0)),
+                        Err(SignerError::OutputKeyMismatch) => continue,
+                        Err(e) => return Err(e.into()),
+                    }
+                },
+                _ => continue,
+            };
+
+            signatures.push((input_index, sighash_type, signature));
+        }
+
+        let signed = signatures.iter().map(|(input_index, _, _)| *input_index).collect();
+
+        for (input_index, sighash_type, signature) in signatures {
+            let input = &mut self.inputs[input_index];
+
+            match signature {
+                InputSignature::Ecdsa(signature) => {
+                    input.partial_sigs.insert(signature.public_key, signature.signature);
+                },
+                InputSignature::TaprootKeyPath(signature) => input.tap_key_sig = Some(signature),
+            }
+
+            if self.version == 2 {
+                self.restrict_tx_modifiable(sighash_type);
+            }
+        }
+
+        Ok(signed)
+    }
+
+    /// Combiner: adds what `other`, a PSBT for the same transaction, knows
+    ///  and this one doesn't.
+    pub fn combine(&mut self, other: PartiallySignedTransaction) -> Result<(), PsbtError> {
+        if self.version != other.version {
+            return Err(PsbtError::VersionMismatch(self.version, other.version));
+        }
+
+        if self.unsigned_tx != other.unsigned_tx {
+            return Err(PsbtError::UnsignedTxMismatch);
+        }
+
+        input::combine_option(&mut self.fallback_lock_time, other.fallback_lock_time);
+
+        // Whatever a signature in either fixed stays fixed.
+        let sighash_single = (self.tx_modifiable | other.tx_modifiable) & TxModifiable::SIGHASH_SINGLE;
+        self.tx_modifiable = (self.tx_modifiable & other.tx_modifiable) | sighash_single;
+
+        input::combine_map(&mut self.xpubs, other.xpubs);
+        input::combine_map(&mut self.proprietary, other.proprietary);
+        input::combine_map(&mut self.unknown, other.unknown);
+
+        for (input, other) in self.inputs.iter_mut().zip(other.inputs) {
+            input.combine(other);
+        }
+
+        for (output, other) in self.outputs.iter_mut().zip(other.outputs) {
+            output.combine(other);
+        }
+
+        Ok(())
+    }
+
+    /// Finalizer: builds the scriptSig and witness of every input not yet
+    ///  finalized from its partial signatures, then drops everything but
+    ///  the UTXOs, the lock time requirements and the pairs it doesn't know.
+    ///  Taproot inputs are finalized on the key path only. Multisig signatures are
+    ///  checked with the interpreter on the way. Nothing changes unless
+    ///  every input can be finalized.
+    pub fn finalize(&mut self) -> Result<(), PsbtError> {
+        let finalized = (0..self.inputs.len())
+            .filter(|input_index| !self.inputs[*input_index].is_finalized())
+            .map(|input_index| self.finalize_input(input_index).map(|scripts| (input_index, scripts)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (input_index, (script_sig, witness)) in finalized {
+            let input = &mut self.inputs[input_index];
+
+            *input = Input {
+                non_witness_utxo: input.non_witness_utxo.take(),
+                witness_utxo: input.witness_utxo.take(),
+                final_script_sig: (!script_sig.is_empty()).then_some(script_sig),
+                final_script_witness: (!witness.is_empty()).then_some(witness),
+                required_time_lock_time: input.required_time_lock_time,
+                required_height_lock_time: input.required_height_lock_time,
+                proprietary: std::mem::take(&mut input.proprietary),
+                unknown: std::mem::take(&mut input.unknown),
+                ..Input::default()
+            };
+        }
+
+        Ok(())
+    }
+
+    /// Extractor: the signed transaction, once every input is finalized
+    ///  and passes the interpreter under standard flags.
+    pub fn extract_tx(&self) -> Result<Transaction, PsbtError> {
+        let mut tx = self.unsigned_tx.clone();
+        let mut prevouts = Vec::with_capacity(tx.inputs.len());
+
+        for (input_index, (tx_in, input)) in tx.inputs.iter_mut().zip(&self.inputs).enumerate() {
+            if !input.is_finalized() {
+                return Err(PsbtError::NotFinalized(input_index));
+            }
+
+            tx_in.script_sig = input.final_script_sig.clone().unwrap_or_default();
+            tx_in.witness = input.final_script_witness.clone().unwrap_or_default();
+
+            prevouts.push(self.spent_output(input_index)?.clone());
+        }
+
+        for input_index in 0..tx.inputs.len() {
+            verify_input(&tx, input_index, &prevouts, VerifyFlags::STANDARD).map_err(|e| PsbtError::Verification(input_index, e))?;
+        }
+
+        Ok(tx)
+    }
+
+    /// The scriptSig and witness spending input `input_index`.
+    fn finalize_input(&self, input_index: usize) -> Result<(Vec<u8>, Witness), PsbtError> {
+        let input = &self.inputs[input_index];
+
+        let signature_by = |hash: &PubkeyHash| input.partial_sigs.iter()
+            .find(|(public_key, _)| PubkeyHash::hash(public_key) == *hash)
+            .ok_or(PsbtError::MissingSignature(input_index));
+
+        match self.spend(input_index)? {
+            Spend::P2PKH(hash) => {
+                let (public_key, signature) = signature_by(&hash)?;
+
+                let mut script_sig = Script::new();
+                script_sig.push_slice(signature);
+                script_sig.push_slice(public_key);
+
+                Ok((script_sig.into_bytes(), Witness::new()))
+            },
+            Spend::P2WPKH { hash, redeem_script } => {
+                let (public_key, signature) = signature_by(&hash)?;
+
+                let mut script_sig = Script::new();
+
+                if let Some(redeem_script) = redeem_script {
+                    script_sig.push_slice(redeem_script.as_bytes());
+                }
+
+                Ok((script_sig.into_bytes(), Witness::from_items(vec![signature.clone(), public_key.clone()])))
+            },
+            Spend::Multisig(multisig) => {
+                let signatures: Vec<PartialSignature> = input.partial_sigs.iter()
+                    .map(|(public_key, signature)| PartialSignature {
+                        public_key: public_key.clone(),
+                        signature: signature.clone(),
+                    })
+                    .collect();
+
+                let mut tx = self.unsigned_tx.clone();
+                multisig.finalize(&mut tx, input_index, self.spent_output(input_index)?.value, &signatures)?;
+
+                let tx_in = tx.inputs.swap_remove(input_index);
+
+                Ok((tx_in.script_sig, tx_in.witness))
+            },
+            Spend::P2TR => {
+                let signature = input.tap_key_sig.as_ref().ok_or(PsbtError::MissingSignature(input_index))?;
+
+                Ok((Vec::new(), Witness::from_items(vec![signature.clone()])))
+            },
+        }
+    }
+
+    /// Works out how input `input_index` is spent from its UTXO and the
+    ///  scripts it hashes to.
+    fn spend(&self, input_index: usize) -> Result<Spend, PsbtError> {
+        let input = &self.inputs[input_index];
+        let script_pubkey = Script::from(&self.spent_output(input_index)?.script_pubkey[..]);
+
+        let witness_script = |hash: [u8; 32]| {
+            let witness_script = input.witness_script.as_ref().ok_or(PsbtError::MissingWitnessScript(input_index))?;
+
+            match sha256(witness_script.as_bytes()) == hash {
+                true => Ok(witness_script),
+                false => Err(PsbtError::WitnessScriptMismatch(input_index)),
+            }
+        };
+
+        let multisig = |script: &Script, wrapper: MultisigWrapper| match script.classify() {
+            StandardScript::P2MS { required, public_keys } => Ok(Spend::Multisig(Multisig::from_sec_keys(required, public_keys, wrapper)?)),
+            _ => Err(PsbtError::UnsupportedScript(input_index)),
+        };
+
+        match script_pubkey.classify() {
+            StandardScript::P2PKH(hash) => Ok(Spend::P2PKH(hash)),
+            StandardScript::P2WPKH(hash) => Ok(Spend::P2WPKH { hash, redeem_script: None }),
+            StandardScript::P2WSH(hash) => multisig(witness_script(hash)?, MultisigWrapper::P2WSH),
+            StandardScript::P2TR(_) => Ok(Spend::P2TR),
+            StandardScript::P2SH(hash) => {
+                let redeem_script = input.redeem_script.as_ref().ok_or(PsbtError::MissingRedeemScript(input_index))?;
+
+                if ScriptHash::hash(redeem_script.as_bytes()) != hash {
+                    return Err(PsbtError::RedeemScriptMismatch(input_index));
+                }
+
+                match redeem_script.classify() {
+                    StandardScript::P2WPKH(hash) => Ok(Spend::P2WPKH { hash, redeem_script: Some(redeem_script.clone()) }),
+                    StandardScript::P2WSH(hash) => multisig(witness_script(hash)?, MultisigWrapper::P2SHP2WSH),
+                    _ => multisig(redeem_script, MultisigWrapper::P2SH),
+                }
+            },
+            _ => Err(PsbtError::UnsupportedScript(input_index)),
+        }
+    }
+
+    /// Legacy signatures don't commit to the value spent, so BIP174 has the
+    ///  signer check it against the whole transaction spent from.
+    fn expect_non_witness_utxo(&self, input_index: usize) -> Result<(), PsbtError> {
+        match self.inputs[input_index].non_witness_utxo {
+            Some(_) => Ok(()),
+            None => Err(PsbtError::MissingNonWitnessUtxo(input_index)),
+        }
+    }
+
+    /// BIP370: what a signature of `sighash_type` commits to can't change
+    ///  any more.
+    fn restrict_tx_modifiable(&mut self, sighash_type: SighashType) {
+        if !sighash_type.is_anyone_can_pay() {
+            self.tx_modifiable = self.tx_modifiable.without(TxModifiable::INPUTS);
+        }
+
+        match sighash_type.base_type() {
+            base_type if base_type == SighashType::NONE.to_u32() => { },
+            base_type if base_type == SighashType::SINGLE.to_u32() => self.tx_modifiable = self.tx_modifiable.without(TxModifiable::OUTPUTS) | TxModifiable::SIGHASH_SINGLE,
+            _ => self.tx_modifiable = self.tx_modifiable.without(TxModifiable::OUTPUTS),
+        }
+    }
+
+    fn check_input_index(&self, input_index: usize) -> Result<(), PsbtError> {
+        match input_index < self.inputs.len() {
+            true => Ok(()),
+            false => Err(SighashError::InputIndexOutOfRange(input_index, self.inputs.len()).into()),
+        }
+    }
+}
+
+/// BIP370's lock time for a version 2 PSBT with `inputs`: the latest any
+///  of them requires, in blocks if every input with a requirement accepts
+///  a height, else in seconds if they all accept a time. Without any
+///  requirement it's the fallback, or 0. `None` when neither type works.
+pub(crate) fn determine_lock_time<'a>(fallback_lock_time: Option<u32>, inputs: impl Iterator<Item = &'a Input> + Clone) -> Option<u32> {
+    let constrained = inputs.filter(|input| input.required_time_lock_time.is_some() || input.required_height_lock_time.is_some());
+
+    if constrained.clone().next().is_none() {
+        return Some(fallback_lock_time.unwrap_or(0));
+    }
+
+    let latest = |required: fn(&Input) -> Option<u32>| constrained.clone().try_fold(0, |latest, input| required(input).map(|lock_time| lock_time.max(latest)));
+
+    latest(|input| input.required_height_lock_time).or_else(|| latest(|input| input.required_time_lock_time))
+}
+
+/// Whether `tx` is the transaction `tx_in` spends from, with an output at
+///  the index spent.
+pub(crate) fn spends_from(tx_in: &TxIn, tx: &Transaction) -> bool {
+    tx.txid() == tx_in.previous_output.txid && (tx_in.previous_output.vout as usize) < tx.outputs.len()
+}
+
+/// The base64 encoding PSBTs are usually passed around in.
+impl Display for PartiallySignedTransaction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", base64::encode(&self.serialize_psbt()))
+    }
+}
+
+impl FromStr for PartiallySignedTransaction {
+    type Err = DeserializePSBTError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::deserialize_psbt(&base64::decode(s)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+    use crate::{cryptography::{bitcoin_private_key::BitcoinPrivateKey, hash::digest::{PubkeyHash, ScriptHash, Txid}, taproot::{tweak_public_key, x_only}}, network::Network, script::Script, serialization::{consensus::serialize::SerializeConsensus, psbt::{deserialize::DeserializePSBT, serialize::SerializePSBT}, wif::deserialize::DeserializeWIF}, transaction::{out_point::OutPoint, sighash::SighashType, signer::error::SignerError, tx_in::TxIn, tx_out::TxOut, Transaction}};
+    use super::{error::PsbtError, input::Input, output::Output, tx_modifiable::TxModifiable, PartiallySignedTransaction};
+
+    const BIP174_VECTORS: &str = include_str!("../../../test_data/bip174_vectors.json");
+
+    /// The keys of BIP174's two signers, each holding one key of both
+    ///  2-of-2 inputs.
+    const FIRST_SIGNER_KEYS: [&str; 2] = ["cP53pDbR5WtAD8dYAW9hhTjuvvTVaEiQBdrz9XPrgLBeRFiyCbQr", "cR6SXDoyfQrcp4piaiHE97Rsgta9mNhGTen9XeonVgwsh4iSgw6d"];
+    const SECOND_SIGNER_KEYS: [&str; 2] = ["cT7J9YpCwY3AVRFSjN6ukeEeWY6mhpbJPxRaDaP5QTdygQRxP9Au", "cNBc3SWUip9PPm1GjRoLEJT6T41iNzCYtD7qro84FMnM5zEqeJsE"];
+
+    fn signer_vector(name: &str) -> PartiallySignedTransaction {
+        let vectors: Value = serde_json::from_str(BIP174_VECTORS).unwrap();
+        PartiallySignedTransaction::deserialize_psbt(&hex::decode(vectors["signer"][name].as_str().unwrap()).unwrap()).unwrap()
+    }
+
+    /// The updater's output: the first signer's PSBT before it signed.
+    fn updated() -> PartiallySignedTransaction {
+        let mut psbt = signer_vector("first_signer");
+
+        for input in &mut psbt.inputs {
+            input.partial_sigs.clear();
+        }
+
+        psbt
+    }
+
+    fn sign(psbt: &mut PartiallySignedTransaction, keys: &[&str]) {
+        for wif in keys {
+            let key = BitcoinPrivateKey::deserialize_wif(wif, Network::Testnet3).unwrap();
+            assert_eq!(psbt.sign(&key).unwrap().len(), 1);
+        }
+    }
+
+    fn signing_keys(psbt: &PartiallySignedTransaction) -> Vec<Vec<Vec<u8>>> {
+        psbt.inputs.iter().map(|input| input.partial_sigs.keys().cloned().collect()).collect()
+    }
+
+    #[test]
+    pub fn test_creator() {
+        let updated = updated();
+        let psbt = PartiallySignedTransaction::new(updated.unsigned_tx.clone()).unwrap();
+
+        assert_eq!((psbt.inputs.len(), psbt.outputs.len()), (2, 2));
+        assert!(psbt.inputs.iter().all(|input| *input == Default::default()));
+
+        let mut tx = updated.unsigned_tx.clone();
+        tx.inputs[0].script_sig = vec![0x00];
+        assert!(matches!(PartiallySignedTransaction::new(tx), Err(PsbtError::UnsignedTxHasScriptSigs)));
+    }
+
+    #[test]
+    pub fn test_sign_combine_finalize_extract() {
+        let mut first = updated();
+        sign(&mut first, &FIRST_SIGNER_KEYS);
+
+        // Signed with the same keys as BIP174's first signer, but not to
+        //  the same bytes, as signing picks a random nonce.
+        assert_eq!(signing_keys(&first), signing_keys(&signer_vector("first_signer")));
+
+        let mut second = updated();
+        sign(&mut second, &SECOND_SIGNER_KEYS);
+
+        // Finalizing takes both signatures.
+        assert!(matches!(first.clone().finalize(), Err(PsbtError::Signer(SignerError::NotEnoughSignatures(1, 2)))));
+        assert!(matches!(first.extract_tx(), Err(PsbtError::NotFinalized(0))));
+
+        first.combine(second).unwrap();
+        assert_eq!(signing_keys(&first), signing_keys(&signer_vector("combined")));
+
+        first.finalize().unwrap();
+
+        for input in &first.inputs {
+            assert!(input.is_finalized());
+            assert!(input.partial_sigs.is_empty() && input.redeem_script.is_none() && input.bip32_derivation.is_empty());
+        }
+
+        // P2SH multisig, then P2SH-P2WSH.
+        assert!(first.inputs[0].final_script_witness.is_none() && first.inputs[0].non_witness_utxo.is_some());
+        assert_eq!(first.inputs[1].final_script_witness.as_ref().unwrap().len(), 4);
+
+        let tx = first.extract_tx().unwrap();
+        assert_eq!(tx.outputs, first.unsigned_tx.outputs);
+        assert!(tx.has_witness());
+    }
+
+    #[test]
+    pub fn test_vector_signatures() {
+        let mut combined = signer_vector("combined");
+
+        let mut with_first = updated();
+        with_first.combine(signer_vector("first_signer")).unwrap();
+        assert_eq!(signing_keys(&with_first), signing_keys(&signer_vector("first_signer")));
+
+        // Finalizing the vector's own signatures gives BIP174's extracted
+        //  transaction.
+        combined.finalize().unwrap();
+        let tx = combined.extract_tx().unwrap();
+
+        assert_eq!(hex::encode(tx.serialize_consensus()), "0200000000010258e87a21b56daf0c23be8e7070456c336f7cbaa5c8757924f545887bb2abdd7500000000da00473044022074018ad4180097b873323c0015720b3684cc8123891048e7dbcd9b55ad679c99022073d369b740e3eb53dcefa33823c8070514ca55a7dd9544f157c167913261118c01483045022100f61038b308dc1da865a34852746f015772934208c6d24454393cd99bdf2217770220056e675a675a6d0a02b85b14e5e29074d8a25a9b5760bea2816f661910a006ea01475221029583bf39ae0a609747ad199addd634fa6108559d6c5cd39b4c2183f1ab96e07f2102dab61ff49a14db6a7d02b0cd1fbb78fc4b18312b5b4e54dae4dba2fbfef536d752aeffffffff838d0427d0ec650a68aa46bb0b098aea4422c071b2ca78352a077959d07cea1d01000000232200208c2353173743b595dfb4a07b72ba8e42e3797da74e87fe7d9d7497e3b2028903ffffffff0270aaf00800000000160014d85c2b71d0060b09c9886aeb815e50991dda124d00e1f5050000000016001400aea9a2e5f0f876a588df5546e8742d1d87008f000400473044022062eb7a556107a7c73f45ac4ab5a1dddf6f7075fb1275969a7f383efff784bcb202200c05dbb7470dbf2f08557dd356c7325c1ed30913e996cd3840945db12228da5f01473044022065f45ba5998b59a27ffe1a7bed016af1f1f90d54b3aa8f7450aa5f56a25103bd02207f724703ad1edb96680b284b56d4ffcb88f7fb759eabbe08aa30f29b851383d20147522103089dc10c7ac6db54f91329af617333db388cead0c231f723379d1b99030b02dc21023add904f3d6dcf59ddb906b0dee23529b7ffb9ed50e5e86151926860221f0e7352ae00000000");
+    }
+
+    #[test]
+    pub fn test_single_key_inputs() {
+        let key = BitcoinPrivateKey::new(12345u32).unwrap();
+        let hash = PubkeyHash::hash(&key.serialize_public_key().unwrap());
+        let nested = Script::p2wpkh(&hash);
+
+        let funding = Transaction::new(2, vec![TxIn::new(OutPoint::new(Txid::from_byte_array([0x01; 32]), 0))], vec![
+            TxOut::new(10_000, Script::p2pkh(&hash).into_bytes()),
+            TxOut::new(20_000, Script::p2wpkh(&hash).into_bytes()),
+            TxOut::new(30_000, Script::p2sh(&ScriptHash::hash(nested.as_bytes())).into_bytes()),
+        ], 0);
+
+        let inputs = (0..3).map(|vout| TxIn::new(OutPoint::new(funding.txid(), vout))).collect();
+        let mut psbt = PartiallySignedTransaction::new(Transaction::new(2, inputs, vec![TxOut::new(59_000, nested.as_bytes().to_vec())], 0)).unwrap();
+
+        // Legacy inputs need the whole transaction spent from.
+        psbt.inputs[0].witness_utxo = Some(funding.outputs[0].clone());
+        assert!(matches!(psbt.sign(&key), Err(PsbtError::MissingNonWitnessUtxo(0))));
+
+        let mut other = funding.clone();
+        other.lock_time = 1;
+        assert!(matches!(psbt.add_non_witness_utxo(0, other), Err(PsbtError::NonWitnessUtxoMismatch(0))));
+
+        psbt.inputs[0].witness_utxo = None;
+        psbt.add_non_witness_utxo(0, funding.clone()).unwrap();
+        psbt.inputs[1].witness_utxo = Some(funding.outputs[1].clone());
+        psbt.inputs[2].witness_utxo = Some(funding.outputs[2].clone());
+
+        // Until the updater adds the redeem script the nested input is left.
+        assert_eq!(psbt.clone().sign(&key).unwrap(), [0, 1]);
+
+        psbt.inputs[2].redeem_script = Some(nested.clone());
+        assert_eq!(psbt.sign(&key).unwrap(), [0, 1, 2]);
+        assert!(psbt.sign(&BitcoinPrivateKey::new(1u32).unwrap()).unwrap().is_empty());
+
+        psbt.finalize().unwrap();
+        assert!(psbt.inputs[0].final_script_witness.is_none());
+        assert!(psbt.inputs[1].final_script_sig.is_none());
+        assert_eq!(psbt.inputs[2].final_script_sig.as_ref().unwrap()[1..], *nested.as_bytes());
+
+        // Finalized inputs are not signed again.
+        assert!(psbt.sign(&key).unwrap().is_empty());
+
+        let tx = psbt.extract_tx().unwrap();
+        assert_eq!(tx.inputs[1].witness.len(), 2);
+
+        // The signatures commit to the values spent.
+        psbt.inputs[1].witness_utxo.as_mut().unwrap().value += 1;
+        assert!(matches!(psbt.extract_tx(), Err(PsbtError::Verification(1, _))));
+    }
+
+    fn lock_time_input(time: Option<u32>, height: Option<u32>) -> Input {
+        Input {
+            required_time_lock_time: time,
+            required_height_lock_time: height,
+            ..Input::default()
+        }
+    }
+
+    fn tx_in(n: u8) -> TxIn {
+        TxIn::new(OutPoint::new(Txid::from_byte_array([n; 32]), 0))
+    }
+
+    #[test]
+    pub fn test_constructor() {
+        let mut psbt = PartiallySignedTransaction::new_v2(2, None);
+        assert_eq!(psbt.unsigned_tx.lock_time, 0);
+
+        // Heights win when every input takes one.
+        psbt.add_input(tx_in(1), lock_time_input(Some(1_700_000_000), Some(800_000))).unwrap();
+        psbt.add_input(tx_in(2), lock_time_input(None, None)).unwrap();
+        assert_eq!(psbt.unsigned_tx.lock_time, 800_000);
+
+        psbt.add_input(tx_in(3), lock_time_input(Some(1_800_000_000), None)).unwrap();
+        assert_eq!(psbt.unsigned_tx.lock_time, 1_800_000_000);
+
+        assert!(matches!(psbt.add_input(tx_in(4), lock_time_input(None, Some(900_000))), Err(PsbtError::LockTimeConflict(3))));
+        assert!(matches!(psbt.add_input(tx_in(4), lock_time_input(Some(900_000), None)), Err(PsbtError::InvalidRequiredLockTime(3))));
+        assert!(matches!(psbt.add_input(tx_in(4), lock_time_input(None, Some(0))), Err(PsbtError::InvalidRequiredLockTime(3))));
+
+        let mut signed = tx_in(4);
+        signed.script_sig = vec![0x00];
+        assert!(matches!(psbt.add_input(signed, Input::default()), Err(PsbtError::UnsignedTxHasScriptSigs)));
+
+        psbt.add_output(TxOut::new(1_000, vec![0x6a]), Output::default()).unwrap();
+        assert_eq!((psbt.unsigned_tx.inputs.len(), psbt.inputs.len(), psbt.unsigned_tx.outputs.len(), psbt.outputs.len()), (3, 3, 1, 1));
+
+        // Only version 2 PSBTs let constructors in.
+        let mut psbt = PartiallySignedTransaction::new(psbt.unsigned_tx.clone()).unwrap();
+        assert!(matches!(psbt.add_output(TxOut::new(1_000, vec![0x6a]), Output::default()), Err(PsbtError::NotModifiable)));
+        assert!(matches!(psbt.combine(PartiallySignedTransaction::new_v2(2, None)), Err(PsbtError::VersionMismatch(0, 2))));
+    }
+
+    #[test]
+    pub fn test_version_2_signing() {
+        let key = BitcoinPrivateKey::new(12345u32).unwrap();
+        let taproot_key = BitcoinPrivateKey::new(67890u32).unwrap();
+
+        let (output_key, _) = tweak_public_key(&x_only(&taproot_key.public_key()).unwrap(), None).unwrap();
+        let spent = [
+            TxOut::new(20_000, Script::p2wpkh(&PubkeyHash::hash(&key.serialize_public_key().unwrap())).into_bytes()),
+            TxOut::new(30_000, Script::p2tr(&x_only(&output_key).unwrap()).into_bytes()),
+        ];
+
+        let mut psbt = PartiallySignedTransaction::new_v2(2, Some(100));
+        psbt.add_input(tx_in(1), Input { witness_utxo: Some(spent[0].clone()), sighash_type: Some(SighashType::SINGLE.anyone_can_pay()), ..Input::default() }).unwrap();
+        psbt.add_output(TxOut::new(19_000, vec![0x6a]), Output::default()).unwrap();
+        psbt.add_output(TxOut::new(29_000, vec![0x6a]), Output::default()).unwrap();
+
+        // SIGHASH_SINGLE | ANYONECANPAY leaves room for more inputs, as long
+        //  as the lock time stays, but BIP370 fixes the outputs of any
+        //  signature not using SIGHASH_NONE.
+        assert_eq!(psbt.sign(&key).unwrap(), [0]);
+        assert_eq!(psbt.tx_modifiable, TxModifiable::INPUTS | TxModifiable::SIGHASH_SINGLE);
+        assert!(matches!(psbt.add_output(TxOut::new(1_000, vec![0x6a]), Output::default()), Err(PsbtError::NotModifiable)));
+
+        // Only SIGHASH_NONE leaves the outputs open.
+        let mut unrestricted = PartiallySignedTransaction::new_v2(2, None);
+        unrestricted.restrict_tx_modifiable(SighashType::NONE.anyone_can_pay());
+        assert_eq!(unrestricted.tx_modifiable, TxModifiable::INPUTS | TxModifiable::OUTPUTS);
+        unrestricted.restrict_tx_modifiable(SighashType::NONE);
+        assert_eq!(unrestricted.tx_modifiable, TxModifiable::OUTPUTS);
+
+        let taproot_input = Input { witness_utxo: Some(spent[1].clone()), tap_internal_key: x_only(&taproot_key.public_key()), ..Input::default() };
+        assert!(matches!(psbt.add_input(tx_in(2), Input { required_height_lock_time: Some(200), ..taproot_input.clone() }), Err(PsbtError::SignedLockTimeChange(1))));
+
+        psbt.add_input(tx_in(2), taproot_input).unwrap();
+
+        // The ECDSA key isn't the taproot one, and SIGHASH_DEFAULT then
+        //  commits to everything.
+        let unsigned = psbt.clone();
+        assert_eq!(psbt.sign(&key).unwrap(), [0]);
+        assert_eq!(psbt.sign(&taproot_key).unwrap(), [1]);
+        assert_eq!(psbt.inputs[1].tap_key_sig.as_ref().unwrap().len(), 64);
+        assert_eq!(psbt.tx_modifiable, TxModifiable::SIGHASH_SINGLE);
+        assert!(matches!(psbt.add_output(TxOut::new(1_000, vec![0x6a]), Output::default()), Err(PsbtError::NotModifiable)));
+
+        // Combining keeps the restrictions of either.
+        let mut combined = unsigned.clone();
+        combined.combine(psbt.clone()).unwrap();
+        assert_eq!(combined.tx_modifiable, TxModifiable::SIGHASH_SINGLE);
+
+        psbt.finalize().unwrap();
+        assert_eq!(psbt.inputs[1].final_script_witness.as_ref().unwrap().len(), 1);
+
+        let tx = psbt.extract_tx().unwrap();
+        assert_eq!(tx.lock_time, 100);
+        assert_eq!(PartiallySignedTransaction::deserialize_psbt(&psbt.serialize_psbt()).unwrap(), psbt);
+
+        // Without every UTXO a taproot input can't be signed.
+        let mut psbt = unsigned;
+        psbt.inputs[0].witness_utxo = None;
+        assert!(psbt.sign(&taproot_key).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::BTreeMap;
+use crate::script::Script;
+use super::{input::{combine_map, combine_option}, KeySource, ProprietaryKey};
+
+/// What a PSBT knows about one output of its transaction, mostly for
+///  signers to recognize their change.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Output {
+    pub redeem_script: Option<Script>,
+    pub witness_script: Option<Script>,
+    pub bip32_derivation: BTreeMap<Vec<u8>, KeySource>,
+    pub tap_internal_key: Option<[u8; 32]>,
+    /// The leaves of the output's script tree, depth first from the left.
+    pub tap_tree: Option<Vec<TapTreeLeaf>>,
+    /// See [`Input::tap_bip32_derivation`](super::input::Input::tap_bip32_derivation).
+    pub tap_bip32_derivation: BTreeMap<[u8; 32], (Vec<[u8; 32]>, KeySource)>,
+    pub proprietary: BTreeMap<ProprietaryKey, Vec<u8>>,
+    pub unknown: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+/// One leaf of a BIP371 tap tree, with its depth from the root.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TapTreeLeaf {
+    pub depth: u8,
+    pub leaf_version: u8,
+    pub script: Script,
+}
+
+impl Output {
+    /// See [`Input::combine`](super::input::Input::combine).
+    pub fn combine(&mut self, other: Output) {
+        combine_option(&mut self.redeem_script, other.redeem_script);
+        combine_option(&mut self.witness_script, other.witness_script);
+        combine_map(&mut self.bip32_derivation, other.bip32_derivation);
+        combine_option(&mut self.tap_internal_key, other.tap_internal_key);
+        combine_option(&mut self.tap_tree, other.tap_tree);
+        combine_map(&mut self.tap_bip32_derivation, other.tap_bip32_derivation);
+        combine_map(&mut self.proprietary, other.proprietary);
+        combine_map(&mut self.unknown, other.unknown);
+    }
+}
+
+/// Whether leaves at `depths`, depth first from the left, make a whole
+///  binary tree: every node but the root has a sibling.
+pub(crate) fn is_complete_tree(depths: impl IntoIterator<Item = u8>) -> bool {
+    // Depths of the subtrees finished so far that still need a sibling.
+    let mut open: Vec<u8> = Vec::new();
+
+    for mut depth in depths {
+        while open.last() == Some(&depth) {
+            if depth == 0 {
+                return false;
+            }
+
+            open.pop();
+            depth -= 1;
+        }
+
+        // Moving back up before a deeper subtree got its sibling.
+        if open.last().is_some_and(|last| *last > depth) {
+            return false;
+        }
+
+        open.push(depth);
+    }
+
+    open == [0]
+}
+
+#[cfg(test)]
+mod test {
+    use super::is_complete_tree;
+
+    #[test]
+    pub fn test_is_complete_tree() {
+        assert!(is_complete_tree([0]));
+        assert!(is_complete_tree([1, 1]));
+        assert!(is_complete_tree([1, 2, 2]));
+        assert!(is_complete_tree([2, 2, 2, 3, 3]));
+
+        assert!(!is_complete_tree([]));
+        assert!(!is_complete_tree([0, 0]));
+        assert!(!is_complete_tree([1]));
+        assert!(!is_complete_tree([2, 1]));
+        assert!(!is_complete_tree([1, 1, 1]));
+        assert!(!is_complete_tree([2, 2, 1, 1]));
+    }
+}
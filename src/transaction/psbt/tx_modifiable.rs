@@ -0,0 +1,55 @@
+use std::ops::{BitAnd, BitOr, BitOrAssign};
+
+/// BIP370 flags saying what a version 2 PSBT's constructors may still
+///  change, given the signatures made so far.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TxModifiable(u8);
+
+impl TxModifiable {
+    pub const NONE: TxModifiable = TxModifiable(0);
+    /// No signature commits to every input, so inputs may be added.
+    pub const INPUTS: TxModifiable = TxModifiable(1 << 0);
+    /// No signature commits to the outputs, so outputs may be added.
+    pub const OUTPUTS: TxModifiable = TxModifiable(1 << 1);
+    /// Some input is signed with SIGHASH_SINGLE, so its output has to keep
+    ///  the same index.
+    pub const SIGHASH_SINGLE: TxModifiable = TxModifiable(1 << 2);
+
+    pub fn from_u8(value: u8) -> Self {
+        Self(value)
+    }
+
+    pub fn to_u8(self) -> u8 {
+        self.0
+    }
+
+    pub fn contains(self, other: TxModifiable) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn without(self, other: TxModifiable) -> Self {
+        Self(self.0 & !other.0)
+    }
+}
+
+impl BitOr for TxModifiable {
+    type Output = TxModifiable;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for TxModifiable {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitAnd for TxModifiable {
+    type Output = TxModifiable;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self(self.0 & rhs.0)
+    }
+}
@@ -0,0 +1,32 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SighashError {
+    InputIndexOutOfRange(usize, usize),
+    NotP2wpkhScript,
+    InvalidSighashType(u32),
+    InvalidAnnex,
+    PrevoutsLengthMismatch(usize, usize),
+    PrevoutIndexMismatch(usize, usize),
+    PrevoutsRequired,
+    CachedPrevoutsMismatch,
+    SingleWithoutOutput(usize, usize),
+}
+
+impl Error for SighashError { }
+
+impl Display for SighashError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SighashError::InputIndexOutOfRange(index, len) => write!(f, "SighashError::InputIndexOutOfRange({} with {} inputs)", index, len),
+            SighashError::NotP2wpkhScript => write!(f, "SighashError::NotP2wpkhScript(expected OP_0 <20 bytes>)"),
+            SighashError::InvalidSighashType(sighash_type) => write!(f, "SighashError::InvalidSighashType({:#04x})", sighash_type),
+            SighashError::InvalidAnnex => write!(f, "SighashError::InvalidAnnex(must start with 0x50)"),
+            SighashError::PrevoutsLengthMismatch(len, inputs) => write!(f, "SighashError::PrevoutsLengthMismatch({} prevouts for {} inputs)", len, inputs),
+            SighashError::PrevoutIndexMismatch(prevout, input) => write!(f, "SighashError::PrevoutIndexMismatch(prevout for input {} while signing input {})", prevout, input),
+            SighashError::PrevoutsRequired => write!(f, "SighashError::PrevoutsRequired(every prevout is needed without ANYONECANPAY)"),
+            SighashError::CachedPrevoutsMismatch => write!(f, "SighashError::CachedPrevoutsMismatch(prevouts differ from the ones this cache hashed)"),
+            SighashError::SingleWithoutOutput(index, len) => write!(f, "SighashError::SingleWithoutOutput(input {} with {} outputs)", index, len),
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use crate::{cryptography::hash::digest::Sighash, script::{instruction::{Instruction, Instructions}, opcode::Opcode}, serialization::consensus::{compact_size::write_compact_size, serialize::SerializeConsensus}, transaction::Transaction};
+use super::{error::SighashError, SighashType};
+
+/// What Core returns instead of a hash when SIGHASH_SINGLE has no matching
+///  output. Signatures over it are valid consensus-wide, so the bug has to
+///  be reproduced rather than reported as an error.
+const SIGHASH_SINGLE_BUG: [u8; 32] = {
+    let mut one = [0u8; 32];
+    one[0] = 0x01;
+    one
+};
+
+/// Pre-segwit signature hash of input `input_index`, where `script_code` is
+///  the script being executed (the previous output's scriptPubKey, or the
+///  redeem script for P2SH) from the last executed OP_CODESEPARATOR on.
+pub fn legacy_sighash(tx: &Transaction, input_index: usize, script_code: &[u8], sighash_type: SighashType) -> Result<Sighash, SighashError> {
+    if input_index >= tx.inputs.len() {
+        return Err(SighashError::InputIndexOutOfRange(input_index, tx.inputs.len()));
+    }
+
+    let base_type = sighash_type.base_type();
+
+    if base_type == SighashType::SINGLE.to_u32() && input_index >= tx.outputs.len() {
+        return Ok(Sighash::from_byte_array(SIGHASH_SINGLE_BUG));
+    }
+
+    let mut buffer = Vec::new();
+
+    tx.version.serialize_consensus_to(&mut buffer);
+
+    if sighash_type.is_anyone_can_pay() {
+        write_compact_size(&mut buffer, 1);
+    } else {
+        write_compact_size(&mut buffer, tx.inputs.len() as u64);
+    }
+
+    for (index, input) in tx.inputs.iter().enumerate() {
+        if sighash_type.is_anyone_can_pay() && index != input_index {
+            continue;
+        }
+
+        input.previous_output.serialize_consensus_to(&mut buffer);
+
+        if index == input_index {
+            remove_code_separators(script_code).serialize_consensus_to(&mut buffer);
+        } else {
+            write_compact_size(&mut buffer, 0);
+        }
+
+        // NONE and SINGLE let other signers replace their inputs.
+        if index != input_index && (base_type == SighashType::NONE.to_u32() || base_type == SighashType::SINGLE.to_u32()) {
+            0u32.serialize_consensus_to(&mut buffer);
+        } else {
+            input.sequence.serialize_consensus_to(&mut buffer);
+        }
+    }
+
+    if base_type == SighashType::NONE.to_u32() {
+        write_compact_size(&mut buffer, 0);
+    } else if base_type == SighashType::SINGLE.to_u32() {
+        write_compact_size(&mut buffer, input_index as u64 + 1);
+
+        // Outputs before ours are blanked to value -1 and an empty script.
+        for _ in 0..input_index {
+            u64::MAX.serialize_consensus_to(&mut buffer);
+            write_compact_size(&mut buffer, 0);
+        }
+
+        tx.outputs[input_index].serialize_consensus_to(&mut buffer);
+    } else {
+        tx.outputs.serialize_consensus_to(&mut buffer);
+    }
+
+    tx.lock_time.serialize_consensus_to(&mut buffer);
+    sighash_type.to_u32().serialize_consensus_to(&mut buffer);
+
+    Ok(Sighash::hash(&buffer))
+}
+
+/// Drops every OP_CODESEPARATOR from `script`, stepping over push data so
+///  that 0xab bytes inside pushes survive. Like Core, an unparseable tail
+///  is copied through untouched.
+fn remove_code_separators(script: &[u8]) -> Vec<u8> {
+    let mut stripped = Vec::with_capacity(script.len());
+    let mut instructions = Instructions::new(script);
+    let mut start = 0;
+
+    loop {
+        let position = instructions.position();
+
+        match instructions.next() {
+            Some(Ok(Instruction::Op(Opcode::OP_CODESEPARATOR))) => {
+                stripped.extend_from_slice(&script[start..position]);
+                start = instructions.position();
+            },
+            Some(Ok(_)) => { },
+            Some(Err(_)) | None => break,
+        }
+    }
+
+    stripped.extend_from_slice(&script[start..]);
+
+    stripped
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+    use crate::{cryptography::bitcoin_private_key::BitcoinPrivateKey, serialization::consensus::deserialize::DeserializeConsensus, transaction::{sighash::{error::SighashError, SighashType}, Transaction}};
+    use super::legacy_sighash;
+
+    const SIGHASH_VECTORS: &str = include_str!("../../../test_data/sighash.json");
+
+    // Two inputs, one output.
+    const TWO_INPUT_TX: &str = "0100000002a15d57094aa7a21a28cb20b59aab8fc7d1149a3bdbcddba9c622e4f5f6a99ece0100000000ffffffffa15d57094aa7a21a28cb20b59aab8fc7d1149a3bdbcddba9c622e4f5f6a99ece0200000000ffffffff0100e1f505000000001976a9140389035a9225b3839e2bbf32d826a1e222031fd888ac00000000";
+
+    #[test]
+    pub fn test_sighash_json() {
+        let vectors: Value = serde_json::from_str(SIGHASH_VECTORS).unwrap();
+
+        // The first entry is the column header.
+        for vector in vectors.as_array().unwrap().iter().skip(1) {
+            let tx = Transaction::deserialize_consensus(&hex::decode(vector[0].as_str().unwrap()).unwrap()).unwrap();
+            let script = hex::decode(vector[1].as_str().unwrap()).unwrap();
+            let input_index = vector[2].as_u64().unwrap() as usize;
+            let sighash_type = SighashType::from_u32(vector[3].as_i64().unwrap() as i32 as u32);
+
+            let sighash = legacy_sighash(&tx, input_index, &script, sighash_type).unwrap();
+
+            assert_eq!(sighash.to_string(), vector[4].as_str().unwrap());
+        }
+    }
+
+    #[test]
+    pub fn test_sighash_single_bug() {
+        let tx = Transaction::deserialize_consensus(&hex::decode(TWO_INPUT_TX).unwrap()).unwrap();
+
+        let sighash = legacy_sighash(&tx, 1, &[0x51], SighashType::SINGLE).unwrap();
+        assert_eq!(sighash.to_string(), "0000000000000000000000000000000000000000000000000000000000000001");
+
+        let sighash = legacy_sighash(&tx, 1, &[0x51], SighashType::SINGLE.anyone_can_pay()).unwrap();
+        assert_eq!(sighash.to_string(), "0000000000000000000000000000000000000000000000000000000000000001");
+
+        let sighash = legacy_sighash(&tx, 0, &[0x51], SighashType::SINGLE).unwrap();
+        assert_ne!(sighash.to_string(), "0000000000000000000000000000000000000000000000000000000000000001");
+
+        assert_eq!(legacy_sighash(&tx, 2, &[0x51], SighashType::ALL), Err(SighashError::InputIndexOutOfRange(2, 2)));
+    }
+
+    #[test]
+    pub fn test_code_separators_removed() {
+        let tx = Transaction::deserialize_consensus(&hex::decode(TWO_INPUT_TX).unwrap()).unwrap();
+
+        // OP_CODESEPARATOR OP_DUP OP_CODESEPARATOR OP_CODESEPARATOR PUSH(ab) OP_CODESEPARATOR
+        let with_separators = hex::decode("ab76abab01abab").unwrap();
+        // OP_DUP PUSH(ab)
+        let without_separators = hex::decode("7601ab").unwrap();
+
+        for sighash_type in [SighashType::ALL, SighashType::NONE, SighashType::SINGLE.anyone_can_pay()] {
+            assert_eq!(
+                legacy_sighash(&tx, 0, &with_separators, sighash_type).unwrap(),
+                legacy_sighash(&tx, 0, &without_separators, sighash_type).unwrap()
+            );
+        }
+
+        // A push running off the end stops the scan; the rest is kept verbatim.
+        let truncated = hex::decode("ab4c05ab").unwrap();
+        let kept = hex::decode("4c05ab").unwrap();
+
+        assert_eq!(
+            legacy_sighash(&tx, 0, &truncated, SighashType::ALL).unwrap(),
+            legacy_sighash(&tx, 0, &kept, SighashType::ALL).unwrap()
+        );
+    }
+
+    #[test]
+    pub fn test_sign_sighash() {
+        let tx = Transaction::deserialize_consensus(&hex::decode(TWO_INPUT_TX).unwrap()).unwrap();
+        let key = BitcoinPrivateKey::new(8675309u32).unwrap();
+
+        let script_code = hex::decode("76a9140389035a9225b3839e2bbf32d826a1e222031fd888ac").unwrap();
+        let z = legacy_sighash(&tx, 0, &script_code, SighashType::ALL).unwrap().to_z();
+
+        let signature = key.sign(&z).unwrap();
+
+        assert!(key.point().verify_signature(&z, signature).unwrap());
+    }
+}
@@ -0,0 +1,83 @@
+use std::cell::OnceCell;
+use num_bigint::BigUint;
+use crate::{cryptography::hash::digest::Sighash, transaction::Transaction};
+use self::{error::SighashError, segwit_v0::SegwitV0Hashes, taproot::{TaprootPrevoutHashes, TaprootTxHashes}};
+
+pub mod error;
+pub mod legacy;
+pub mod segwit_v0;
+pub mod taproot;
+
+/// The hash type byte appended to ECDSA signatures. Consensus lets any
+///  value through, so this wraps the raw `u32` instead of an enum.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SighashType(u32);
+
+impl SighashType {
+    pub const ALL: SighashType = SighashType(0x01);
+    pub const NONE: SighashType = SighashType(0x02);
+    pub const SINGLE: SighashType = SighashType(0x03);
+    /// Taproot only: commits to everything, like ALL, and is left off the
+    ///  signature.
+    pub const DEFAULT: SighashType = SighashType(0x00);
+    pub const ANYONECANPAY: u32 = 0x80;
+
+    pub fn from_u32(value: u32) -> Self {
+        Self(value)
+    }
+
+    pub fn to_u32(self) -> u32 {
+        self.0
+    }
+
+    /// The type with the ANYONECANPAY bit set.
+    pub fn anyone_can_pay(self) -> Self {
+        Self(self.0 | Self::ANYONECANPAY)
+    }
+
+    pub fn is_anyone_can_pay(self) -> bool {
+        self.0 & Self::ANYONECANPAY != 0
+    }
+
+    /// The low five bits, which are all legacy signature hashing looks at
+    ///  when choosing which outputs to commit to.
+    pub fn base_type(self) -> u32 {
+        self.0 & 0x1f
+    }
+}
+
+/// Computes signature hashes for the inputs of one transaction, keeping
+///  the per-transaction hashes that segwit signing reuses across inputs.
+pub struct SighashCache<'a> {
+    tx: &'a Transaction,
+    segwit_v0: OnceCell<SegwitV0Hashes>,
+    taproot: OnceCell<TaprootTxHashes>,
+    taproot_prevouts: OnceCell<TaprootPrevoutHashes>,
+}
+
+impl<'a> SighashCache<'a> {
+    pub fn new(tx: &'a Transaction) -> Self {
+        Self {
+            tx,
+            segwit_v0: OnceCell::new(),
+            taproot: OnceCell::new(),
+            taproot_prevouts: OnceCell::new(),
+        }
+    }
+
+    pub fn transaction(&self) -> &'a Transaction {
+        self.tx
+    }
+
+    /// See [`legacy::legacy_sighash`]; nothing is cached for legacy inputs.
+    pub fn legacy_sighash(&self, input_index: usize, script_code: &[u8], sighash_type: SighashType) -> Result<Sighash, SighashError> {
+        legacy::legacy_sighash(self.tx, input_index, script_code, sighash_type)
+    }
+}
+
+impl Sighash {
+    /// Interprets the digest as the `z` value expected by `sign` and `verify_signature`.
+    pub fn to_z(&self) -> BigUint {
+        BigUint::from_bytes_be(self.as_bytes())
+    }
+}
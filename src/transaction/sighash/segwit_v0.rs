@@ -0,0 +1,198 @@
+use crate::{cryptography::hash::{digest::Sighash, hash256}, serialization::consensus::{compact_size::write_compact_size, serialize::SerializeConsensus}, transaction::Transaction};
+use super::{error::SighashError, SighashCache, SighashType};
+
+/// The BIP143 midstate hashes, which only depend on the transaction and so
+///  are shared by every input signed with the same cache.
+#[derive(Clone, Debug)]
+pub(crate) struct SegwitV0Hashes {
+    hash_prevouts: [u8; 32],
+    hash_sequence: [u8; 32],
+    hash_outputs: [u8; 32],
+}
+
+impl SegwitV0Hashes {
+    fn new(tx: &Transaction) -> Self {
+        let mut prevouts = Vec::new();
+        let mut sequences = Vec::new();
+        let mut outputs = Vec::new();
+
+        for input in &tx.inputs {
+            input.previous_output.serialize_consensus_to(&mut prevouts);
+            input.sequence.serialize_consensus_to(&mut sequences);
+        }
+
+        for output in &tx.outputs {
+            output.serialize_consensus_to(&mut outputs);
+        }
+
+        Self {
+            hash_prevouts: hash256(&prevouts),
+            hash_sequence: hash256(&sequences),
+            hash_outputs: hash256(&outputs),
+        }
+    }
+}
+
+impl SighashCache<'_> {
+    /// BIP143 signature hash of input `input_index`, which spends `value`
+    ///  satoshis. For P2WSH `script_code` is the witness script from the last
+    ///  executed OP_CODESEPARATOR on; unlike legacy hashing, separators in it
+    ///  are kept.
+    pub fn segwit_v0_sighash(&self, input_index: usize, script_code: &[u8], value: u64, sighash_type: SighashType) -> Result<Sighash, SighashError> {
+        let tx = self.transaction();
+        let input = tx.inputs.get(input_index).ok_or(SighashError::InputIndexOutOfRange(input_index, tx.inputs.len()))?;
+
+        let hashes = self.segwit_v0.get_or_init(|| SegwitV0Hashes::new(tx));
+        let base_type = sighash_type.base_type();
+        let single_or_none = base_type == SighashType::SINGLE.to_u32() || base_type == SighashType::NONE.to_u32();
+
+        let mut buffer = Vec::new();
+
+        tx.version.serialize_consensus_to(&mut buffer);
+
+        if sighash_type.is_anyone_can_pay() {
+            buffer.extend_from_slice(&[0u8; 32]);
+        } else {
+            buffer.extend_from_slice(&hashes.hash_prevouts);
+        }
+
+        if sighash_type.is_anyone_can_pay() || single_or_none {
+            buffer.extend_from_slice(&[0u8; 32]);
+        } else {
+            buffer.extend_from_slice(&hashes.hash_sequence);
+        }
+
+        input.previous_output.serialize_consensus_to(&mut buffer);
+        write_compact_size(&mut buffer, script_code.len() as u64);
+        buffer.extend_from_slice(script_code);
+        value.serialize_consensus_to(&mut buffer);
+        input.sequence.serialize_consensus_to(&mut buffer);
+
+        if !single_or_none {
+            buffer.extend_from_slice(&hashes.hash_outputs);
+        } else if base_type == SighashType::SINGLE.to_u32() && input_index < tx.outputs.len() {
+            buffer.extend_from_slice(&hash256(&tx.outputs[input_index].serialize_consensus()));
+        } else {
+            // Segwit fixed the SIGHASH_SINGLE bug: a missing output just
+            //  commits to nothing.
+            buffer.extend_from_slice(&[0u8; 32]);
+        }
+
+        tx.lock_time.serialize_consensus_to(&mut buffer);
+        sighash_type.to_u32().serialize_consensus_to(&mut buffer);
+
+        Ok(Sighash::hash(&buffer))
+    }
+
+    /// BIP143 signature hash for a P2WPKH input, native or nested in P2SH.
+    ///  `witness_program` is the `OP_0 <20-byte hash>` script: the output's
+    ///  scriptPubKey when native, or the redeem script when nested.
+    pub fn p2wpkh_sighash(&self, input_index: usize, witness_program: &[u8], value: u64, sighash_type: SighashType) -> Result<Sighash, SighashError> {
+        let script_code = p2wpkh_script_code(witness_program).ok_or(SighashError::NotP2wpkhScript)?;
+
+        self.segwit_v0_sighash(input_index, &script_code, value, sighash_type)
+    }
+
+    /// BIP143 signature hash for a P2WSH input, native or nested in P2SH.
+    pub fn p2wsh_sighash(&self, input_index: usize, witness_script: &[u8], value: u64, sighash_type: SighashType) -> Result<Sighash, SighashError> {
+        self.segwit_v0_sighash(input_index, witness_script, value, sighash_type)
+    }
+}
+
+/// The `OP_DUP OP_HASH160 <hash> OP_EQUALVERIFY OP_CHECKSIG` script BIP143
+///  signs in place of a P2WPKH program.
+pub fn p2wpkh_script_code(witness_program: &[u8]) -> Option<Vec<u8>> {
+    if witness_program.len() != 22 || witness_program[0] != 0x00 || witness_program[1] != 0x14 {
+        return None;
+    }
+
+    Some([&[0x76, 0xa9, 0x14][..], &witness_program[2..], &[0x88, 0xac]].concat())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{cryptography::{bitcoin_private_key::BitcoinPrivateKey, hash::digest::PubkeyHash}, serialization::consensus::deserialize::DeserializeConsensus, transaction::{sighash::{error::SighashError, SighashCache, SighashType}, Transaction}};
+
+    fn transaction(hex: &str) -> Transaction {
+        Transaction::deserialize_consensus(&hex::decode(hex).unwrap()).unwrap()
+    }
+
+    #[test]
+    pub fn test_bip143_native_p2wpkh() {
+        let tx = transaction("0100000002fff7f7881a8099afa6940d42d1e7f6362bec38171ea3edf433541db4e4ad969f0000000000eeffffffef51e1b804cc89d182d279655c3aa89e815b1b309fe287d9b2b55d57b90ec68a0100000000ffffffff02202cb206000000001976a9148280b37df378db99f66f85c95a783a76ac7a6d5988ac9093510d000000001976a9143bde42dbee7e4dbe6a21b2d50ce2f0167faa815988ac11000000");
+        let witness_program = hex::decode("00141d0f172a0ecb48aee1be1f2687d2963ae33f71a1").unwrap();
+
+        let cache = SighashCache::new(&tx);
+        let sighash = cache.p2wpkh_sighash(1, &witness_program, 600_000_000, SighashType::ALL).unwrap();
+
+        assert_eq!(hex::encode(sighash.as_bytes()), "c37af31116d1b27caf68aae9e3ac82f1477929014d5b917657d0eb49478cb670");
+
+        let hashes = cache.segwit_v0.get().unwrap();
+        assert_eq!(hex::encode(hashes.hash_prevouts), "96b827c8483d4e9b96712b6713a7b68d6e8003a781feba36c31143470b4efd37");
+        assert_eq!(hex::encode(hashes.hash_sequence), "52b0a642eea2fb7ae638c36f6252b6750293dbe574a806984b8e4d8548339a3b");
+        assert_eq!(hex::encode(hashes.hash_outputs), "863ef3e1a92afbfdb97f31ad0fc7683ee943e9abcf2501590ff8f6551f47e5e5");
+    }
+
+    #[test]
+    pub fn test_bip143_p2sh_p2wpkh() {
+        let tx = transaction("0100000001db6b1b20aa0fd7b23880be2ecbd4a98130974cf4748fb66092ac4d3ceb1a54770100000000feffffff02b8b4eb0b000000001976a914a457b684d7f0d539a46a45bbc043f35b59d0d96388ac0008af2f000000001976a914fd270b1ee6abcaea97fea7ad0402e8bd8ad6d77c88ac92040000");
+        let redeem_script = hex::decode("001479091972186c449eb1ded22b78e40d009bdf0089").unwrap();
+
+        let cache = SighashCache::new(&tx);
+        let sighash = cache.p2wpkh_sighash(0, &redeem_script, 1_000_000_000, SighashType::ALL).unwrap();
+
+        assert_eq!(hex::encode(sighash.as_bytes()), "64f3b0f4dd2bb3aa1ce8566d220cc74dda9df97d8490cc81d89d735c92e59fb6");
+
+        let hashes = cache.segwit_v0.get().unwrap();
+        assert_eq!(hex::encode(hashes.hash_prevouts), "b0287b4a252ac05af83d2dcef00ba313af78a3e9c329afa216eb3aa2a7b4613a");
+        assert_eq!(hex::encode(hashes.hash_sequence), "18606b350cd8bf565266bc352f0caddcf01e8fa789dd8a15386327cf8cabe198");
+        assert_eq!(hex::encode(hashes.hash_outputs), "de984f44532e2173ca0d64314fcefe6d30da6f8cf27bafa706da61df8a226c83");
+
+        assert_eq!(cache.p2wpkh_sighash(0, &redeem_script[1..], 1_000_000_000, SighashType::ALL), Err(SighashError::NotP2wpkhScript));
+    }
+
+    #[test]
+    pub fn test_bip143_p2sh_p2wsh_all_types() {
+        let tx = transaction("010000000136641869ca081e70f394c6948e8af409e18b619df2ed74aa106c1ca29787b96e0100000000ffffffff0200e9a435000000001976a914389ffce9cd9ae88dcc0631e88a821ffdbe9bfe2688acc0832f05000000001976a9147480a33f950689af511e6e84c138dbbd3c3ee41588ac00000000");
+        let witness_script = hex::decode("56210307b8ae49ac90a048e9b53357a2354b3334e9c8bee813ecb98e99a7e07e8c3ba32103b28f0c28bfab54554ae8c658ac5c3e0ce6e79ad336331f78c428dd43eea8449b21034b8113d703413d57761b8b9781957b8c0ac1dfe69f492580ca4195f50376ba4a21033400f6afecb833092a9a21cfdf1ed1376e58c5d1f47de74683123987e967a8f42103a6d48b1131e94ba04d9737d61acdaa1322008af9602b3b14862c07a1789aac162102d8b661b0b3302ee2f162b09e07a55ad5dfbe673a9f01d9f0c19617681024306b56ae").unwrap();
+
+        let cache = SighashCache::new(&tx);
+
+        for (sighash_type, expected) in [
+            (SighashType::ALL, "185c0be5263dce5b4bb50a047973c1b6272bfbd0103a89444597dc40b248ee7c"),
+            (SighashType::NONE, "e9733bc60ea13c95c6527066bb975a2ff29a925e80aa14c213f686cbae5d2f36"),
+            (SighashType::SINGLE, "1e1f1c303dc025bd664acb72e583e933fae4cff9148bf78c157d1e8f78530aea"),
+            (SighashType::ALL.anyone_can_pay(), "2a67f03e63a6a422125878b40b82da593be8d4efaafe88ee528af6e5a9955c6e"),
+            (SighashType::NONE.anyone_can_pay(), "781ba15f3779d5542ce8ecb5c18716733a5ee42a6f51488ec96154934e2c890a"),
+            (SighashType::SINGLE.anyone_can_pay(), "511e8e52ed574121fc1b654970395502128263f62662e076dc6baf05c2e6a99b"),
+        ] {
+            let sighash = cache.p2wsh_sighash(0, &witness_script, 987_654_321, sighash_type).unwrap();
+
+            assert_eq!(hex::encode(sighash.as_bytes()), expected);
+        }
+    }
+
+    #[test]
+    pub fn test_code_separators_kept() {
+        let tx = transaction("010000000136641869ca081e70f394c6948e8af409e18b619df2ed74aa106c1ca29787b96e0100000000ffffffff0200e9a435000000001976a914389ffce9cd9ae88dcc0631e88a821ffdbe9bfe2688acc0832f05000000001976a9147480a33f950689af511e6e84c138dbbd3c3ee41588ac00000000");
+        let cache = SighashCache::new(&tx);
+
+        assert_ne!(
+            cache.p2wsh_sighash(0, &hex::decode("ab51").unwrap(), 1000, SighashType::ALL).unwrap(),
+            cache.p2wsh_sighash(0, &hex::decode("51").unwrap(), 1000, SighashType::ALL).unwrap()
+        );
+    }
+
+    #[test]
+    pub fn test_sign_p2wpkh() {
+        let tx = transaction("0100000001db6b1b20aa0fd7b23880be2ecbd4a98130974cf4748fb66092ac4d3ceb1a54770100000000feffffff02b8b4eb0b000000001976a914a457b684d7f0d539a46a45bbc043f35b59d0d96388ac0008af2f000000001976a914fd270b1ee6abcaea97fea7ad0402e8bd8ad6d77c88ac92040000");
+        let key = BitcoinPrivateKey::new(8675309u32).unwrap();
+
+        let witness_program = [&[0x00, 0x14][..], PubkeyHash::hash(&key.serialize_public_key().unwrap()).as_bytes()].concat();
+        let z = SighashCache::new(&tx).p2wpkh_sighash(0, &witness_program, 50_000, SighashType::ALL).unwrap().to_z();
+
+        let signature = key.sign(&z).unwrap();
+
+        assert!(key.point().verify_signature(&z, signature).unwrap());
+    }
+}
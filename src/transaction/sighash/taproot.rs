@@ -0,0 +1,367 @@
+use crate::{cryptography::hash::{digest::TapSighash, sha256}, serialization::consensus::{compact_size::write_compact_size, serialize::SerializeConsensus}, transaction::{tx_out::TxOut, Transaction}};
+use super::{error::SighashError, SighashCache, SighashType};
+
+/// Only defined sighash epoch, prefixed to every BIP341 signature message.
+const SIGHASH_EPOCH: u8 = 0x00;
+const ANNEX_TAG: u8 = 0x50;
+
+/// The outputs spent by a transaction. Without ANYONECANPAY BIP341 commits
+///  to every one of them, so `One` only works alongside that flag.
+#[derive(Clone, Copy, Debug)]
+pub enum Prevouts<'a> {
+    All(&'a [TxOut]),
+    One(usize, &'a TxOut),
+}
+
+impl<'a> Prevouts<'a> {
+    pub(crate) fn get(&self, input_index: usize) -> Result<&'a TxOut, SighashError> {
+        match *self {
+            Prevouts::All(prevouts) => prevouts.get(input_index).ok_or(SighashError::PrevoutsLengthMismatch(prevouts.len(), input_index + 1)),
+            Prevouts::One(index, prevout) if index == input_index => Ok(prevout),
+            Prevouts::One(index, _) => Err(SighashError::PrevoutIndexMismatch(index, input_index)),
+        }
+    }
+}
+
+/// The tapscript leaf being executed, for script-path signature hashes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScriptPath {
+    pub leaf_hash: [u8; 32],
+    /// Opcode position of the last executed OP_CODESEPARATOR, or `u32::MAX`
+    ///  if none has been.
+    pub code_separator_position: u32,
+}
+
+impl ScriptPath {
+    pub fn new(leaf_hash: [u8; 32]) -> Self {
+        Self {
+            leaf_hash,
+            code_separator_position: u32::MAX,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct TaprootTxHashes {
+    sha_prevouts: [u8; 32],
+    sha_sequences: [u8; 32],
+    sha_outputs: [u8; 32],
+}
+
+impl TaprootTxHashes {
+    fn new(tx: &Transaction) -> Self {
+        let mut prevouts = Vec::new();
+        let mut sequences = Vec::new();
+        let mut outputs = Vec::new();
+
+        for input in &tx.inputs {
+            input.previous_output.serialize_consensus_to(&mut prevouts);
+            input.sequence.serialize_consensus_to(&mut sequences);
+        }
+
+        for output in &tx.outputs {
+            output.serialize_consensus_to(&mut outputs);
+        }
+
+        Self {
+            sha_prevouts: sha256(&prevouts),
+            sha_sequences: sha256(&sequences),
+            sha_outputs: sha256(&outputs),
+        }
+    }
+}
+
+/// Hashes of the spent outputs, kept along with the outputs so that a
+///  later call passing others can be refused.
+#[derive(Clone, Debug)]
+pub(crate) struct TaprootPrevoutHashes {
+    prevouts: Vec<TxOut>,
+    sha_amounts: [u8; 32],
+    sha_scriptpubkeys: [u8; 32],
+}
+
+impl TaprootPrevoutHashes {
+    fn new(prevouts: &[TxOut]) -> Self {
+        let mut amounts = Vec::new();
+        let mut script_pubkeys = Vec::new();
+
+        for prevout in prevouts {
+            prevout.value.serialize_consensus_to(&mut amounts);
+            prevout.script_pubkey.serialize_consensus_to(&mut script_pubkeys);
+        }
+
+        Self {
+            prevouts: prevouts.to_vec(),
+            sha_amounts: sha256(&amounts),
+            sha_scriptpubkeys: sha256(&script_pubkeys),
+        }
+    }
+}
+
+impl SighashCache<'_> {
+    /// The BIP341 `SigMsg`, including the leading epoch byte, that
+    ///  `taproot_sighash` hashes. The hashes of `prevouts` are cached on first
+    ///  use, and later calls passing other prevouts are an error.
+    pub fn taproot_signature_message(&self, input_index: usize, prevouts: &Prevouts, annex: Option<&[u8]>, script_path: Option<&ScriptPath>, sighash_type: SighashType) -> Result<Vec<u8>, SighashError> {
+        let tx = self.transaction();
+        let input = tx.inputs.get(input_index).ok_or(SighashError::InputIndexOutOfRange(input_index, tx.inputs.len()))?;
+
+        if !matches!(sighash_type.to_u32(), 0x00..=0x03 | 0x81..=0x83) {
+            return Err(SighashError::InvalidSighashType(sighash_type.to_u32()));
+        }
+
+        let output_type = sighash_type.to_u32() & 0x03;
+        let anyone_can_pay = sighash_type.is_anyone_can_pay();
+
+        let mut message = vec![SIGHASH_EPOCH, sighash_type.to_u32() as u8];
+
+        tx.version.serialize_consensus_to(&mut message);
+        tx.lock_time.serialize_consensus_to(&mut message);
+
+        if !anyone_can_pay {
+            let all = match prevouts {
+                Prevouts::All(all) if all.len() == tx.inputs.len() => all,
+                Prevouts::All(all) => return Err(SighashError::PrevoutsLengthMismatch(all.len(), tx.inputs.len())),
+                Prevouts::One(..) => return Err(SighashError::PrevoutsRequired),
+            };
+
+            let tx_hashes = self.taproot.get_or_init(|| TaprootTxHashes::new(tx));
+            let prevout_hashes = self.taproot_prevouts.get_or_init(|| TaprootPrevoutHashes::new(all));
+
+            if prevout_hashes.prevouts != *all {
+                return Err(SighashError::CachedPrevoutsMismatch);
+            }
+
+            message.extend_from_slice(&tx_hashes.sha_prevouts);
+            message.extend_from_slice(&prevout_hashes.sha_amounts);
+            message.extend_from_slice(&prevout_hashes.sha_scriptpubkeys);
+            message.extend_from_slice(&tx_hashes.sha_sequences);
+        }
+
+        if output_type != SighashType::NONE.to_u32() && output_type != SighashType::SINGLE.to_u32() {
+            let tx_hashes = self.taproot.get_or_init(|| TaprootTxHashes::new(tx));
+
+            message.extend_from_slice(&tx_hashes.sha_outputs);
+        }
+
+        let spend_type = (script_path.is_some() as u8) << 1 | annex.is_some() as u8;
+        message.push(spend_type);
+
+        if anyone_can_pay {
+            let prevout = prevouts.get(input_index)?;
+
+            input.previous_output.serialize_consensus_to(&mut message);
+            prevout.serialize_consensus_to(&mut message);
+            input.sequence.serialize_consensus_to(&mut message);
+        } else {
+            (input_index as u32).serialize_consensus_to(&mut message);
+        }
+
+        if let Some(annex) = annex {
+            if annex.first() != Some(&ANNEX_TAG) {
+                return Err(SighashError::InvalidAnnex);
+            }
+
+            let mut serialized = Vec::new();
+            write_compact_size(&mut serialized, annex.len() as u64);
+            serialized.extend_from_slice(annex);
+
+            message.extend_from_slice(&sha256(&serialized));
+        }
+
+        if output_type == SighashType::SINGLE.to_u32() {
+            let output = tx.outputs.get(input_index).ok_or(SighashError::SingleWithoutOutput(input_index, tx.outputs.len()))?;
+
+            message.extend_from_slice(&sha256(&output.serialize_consensus()));
+        }
+
+        if let Some(script_path) = script_path {
+            message.extend_from_slice(&script_path.leaf_hash);
+            // key_version, the only one BIP342 defines.
+            message.push(0x00);
+            script_path.code_separator_position.serialize_consensus_to(&mut message);
+        }
+
+        Ok(message)
+    }
+
+    /// BIP341 signature hash. `script_path` is `None` for key-path spends.
+    pub fn taproot_sighash(&self, input_index: usize, prevouts: &Prevouts, annex: Option<&[u8]>, script_path: Option<&ScriptPath>, sighash_type: SighashType) -> Result<TapSighash, SighashError> {
+        let message = self.taproot_signature_message(input_index, prevouts, annex, script_path, sighash_type)?;
+
+        Ok(TapSighash::hash(&message))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+    use crate::{cryptography::taproot::{tap_leaf_hash, TAPSCRIPT_LEAF_VERSION}, serialization::consensus::deserialize::DeserializeConsensus, transaction::{out_point::OutPoint, sighash::{error::SighashError, SighashCache, SighashType}, tx_in::TxIn, tx_out::TxOut, Transaction}};
+    use super::{Prevouts, ScriptPath};
+
+    const BIP341_VECTORS: &str = include_str!("../../../test_data/bip341_wallet_vectors.json");
+
+    fn check_sighash(tx: &str, prevouts: &str, input_index: usize, sighash_type: u32, annex: Option<&str>, script: Option<&str>, expected: &str) {
+        let tx = Transaction::deserialize_consensus(&hex::decode(tx).unwrap()).unwrap();
+        let prevouts = Vec::<TxOut>::deserialize_consensus(&hex::decode(prevouts).unwrap()).unwrap();
+        let annex = annex.map(|annex| hex::decode(annex).unwrap());
+        let script_path = script.map(|script| ScriptPath::new(tap_leaf_hash(TAPSCRIPT_LEAF_VERSION, &hex::decode(script).unwrap())));
+        let sighash_type = SighashType::from_u32(sighash_type);
+
+        let cache = SighashCache::new(&tx);
+        let sighash = cache.taproot_sighash(input_index, &Prevouts::All(&prevouts), annex.as_deref(), script_path.as_ref(), sighash_type).unwrap();
+
+        assert_eq!(sighash.to_string(), expected);
+
+        if sighash_type.is_anyone_can_pay() {
+            let one = Prevouts::One(input_index, &prevouts[input_index]);
+            let sighash = cache.taproot_sighash(input_index, &one, annex.as_deref(), script_path.as_ref(), sighash_type).unwrap();
+
+            assert_eq!(sighash.to_string(), expected);
+        }
+    }
+
+    // Vectors from Bitcoin Core's functional test framework.
+    #[test]
+    pub fn test_key_path_sighash_types() {
+        check_sighash(
+            "020000000164eb050a5e3da0c2a65e4786f26d753b7bc69691fabccafb11f7acef36641f1846010000003101b2b404392a22000000000017a9147f2bde86fe78bf68a0544a4f290e12f0b7e0a08c87580200000000000017a91425d11723074ecfb96a0a83c3956bfaf362ae0c908758020000000000001600147e20f938993641de67bb0cdd71682aa34c4d29ad5802000000000000160014c64984dc8761acfa99418bd6bedc79b9287d652d72000000",
+            "01365724000000000023542156b39dab4f8f3508e0432cfb41fab110170acaa2d4c42539cb90a4dc7c093bc500",
+            0, 0x00, None, None,
+            "33ca0ebfb4a945eeee9569fc0f5040221275f88690b7f8592ada88ce3bdf6703",
+        );
+        check_sighash(
+            "0200000002fff49be59befe7566050737910f6ccdc5e749c7f8860ddc140386463d88c5ad0f3000000002cf68eb4a3d67f9d4c079249f7e4f27b8854815cb1ed13842d4fbf395f9e217fd605ee24090100000065235d9203f458520000000000160014b6d48333bb13b4c644e57c43a9a26df3a44b785e58020000000000001976a914eea9461a9e1e3f765d3af3e726162e0229fe3eb688ac58020000000000001976a9143a8869c9f2b5ea1d4ff3aeeb6a8fb2fffb1ad5fe88ac0ad7125c",
+            "02591f220000000000225120f25ad35583ea31998d968871d7de1abd2a52f6fe4178b54ea158274806ff4ece48fb310000000000225120f25ad35583ea31998d968871d7de1abd2a52f6fe4178b54ea158274806ff4ece",
+            1, 0x01, None, None,
+            "626ab955d58c9a8a600a0c580549d06dc7da4e802eb2a531f62a588e430967a8",
+        );
+        check_sighash(
+            "0200000001350005f65aa830ced2079df348e2d8c2bdb4f10e2dde6a161d8a07b40d1ad87dae000000001611d0d603d9dc0e000000000017a914459b6d7d6bbb4d8837b4bf7e9a4556f952da2f5c8758020000000000001976a9141dd70e1299ffc2d5b51f6f87de9dfe9398c33cbb88ac58020000000000001976a9141dd70e1299ffc2d5b51f6f87de9dfe9398c33cbb88aca71c1f4f",
+            "01c4811000000000002251201bf9297d0a2968ae6693aadd0fa514717afefd218087a239afb7418e2d22e65c",
+            0, 0x81, None, None,
+            "dfa9437f9c9a1d1f9af271f79f2f5482f287cdb0d2e03fa92c8a9b216cc6061c",
+        );
+        check_sighash(
+            "020000000185bed1a6da2bffbd60ec681a1bfb71c5111d6395b99b3f8b2bf90167111bcb18f5010000007c83ace802ded24a00000000001600142c4698f9f7a773866879755aa78c516fb332af8e5802000000000000160014d38639dfbac4259323b98a472405db0c461b31fa61073747",
+            "0144c84d0000000000225120e3f2107989c88e67296ab2faca930efa2e3a5bd3ff0904835a11c9e807458621",
+            0, 0x02, None, None,
+            "3129de36a5d05fff97ffca31eb75fcccbbbc27b3147a7a36a9e4b45d8b625067",
+        );
+        check_sighash(
+            "eb93dbb901028c8515589dac980b6e7f8e4088b77ed866ca0d6d210a7218b6fd0f6b22dd6d7300000000eb4740a9047efc0e0000000000160014913da2128d8fcf292b3691db0e187414aa1783825802000000000000160014913da2128d8fcf292b3691db0e187414aa178382580200000000000017a9143dd27f01c6f7ef9bb9159937b17f17065ed01a0c875802000000000000160014d7630e19df70ada9905ede1722b800c0005f246641000000",
+            "013fed110000000000225120eb536ae8c33580290630fc495046e998086a64f8f33b93b07967d9029b265c55",
+            0, 0x82, None, None,
+            "2441e8b0e063a2083ee790f14f2045022f07258ddde5ee01de543c9e789d80ae",
+        );
+        check_sighash(
+            "02000000017836b409a5fed32211407e44b971591f2032053f14701fb5b3a30c0ff382f2cc9c0100000061ac55f60288fb5600000000001976a9144ea02f6f182b082fb6ce47e36bbde390b6a41b5088ac58020000000000001976a9144ea02f6f182b082fb6ce47e36bbde390b6a41b5088ace4000000",
+            "01efa558000000000022512007071ea3dc7e331b0687d0193d1e6d6ed10e645ef36f10ef8831d5e522ac9e80",
+            0, 0x03, None, None,
+            "30239345177cadd0e3ea413d49803580abb6cb27971b481b7788a78d35117a88",
+        );
+        check_sighash(
+            "0100000001aa6deae89d5e0aaca58714fc76ef6f3c8284224888089232d4e663843ed3ab3eae010000008b6657a60450cb4c0000000000160014a3d42b5413ef0c0701c4702f3cd7d4df222c147058020000000000001976a91430b4ed8723a4ee8992aa2c8814cfe5c3ad0ab9d988ac5802000000000000160014365b1166a6ed0a5e8e9dff17a6d00bbb43454bc758020000000000001976a914bc98c51a84fe7fad5dc380eb8b39586eff47241688ac4f313247",
+            "0107af4e00000000002251202c36d243dfc06cb56a248e62df27ecba7417307511a81ae61aa41c597a929c69",
+            0, 0x83, None, None,
+            "bf9c83f26c6dd16449e4921f813f551c4218e86f2ec906ca8611175b41b566df",
+        );
+    }
+
+    #[test]
+    pub fn test_annex_and_script_path_sighash() {
+        check_sighash(
+            "0200000001df8123752e8f37d132c4e9f1ff7e4f9b986ade9211267e9ebd5fd22a5e718dec6d01000000ce4023b903cb7b23000000000017a914a18b36ea7a094db2f4940fc09edf154e86de7bd787580200000000000017a914afd0d512a2c5c2b40e25669e9cc460303c325b8b87580200000000000017a914a18b36ea7a094db2f4940fc09edf154e86de7bd787f6020000",
+            "01ea49260000000000225120ab5e9800806bf18cb246edcf5fe63441208fe955a4b5a35bbff65f5db622a010",
+            0, 0x83,
+            Some("507b979802e62d397acb29f56743a791894b99372872fc5af06a4f6e8d242d0615cda53062bb20e6ec79756fe39183f0c128adfe85559a8fa042b042c018aa8010143799e44f0893c40e1e"),
+            None,
+            "3b003000add359a364a156e73e02846782a59d0d95ca8c4638aaad99f2ef915c",
+        );
+        check_sighash(
+            "020000000189fc651483f9296b906455dd939813bf086b1bbe7c77635e157c8e14ae29062195010000004445b5c7044561320000000000160014331414dbdada7fb578f700f38fb69995fc9b5ab958020000000000001976a914268db0a8104cc6d8afd91233cc8b3d1ace8ac3ef88ac580200000000000017a914ec00dcb368d6a693e11986d265f659d2f59e8be2875802000000000000160014c715799a49a0bae3956df9c17cb4440a673ac0df6f010000",
+            "011bec34000000000022512028055142ea437db73382e991861446040b61dd2185c4891d7daf6893d79f7182",
+            0, 0x01, None,
+            Some("20cc4e1107aea1d170c5ff5b6817e1303010049724fb3caa7941792ea9d29b3e2bacab"),
+            "d66de5274a60400c7b08c86ba6b7f198f40660079edf53aca89d2a9501317f2e",
+        );
+        check_sighash(
+            "020000000132fb72cb8fba496755f027a9743e2d698c831fdb8304e4d1a346ac92cbf51acba50100000026bdc7df044aad34000000000017a9144fa2554ed6174586854fa3bc01de58dcf33567d0875802000000000000160014950367e1e62cdf240b35b883fc2f5e39f0eb9ab95802000000000000160014950367e1e62cdf240b35b883fc2f5e39f0eb9ab958020000000000001600141b31217d48ccc8760dcc0710fade5866d628e733a02d5122",
+            "011458360000000000225120a7baec3fb9f84614e3899fcc010c638f80f13539344120e1f4d8b68a9a011a13",
+            0, 0x01,
+            Some("50a6272b470e1460e3332ade7bb14b81671c564fb6245761bd5bd531394b28860e0b3808ab229fb51791fb6ae6fa82d915b2efb8f6df83ae1f5ab3db13e30928875e2a22b749d89358de481f19286cd4caa792ce27f9559082d227a731c5486882cc707f83da361c51b7aadd9a0cf68fe7480c410fa137b454482d9a1ebf0f96d760b4d61426fc109c6e8e99a508372c45caa7b000a41f8251305da3f206c1849985ba03f3d9592832b4053afbd23ab25d0465df0bc25a36c223aacf8e04ec736a418c72dc319e4da3e972e349713ca600965e7c665f2090d5a70e241ac164115a1f5639f28b1773327715ca307ace64a2de7f0e3df70a2ffee3857689f909c0dad46d8a20fa373a4cc6eed6d4c9806bf146f0d76baae1"),
+            Some("7520ab9160dd8299dc1367659be3e8f66781fe440d52940c7f8d314a89b9f2698d406ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6ead6eadac"),
+            "a0042aa434f9a75904b64043f2a283f8b4c143c7f4f7f49a6cbe5b9f745f4c15",
+        );
+    }
+
+    #[test]
+    pub fn test_bip341_signature_messages() {
+        let vectors: Value = serde_json::from_str(BIP341_VECTORS).unwrap();
+        let key_path = &vectors["keyPathSpending"][0];
+
+        let tx = Transaction::deserialize_consensus(&hex::decode(key_path["given"]["rawUnsignedTx"].as_str().unwrap()).unwrap()).unwrap();
+        let prevouts: Vec<TxOut> = key_path["given"]["utxosSpent"].as_array().unwrap().iter()
+            .map(|utxo| TxOut::new(utxo["amountSats"].as_u64().unwrap(), hex::decode(utxo["scriptPubKey"].as_str().unwrap()).unwrap()))
+            .collect();
+
+        let cache = SighashCache::new(&tx);
+
+        for input in key_path["inputSpending"].as_array().unwrap() {
+            let input_index = input["given"]["txinIndex"].as_u64().unwrap() as usize;
+            let sighash_type = SighashType::from_u32(input["given"]["hashType"].as_u64().unwrap() as u32);
+
+            let message = cache.taproot_signature_message(input_index, &Prevouts::All(&prevouts), None, None, sighash_type).unwrap();
+            let sighash = cache.taproot_sighash(input_index, &Prevouts::All(&prevouts), None, None, sighash_type).unwrap();
+
+            assert_eq!(hex::encode(message), input["intermediary"]["sigMsg"].as_str().unwrap());
+            assert_eq!(sighash.to_string(), input["intermediary"]["sigHash"].as_str().unwrap());
+        }
+
+        let intermediary = &key_path["intermediary"];
+        let tx_hashes = cache.taproot.get().unwrap();
+        let prevout_hashes = cache.taproot_prevouts.get().unwrap();
+
+        assert_eq!(hex::encode(tx_hashes.sha_prevouts), intermediary["hashPrevouts"].as_str().unwrap());
+        assert_eq!(hex::encode(tx_hashes.sha_sequences), intermediary["hashSequences"].as_str().unwrap());
+        assert_eq!(hex::encode(tx_hashes.sha_outputs), intermediary["hashOutputs"].as_str().unwrap());
+        assert_eq!(hex::encode(prevout_hashes.sha_amounts), intermediary["hashAmounts"].as_str().unwrap());
+        assert_eq!(hex::encode(prevout_hashes.sha_scriptpubkeys), intermediary["hashScriptPubkeys"].as_str().unwrap());
+    }
+
+    #[test]
+    pub fn test_taproot_sighash_errors() {
+        let tx = Transaction::new(2, vec![TxIn::new(OutPoint::null())], vec![], 0);
+        let prevout = TxOut::new(1000, vec![0x51]);
+        let cache = SighashCache::new(&tx);
+
+        assert_eq!(cache.taproot_sighash(0, &Prevouts::All(&[]), None, None, SighashType::ALL), Err(SighashError::PrevoutsLengthMismatch(0, 1)));
+        assert_eq!(cache.taproot_sighash(0, &Prevouts::One(0, &prevout), None, None, SighashType::ALL), Err(SighashError::PrevoutsRequired));
+        assert_eq!(cache.taproot_sighash(0, &Prevouts::One(1, &prevout), None, None, SighashType::ALL.anyone_can_pay()), Err(SighashError::PrevoutIndexMismatch(1, 0)));
+        assert_eq!(cache.taproot_sighash(1, &Prevouts::One(1, &prevout), None, None, SighashType::ALL.anyone_can_pay()), Err(SighashError::InputIndexOutOfRange(1, 1)));
+        assert_eq!(cache.taproot_sighash(0, &Prevouts::One(0, &prevout), None, None, SighashType::SINGLE.anyone_can_pay()), Err(SighashError::SingleWithoutOutput(0, 0)));
+        assert_eq!(cache.taproot_sighash(0, &Prevouts::One(0, &prevout), None, None, SighashType::from_u32(0x04)), Err(SighashError::InvalidSighashType(0x04)));
+        assert_eq!(cache.taproot_sighash(0, &Prevouts::One(0, &prevout), Some(&[0x51]), None, SighashType::ALL.anyone_can_pay()), Err(SighashError::InvalidAnnex));
+    }
+
+    #[test]
+    pub fn test_cached_prevouts() {
+        let tx = Transaction::new(2, vec![TxIn::new(OutPoint::null())], vec![TxOut::new(900, vec![0x51])], 0);
+        let prevouts = [TxOut::new(1000, vec![0x51])];
+        let other = [TxOut::new(2000, vec![0x51])];
+
+        let cache = SighashCache::new(&tx);
+        let sighash = cache.taproot_sighash(0, &Prevouts::All(&prevouts), None, None, SighashType::DEFAULT).unwrap();
+
+        // The amounts are hashed once, so other prevouts can't reuse them.
+        assert_eq!(cache.taproot_sighash(0, &Prevouts::All(&other), None, None, SighashType::DEFAULT), Err(SighashError::CachedPrevoutsMismatch));
+        assert_eq!(cache.taproot_sighash(0, &Prevouts::All(&prevouts), None, None, SighashType::DEFAULT), Ok(sighash));
+
+        let other_sighash = SighashCache::new(&tx).taproot_sighash(0, &Prevouts::All(&other), None, None, SighashType::DEFAULT).unwrap();
+        assert_ne!(other_sighash, sighash);
+
+        // ANYONECANPAY commits to its own prevout only, which isn't cached.
+        let one = cache.taproot_sighash(0, &Prevouts::All(&other), None, None, SighashType::ALL.anyone_can_pay()).unwrap();
+        assert_eq!(SighashCache::new(&tx).taproot_sighash(0, &Prevouts::One(0, &other[0]), None, None, SighashType::ALL.anyone_can_pay()), Ok(one));
+    }
+}
@@ -0,0 +1,71 @@
+use std::{error::Error, fmt::Display};
+use crate::{cryptography::{elliptic_curve::error::PointError, schnorr::error::SchnorrError}, script::{error::ScriptError, interpreter::error::InterpreterError}, serialization::sec::serialize::SerializeSECError, transaction::sighash::error::SighashError};
+
+#[derive(Debug)]
+pub enum SignerError {
+    Sighash(SighashError),
+    Schnorr(SchnorrError),
+    Point(PointError),
+    NotP2trScript,
+    OutputKeyMismatch,
+    InvalidPublicKey(SerializeSECError),
+    MissingKey(usize),
+    UnsupportedScript(usize),
+    Verification(usize, InterpreterError),
+    Script(ScriptError),
+    KeyNotInScript,
+    NotEnoughSignatures(usize, usize),
+    RedeemScriptTooLarge(usize),
+}
+
+impl Error for SignerError { }
+
+impl Display for SignerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignerError::Sighash(e) => write!(f, "SignerError::Sighash({})", e),
+            SignerError::Schnorr(e) => write!(f, "SignerError::Schnorr({})", e),
+            SignerError::Point(e) => write!(f, "SignerError::Point({})", e),
+            SignerError::NotP2trScript => write!(f, "SignerError::NotP2trScript(spent output is not OP_1 <32 bytes>)"),
+            SignerError::OutputKeyMismatch => write!(f, "SignerError::OutputKeyMismatch(tweaked key does not match the spent output)"),
+            SignerError::InvalidPublicKey(e) => write!(f, "SignerError::InvalidPublicKey({})", e),
+            SignerError::MissingKey(index) => write!(f, "SignerError::MissingKey(no key for input {})", index),
+            SignerError::UnsupportedScript(index) => write!(f, "SignerError::UnsupportedScript(input {} spends an output of unsupported type)", index),
+            SignerError::Verification(index, e) => write!(f, "SignerError::Verification(input {}: {})", index, e),
+            SignerError::Script(e) => write!(f, "SignerError::Script({})", e),
+            SignerError::KeyNotInScript => write!(f, "SignerError::KeyNotInScript"),
+            SignerError::NotEnoughSignatures(have, required) => write!(f, "SignerError::NotEnoughSignatures({} of {} required)", have, required),
+            SignerError::RedeemScriptTooLarge(len) => write!(f, "SignerError::RedeemScriptTooLarge({} bytes can't be pushed by a scriptSig)", len),
+        }
+    }
+}
+
+impl From<SighashError> for SignerError {
+    fn from(value: SighashError) -> Self {
+        SignerError::Sighash(value)
+    }
+}
+
+impl From<SchnorrError> for SignerError {
+    fn from(value: SchnorrError) -> Self {
+        SignerError::Schnorr(value)
+    }
+}
+
+impl From<PointError> for SignerError {
+    fn from(value: PointError) -> Self {
+        SignerError::Point(value)
+    }
+}
+
+impl From<SerializeSECError> for SignerError {
+    fn from(value: SerializeSECError) -> Self {
+        SignerError::InvalidPublicKey(value)
+    }
+}
+
+impl From<ScriptError> for SignerError {
+    fn from(value: ScriptError) -> Self {
+        SignerError::Script(value)
+    }
+}
@@ -0,0 +1,248 @@
+use crate::{cryptography::{bitcoin_private_key::BitcoinPrivateKey, elliptic_curve::bitcoin_point::BitcoinPoint, hash::digest::{PubkeyHash, ScriptHash, Sighash}, schnorr, taproot::{tweak_private_key, x_only}}, script::{interpreter::{flags::VerifyFlags, verify_input}, standard::StandardScript, Script}, serialization::der::serialize::SerializeDER};
+use self::error::SignerError;
+use super::{sighash::{error::SighashError, taproot::Prevouts, SighashCache, SighashType}, tx_out::TxOut, witness::Witness, Transaction};
+
+pub mod error;
+pub mod multisig;
+
+/// Signs every input of `tx` with whichever of `keys` the output it spends
+///  pays to. Inputs must spend P2PKH, P2WPKH or P2SH-P2WPKH outputs, which
+///  `prevouts` lists in input order. Each signed input is checked with the
+///  interpreter under standard flags, and `tx` is only updated once every
+///  input passes.
+pub fn sign_transaction(tx: &mut Transaction, prevouts: &[TxOut], keys: &[BitcoinPrivateKey], sighash_type: SighashType) -> Result<(), SignerError> {
+    if prevouts.len() != tx.inputs.len() {
+        return Err(SighashError::PrevoutsLengthMismatch(prevouts.len(), tx.inputs.len()).into());
+    }
+
+    let public_keys = keys.iter().map(|key| key.serialize_public_key()).collect::<Result<Vec<_>, _>>()?;
+
+    let cache = SighashCache::new(tx);
+    let signed = prevouts.iter().enumerate()
+        .map(|(input_index, prevout)| sign_input(&cache, input_index, prevout, keys, &public_keys, sighash_type))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut signed_tx = tx.clone();
+
+    for (input, (script_sig, witness)) in signed_tx.inputs.iter_mut().zip(signed) {
+        input.script_sig = script_sig;
+        input.witness = witness;
+    }
+
+    for input_index in 0..signed_tx.inputs.len() {
+        verify_input(&signed_tx, input_index, prevouts, VerifyFlags::STANDARD).map_err(|e| SignerError::Verification(input_index, e))?;
+    }
+
+    *tx = signed_tx;
+
+    Ok(())
+}
+
+/// The scriptSig and witness spending `prevout`.
+fn sign_input(cache: &SighashCache, input_index: usize, prevout: &TxOut, keys: &[BitcoinPrivateKey], public_keys: &[Vec<u8>], sighash_type: SighashType) -> Result<(Vec<u8>, Witness), SignerError> {
+    let find_key = |matches: &dyn Fn(&[u8]) -> bool| {
+        keys.iter().zip(public_keys).find(|(_, public_key)| matches(public_key)).ok_or(SignerError::MissingKey(input_index))
+    };
+
+    match Script::from(&prevout.script_pubkey[..]).classify() {
+        StandardScript::P2PKH(hash) => {
+            let (key, public_key) = find_key(&|public_key| PubkeyHash::hash(public_key) == hash)?;
+            let sighash = cache.legacy_sighash(input_index, &prevout.script_pubkey, sighash_type)?;
+
+            let mut script_sig = Script::new();
+            script_sig.push_slice(&ecdsa_signature(key, &sighash, sighash_type)?);
+            script_sig.push_slice(public_key);
+
+            Ok((script_sig.into_bytes(), Witness::new()))
+        },
+        // Segwit v0 only spends with compressed keys.
+        StandardScript::P2WPKH(hash) => {
+            let (key, public_key) = find_key(&|public_key| public_key.len() == 33 && PubkeyHash::hash(public_key) == hash)?;
+            let sighash = cache.p2wpkh_sighash(input_index, &prevout.script_pubkey, prevout.value, sighash_type)?;
+
+            Ok((Vec::new(), Witness::from_items(vec![ecdsa_signature(key, &sighash, sighash_type)?, public_key.clone()])))
+        },
+        StandardScript::P2SH(hash) => {
+            let redeem_script = |public_key: &[u8]| Script::p2wpkh(&PubkeyHash::hash(public_key));
+
+            let (key, public_key) = find_key(&|public_key| public_key.len() == 33 && ScriptHash::hash(redeem_script(public_key).as_bytes()) == hash)?;
+            let redeem_script = redeem_script(public_key);
+            let sighash = cache.p2wpkh_sighash(input_index, redeem_script.as_bytes(), prevout.value, sighash_type)?;
+
+            let mut script_sig = Script::new();
+            script_sig.push_slice(redeem_script.as_bytes());
+
+            Ok((script_sig.into_bytes(), Witness::from_items(vec![ecdsa_signature(key, &sighash, sighash_type)?, public_key.clone()])))
+        },
+        _ => Err(SignerError::UnsupportedScript(input_index)),
+    }
+}
+
+/// DER encoded signature followed by its sighash byte, as ECDSA signatures
+///  appear in scripts.
+pub(crate) fn ecdsa_signature(key: &BitcoinPrivateKey, sighash: &Sighash, sighash_type: SighashType) -> Result<Vec<u8>, SignerError> {
+    let mut signature = key.sign(&sighash.to_z())?.serialize_der();
+    signature.push(sighash_type.to_u32() as u8);
+
+    Ok(signature)
+}
+
+/// Signs a P2TR key-path spend of input `input_index` and returns its witness.
+///  `key` is the internal key and `merkle_root` the root of the output's
+///  script tree, if it has one. The signature is only 64 bytes for
+///  SIGHASH_DEFAULT; other types append their byte.
+pub fn sign_taproot_key_spend(cache: &SighashCache, input_index: usize, prevouts: &Prevouts, key: &BitcoinPrivateKey, merkle_root: Option<&[u8; 32]>, sighash_type: SighashType, aux_rand: &[u8; 32]) -> Result<Witness, SignerError> {
+    let prevout = prevouts.get(input_index)?;
+
+    let output_key = match prevout.script_pubkey.as_slice() {
+        [0x51, 0x20, program @ ..] if program.len() == 32 => program,
+        _ => return Err(SignerError::NotP2trScript),
+    };
+
+    let tweaked = tweak_private_key(key.secret(), merkle_root)?;
+
+    if x_only(&(BitcoinPoint::g() * &tweaked)?).as_ref().map(|key| &key[..]) != Some(output_key) {
+        return Err(SignerError::OutputKeyMismatch);
+    }
+
+    let sighash = cache.taproot_sighash(input_index, prevouts, None, None, sighash_type)?;
+    let signature = schnorr::sign(&tweaked, sighash.as_bytes(), aux_rand)?;
+
+    let mut signature = signature.to_byte_array().to_vec();
+
+    if sighash_type != SighashType::DEFAULT {
+        signature.push(sighash_type.to_u32() as u8);
+    }
+
+    Ok(Witness::from_items(vec![signature]))
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigUint;
+    use serde_json::Value;
+    use crate::{cryptography::{bitcoin_private_key::BitcoinPrivateKey, hash::digest::{PubkeyHash, ScriptHash}, taproot::tweak_private_key}, script::{instruction::{Instruction, Instructions}, Script}, serialization::consensus::deserialize::DeserializeConsensus, transaction::{sighash::{error::SighashError, taproot::Prevouts, SighashCache, SighashType}, signer::error::SignerError, test::{keys, public_key_hash, spending}, tx_out::TxOut, Transaction}};
+    use super::{sign_taproot_key_spend, sign_transaction};
+
+    const BIP341_VECTORS: &str = include_str!("../../../test_data/bip341_wallet_vectors.json");
+
+    fn decode_merkle_root(value: &Value) -> Option<[u8; 32]> {
+        value.as_str().map(|root| hex::decode(root).unwrap().try_into().unwrap())
+    }
+
+    #[test]
+    pub fn test_bip341_key_path_spending() {
+        let vectors: Value = serde_json::from_str(BIP341_VECTORS).unwrap();
+        let key_path = &vectors["keyPathSpending"][0];
+
+        let tx = Transaction::deserialize_consensus(&hex::decode(key_path["given"]["rawUnsignedTx"].as_str().unwrap()).unwrap()).unwrap();
+        let prevouts: Vec<TxOut> = key_path["given"]["utxosSpent"].as_array().unwrap().iter()
+            .map(|utxo| TxOut::new(utxo["amountSats"].as_u64().unwrap(), hex::decode(utxo["scriptPubKey"].as_str().unwrap()).unwrap()))
+            .collect();
+
+        let cache = SighashCache::new(&tx);
+
+        for input in key_path["inputSpending"].as_array().unwrap() {
+            let given = &input["given"];
+            let input_index = given["txinIndex"].as_u64().unwrap() as usize;
+            let secret = BigUint::from_bytes_be(&hex::decode(given["internalPrivkey"].as_str().unwrap()).unwrap());
+            let merkle_root = decode_merkle_root(&given["merkleRoot"]);
+            let sighash_type = SighashType::from_u32(given["hashType"].as_u64().unwrap() as u32);
+
+            let tweaked = tweak_private_key(&secret, merkle_root.as_ref()).unwrap();
+            assert_eq!(hex::encode(tweaked.to_bytes_be()), input["intermediary"]["tweakedPrivkey"].as_str().unwrap());
+
+            let key = BitcoinPrivateKey::new(secret).unwrap();
+            let witness = sign_taproot_key_spend(&cache, input_index, &Prevouts::All(&prevouts), &key, merkle_root.as_ref(), sighash_type, &[0u8; 32]).unwrap();
+
+            let expected: Vec<Vec<u8>> = input["expected"]["witness"].as_array().unwrap().iter()
+                .map(|item| hex::decode(item.as_str().unwrap()).unwrap())
+                .collect();
+
+            assert_eq!(witness.items(), &expected[..]);
+        }
+    }
+
+    #[test]
+    pub fn test_key_spend_wrong_output() {
+        let vectors: Value = serde_json::from_str(BIP341_VECTORS).unwrap();
+        let key_path = &vectors["keyPathSpending"][0];
+
+        let tx = Transaction::deserialize_consensus(&hex::decode(key_path["given"]["rawUnsignedTx"].as_str().unwrap()).unwrap()).unwrap();
+        let cache = SighashCache::new(&tx);
+        let key = BitcoinPrivateKey::new(1u32).unwrap();
+
+        let p2wpkh = TxOut::new(1000, hex::decode("00141d0f172a0ecb48aee1be1f2687d2963ae33f71a1").unwrap());
+        let p2tr = TxOut::new(1000, hex::decode("512053a1f6e454df1aa2776a2814a721372d6258050de330b3c6d10ee8f4e0dda343").unwrap());
+
+        assert!(matches!(sign_taproot_key_spend(&cache, 0, &Prevouts::One(0, &p2wpkh), &key, None, SighashType::ALL.anyone_can_pay(), &[0u8; 32]), Err(SignerError::NotP2trScript)));
+        assert!(matches!(sign_taproot_key_spend(&cache, 0, &Prevouts::One(0, &p2tr), &key, None, SighashType::ALL.anyone_can_pay(), &[0u8; 32]), Err(SignerError::OutputKeyMismatch)));
+    }
+
+    /// An unsigned transaction spending one output of each type `keys`
+    ///  can sign for: P2PKH with an uncompressed key, P2WPKH and
+    ///  P2SH-P2WPKH.
+    fn unsigned(keys: &[BitcoinPrivateKey]) -> (Transaction, Vec<TxOut>) {
+        let nested = Script::p2wpkh(&public_key_hash(&keys[2]));
+
+        let prevouts = vec![
+            TxOut::new(10_000, Script::p2pkh(&public_key_hash(&keys[0])).into_bytes()),
+            TxOut::new(20_000, Script::p2wpkh(&public_key_hash(&keys[1])).into_bytes()),
+            TxOut::new(30_000, Script::p2sh(&ScriptHash::hash(nested.as_bytes())).into_bytes()),
+        ];
+
+        let outputs = vec![TxOut::new(55_000, Script::p2wpkh(&PubkeyHash::from_byte_array([0x11; 20])).into_bytes())];
+
+        (spending(&prevouts, outputs), prevouts)
+    }
+
+    #[test]
+    pub fn test_sign_transaction() {
+        let keys = keys();
+        let (mut tx, prevouts) = unsigned(&keys);
+
+        for sighash_type in [SighashType::ALL, SighashType::NONE.anyone_can_pay()] {
+            sign_transaction(&mut tx, &prevouts, &keys, sighash_type).unwrap();
+
+            // P2PKH spends with `<signature> <public key>` in the scriptSig.
+            let script_sig: Vec<_> = Instructions::new(&tx.inputs[0].script_sig).map(|instruction| instruction.unwrap()).collect();
+            assert!(matches!(script_sig[..], [Instruction::Push(_, signature), Instruction::Push(_, public_key)] if signature.last() == Some(&(sighash_type.to_u32() as u8)) && public_key.len() == 65));
+            assert!(tx.inputs[0].witness.is_empty());
+
+            // The others move both into the witness, leaving at most the
+            //  redeem script behind.
+            assert!(tx.inputs[1].script_sig.is_empty());
+            assert_eq!(tx.inputs[1].witness.len(), 2);
+            assert_eq!(tx.inputs[2].script_sig, [&[0x16][..], Script::p2wpkh(&public_key_hash(&keys[2])).as_bytes()].concat());
+            assert_eq!(tx.inputs[2].witness.items()[1], keys[2].serialize_public_key().unwrap());
+        }
+
+        assert_eq!(tx.txid(), Transaction::deserialize_consensus(&tx.serialize_without_witness()).unwrap().txid());
+    }
+
+    #[test]
+    pub fn test_sign_transaction_errors() {
+        let keys = keys();
+        let (mut tx, prevouts) = unsigned(&keys);
+        let unsigned = tx.clone();
+
+        assert!(matches!(sign_transaction(&mut tx, &prevouts, &keys[1..], SighashType::ALL), Err(SignerError::MissingKey(0))));
+        assert!(matches!(sign_transaction(&mut tx, &prevouts[1..], &keys, SighashType::ALL), Err(SignerError::Sighash(SighashError::PrevoutsLengthMismatch(2, 3)))));
+
+        // Legacy signature hashing takes any type, the interpreter does not.
+        assert!(matches!(sign_transaction(&mut tx, &prevouts, &keys, SighashType::DEFAULT), Err(SignerError::Verification(0, _))));
+
+        let mut unsupported = prevouts.clone();
+        unsupported[1].script_pubkey = vec![0x51];
+        assert!(matches!(sign_transaction(&mut tx, &unsupported, &keys, SighashType::ALL), Err(SignerError::UnsupportedScript(1))));
+
+        // Segwit outputs can't be spent with an uncompressed key, even one
+        //  that hashes to the program.
+        let uncompressed = BitcoinPrivateKey::new_with_compression(1002u32, false).unwrap();
+        let mut segwit_uncompressed = prevouts.clone();
+        segwit_uncompressed[1].script_pubkey = Script::p2wpkh(&public_key_hash(&uncompressed)).into_bytes();
+        assert!(matches!(sign_transaction(&mut tx, &segwit_uncompressed, &[keys.into_iter().next().unwrap(), uncompressed], SighashType::ALL), Err(SignerError::MissingKey(1))));
+
+        assert_eq!(tx, unsigned);
+    }
+}
@@ -0,0 +1,340 @@
+use crate::{address::Address, cryptography::{bitcoin_private_key::BitcoinPrivateKey, elliptic_curve::bitcoin_point::BitcoinPoint, hash::{digest::ScriptHash, sha256}}, network::Network, script::{interpreter::{checker::TransactionSignatureChecker, flags::VerifyFlags, verify_script}, Script, MAX_SCRIPT_ELEMENT_SIZE}, serialization::sec::serialize::SerializeSEC, transaction::{sighash::{error::SighashError, SighashCache, SighashType}, witness::Witness, Transaction}};
+use super::{ecdsa_signature, error::SignerError};
+
+/// How the multisig script is committed to by the output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MultisigWrapper {
+    P2SH,
+    P2WSH,
+    /// P2WSH nested in P2SH, for wallets that can't pay to bech32 addresses.
+    P2SHP2WSH,
+}
+
+/// A signature by one of a multisig's keys, with its sighash byte.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PartialSignature {
+    pub public_key: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+/// An m-of-n `OP_CHECKMULTISIG` script over compressed keys and the output
+///  paying to it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Multisig {
+    required: usize,
+    public_keys: Vec<Vec<u8>>,
+    wrapper: MultisigWrapper,
+    script: Script,
+}
+
+impl Multisig {
+    /// Multisig over `public_keys` in the order given.
+    pub fn new(required: usize, public_keys: &[BitcoinPoint], wrapper: MultisigWrapper) -> Result<Self, SignerError> {
+        let public_keys = public_keys.iter().map(|point| point.serialize_sec(true)).collect::<Result<Vec<_>, _>>()?;
+
+        Self::from_sec_keys(required, public_keys, wrapper)
+    }
+
+    /// BIP67 multisig, whose keys are sorted by their compressed encoding
+    ///  so that every signer derives the same script whatever order they
+    ///  were shared in.
+    pub fn sorted(required: usize, public_keys: &[BitcoinPoint], wrapper: MultisigWrapper) -> Result<Self, SignerError> {
+        let mut public_keys = public_keys.iter().map(|point| point.serialize_sec(true)).collect::<Result<Vec<_>, _>>()?;
+        public_keys.sort();
+
+        Self::from_sec_keys(required, public_keys, wrapper)
+    }
+
+    pub(crate) fn from_sec_keys(required: usize, public_keys: Vec<Vec<u8>>, wrapper: MultisigWrapper) -> Result<Self, SignerError> {
+        let script = Script::p2ms(required, &public_keys)?;
+
+        // A P2SH redeem script is pushed by the scriptSig, so it is bound by
+        //  the push limit: more than fifteen compressed keys could never be
+        //  spent, as Core's `createmultisig` refuses too.
+        if wrapper == MultisigWrapper::P2SH && script.as_bytes().len() > MAX_SCRIPT_ELEMENT_SIZE {
+            return Err(SignerError::RedeemScriptTooLarge(script.as_bytes().len()));
+        }
+
+        Ok(Self {
+            required,
+            public_keys,
+            wrapper,
+            script,
+        })
+    }
+
+    pub fn required(&self) -> usize {
+        self.required
+    }
+
+    /// The compressed keys, in script order.
+    pub fn public_keys(&self) -> &[Vec<u8>] {
+        &self.public_keys
+    }
+
+    pub fn wrapper(&self) -> MultisigWrapper {
+        self.wrapper
+    }
+
+    /// The multisig script itself, which signatures commit to.
+    pub fn script(&self) -> &Script {
+        &self.script
+    }
+
+    /// The script the P2SH output hashes, if there is one: the multisig
+    ///  itself or, when nested, the P2WSH program.
+    pub fn redeem_script(&self) -> Option<Script> {
+        match self.wrapper {
+            MultisigWrapper::P2SH => Some(self.script.clone()),
+            MultisigWrapper::P2WSH => None,
+            MultisigWrapper::P2SHP2WSH => Some(Script::p2wsh(&sha256(self.script.as_bytes()))),
+        }
+    }
+
+    /// The script the witness carries, for the segwit wrappers.
+    pub fn witness_script(&self) -> Option<&Script> {
+        match self.wrapper {
+            MultisigWrapper::P2SH => None,
+            MultisigWrapper::P2WSH | MultisigWrapper::P2SHP2WSH => Some(&self.script),
+        }
+    }
+
+    pub fn script_pubkey(&self) -> Script {
+        match self.redeem_script() {
+            Some(redeem_script) => Script::p2sh(&ScriptHash::hash(redeem_script.as_bytes())),
+            None => Script::p2wsh(&sha256(self.script.as_bytes())),
+        }
+    }
+
+    pub fn address(&self, network: Network) -> Address {
+        match self.redeem_script() {
+            Some(redeem_script) => Address::p2sh(redeem_script.as_bytes(), network),
+            None => Address::p2wsh(self.script.as_bytes(), network),
+        }
+    }
+
+    /// Signs input `input_index`, which spends `value` from this multisig,
+    ///  with one of its keys.
+    pub fn sign(&self, cache: &SighashCache, input_index: usize, value: u64, key: &BitcoinPrivateKey, sighash_type: SighashType) -> Result<PartialSignature, SignerError> {
+        let public_key = key.public_key().serialize_sec(true)?;
+
+        if !self.public_keys.contains(&public_key) {
+            return Err(SignerError::KeyNotInScript);
+        }
+
+        let sighash = match self.wrapper {
+            MultisigWrapper::P2SH => cache.legacy_sighash(input_index, self.script.as_bytes(), sighash_type)?,
+            MultisigWrapper::P2WSH | MultisigWrapper::P2SHP2WSH => cache.p2wsh_sighash(input_index, self.script.as_bytes(), value, sighash_type)?,
+        };
+
+        Ok(PartialSignature {
+            public_key,
+            signature: ecdsa_signature(key, &sighash, sighash_type)?,
+        })
+    }
+
+    /// Picks `required` of `signatures` in the order of their keys in the
+    ///  script, as OP_CHECKMULTISIG only walks the keys once. Signatures
+    ///  by other keys are ignored, as are extra ones.
+    pub fn combine(&self, signatures: &[PartialSignature]) -> Result<Vec<Vec<u8>>, SignerError> {
+        let ordered: Vec<Vec<u8>> = self.public_keys.iter()
+            .filter_map(|public_key| signatures.iter().find(|signature| signature.public_key == *public_key))
+            .map(|signature| signature.signature.clone())
+            .take(self.required)
+            .collect();
+
+        if ordered.len() < self.required {
+            return Err(SignerError::NotEnoughSignatures(ordered.len(), self.required));
+        }
+
+        Ok(ordered)
+    }
+
+    /// Sets the scriptSig and witness of input `input_index` from
+    ///  `signatures`, once the interpreter accepts them under standard
+    ///  flags. `value` is what the input spends.
+    pub fn finalize(&self, tx: &mut Transaction, input_index: usize, value: u64, signatures: &[PartialSignature]) -> Result<(), SignerError> {
+        if input_index >= tx.inputs.len() {
+            return Err(SighashError::InputIndexOutOfRange(input_index, tx.inputs.len()).into());
+        }
+
+        // OP_CHECKMULTISIG pops one item more than it uses, which NULLDUMMY
+        //  requires to be empty.
+        let mut items = vec![Vec::new()];
+        items.extend(self.combine(signatures)?);
+
+        let (script_sig, witness) = match self.wrapper {
+            MultisigWrapper::P2SH => {
+                let mut script_sig = Script::new();
+
+                for item in items {
+                    script_sig.push_slice(&item);
+                }
+
+                script_sig.push_slice(self.script.as_bytes());
+
+                (script_sig.into_bytes(), Witness::new())
+            },
+            MultisigWrapper::P2WSH | MultisigWrapper::P2SHP2WSH => {
+                items.push(self.script.as_bytes().to_vec());
+
+                let script_sig = match self.redeem_script() {
+                    Some(redeem_script) => {
+                        let mut script_sig = Script::new();
+                        script_sig.push_slice(redeem_script.as_bytes());
+
+                        script_sig.into_bytes()
+                    },
+                    None => Vec::new(),
+                };
+
+                (script_sig, Witness::from_items(items))
+            },
+        };
+
+        let mut signed_tx = tx.clone();
+        signed_tx.inputs[input_index].script_sig = script_sig;
+        signed_tx.inputs[input_index].witness = witness;
+
+        let checker = TransactionSignatureChecker::new(&signed_tx, input_index, value);
+        let input = &signed_tx.inputs[input_index];
+
+        verify_script(&input.script_sig, self.script_pubkey().as_bytes(), &input.witness, VerifyFlags::STANDARD, &checker).map_err(|e| SignerError::Verification(input_index, e))?;
+
+        *tx = signed_tx;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigUint;
+    use crate::{cryptography::{bitcoin_private_key::BitcoinPrivateKey, elliptic_curve::bitcoin_point::BitcoinPoint}, network::Network, script::{error::ScriptError, standard::StandardScript}, serialization::sec::{deserialize::DeserializeSEC, serialize::SerializeSEC}, transaction::{sighash::{SighashCache, SighashType}, signer::error::SignerError, test::{keys, spending}, tx_out::TxOut, Transaction}};
+    use super::{Multisig, MultisigWrapper};
+
+    const WRAPPERS: [MultisigWrapper; 3] = [MultisigWrapper::P2SH, MultisigWrapper::P2WSH, MultisigWrapper::P2SHP2WSH];
+
+    /// Spends a 50_000 sat output of `multisig`.
+    fn spend(multisig: &Multisig) -> Transaction {
+        spending(&[TxOut::new(50_000, multisig.script_pubkey().into_bytes())], vec![TxOut::new(49_000, vec![0x51])])
+    }
+
+    #[test]
+    pub fn test_bip67_sorting() {
+        // The compressed keys of rust-bitcoin's BIP67 sorting example.
+        let sec = [
+            "038f47dcd43ba6d97fc9ed2e3bba09b175a45fac55f0683e8cf771e8ced4572354",
+            "028bde91b10013e08949a318018fedbd896534a549a278e220169ee2a36517c7aa",
+            "032b8324c93575034047a52e9bca05a46d8347046b91a032eff07d5de8d3f2730b",
+            "0234dd69c56c36a41230d573d68adeae0030c9bc0bf26f24d3e1b64c604d293c68",
+        ];
+        let points: Vec<_> = sec.iter().map(|sec| BitcoinPoint::deserialize_sec(&hex::decode(sec).unwrap()[..]).unwrap()).collect();
+
+        let sorted = Multisig::sorted(2, &points, MultisigWrapper::P2SH).unwrap();
+        let order: Vec<_> = sorted.public_keys().iter().map(hex::encode).collect();
+        assert_eq!(order, [sec[3], sec[1], sec[2], sec[0]]);
+
+        let unsorted = Multisig::new(2, &points, MultisigWrapper::P2SH).unwrap();
+        assert_eq!(unsorted.script().classify(), StandardScript::P2MS { required: 2, public_keys: sec.iter().map(|sec| hex::decode(sec).unwrap()).collect() });
+        assert_ne!(unsorted.script_pubkey(), sorted.script_pubkey());
+    }
+
+    #[test]
+    pub fn test_wrappers() {
+        let points: Vec<_> = keys().iter().map(|key| key.public_key()).collect();
+
+        let p2sh = Multisig::sorted(2, &points, MultisigWrapper::P2SH).unwrap();
+        assert!(p2sh.script_pubkey().is_p2sh());
+        assert_eq!(p2sh.redeem_script().as_ref(), Some(p2sh.script()));
+        assert!(p2sh.address(Network::Mainnet).to_string().starts_with('3'));
+
+        let p2wsh = Multisig::sorted(2, &points, MultisigWrapper::P2WSH).unwrap();
+        assert!(matches!(p2wsh.script_pubkey().classify(), StandardScript::P2WSH(_)));
+        assert_eq!(p2wsh.redeem_script(), None);
+        assert_eq!(p2wsh.address(Network::Mainnet).script_pubkey(), p2wsh.script_pubkey());
+
+        let nested = Multisig::sorted(2, &points, MultisigWrapper::P2SHP2WSH).unwrap();
+        assert_eq!(nested.redeem_script(), Some(p2wsh.script_pubkey()));
+        assert_eq!(nested.witness_script(), Some(p2wsh.script()));
+        assert_eq!(nested.address(Network::Testnet3).script_pubkey(), nested.script_pubkey());
+
+        assert!(matches!(Multisig::new(4, &points, MultisigWrapper::P2SH), Err(SignerError::Script(ScriptError::InvalidMultisig(4, 3)))));
+        assert!(Multisig::new(1, &[BitcoinPoint::identity()], MultisigWrapper::P2WSH).is_err());
+    }
+
+    #[test]
+    pub fn test_p2sh_size_limit() {
+        let keys: Vec<_> = (1..=16u32).map(|n| BitcoinPrivateKey::new(BigUint::from(n)).unwrap()).collect();
+        let points: Vec<_> = keys.iter().map(|key| key.public_key()).collect();
+
+        // Fifteen compressed keys make a 513 byte redeem script, which spends.
+        let multisig = Multisig::sorted(1, &points[..15], MultisigWrapper::P2SH).unwrap();
+        assert_eq!(multisig.script().as_bytes().len(), 513);
+
+        let mut tx = spend(&multisig);
+        let signature = multisig.sign(&SighashCache::new(&tx), 0, 50_000, &keys[0], SighashType::ALL).unwrap();
+        multisig.finalize(&mut tx, 0, 50_000, &[signature]).unwrap();
+
+        // Sixteen make 547.
+        assert!(matches!(Multisig::sorted(1, &points, MultisigWrapper::P2SH), Err(SignerError::RedeemScriptTooLarge(547))));
+
+        // A witness script has no such limit.
+        assert!(Multisig::sorted(1, &points, MultisigWrapper::P2WSH).is_ok());
+        assert!(Multisig::sorted(1, &points, MultisigWrapper::P2SHP2WSH).is_ok());
+    }
+
+    #[test]
+    pub fn test_sign_and_finalize() {
+        let keys = keys();
+        let points: Vec<_> = keys.iter().map(|key| key.public_key()).collect();
+
+        for wrapper in WRAPPERS {
+            let multisig = Multisig::sorted(2, &points, wrapper).unwrap();
+            let mut tx = spend(&multisig);
+
+            // Signers work independently and in any order.
+            let signatures: Vec<_> = keys.iter().rev().map(|key| multisig.sign(&SighashCache::new(&tx), 0, 50_000, key, SighashType::ALL).unwrap()).collect();
+
+            let unsigned = tx.clone();
+            assert!(matches!(multisig.finalize(&mut tx, 0, 50_000, &signatures[..1]), Err(SignerError::NotEnoughSignatures(1, 2))));
+            assert_eq!(tx, unsigned);
+
+            // Any two of the three will do.
+            multisig.finalize(&mut tx, 0, 50_000, &signatures[1..]).unwrap();
+            multisig.finalize(&mut tx, 0, 50_000, &[signatures[2].clone(), signatures[0].clone()]).unwrap();
+
+            match wrapper {
+                MultisigWrapper::P2SH => assert!(tx.inputs[0].witness.is_empty()),
+                MultisigWrapper::P2WSH => assert!(tx.inputs[0].script_sig.is_empty()),
+                MultisigWrapper::P2SHP2WSH => assert_eq!(tx.inputs[0].script_sig.len(), 35),
+            }
+
+            if wrapper != MultisigWrapper::P2SH {
+                assert_eq!(tx.inputs[0].witness.len(), 4);
+                assert!(tx.inputs[0].witness.items()[0].is_empty());
+            }
+
+            // Only segwit signatures commit to the amount.
+            let result = multisig.finalize(&mut spend(&multisig), 0, 50_001, &signatures);
+
+            match wrapper {
+                MultisigWrapper::P2SH => assert!(result.is_ok()),
+                _ => assert!(matches!(result, Err(SignerError::Verification(0, _)))),
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_sign_with_foreign_key() {
+        let keys = keys();
+        let points: Vec<_> = keys[..2].iter().map(|key| key.public_key()).collect();
+        let multisig = Multisig::new(1, &points, MultisigWrapper::P2WSH).unwrap();
+        let tx = spend(&multisig);
+
+        assert!(matches!(multisig.sign(&SighashCache::new(&tx), 0, 50_000, &keys[2], SighashType::ALL), Err(SignerError::KeyNotInScript)));
+
+        let signature = multisig.sign(&SighashCache::new(&tx), 0, 50_000, &keys[1], SighashType::ALL).unwrap();
+        assert_eq!(signature.public_key, keys[1].public_key().serialize_sec(true).unwrap());
+    }
+}
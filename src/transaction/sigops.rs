@@ -0,0 +1,175 @@
+use crate::script::{instruction::{Instruction, Instructions}, interpreter::flags::VerifyFlags, opcode::Opcode, standard::{is_p2sh, witness_program}, Script};
+use super::{tx_out::TxOut, witness::Witness, Transaction, WITNESS_SCALE_FACTOR};
+
+/// Core's `GetLegacySigOpCount`: the sigops of every scriptSig and
+///  scriptPubKey, counted inaccurately.
+pub fn legacy_sigop_count(tx: &Transaction) -> usize {
+    let inputs = tx.inputs.iter().map(|input| Script::from(input.script_sig.as_slice()).sigop_count(false));
+    let outputs = tx.outputs.iter().map(|output| Script::from(output.script_pubkey.as_slice()).sigop_count(false));
+
+    inputs.chain(outputs).sum()
+}
+
+/// Core's `GetP2SHSigOpCount`: the sigops of the redeem scripts of inputs
+///  spending P2SH outputs, counted accurately. `prevouts` are the outputs
+///  spent by every input in order.
+pub fn p2sh_sigop_count(tx: &Transaction, prevouts: &[TxOut]) -> usize {
+    if tx.is_coinbase() {
+        return 0;
+    }
+
+    tx.inputs
+        .iter()
+        .zip(prevouts)
+        .filter(|(_, prevout)| is_p2sh(&prevout.script_pubkey))
+        .map(|(input, _)| last_push(&input.script_sig).map_or(0, |redeem_script| Script::from(redeem_script).sigop_count(true)))
+        .sum()
+}
+
+/// Core's `GetTransactionSigOpCost`, what blocks are limited by: legacy and
+///  P2SH sigops cost four times as much as witness sigops, which `flags`
+///  decide whether to count. Coinbases only have legacy sigops.
+pub fn sigop_cost(tx: &Transaction, prevouts: &[TxOut], flags: VerifyFlags) -> usize {
+    let mut cost = legacy_sigop_count(tx) * WITNESS_SCALE_FACTOR;
+
+    if tx.is_coinbase() {
+        return cost;
+    }
+
+    if flags.contains(VerifyFlags::P2SH) {
+        cost += p2sh_sigop_count(tx, prevouts) * WITNESS_SCALE_FACTOR;
+    }
+
+    if flags.contains(VerifyFlags::WITNESS) {
+        for (input, prevout) in tx.inputs.iter().zip(prevouts) {
+            cost += witness_sigop_count(&input.script_sig, &prevout.script_pubkey, &input.witness);
+        }
+    }
+
+    cost
+}
+
+/// Core's `CountWitnessSigOps`, for native and P2SH wrapped witness programs.
+fn witness_sigop_count(script_sig: &[u8], script_pubkey: &[u8], witness: &Witness) -> usize {
+    if let Some((version, program)) = witness_program(script_pubkey) {
+        return witness_program_sigop_count(version, program, witness);
+    }
+
+    if !is_p2sh(script_pubkey) || !Script::from(script_sig).is_push_only() {
+        return 0;
+    }
+
+    match last_push(script_sig).and_then(witness_program) {
+        Some((version, program)) => witness_program_sigop_count(version, program, witness),
+        None => 0,
+    }
+}
+
+/// P2WPKH is one signature check and a P2WSH script is counted accurately.
+///  Taproot and unknown versions have no sigops, tapscript having its own
+///  validation weight budget instead.
+fn witness_program_sigop_count(version: u8, program: &[u8], witness: &Witness) -> usize {
+    match (version, program.len(), witness.last()) {
+        (0, 20, _) => 1,
+        (0, 32, Some(witness_script)) => Script::from(witness_script.as_slice()).sigop_count(true),
+        _ => 0,
+    }
+}
+
+/// The data of a scriptSig's last push, which P2SH runs as the redeem
+///  script. OP_1 through OP_16 count as empty pushes, as in Core; any other
+///  opcode or a malformed push means there is none.
+fn last_push(script_sig: &[u8]) -> Option<&[u8]> {
+    let mut data: &[u8] = &[];
+
+    for instruction in Instructions::new(script_sig) {
+        match instruction.ok()? {
+            Instruction::Push(_, push) => data = push,
+            Instruction::Op(opcode) if opcode <= Opcode::OP_16 => data = &[],
+            Instruction::Op(_) => return None,
+        }
+    }
+
+    Some(data)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{cryptography::hash::{digest::{PubkeyHash, ScriptHash}, sha256}, script::{interpreter::flags::VerifyFlags, Script}, transaction::{out_point::OutPoint, tx_in::TxIn, tx_out::TxOut, witness::Witness, Transaction}};
+    use super::{legacy_sigop_count, p2sh_sigop_count, sigop_cost};
+
+    fn spend(script_sig: &Script, witness: Vec<Vec<u8>>) -> TxIn {
+        let mut input = TxIn::new(OutPoint::new(Default::default(), 0));
+        input.script_sig = script_sig.as_bytes().to_vec();
+        input.witness = Witness::from_items(witness);
+
+        input
+    }
+
+    #[test]
+    pub fn test_sigop_cost() {
+        let public_key = [0x02; 33];
+        let signature = vec![0x30; 71];
+
+        // P2SH 2-of-3 multisig.
+        let multisig = Script::p2ms(2, &[public_key; 3]).unwrap();
+        let mut p2sh_sig = Script::new();
+        p2sh_sig.push_int(0);
+        p2sh_sig.push_slice(&signature);
+        p2sh_sig.push_slice(&signature);
+        p2sh_sig.push_slice(multisig.as_bytes());
+
+        // P2SH wrapped P2WSH 1-of-2 multisig.
+        let witness_script = Script::p2ms(1, &[public_key; 2]).unwrap();
+        let redeem_script = Script::p2wsh(&sha256(witness_script.as_bytes()));
+        let mut wrapped_sig = Script::new();
+        wrapped_sig.push_slice(redeem_script.as_bytes());
+
+        let tx = Transaction::new(
+            2,
+            vec![
+                spend(&p2sh_sig, vec![]),
+                spend(&Script::new(), vec![signature.clone(), public_key.to_vec()]),
+                spend(&wrapped_sig, vec![vec![], signature.clone(), witness_script.into_bytes()]),
+                spend(&Script::new(), vec![vec![0x01; 64]]),
+            ],
+            vec![TxOut::new(1000, Script::p2pkh(&PubkeyHash::hash(&public_key)).into_bytes())],
+            0,
+        );
+
+        let prevouts: Vec<TxOut> = [
+            Script::p2sh(&ScriptHash::hash(multisig.as_bytes())),
+            Script::p2wpkh(&PubkeyHash::hash(&public_key)),
+            Script::p2sh(&ScriptHash::hash(redeem_script.as_bytes())),
+            Script::p2tr(&[0x01; 32]),
+        ]
+        .into_iter()
+        .map(|script| TxOut::new(1000, script.into_bytes()))
+        .collect();
+
+        // The P2PKH output.
+        assert_eq!(legacy_sigop_count(&tx), 1);
+        // The multisig redeem script counts its three keys, not twenty.
+        assert_eq!(p2sh_sigop_count(&tx, &prevouts), 3);
+        // P2WPKH is one, the wrapped witness script two, taproot nothing.
+        assert_eq!(sigop_cost(&tx, &prevouts, VerifyFlags::CONSENSUS), 4 + 12 + 1 + 2);
+        assert_eq!(sigop_cost(&tx, &prevouts, VerifyFlags::P2SH), 4 + 12);
+        assert_eq!(sigop_cost(&tx, &prevouts, VerifyFlags::NONE), 4);
+
+        // A non-push in the scriptSig leaves no redeem script to count.
+        let mut spoiled = tx.clone();
+        spoiled.inputs[0].script_sig.insert(0, 0x61);
+        assert_eq!(p2sh_sigop_count(&spoiled, &prevouts), 0);
+    }
+
+    #[test]
+    pub fn test_coinbase_sigop_cost() {
+        let mut coinbase = TxIn::new(OutPoint::null());
+        coinbase.script_sig = vec![0x03, 0x01, 0x02, 0x03, 0xac];
+
+        let tx = Transaction::new(1, vec![coinbase], vec![TxOut::new(5_000_000_000, Script::p2pk(&[0x02; 33]).into_bytes())], 0);
+
+        // The scriptSig's OP_CHECKSIG counts too, though it never runs.
+        assert_eq!(sigop_cost(&tx, &[], VerifyFlags::CONSENSUS), 8);
+    }
+}
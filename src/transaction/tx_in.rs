@@ -0,0 +1,35 @@
+use super::{out_point::OutPoint, witness::Witness};
+
+/// Sequence that disables both relative locktime and opt-in RBF.
+pub const SEQUENCE_FINAL: u32 = 0xffff_ffff;
+/// Highest sequence that still enforces the transaction's locktime.
+pub const SEQUENCE_MAX_NONFINAL: u32 = 0xffff_fffe;
+/// BIP125: sequences up to this one signal that the transaction may be
+///  replaced by one paying a higher fee.
+pub const SEQUENCE_MAX_BIP125_RBF: u32 = 0xffff_fffd;
+/// BIP68: with this bit set the sequence is not a relative locktime.
+pub const SEQUENCE_LOCKTIME_DISABLE_FLAG: u32 = 1 << 31;
+/// BIP68: relative locktimes with this bit set count 512 second units
+///  rather than blocks.
+pub const SEQUENCE_LOCKTIME_TYPE_FLAG: u32 = 1 << 22;
+pub const SEQUENCE_LOCKTIME_MASK: u32 = 0x0000_ffff;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TxIn {
+    pub previous_output: OutPoint,
+    pub script_sig: Vec<u8>,
+    pub sequence: u32,
+    pub witness: Witness,
+}
+
+impl TxIn {
+    /// Unsigned input spending `previous_output`.
+    pub fn new(previous_output: OutPoint) -> Self {
+        Self {
+            previous_output,
+            script_sig: Vec::new(),
+            sequence: SEQUENCE_FINAL,
+            witness: Witness::new(),
+        }
+    }
+}
@@ -0,0 +1,15 @@
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TxOut {
+    /// Amount in satoshis.
+    pub value: u64,
+    pub script_pubkey: Vec<u8>,
+}
+
+impl TxOut {
+    pub fn new(value: u64, script_pubkey: Vec<u8>) -> Self {
+        Self {
+            value,
+            script_pubkey,
+        }
+    }
+}
@@ -0,0 +1,43 @@
+/// BIP141 witness stack for a single input, bottom item first.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Witness(Vec<Vec<u8>>);
+
+impl Witness {
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn from_items(items: Vec<Vec<u8>>) -> Self {
+        Self(items)
+    }
+
+    pub fn push(&mut self, item: impl Into<Vec<u8>>) {
+        self.0.push(item.into());
+    }
+
+    pub fn items(&self) -> &[Vec<u8>] {
+        &self.0
+    }
+
+    pub fn into_items(self) -> Vec<Vec<u8>> {
+        self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn last(&self) -> Option<&Vec<u8>> {
+        self.0.last()
+    }
+}
+
+impl From<Vec<Vec<u8>>> for Witness {
+    fn from(value: Vec<Vec<u8>>) -> Self {
+        Self(value)
+    }
+}